@@ -15,6 +15,10 @@ local to_signed = bit.tobit
 local NUM_ZERO = i64(0)
 local NUM_ONE = i64(1)
 
+local function to_unsigned(num)
+	return num % 0x100000000
+end
+
 local function truncate_f64(num)
 	if num >= 0 then
 		return (math_floor(num))
@@ -493,6 +497,15 @@ do
 
 	saturate.u64_f64 = saturate.u64_f32
 
+	-- Only used for memory addresses, which are expected to be unsigned
+	function saturate.u32_i64(num)
+		if u64(num) >= 0xFFFFFFFF then
+			return 0xFFFFFFFF
+		else
+			return to_number(num)
+		end
+	end
+
 	function extend.i32_n8(num)
 		num = bit_and(num, 0xFF)
 
@@ -633,6 +646,7 @@ do
 		uint32_t min;
 		uint32_t max;
 		union Any *data;
+		bool shared;
 	};
 
 	void *calloc(size_t num, size_t size);
@@ -748,10 +762,17 @@ do
 		by_offset(memory.data, addr).f64 = value
 	end
 
-	function store.string(memory, addr, data, len)
+	function store.init(memory, addr, data, offset, len)
+		offset = to_unsigned(offset)
+		len = to_unsigned(len)
+
+		if offset + len > #data then
+			error("out of bounds memory access")
+		end
+
 		local start = by_offset(memory.data, addr)
 
-		ffi.copy(start, data, len or #data)
+		ffi.copy(start, cast("const char *", data) + offset, len)
 	end
 
 	function store.copy(memory_1, addr_1, memory_2, addr_2, len)
@@ -767,6 +788,200 @@ do
 		ffi.fill(start, len, value)
 	end
 
+	-- Atomic accesses trap unless their address is a multiple of their size
+	local function check_aligned(addr, size)
+		if addr % size ~= 0 then
+			error("unaligned atomic")
+		end
+	end
+
+	local function atomic_load_of(load_fn, size)
+		return function(memory, addr)
+			check_aligned(addr, size)
+
+			return load_fn(memory, addr)
+		end
+	end
+
+	local function atomic_store_of(store_fn, size)
+		return function(memory, addr, value)
+			check_aligned(addr, size)
+
+			store_fn(memory, addr, value)
+		end
+	end
+
+	load.atomic_i32 = atomic_load_of(load.i32, 4)
+	load.atomic_i32_u8 = atomic_load_of(load.i32_u8, 1)
+	load.atomic_i32_u16 = atomic_load_of(load.i32_u16, 2)
+	load.atomic_i64 = atomic_load_of(load.i64, 8)
+	load.atomic_i64_u8 = atomic_load_of(load.i64_u8, 1)
+	load.atomic_i64_u16 = atomic_load_of(load.i64_u16, 2)
+	load.atomic_i64_u32 = atomic_load_of(load.i64_u32, 4)
+
+	store.atomic_i32 = atomic_store_of(store.i32, 4)
+	store.atomic_i32_n8 = atomic_store_of(store.i32_n8, 1)
+	store.atomic_i32_n16 = atomic_store_of(store.i32_n16, 2)
+	store.atomic_i64 = atomic_store_of(store.i64, 8)
+	store.atomic_i64_n8 = atomic_store_of(store.i64_n8, 1)
+	store.atomic_i64_n16 = atomic_store_of(store.i64_n16, 2)
+	store.atomic_i64_n32 = atomic_store_of(store.i64_n32, 4)
+
+	-- Memories are never shared between threads, so atomic operations
+	-- are done as plain read-modify-write sequences, which are checked
+	-- for alignment by their first load
+	local function rmw_of(load_fn, store_fn, operation)
+		return function(memory, addr, value)
+			local old = load_fn(memory, addr)
+
+			store_fn(memory, addr, operation(old, value))
+
+			return old
+		end
+	end
+
+	local function cmpxchg_of(load_fn, store_fn, is_equal, wrap)
+		return function(memory, addr, expected, replacement)
+			local old = load_fn(memory, addr)
+
+			if is_equal(old, wrap(expected)) then
+				store_fn(memory, addr, replacement)
+			end
+
+			return old
+		end
+	end
+
+	local function wrap_num(mask)
+		return function(num)
+			return bit.band(num, mask)
+		end
+	end
+
+	local function no_op(num)
+		return num
+	end
+
+	local function is_equal(lhs, rhs)
+		return lhs == rhs
+	end
+
+	local function swap(_, rhs)
+		return rhs
+	end
+
+	local rmw_i32 = {
+		add = module.add.i32,
+		sub = module.sub.i32,
+		band = bit.band,
+		bor = bit.bor,
+		bxor = bit.bxor,
+		xchg = swap,
+	}
+
+	local rmw_i64 = {
+		add = function(lhs, rhs)
+			return lhs + rhs
+		end,
+		sub = function(lhs, rhs)
+			return lhs - rhs
+		end,
+		band = bit.band,
+		bor = bit.bor,
+		bxor = bit.bxor,
+		xchg = swap,
+	}
+
+	store.rmw_add_i32 = rmw_of(load.atomic_i32, store.i32, rmw_i32.add)
+	store.rmw_add_i32_n8 = rmw_of(load.atomic_i32_u8, store.i32_n8, rmw_i32.add)
+	store.rmw_add_i32_n16 = rmw_of(load.atomic_i32_u16, store.i32_n16, rmw_i32.add)
+	store.rmw_add_i64 = rmw_of(load.atomic_i64, store.i64, rmw_i64.add)
+	store.rmw_add_i64_n8 = rmw_of(load.atomic_i64_u8, store.i64_n8, rmw_i64.add)
+	store.rmw_add_i64_n16 = rmw_of(load.atomic_i64_u16, store.i64_n16, rmw_i64.add)
+	store.rmw_add_i64_n32 = rmw_of(load.atomic_i64_u32, store.i64_n32, rmw_i64.add)
+
+	store.rmw_sub_i32 = rmw_of(load.atomic_i32, store.i32, rmw_i32.sub)
+	store.rmw_sub_i32_n8 = rmw_of(load.atomic_i32_u8, store.i32_n8, rmw_i32.sub)
+	store.rmw_sub_i32_n16 = rmw_of(load.atomic_i32_u16, store.i32_n16, rmw_i32.sub)
+	store.rmw_sub_i64 = rmw_of(load.atomic_i64, store.i64, rmw_i64.sub)
+	store.rmw_sub_i64_n8 = rmw_of(load.atomic_i64_u8, store.i64_n8, rmw_i64.sub)
+	store.rmw_sub_i64_n16 = rmw_of(load.atomic_i64_u16, store.i64_n16, rmw_i64.sub)
+	store.rmw_sub_i64_n32 = rmw_of(load.atomic_i64_u32, store.i64_n32, rmw_i64.sub)
+
+	store.rmw_and_i32 = rmw_of(load.atomic_i32, store.i32, rmw_i32.band)
+	store.rmw_and_i32_n8 = rmw_of(load.atomic_i32_u8, store.i32_n8, rmw_i32.band)
+	store.rmw_and_i32_n16 = rmw_of(load.atomic_i32_u16, store.i32_n16, rmw_i32.band)
+	store.rmw_and_i64 = rmw_of(load.atomic_i64, store.i64, rmw_i64.band)
+	store.rmw_and_i64_n8 = rmw_of(load.atomic_i64_u8, store.i64_n8, rmw_i64.band)
+	store.rmw_and_i64_n16 = rmw_of(load.atomic_i64_u16, store.i64_n16, rmw_i64.band)
+	store.rmw_and_i64_n32 = rmw_of(load.atomic_i64_u32, store.i64_n32, rmw_i64.band)
+
+	store.rmw_or_i32 = rmw_of(load.atomic_i32, store.i32, rmw_i32.bor)
+	store.rmw_or_i32_n8 = rmw_of(load.atomic_i32_u8, store.i32_n8, rmw_i32.bor)
+	store.rmw_or_i32_n16 = rmw_of(load.atomic_i32_u16, store.i32_n16, rmw_i32.bor)
+	store.rmw_or_i64 = rmw_of(load.atomic_i64, store.i64, rmw_i64.bor)
+	store.rmw_or_i64_n8 = rmw_of(load.atomic_i64_u8, store.i64_n8, rmw_i64.bor)
+	store.rmw_or_i64_n16 = rmw_of(load.atomic_i64_u16, store.i64_n16, rmw_i64.bor)
+	store.rmw_or_i64_n32 = rmw_of(load.atomic_i64_u32, store.i64_n32, rmw_i64.bor)
+
+	store.rmw_xor_i32 = rmw_of(load.atomic_i32, store.i32, rmw_i32.bxor)
+	store.rmw_xor_i32_n8 = rmw_of(load.atomic_i32_u8, store.i32_n8, rmw_i32.bxor)
+	store.rmw_xor_i32_n16 = rmw_of(load.atomic_i32_u16, store.i32_n16, rmw_i32.bxor)
+	store.rmw_xor_i64 = rmw_of(load.atomic_i64, store.i64, rmw_i64.bxor)
+	store.rmw_xor_i64_n8 = rmw_of(load.atomic_i64_u8, store.i64_n8, rmw_i64.bxor)
+	store.rmw_xor_i64_n16 = rmw_of(load.atomic_i64_u16, store.i64_n16, rmw_i64.bxor)
+	store.rmw_xor_i64_n32 = rmw_of(load.atomic_i64_u32, store.i64_n32, rmw_i64.bxor)
+
+	store.rmw_xchg_i32 = rmw_of(load.atomic_i32, store.i32, rmw_i32.xchg)
+	store.rmw_xchg_i32_n8 = rmw_of(load.atomic_i32_u8, store.i32_n8, rmw_i32.xchg)
+	store.rmw_xchg_i32_n16 = rmw_of(load.atomic_i32_u16, store.i32_n16, rmw_i32.xchg)
+	store.rmw_xchg_i64 = rmw_of(load.atomic_i64, store.i64, rmw_i64.xchg)
+	store.rmw_xchg_i64_n8 = rmw_of(load.atomic_i64_u8, store.i64_n8, rmw_i64.xchg)
+	store.rmw_xchg_i64_n16 = rmw_of(load.atomic_i64_u16, store.i64_n16, rmw_i64.xchg)
+	store.rmw_xchg_i64_n32 = rmw_of(load.atomic_i64_u32, store.i64_n32, rmw_i64.xchg)
+
+	store.cmpxchg_i32 = cmpxchg_of(load.atomic_i32, store.i32, is_equal, no_op)
+	store.cmpxchg_i32_n8 = cmpxchg_of(load.atomic_i32_u8, store.i32_n8, is_equal, wrap_num(0xFF))
+	store.cmpxchg_i32_n16 = cmpxchg_of(load.atomic_i32_u16, store.i32_n16, is_equal, wrap_num(0xFFFF))
+	store.cmpxchg_i64 = cmpxchg_of(load.atomic_i64, store.i64, is_equal, no_op)
+	store.cmpxchg_i64_n8 = cmpxchg_of(load.atomic_i64_u8, store.i64_n8, is_equal, wrap_num(0xFF))
+	store.cmpxchg_i64_n16 = cmpxchg_of(load.atomic_i64_u16, store.i64_n16, is_equal, wrap_num(0xFFFF))
+	store.cmpxchg_i64_n32 = cmpxchg_of(load.atomic_i64_u32, store.i64_n32, is_equal, wrap_num(0xFFFFFFFF))
+
+	local function check_shared(memory)
+		if not memory.shared then
+			error("expected shared memory")
+		end
+	end
+
+	-- Nothing could ever notify a waiter, so it either
+	-- sees a different value or times out
+	function load.wait_i32(memory, addr, expected, _)
+		check_shared(memory)
+
+		if load.atomic_i32(memory, addr) == expected then
+			return 2
+		else
+			return 1
+		end
+	end
+
+	function load.wait_i64(memory, addr, expected, _)
+		check_shared(memory)
+
+		if load.atomic_i64(memory, addr) == expected then
+			return 2
+		else
+			return 1
+		end
+	end
+
+	function store.notify(_, addr, _)
+		check_aligned(addr, 4)
+
+		return 0
+	end
+
 	local WASM_PAGE_SIZE = 65536
 
 	local function finalizer(memory)
@@ -781,12 +996,12 @@ do
 		ffi.fill(by_offset(memory.data, old), new - old, 0)
 	end
 
-	function allocator.new(min, max)
+	function allocator.new(min, max, shared)
 		local data = ffi.C.calloc(max, WASM_PAGE_SIZE)
 
 		assert(data ~= nil, "failed to allocate")
 
-		local memory = ffi.new("struct Memory", min, max, data)
+		local memory = ffi.new("struct Memory", min, max, data, shared == true)
 
 		return ffi.gc(memory, finalizer)
 	end
@@ -814,4 +1029,1075 @@ do
 	module.allocator = allocator
 end
 
+do
+	local table_ops = {}
+
+	local table_move = table.move
+
+	local function check_bounds(list, index, len)
+		if index + len > list.min then
+			error("out of bounds table access")
+		end
+	end
+
+	function table_ops.get(list, index)
+		index = to_unsigned(index)
+
+		check_bounds(list, index, 1)
+
+		return list.data[index]
+	end
+
+	function table_ops.set(list, index, value)
+		index = to_unsigned(index)
+
+		check_bounds(list, index, 1)
+
+		list.data[index] = value
+	end
+
+	function table_ops.grow(list, num, value)
+		local old = list.min
+		local new = old + to_unsigned(num)
+
+		if new > list.max then
+			return -1
+		end
+
+		local data = list.data
+
+		for i = old, new - 1 do
+			data[i] = value
+		end
+
+		list.min = new
+
+		return old
+	end
+
+	function table_ops.fill(list, index, len, value)
+		index = to_unsigned(index)
+		len = to_unsigned(len)
+
+		check_bounds(list, index, len)
+
+		local data = list.data
+
+		for i = index, index + len - 1 do
+			data[i] = value
+		end
+	end
+
+	function table_ops.init(list, index, segment, offset, len)
+		index = to_unsigned(index)
+		offset = to_unsigned(offset)
+		len = to_unsigned(len)
+
+		check_bounds(list, index, len)
+
+		if offset + len > segment.n then
+			error("out of bounds table access")
+		end
+
+		table_move(segment, offset + 1, offset + len, index, list.data)
+	end
+
+	function table_ops.copy(list_1, index_1, list_2, index_2, len)
+		index_1 = to_unsigned(index_1)
+		index_2 = to_unsigned(index_2)
+		len = to_unsigned(len)
+
+		check_bounds(list_1, index_1, len)
+		check_bounds(list_2, index_2, len)
+
+		table_move(list_2.data, index_2, index_2 + len - 1, index_1, list_1.data)
+	end
+
+	module.table = table_ops
+end
+
+do
+	local exception = {}
+
+	local table_unpack = unpack
+
+	function exception.new(tag, ...)
+		return { tag = tag, n = select("#", ...), ... }
+	end
+
+	function exception.tag_of(data)
+		if type(data) == "table" then
+			return data.tag
+		end
+
+		return nil
+	end
+
+	function exception.unpack(data)
+		return table_unpack(data, 1, data.n)
+	end
+
+	module.exception = exception
+end
+
+do
+	local v128 = {}
+	local abs = {}
+	local ceil = {}
+	local floor = {}
+	local sqrt = {}
+	local eq = {}
+	local ne = {}
+	local band = {}
+	local bor = {}
+	local bxor = {}
+	local bnot = {}
+	local shl = {}
+	local shr = {}
+	local splat = {}
+	local extract_lane = {}
+	local replace_lane = {}
+	local shuffle = {}
+	local swizzle = {}
+	local bitselect = {}
+	local band_not = {}
+	local any_true = {}
+	local all_true = {}
+	local bitmask = {}
+	local add_sat = {}
+	local sub_sat = {}
+	local avgr = {}
+	local q15mulr_sat = {}
+	local pmin = {}
+	local pmax = {}
+	local narrow = {}
+	local extend_low = {}
+	local extend_high = {}
+	local extadd_pairwise = {}
+	local extmul_low = {}
+	local extmul_high = {}
+	local dot = {}
+	local saturate_zero = {}
+	local convert_low = {}
+	local demote_zero = {}
+	local promote_low = {}
+	local load_lane = {}
+	local store_lane = {}
+
+	local add = module.add
+	local sub = module.sub
+	local mul = module.mul
+	local div = module.div
+	local neg = module.neg
+	local min = module.min
+	local max = module.max
+	local nearest = module.nearest
+	local popcnt = module.popcnt
+	local le = module.le
+	local lt = module.lt
+	local ge = module.ge
+	local gt = module.gt
+	local truncate = module.truncate
+	local saturate = module.saturate
+	local convert = module.convert
+	local load = module.load
+	local store = module.store
+
+	local math_abs = math.abs
+	local math_sqrt = math.sqrt
+	local math_min = math.min
+	local math_max = math.max
+
+	local bit_and = bit.band
+	local bit_or = bit.bor
+	local bit_xor = bit.bxor
+	local bit_not = bit.bnot
+	local bit_lshift = bit.lshift
+	local bit_rshift = bit.rshift
+	local bit_arshift = bit.arshift
+
+	ffi.cdef([[
+	union V128 {
+		int8_t i8[16];
+		int16_t i16[8];
+		int32_t i32[4];
+		int64_t i64[2];
+
+		uint8_t u8[16];
+		uint16_t u16[8];
+		uint32_t u32[4];
+		uint64_t u64[2];
+
+		float f32[4];
+		double f64[2];
+	};
+	]])
+
+	local v128_t = ffi.typeof("union V128")
+	local alias_t = ffi.typeof("uint8_t *")
+	local cast = ffi.cast
+
+	local NUM_NEGATIVE_ONE = i64(-1)
+
+	local function by_offset(pointer, offset)
+		return cast(alias_t, pointer) + offset
+	end
+
+	local function copy_of(value)
+		local result = v128_t()
+
+		ffi.copy(result, value, 16)
+
+		return result
+	end
+
+	-- Lanes are read from one field and written to another, so that
+	-- results can be wrapped before being stored in the union
+	local function new_lane(count, read, write, wrap, all)
+		return { count = count, read = read, write = write, wrap = wrap, all = all }
+	end
+
+	local function wrap_n8(num)
+		return bit_and(num, 0xFF)
+	end
+
+	local function wrap_n16(num)
+		return bit_and(num, 0xFFFF)
+	end
+
+	local function no_op(num)
+		return num
+	end
+
+	local I8 = new_lane(16, "i8", "u8", wrap_n8, 0xFF)
+	local U8 = new_lane(16, "u8", "u8", wrap_n8, 0xFF)
+	local I16 = new_lane(8, "i16", "u16", wrap_n16, 0xFFFF)
+	local U16 = new_lane(8, "u16", "u16", wrap_n16, 0xFFFF)
+	local I32 = new_lane(4, "i32", "i32", to_signed, -1)
+	local U32 = new_lane(4, "u32", "i32", to_signed, -1)
+	local I64 = new_lane(2, "i64", "i64", no_op, NUM_NEGATIVE_ONE)
+	local F32 = new_lane(4, "f32", "f32", no_op)
+	local F64 = new_lane(2, "f64", "f64", no_op)
+
+	local function clamp(num, lower, upper)
+		return math_min(math_max(num, lower), upper)
+	end
+
+	local function lanewise_unary(lane, func)
+		local last, read, write, wrap = lane.count - 1, lane.read, lane.write, lane.wrap
+
+		return function(value)
+			local result = v128_t()
+			local source = value[read]
+			local target = result[write]
+
+			for i = 0, last do
+				target[i] = wrap(func(source[i]))
+			end
+
+			return result
+		end
+	end
+
+	local function lanewise_binary(lane, func)
+		local last, read, write, wrap = lane.count - 1, lane.read, lane.write, lane.wrap
+
+		return function(lhs, rhs)
+			local result = v128_t()
+			local source_1 = lhs[read]
+			local source_2 = rhs[read]
+			local target = result[write]
+
+			for i = 0, last do
+				target[i] = wrap(func(source_1[i], source_2[i]))
+			end
+
+			return result
+		end
+	end
+
+	local function lanewise_shift(lane, func)
+		local last, read, write, wrap = lane.count - 1, lane.read, lane.write, lane.wrap
+		local bits = 128 / lane.count
+
+		return function(lhs, rhs)
+			local result = v128_t()
+			local source = lhs[read]
+			local target = result[write]
+			local shift = bit_and(rhs, bits - 1)
+
+			for i = 0, last do
+				target[i] = wrap(func(source[i], shift))
+			end
+
+			return result
+		end
+	end
+
+	-- Comparisons write all bits of a lane when true, which for
+	-- floating point lanes means going through the integer fields
+	local function lanewise_compare(lane, mask, func)
+		local last, read, write, all = lane.count - 1, lane.read, mask.write, mask.all
+
+		return function(lhs, rhs)
+			local result = v128_t()
+			local source_1 = lhs[read]
+			local source_2 = rhs[read]
+			local target = result[write]
+
+			for i = 0, last do
+				if func(source_1[i], source_2[i]) then
+					target[i] = all
+				end
+			end
+
+			return result
+		end
+	end
+
+	-- Reads `count` lanes starting at lane `first` of the source and writes
+	-- them to the lower lanes of the result, leaving the rest zeroed
+	local function lanewise_convert(from, into, count, first, func)
+		local read, write, wrap = from.read, into.write, into.wrap
+
+		return function(value)
+			local result = v128_t()
+			local source = value[read]
+			local target = result[write]
+
+			for i = 0, count - 1 do
+				target[i] = wrap(func(source[first + i]))
+			end
+
+			return result
+		end
+	end
+
+	local function lanewise_widen(from, into, first, func)
+		local read, write, wrap = from.read, into.write, into.wrap
+		local last = into.count - 1
+
+		return function(lhs, rhs)
+			local result = v128_t()
+			local source_1 = lhs[read]
+			local source_2 = rhs[read]
+			local target = result[write]
+
+			for i = 0, last do
+				target[i] = wrap(func(source_1[first + i], source_2[first + i]))
+			end
+
+			return result
+		end
+	end
+
+	local function lanewise_pairwise(from, into, func)
+		local read, write, wrap = from.read, into.write, into.wrap
+		local last = into.count - 1
+
+		return function(lhs, rhs)
+			local result = v128_t()
+			local source_1 = lhs[read]
+			local source_2 = rhs[read]
+			local target = result[write]
+
+			for i = 0, last do
+				local index_1 = i * 2
+				local index_2 = index_1 + 1
+				local value = func(source_1[index_1], source_2[index_1])
+					+ func(source_1[index_2], source_2[index_2])
+
+				target[i] = wrap(value)
+			end
+
+			return result
+		end
+	end
+
+	local function lanewise_narrow(from, into, lower, upper)
+		local read, write, wrap = from.read, into.write, into.wrap
+		local count = from.count
+
+		return function(lhs, rhs)
+			local result = v128_t()
+			local source_1 = lhs[read]
+			local source_2 = rhs[read]
+			local target = result[write]
+
+			for i = 0, count - 1 do
+				target[i] = wrap(clamp(source_1[i], lower, upper))
+				target[i + count] = wrap(clamp(source_2[i], lower, upper))
+			end
+
+			return result
+		end
+	end
+
+	local function lanewise_all_true(lane)
+		local last, read = lane.count - 1, lane.read
+
+		return function(value)
+			local source = value[read]
+
+			for i = 0, last do
+				if source[i] == 0 then
+					return 0
+				end
+			end
+
+			return 1
+		end
+	end
+
+	local function lanewise_bitmask(lane)
+		local last, read = lane.count - 1, lane.read
+
+		return function(value)
+			local source = value[read]
+			local result = 0
+
+			for i = 0, last do
+				if source[i] < 0 then
+					result = bit_or(result, bit_lshift(1, i))
+				end
+			end
+
+			return result
+		end
+	end
+
+	local function lanewise_splat(lane)
+		local last, write, wrap = lane.count - 1, lane.write, lane.wrap
+
+		return function(value)
+			local result = v128_t()
+			local target = result[write]
+
+			value = wrap(value)
+
+			for i = 0, last do
+				target[i] = value
+			end
+
+			return result
+		end
+	end
+
+	local function lanewise_extract(lane)
+		local read = lane.read
+
+		return function(value, index)
+			return value[read][index]
+		end
+	end
+
+	local function lanewise_replace(lane)
+		local write, wrap = lane.write, lane.wrap
+
+		return function(value, index, data)
+			local result = copy_of(value)
+
+			result[write][index] = wrap(data)
+
+			return result
+		end
+	end
+
+	local function add_num(lhs, rhs)
+		return lhs + rhs
+	end
+
+	local function sub_num(lhs, rhs)
+		return lhs - rhs
+	end
+
+	local function mul_num(lhs, rhs)
+		return lhs * rhs
+	end
+
+	local function div_num(lhs, rhs)
+		return lhs / rhs
+	end
+
+	local function neg_num(num)
+		return -num
+	end
+
+	local function abs_num(num)
+		if num < 0 then
+			return -num
+		else
+			return num
+		end
+	end
+
+	local function is_equal(lhs, rhs)
+		return lhs == rhs
+	end
+
+	local function is_not_equal(lhs, rhs)
+		return lhs ~= rhs
+	end
+
+	local function is_less(lhs, rhs)
+		return lhs < rhs
+	end
+
+	local function is_less_equal(lhs, rhs)
+		return lhs <= rhs
+	end
+
+	local function is_greater(lhs, rhs)
+		return lhs > rhs
+	end
+
+	local function is_greater_equal(lhs, rhs)
+		return lhs >= rhs
+	end
+
+	local function avgr_num(lhs, rhs)
+		return math_floor((lhs + rhs + 1) / 2)
+	end
+
+	local function pmin_num(lhs, rhs)
+		if rhs < lhs then
+			return rhs
+		else
+			return lhs
+		end
+	end
+
+	local function pmax_num(lhs, rhs)
+		if lhs < rhs then
+			return rhs
+		else
+			return lhs
+		end
+	end
+
+	local function saturate_between(lower, upper)
+		return function(num)
+			if num ~= num then
+				return 0
+			end
+
+			return clamp(truncate_f64(num), lower, upper)
+		end
+	end
+
+	local function to_i64(num)
+		return (i64(num))
+	end
+
+	function v128.from_u32(data_1, data_2, data_3, data_4)
+		local result = v128_t()
+		local target = result.i32
+
+		target[0] = to_signed(data_1)
+		target[1] = to_signed(data_2)
+		target[2] = to_signed(data_3)
+		target[3] = to_signed(data_4)
+
+		return result
+	end
+
+	v128.ZERO = v128_t()
+
+	splat.i8x16 = lanewise_splat(U8)
+	splat.i16x8 = lanewise_splat(U16)
+	splat.i32x4 = lanewise_splat(U32)
+	splat.i64x2 = lanewise_splat(I64)
+	splat.f32x4 = lanewise_splat(F32)
+	splat.f64x2 = lanewise_splat(F64)
+
+	extract_lane.i32_i8x16 = lanewise_extract(I8)
+	extract_lane.i32_u8x16 = lanewise_extract(U8)
+	extract_lane.i32_i16x8 = lanewise_extract(I16)
+	extract_lane.i32_u16x8 = lanewise_extract(U16)
+	extract_lane.i32_i32x4 = lanewise_extract(I32)
+	extract_lane.i64_i64x2 = lanewise_extract(I64)
+	extract_lane.f32_f32x4 = lanewise_extract(F32)
+	extract_lane.f64_f64x2 = lanewise_extract(F64)
+
+	replace_lane.i8x16 = lanewise_replace(U8)
+	replace_lane.i16x8 = lanewise_replace(U16)
+	replace_lane.i32x4 = lanewise_replace(U32)
+	replace_lane.i64x2 = lanewise_replace(I64)
+	replace_lane.f32x4 = lanewise_replace(F32)
+	replace_lane.f64x2 = lanewise_replace(F64)
+
+	function shuffle.i8x16(lhs, rhs, lanes)
+		local result = v128_t()
+		local source_1 = lhs.u8
+		local source_2 = rhs.u8
+		local target = result.u8
+
+		for i = 0, 15 do
+			local index = lanes[i + 1]
+
+			if index < 16 then
+				target[i] = source_1[index]
+			else
+				target[i] = source_2[index - 16]
+			end
+		end
+
+		return result
+	end
+
+	function swizzle.i8x16(lhs, rhs)
+		local result = v128_t()
+		local source = lhs.u8
+		local indices = rhs.u8
+		local target = result.u8
+
+		for i = 0, 15 do
+			local index = indices[i]
+
+			if index < 16 then
+				target[i] = source[index]
+			end
+		end
+
+		return result
+	end
+
+	function bitselect.v128(lhs, rhs, condition)
+		local result = v128_t()
+		local source_1 = lhs.i32
+		local source_2 = rhs.i32
+		local mask = condition.i32
+		local target = result.i32
+
+		for i = 0, 3 do
+			local data_1 = bit_and(source_1[i], mask[i])
+			local data_2 = bit_and(source_2[i], bit_not(mask[i]))
+
+			target[i] = bit_or(data_1, data_2)
+		end
+
+		return result
+	end
+
+	band.v128 = lanewise_binary(I32, bit_and)
+	bor.v128 = lanewise_binary(I32, bit_or)
+	bxor.v128 = lanewise_binary(I32, bit_xor)
+	bnot.v128 = lanewise_unary(I32, bit_not)
+
+	band_not.v128 = lanewise_binary(I32, function(lhs, rhs)
+		return bit_and(lhs, bit_not(rhs))
+	end)
+
+	function any_true.v128(value)
+		local source = value.i32
+
+		for i = 0, 3 do
+			if source[i] ~= 0 then
+				return 1
+			end
+		end
+
+		return 0
+	end
+
+	all_true.i8x16 = lanewise_all_true(U8)
+	all_true.i16x8 = lanewise_all_true(U16)
+	all_true.i32x4 = lanewise_all_true(I32)
+	all_true.i64x2 = lanewise_all_true(I64)
+
+	bitmask.i8x16 = lanewise_bitmask(I8)
+	bitmask.i16x8 = lanewise_bitmask(I16)
+	bitmask.i32x4 = lanewise_bitmask(I32)
+	bitmask.i64x2 = lanewise_bitmask(I64)
+
+	abs.i8x16 = lanewise_unary(I8, math_abs)
+	abs.i16x8 = lanewise_unary(I16, math_abs)
+	abs.i32x4 = lanewise_unary(I32, math_abs)
+	abs.i64x2 = lanewise_unary(I64, abs_num)
+
+	neg.i8x16 = lanewise_unary(I8, neg_num)
+	neg.i16x8 = lanewise_unary(I16, neg_num)
+	neg.i32x4 = lanewise_unary(I32, neg_num)
+	neg.i64x2 = lanewise_unary(I64, neg_num)
+
+	popcnt.i8x16 = lanewise_unary(U8, popcnt.i32)
+
+	add.i8x16 = lanewise_binary(I8, add_num)
+	add.i16x8 = lanewise_binary(I16, add_num)
+	add.i32x4 = lanewise_binary(I32, add_num)
+	add.i64x2 = lanewise_binary(I64, add_num)
+
+	sub.i8x16 = lanewise_binary(I8, sub_num)
+	sub.i16x8 = lanewise_binary(I16, sub_num)
+	sub.i32x4 = lanewise_binary(I32, sub_num)
+	sub.i64x2 = lanewise_binary(I64, sub_num)
+
+	mul.i16x8 = lanewise_binary(I16, mul_num)
+	mul.i32x4 = lanewise_binary(I32, mul.i32)
+	mul.i64x2 = lanewise_binary(I64, mul_num)
+
+	add_sat.i8x16 = lanewise_binary(I8, function(lhs, rhs)
+		return clamp(lhs + rhs, -0x80, 0x7F)
+	end)
+
+	add_sat.u8x16 = lanewise_binary(U8, function(lhs, rhs)
+		return math_min(lhs + rhs, 0xFF)
+	end)
+
+	add_sat.i16x8 = lanewise_binary(I16, function(lhs, rhs)
+		return clamp(lhs + rhs, -0x8000, 0x7FFF)
+	end)
+
+	add_sat.u16x8 = lanewise_binary(U16, function(lhs, rhs)
+		return math_min(lhs + rhs, 0xFFFF)
+	end)
+
+	sub_sat.i8x16 = lanewise_binary(I8, function(lhs, rhs)
+		return clamp(lhs - rhs, -0x80, 0x7F)
+	end)
+
+	sub_sat.u8x16 = lanewise_binary(U8, function(lhs, rhs)
+		return math_max(lhs - rhs, 0)
+	end)
+
+	sub_sat.i16x8 = lanewise_binary(I16, function(lhs, rhs)
+		return clamp(lhs - rhs, -0x8000, 0x7FFF)
+	end)
+
+	sub_sat.u16x8 = lanewise_binary(U16, function(lhs, rhs)
+		return math_max(lhs - rhs, 0)
+	end)
+
+	min.i8x16 = lanewise_binary(I8, math_min)
+	min.u8x16 = lanewise_binary(U8, math_min)
+	min.i16x8 = lanewise_binary(I16, math_min)
+	min.u16x8 = lanewise_binary(U16, math_min)
+	min.i32x4 = lanewise_binary(I32, math_min)
+	min.u32x4 = lanewise_binary(U32, math_min)
+
+	max.i8x16 = lanewise_binary(I8, math_max)
+	max.u8x16 = lanewise_binary(U8, math_max)
+	max.i16x8 = lanewise_binary(I16, math_max)
+	max.u16x8 = lanewise_binary(U16, math_max)
+	max.i32x4 = lanewise_binary(I32, math_max)
+	max.u32x4 = lanewise_binary(U32, math_max)
+
+	avgr.u8x16 = lanewise_binary(U8, avgr_num)
+	avgr.u16x8 = lanewise_binary(U16, avgr_num)
+
+	q15mulr_sat.i16x8 = lanewise_binary(I16, function(lhs, rhs)
+		return clamp(math_floor((lhs * rhs + 0x4000) / 0x8000), -0x8000, 0x7FFF)
+	end)
+
+	shl.i8x16 = lanewise_shift(U8, bit_lshift)
+	shl.i16x8 = lanewise_shift(U16, bit_lshift)
+	shl.i32x4 = lanewise_shift(I32, bit_lshift)
+	shl.i64x2 = lanewise_shift(I64, bit_lshift)
+
+	shr.i8x16 = lanewise_shift(I8, bit_arshift)
+	shr.i16x8 = lanewise_shift(I16, bit_arshift)
+	shr.i32x4 = lanewise_shift(I32, bit_arshift)
+	shr.i64x2 = lanewise_shift(I64, bit_arshift)
+
+	shr.u8x16 = lanewise_shift(U8, bit_rshift)
+	shr.u16x8 = lanewise_shift(U16, bit_rshift)
+	shr.u32x4 = lanewise_shift(I32, bit_rshift)
+	shr.u64x2 = lanewise_shift(I64, bit_rshift)
+
+	eq.i8x16 = lanewise_compare(U8, U8, is_equal)
+	eq.i16x8 = lanewise_compare(U16, U16, is_equal)
+	eq.i32x4 = lanewise_compare(I32, I32, is_equal)
+	eq.i64x2 = lanewise_compare(I64, I64, is_equal)
+	eq.f32x4 = lanewise_compare(F32, I32, is_equal)
+	eq.f64x2 = lanewise_compare(F64, I64, is_equal)
+
+	ne.i8x16 = lanewise_compare(U8, U8, is_not_equal)
+	ne.i16x8 = lanewise_compare(U16, U16, is_not_equal)
+	ne.i32x4 = lanewise_compare(I32, I32, is_not_equal)
+	ne.i64x2 = lanewise_compare(I64, I64, is_not_equal)
+	ne.f32x4 = lanewise_compare(F32, I32, is_not_equal)
+	ne.f64x2 = lanewise_compare(F64, I64, is_not_equal)
+
+	lt.i8x16 = lanewise_compare(I8, U8, is_less)
+	lt.u8x16 = lanewise_compare(U8, U8, is_less)
+	lt.i16x8 = lanewise_compare(I16, U16, is_less)
+	lt.u16x8 = lanewise_compare(U16, U16, is_less)
+	lt.i32x4 = lanewise_compare(I32, I32, is_less)
+	lt.u32x4 = lanewise_compare(U32, I32, is_less)
+	lt.i64x2 = lanewise_compare(I64, I64, is_less)
+	lt.f32x4 = lanewise_compare(F32, I32, is_less)
+	lt.f64x2 = lanewise_compare(F64, I64, is_less)
+
+	le.i8x16 = lanewise_compare(I8, U8, is_less_equal)
+	le.u8x16 = lanewise_compare(U8, U8, is_less_equal)
+	le.i16x8 = lanewise_compare(I16, U16, is_less_equal)
+	le.u16x8 = lanewise_compare(U16, U16, is_less_equal)
+	le.i32x4 = lanewise_compare(I32, I32, is_less_equal)
+	le.u32x4 = lanewise_compare(U32, I32, is_less_equal)
+	le.i64x2 = lanewise_compare(I64, I64, is_less_equal)
+	le.f32x4 = lanewise_compare(F32, I32, is_less_equal)
+	le.f64x2 = lanewise_compare(F64, I64, is_less_equal)
+
+	gt.i8x16 = lanewise_compare(I8, U8, is_greater)
+	gt.u8x16 = lanewise_compare(U8, U8, is_greater)
+	gt.i16x8 = lanewise_compare(I16, U16, is_greater)
+	gt.u16x8 = lanewise_compare(U16, U16, is_greater)
+	gt.i32x4 = lanewise_compare(I32, I32, is_greater)
+	gt.u32x4 = lanewise_compare(U32, I32, is_greater)
+	gt.i64x2 = lanewise_compare(I64, I64, is_greater)
+	gt.f32x4 = lanewise_compare(F32, I32, is_greater)
+	gt.f64x2 = lanewise_compare(F64, I64, is_greater)
+
+	ge.i8x16 = lanewise_compare(I8, U8, is_greater_equal)
+	ge.u8x16 = lanewise_compare(U8, U8, is_greater_equal)
+	ge.i16x8 = lanewise_compare(I16, U16, is_greater_equal)
+	ge.u16x8 = lanewise_compare(U16, U16, is_greater_equal)
+	ge.i32x4 = lanewise_compare(I32, I32, is_greater_equal)
+	ge.u32x4 = lanewise_compare(U32, I32, is_greater_equal)
+	ge.i64x2 = lanewise_compare(I64, I64, is_greater_equal)
+	ge.f32x4 = lanewise_compare(F32, I32, is_greater_equal)
+	ge.f64x2 = lanewise_compare(F64, I64, is_greater_equal)
+
+	narrow.i8x16_i16x8 = lanewise_narrow(I16, U8, -0x80, 0x7F)
+	narrow.u8x16_i16x8 = lanewise_narrow(I16, U8, 0, 0xFF)
+	narrow.i16x8_i32x4 = lanewise_narrow(I32, U16, -0x8000, 0x7FFF)
+	narrow.u16x8_i32x4 = lanewise_narrow(I32, U16, 0, 0xFFFF)
+
+	extend_low.i16x8_i8x16 = lanewise_convert(I8, U16, 8, 0, no_op)
+	extend_low.i16x8_u8x16 = lanewise_convert(U8, U16, 8, 0, no_op)
+	extend_low.i32x4_i16x8 = lanewise_convert(I16, I32, 4, 0, no_op)
+	extend_low.i32x4_u16x8 = lanewise_convert(U16, I32, 4, 0, no_op)
+	extend_low.i64x2_i32x4 = lanewise_convert(I32, I64, 2, 0, to_i64)
+	extend_low.i64x2_u32x4 = lanewise_convert(U32, I64, 2, 0, to_i64)
+
+	extend_high.i16x8_i8x16 = lanewise_convert(I8, U16, 8, 8, no_op)
+	extend_high.i16x8_u8x16 = lanewise_convert(U8, U16, 8, 8, no_op)
+	extend_high.i32x4_i16x8 = lanewise_convert(I16, I32, 4, 4, no_op)
+	extend_high.i32x4_u16x8 = lanewise_convert(U16, I32, 4, 4, no_op)
+	extend_high.i64x2_i32x4 = lanewise_convert(I32, I64, 2, 2, to_i64)
+	extend_high.i64x2_u32x4 = lanewise_convert(U32, I64, 2, 2, to_i64)
+
+	local function pairwise_sum(from, into)
+		local inner = lanewise_pairwise(from, into, no_op)
+
+		return function(value)
+			return inner(value, value)
+		end
+	end
+
+	extadd_pairwise.i16x8_i8x16 = pairwise_sum(I8, U16)
+	extadd_pairwise.i16x8_u8x16 = pairwise_sum(U8, U16)
+	extadd_pairwise.i32x4_i16x8 = pairwise_sum(I16, I32)
+	extadd_pairwise.i32x4_u16x8 = pairwise_sum(U16, I32)
+
+	local function mul_i64_i32(lhs, rhs)
+		return i64(lhs) * rhs
+	end
+
+	local function mul_i64_u32(lhs, rhs)
+		return (i64(u64(lhs) * rhs))
+	end
+
+	extmul_low.i16x8_i8x16 = lanewise_widen(I8, U16, 0, mul_num)
+	extmul_low.i16x8_u8x16 = lanewise_widen(U8, U16, 0, mul_num)
+	extmul_low.i32x4_i16x8 = lanewise_widen(I16, I32, 0, mul_num)
+	extmul_low.i32x4_u16x8 = lanewise_widen(U16, I32, 0, mul_num)
+	extmul_low.i64x2_i32x4 = lanewise_widen(I32, I64, 0, mul_i64_i32)
+	extmul_low.i64x2_u32x4 = lanewise_widen(U32, I64, 0, mul_i64_u32)
+
+	extmul_high.i16x8_i8x16 = lanewise_widen(I8, U16, 8, mul_num)
+	extmul_high.i16x8_u8x16 = lanewise_widen(U8, U16, 8, mul_num)
+	extmul_high.i32x4_i16x8 = lanewise_widen(I16, I32, 4, mul_num)
+	extmul_high.i32x4_u16x8 = lanewise_widen(U16, I32, 4, mul_num)
+	extmul_high.i64x2_i32x4 = lanewise_widen(I32, I64, 2, mul_i64_i32)
+	extmul_high.i64x2_u32x4 = lanewise_widen(U32, I64, 2, mul_i64_u32)
+
+	dot.i32x4_i16x8 = lanewise_pairwise(I16, I32, mul_num)
+
+	abs.f32x4 = lanewise_unary(F32, math_abs)
+	abs.f64x2 = lanewise_unary(F64, math_abs)
+	neg.f32x4 = lanewise_unary(F32, neg_num)
+	neg.f64x2 = lanewise_unary(F64, neg_num)
+	ceil.f32x4 = lanewise_unary(F32, math_ceil)
+	ceil.f64x2 = lanewise_unary(F64, math_ceil)
+	floor.f32x4 = lanewise_unary(F32, math_floor)
+	floor.f64x2 = lanewise_unary(F64, math_floor)
+	truncate.f32x4 = lanewise_unary(F32, truncate.f32)
+	truncate.f64x2 = lanewise_unary(F64, truncate.f64)
+	nearest.f32x4 = lanewise_unary(F32, nearest.f32)
+	nearest.f64x2 = lanewise_unary(F64, nearest.f64)
+	sqrt.f32x4 = lanewise_unary(F32, math_sqrt)
+	sqrt.f64x2 = lanewise_unary(F64, math_sqrt)
+
+	add.f32x4 = lanewise_binary(F32, add_num)
+	add.f64x2 = lanewise_binary(F64, add_num)
+	sub.f32x4 = lanewise_binary(F32, sub_num)
+	sub.f64x2 = lanewise_binary(F64, sub_num)
+	mul.f32x4 = lanewise_binary(F32, mul_num)
+	mul.f64x2 = lanewise_binary(F64, mul_num)
+	div.f32x4 = lanewise_binary(F32, div_num)
+	div.f64x2 = lanewise_binary(F64, div_num)
+	min.f32x4 = lanewise_binary(F32, min.f32)
+	min.f64x2 = lanewise_binary(F64, min.f64)
+	max.f32x4 = lanewise_binary(F32, max.f32)
+	max.f64x2 = lanewise_binary(F64, max.f64)
+	pmin.f32x4 = lanewise_binary(F32, pmin_num)
+	pmin.f64x2 = lanewise_binary(F64, pmin_num)
+	pmax.f32x4 = lanewise_binary(F32, pmax_num)
+	pmax.f64x2 = lanewise_binary(F64, pmax_num)
+
+	saturate.i32x4_f32x4 = lanewise_convert(F32, I32, 4, 0, saturate_between(-0x80000000, 0x7FFFFFFF))
+	saturate.u32x4_f32x4 = lanewise_convert(F32, I32, 4, 0, saturate_between(0, 0xFFFFFFFF))
+	saturate_zero.i32x4_f64x2 = lanewise_convert(F64, I32, 2, 0, saturate_between(-0x80000000, 0x7FFFFFFF))
+	saturate_zero.u32x4_f64x2 = lanewise_convert(F64, I32, 2, 0, saturate_between(0, 0xFFFFFFFF))
+
+	convert.f32x4_i32x4 = lanewise_convert(I32, F32, 4, 0, no_op)
+	convert.f32x4_u32x4 = lanewise_convert(U32, F32, 4, 0, no_op)
+	convert_low.f64x2_i32x4 = lanewise_convert(I32, F64, 2, 0, no_op)
+	convert_low.f64x2_u32x4 = lanewise_convert(U32, F64, 2, 0, no_op)
+	demote_zero.f32x4_f64x2 = lanewise_convert(F64, F32, 2, 0, no_op)
+	promote_low.f64x2_f32x4 = lanewise_convert(F32, F64, 2, 0, no_op)
+
+	local function load_partial(memory, addr, len)
+		local result = v128_t()
+
+		ffi.copy(result, by_offset(memory.data, addr), len)
+
+		return result
+	end
+
+	local function load_splat(size, splat_lane)
+		return function(memory, addr)
+			return splat_lane(load_partial(memory, addr, size))
+		end
+	end
+
+	function load.v128(memory, addr)
+		return load_partial(memory, addr, 16)
+	end
+
+	function load.i16x8_i8x8(memory, addr)
+		return extend_low.i16x8_i8x16(load_partial(memory, addr, 8))
+	end
+
+	function load.i16x8_u8x8(memory, addr)
+		return extend_low.i16x8_u8x16(load_partial(memory, addr, 8))
+	end
+
+	function load.i32x4_i16x4(memory, addr)
+		return extend_low.i32x4_i16x8(load_partial(memory, addr, 8))
+	end
+
+	function load.i32x4_u16x4(memory, addr)
+		return extend_low.i32x4_u16x8(load_partial(memory, addr, 8))
+	end
+
+	function load.i64x2_i32x2(memory, addr)
+		return extend_low.i64x2_i32x4(load_partial(memory, addr, 8))
+	end
+
+	function load.i64x2_u32x2(memory, addr)
+		return extend_low.i64x2_u32x4(load_partial(memory, addr, 8))
+	end
+
+	load.splat_n8 = load_splat(1, function(value)
+		return splat.i8x16(value.u8[0])
+	end)
+
+	load.splat_n16 = load_splat(2, function(value)
+		return splat.i16x8(value.u16[0])
+	end)
+
+	load.splat_n32 = load_splat(4, function(value)
+		return splat.i32x4(value.i32[0])
+	end)
+
+	load.splat_n64 = load_splat(8, function(value)
+		value.i64[1] = value.i64[0]
+
+		return value
+	end)
+
+	function load.zero_n32(memory, addr)
+		return load_partial(memory, addr, 4)
+	end
+
+	function load.zero_n64(memory, addr)
+		return load_partial(memory, addr, 8)
+	end
+
+	function store.v128(memory, addr, value)
+		ffi.copy(by_offset(memory.data, addr), value, 16)
+	end
+
+	local function lane_loader(size)
+		return function(memory, addr, value, index)
+			local result = copy_of(value)
+
+			ffi.copy(cast(alias_t, result) + index * size, by_offset(memory.data, addr), size)
+
+			return result
+		end
+	end
+
+	local function lane_storer(size)
+		return function(memory, addr, value, index)
+			ffi.copy(by_offset(memory.data, addr), cast(alias_t, value) + index * size, size)
+		end
+	end
+
+	load_lane.i8x16 = lane_loader(1)
+	load_lane.i16x8 = lane_loader(2)
+	load_lane.i32x4 = lane_loader(4)
+	load_lane.i64x2 = lane_loader(8)
+
+	store_lane.i8x16 = lane_storer(1)
+	store_lane.i16x8 = lane_storer(2)
+	store_lane.i32x4 = lane_storer(4)
+	store_lane.i64x2 = lane_storer(8)
+
+	module.v128 = v128
+	module.abs = abs
+	module.ceil = ceil
+	module.floor = floor
+	module.sqrt = sqrt
+	module.eq = eq
+	module.ne = ne
+	module.band = band
+	module.bor = bor
+	module.bxor = bxor
+	module.bnot = bnot
+	module.shl = shl
+	module.shr = shr
+	module.splat = splat
+	module.extract_lane = extract_lane
+	module.replace_lane = replace_lane
+	module.shuffle = shuffle
+	module.swizzle = swizzle
+	module.bitselect = bitselect
+	module.band_not = band_not
+	module.any_true = any_true
+	module.all_true = all_true
+	module.bitmask = bitmask
+	module.add_sat = add_sat
+	module.sub_sat = sub_sat
+	module.avgr = avgr
+	module.q15mulr_sat = q15mulr_sat
+	module.pmin = pmin
+	module.pmax = pmax
+	module.narrow = narrow
+	module.extend_low = extend_low
+	module.extend_high = extend_high
+	module.extadd_pairwise = extadd_pairwise
+	module.extmul_low = extmul_low
+	module.extmul_high = extmul_high
+	module.dot = dot
+	module.saturate_zero = saturate_zero
+	module.convert_low = convert_low
+	module.demote_zero = demote_zero
+	module.promote_low = promote_low
+	module.load_lane = load_lane
+	module.store_lane = store_lane
+end
+
 return module
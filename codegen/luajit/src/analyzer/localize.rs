@@ -2,11 +2,13 @@ use std::collections::BTreeSet;
 
 use wasm_ast::{
 	node::{
-		BinOp, CmpOp, FuncData, LoadAt, MemoryCopy, MemoryFill, MemoryGrow, MemorySize, StoreAt,
-		UnOp,
+		AtomicCmpxchg, AtomicNotify, AtomicRmw, AtomicWait, BinOp, BitSelect, CmpOp, Expression,
+		ExtractLane, FuncData, LoadAt, LoadLane, MemoryCopy, MemoryFill, MemoryGrow, MemoryInit,
+		MemorySize, ReplaceLane, Shuffle, StoreAt, StoreLane, UnOp, Value,
 	},
 	visit::{Driver, Visitor},
 };
+use wasmparser::ValType;
 
 use super::into_string::{IntoName, IntoNameTuple, TryIntoSymbol};
 
@@ -20,14 +22,64 @@ impl Visitor for Visit {
 		let name = v.load_type().into_name();
 
 		self.memory_set.insert(v.memory());
-		self.local_set.insert(("load", name));
+
+		if !v.is_atomic() {
+			self.local_set.insert(("load", name));
+		}
 	}
 
 	fn visit_store_at(&mut self, v: &StoreAt) {
 		let name = v.store_type().into_name();
 
 		self.memory_set.insert(v.memory());
-		self.local_set.insert(("store", name));
+
+		if !v.is_atomic() {
+			self.local_set.insert(("store", name));
+		}
+	}
+
+	fn visit_value(&mut self, v: Value) {
+		let name = match v {
+			Value::V128(0) => "ZERO",
+			Value::V128(_) => "from_u32",
+			_ => return,
+		};
+
+		self.local_set.insert(("v128", name));
+	}
+
+	fn visit_extract_lane(&mut self, v: &ExtractLane) {
+		let name = v.op_type().into_name();
+
+		self.local_set.insert(("extract_lane", name));
+	}
+
+	fn visit_replace_lane(&mut self, v: &ReplaceLane) {
+		let name = v.lane_type().into_name();
+
+		self.local_set.insert(("replace_lane", name));
+	}
+
+	fn visit_shuffle(&mut self, _: &Shuffle) {
+		self.local_set.insert(("shuffle", "i8x16"));
+	}
+
+	fn visit_bit_select(&mut self, _: &BitSelect) {
+		self.local_set.insert(("bitselect", "v128"));
+	}
+
+	fn visit_load_lane(&mut self, v: &LoadLane) {
+		let name = v.lane_type().into_name();
+
+		self.memory_set.insert(v.memory());
+		self.local_set.insert(("load_lane", name));
+	}
+
+	fn visit_store_lane(&mut self, v: &StoreLane) {
+		let name = v.lane_type().into_name();
+
+		self.memory_set.insert(v.memory());
+		self.local_set.insert(("store_lane", name));
 	}
 
 	fn visit_un_op(&mut self, v: &UnOp) {
@@ -72,6 +124,26 @@ impl Visitor for Visit {
 	fn visit_memory_fill(&mut self, m: &MemoryFill) {
 		self.memory_set.insert(m.destination().memory());
 	}
+
+	fn visit_memory_init(&mut self, m: &MemoryInit) {
+		self.memory_set.insert(m.destination().memory());
+	}
+
+	fn visit_atomic_rmw(&mut self, m: &AtomicRmw) {
+		self.memory_set.insert(m.memory());
+	}
+
+	fn visit_atomic_cmpxchg(&mut self, m: &AtomicCmpxchg) {
+		self.memory_set.insert(m.memory());
+	}
+
+	fn visit_atomic_wait(&mut self, m: &AtomicWait) {
+		self.memory_set.insert(m.memory());
+	}
+
+	fn visit_atomic_notify(&mut self, m: &AtomicNotify) {
+		self.memory_set.insert(m.memory());
+	}
 }
 
 pub fn visit(ast: &FuncData) -> (BTreeSet<(&'static str, &'static str)>, BTreeSet<usize>) {
@@ -80,7 +152,22 @@ pub fn visit(ast: &FuncData) -> (BTreeSet<(&'static str, &'static str)>, BTreeSe
 		memory_set: BTreeSet::new(),
 	};
 
+	if ast.local_data().contains(&ValType::V128) {
+		visit.local_set.insert(("v128", "ZERO"));
+	}
+
 	ast.accept(&mut visit);
 
 	(visit.local_set, visit.memory_set)
 }
+
+pub fn visit_constant(ast: &Expression) -> BTreeSet<(&'static str, &'static str)> {
+	let mut visit = Visit {
+		local_set: BTreeSet::new(),
+		memory_set: BTreeSet::new(),
+	};
+
+	ast.accept(&mut visit);
+
+	visit.local_set
+}
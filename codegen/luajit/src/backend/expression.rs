@@ -4,7 +4,9 @@ use std::{
 };
 
 use wasm_ast::node::{
-	BinOp, CmpOp, Expression, GetGlobal, LoadAt, Local, MemorySize, Select, Temporary, UnOp, Value,
+	BinOp, BitSelect, CmpOp, Expression, ExtractLane, GetGlobal, LoadAt, LoadLane, Local,
+	MemorySize, RefFunc, RefIsNull, RefNull, ReplaceLane, Select, Shuffle, TableGet, TableSize,
+	Temporary, UnOp, Value,
 };
 
 use crate::analyzer::into_string::{IntoName, IntoNameTuple, TryIntoSymbol};
@@ -39,31 +41,20 @@ impl Driver for Select {
 
 impl Driver for Temporary {
 	fn write(&self, mng: &mut Manager, w: &mut dyn Write) -> Result<()> {
-		let var = self.var();
-
-		if let Some(var) = var.checked_sub(mng.num_temp()) {
-			write!(w, "reg_spill[{}]", var + 1)
-		} else {
-			write!(w, "reg_{var}")
-		}
+		mng.write_temporary(self.var(), w)
 	}
 }
 
 impl Driver for Local {
 	fn write(&self, mng: &mut Manager, w: &mut dyn Write) -> Result<()> {
-		let var = self.var();
-
-		if let Some(var) = var.checked_sub(mng.num_local()) {
-			write!(w, "loc_spill[{}]", var + 1)
-		} else {
-			write!(w, "loc_{var}")
-		}
+		mng.write_local(self.var(), w)
 	}
 }
 
 impl Driver for GetGlobal {
-	fn write(&self, _mng: &mut Manager, w: &mut dyn Write) -> Result<()> {
-		write!(w, "GLOBAL_LIST[{}].value", self.var())
+	fn write(&self, mng: &mut Manager, w: &mut dyn Write) -> Result<()> {
+		mng.module_names().write_global(self.var(), w)?;
+		write!(w, ".value")
 	}
 }
 
@@ -72,7 +63,15 @@ impl Driver for LoadAt {
 		let name = self.load_type().into_name();
 		let memory = self.memory();
 
-		write!(w, "load_{name}(memory_at_{memory}, ")?;
+		// Atomic accesses are checked for alignment by the runtime
+		if self.is_atomic() {
+			write!(w, "rt.load.atomic_{name}(")?;
+		} else {
+			write!(w, "load_{name}(")?;
+		}
+
+		mng.module_names().write_memory(memory, w)?;
+		write!(w, ", ")?;
 		self.pointer().write(mng, w)?;
 
 		if self.offset() != 0 {
@@ -84,14 +83,132 @@ impl Driver for LoadAt {
 }
 
 impl Driver for MemorySize {
+	fn write(&self, mng: &mut Manager, w: &mut dyn Write) -> Result<()> {
+		mng.module_names().write_memory(self.memory(), w)?;
+		write!(w, ".min")
+	}
+}
+
+impl Driver for RefNull {
+	fn write(&self, _mng: &mut Manager, w: &mut dyn Write) -> Result<()> {
+		write!(w, "nil")
+	}
+}
+
+impl Driver for RefIsNull {
+	fn write(&self, mng: &mut Manager, w: &mut dyn Write) -> Result<()> {
+		write!(w, "(")?;
+		self.value().write(mng, w)?;
+		write!(w, " == nil and 1 or 0)")
+	}
+}
+
+impl Driver for RefFunc {
 	fn write(&self, _mng: &mut Manager, w: &mut dyn Write) -> Result<()> {
-		write!(w, "memory_at_{}.min", self.memory())
+		write!(w, "FUNC_LIST[{}]", self.function())
+	}
+}
+
+impl Driver for TableGet {
+	fn write(&self, mng: &mut Manager, w: &mut dyn Write) -> Result<()> {
+		write!(w, "rt.table.get(")?;
+		mng.module_names().write_table(self.table(), w)?;
+		write!(w, ", ")?;
+		self.index().write(mng, w)?;
+		write!(w, ")")
+	}
+}
+
+impl Driver for TableSize {
+	fn write(&self, mng: &mut Manager, w: &mut dyn Write) -> Result<()> {
+		mng.module_names().write_table(self.table(), w)?;
+		write!(w, ".min")
+	}
+}
+
+impl Driver for ExtractLane {
+	fn write(&self, mng: &mut Manager, w: &mut dyn Write) -> Result<()> {
+		let name = self.op_type().into_name();
+
+		write!(w, "extract_lane_{name}(")?;
+		self.vector().write(mng, w)?;
+		write!(w, ", {})", self.lane())
+	}
+}
+
+impl Driver for ReplaceLane {
+	fn write(&self, mng: &mut Manager, w: &mut dyn Write) -> Result<()> {
+		let name = self.lane_type().into_name();
+
+		write!(w, "replace_lane_{name}(")?;
+		self.vector().write(mng, w)?;
+		write!(w, ", {}, ", self.lane())?;
+		self.value().write(mng, w)?;
+		write!(w, ")")
+	}
+}
+
+impl Driver for Shuffle {
+	fn write(&self, mng: &mut Manager, w: &mut dyn Write) -> Result<()> {
+		write!(w, "shuffle_i8x16(")?;
+		self.lhs().write(mng, w)?;
+		write!(w, ", ")?;
+		self.rhs().write(mng, w)?;
+		write!(w, ", {{ ")?;
+		write_separated(self.lanes().iter(), |l, w| write!(w, "{l}"), w)?;
+		write!(w, " }})")
+	}
+}
+
+impl Driver for BitSelect {
+	fn write(&self, mng: &mut Manager, w: &mut dyn Write) -> Result<()> {
+		write!(w, "bitselect_v128(")?;
+		self.on_true().write(mng, w)?;
+		write!(w, ", ")?;
+		self.on_false().write(mng, w)?;
+		write!(w, ", ")?;
+		self.condition().write(mng, w)?;
+		write!(w, ")")
+	}
+}
+
+impl Driver for LoadLane {
+	fn write(&self, mng: &mut Manager, w: &mut dyn Write) -> Result<()> {
+		let name = self.lane_type().into_name();
+		let memory = self.memory();
+
+		write!(w, "load_lane_{name}(")?;
+		mng.module_names().write_memory(memory, w)?;
+		write!(w, ", ")?;
+		self.pointer().write(mng, w)?;
+
+		if self.offset() != 0 {
+			write!(w, " + {}", self.offset())?;
+		}
+
+		write!(w, ", ")?;
+		self.vector().write(mng, w)?;
+		write!(w, ", {})", self.lane())
 	}
 }
 
 impl_write_number!(write_f32, f32);
 impl_write_number!(write_f64, f64);
 
+fn write_v128(number: u128, w: &mut dyn Write) -> Result<()> {
+	if number == 0 {
+		return write!(w, "v128_ZERO");
+	}
+
+	let list = number.to_le_bytes();
+	let a = u32::from_le_bytes(list[0..4].try_into().unwrap());
+	let b = u32::from_le_bytes(list[4..8].try_into().unwrap());
+	let c = u32::from_le_bytes(list[8..12].try_into().unwrap());
+	let d = u32::from_le_bytes(list[12..16].try_into().unwrap());
+
+	write!(w, "v128_from_u32({a}, {b}, {c}, {d})")
+}
+
 impl Driver for Value {
 	fn write(&self, _mng: &mut Manager, w: &mut dyn Write) -> Result<()> {
 		match self {
@@ -99,6 +216,7 @@ impl Driver for Value {
 			Self::I64(i) => write!(w, "{i}LL"),
 			Self::F32(f) => write_f32(*f, w),
 			Self::F64(f) => write_f64(*f, w),
+			Self::V128(v) => write_v128(*v, w),
 		}
 	}
 }
@@ -175,6 +293,23 @@ impl Driver for Condition<'_> {
 	}
 }
 
+// Written as the opposite of `Condition`, for loops that run
+// until the condition holds
+pub struct InverseCondition<'a>(pub &'a Expression);
+
+impl Driver for InverseCondition<'_> {
+	fn write(&self, mng: &mut Manager, w: &mut dyn Write) -> Result<()> {
+		if let Expression::CmpOp(node) = self.0 {
+			write!(w, "not (")?;
+			CmpOpBoolean(node).write(mng, w)?;
+			write!(w, ")")
+		} else {
+			self.0.write(mng, w)?;
+			write!(w, " == 0")
+		}
+	}
+}
+
 impl Driver for Expression {
 	fn write(&self, mng: &mut Manager, w: &mut dyn Write) -> Result<()> {
 		match self {
@@ -184,6 +319,16 @@ impl Driver for Expression {
 			Self::GetGlobal(e) => e.write(mng, w),
 			Self::LoadAt(e) => e.write(mng, w),
 			Self::MemorySize(e) => e.write(mng, w),
+			Self::RefNull(e) => e.write(mng, w),
+			Self::RefIsNull(e) => e.write(mng, w),
+			Self::RefFunc(e) => e.write(mng, w),
+			Self::TableGet(e) => e.write(mng, w),
+			Self::TableSize(e) => e.write(mng, w),
+			Self::ExtractLane(e) => e.write(mng, w),
+			Self::ReplaceLane(e) => e.write(mng, w),
+			Self::Shuffle(e) => e.write(mng, w),
+			Self::BitSelect(e) => e.write(mng, w),
+			Self::LoadLane(e) => e.write(mng, w),
 			Self::Value(e) => e.write(mng, w),
 			Self::UnOp(e) => e.write(mng, w),
 			Self::BinOp(e) => e.write(mng, w),
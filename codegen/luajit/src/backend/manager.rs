@@ -1,9 +1,14 @@
 use std::{
-	collections::HashMap,
+	collections::{BTreeSet, HashMap},
 	io::{Result, Write},
+	rc::Rc,
 };
 
-use wasm_ast::node::{BrTable, FuncData};
+use wasm_ast::{
+	node::{Block, BrTable, FuncData},
+	optimize::register::{Allocation, Location},
+	outline::Outline,
+};
 
 use crate::analyzer::{br_table, localize};
 
@@ -32,61 +37,174 @@ macro_rules! line {
 	}};
 }
 
-fn get_pinned_registers(
-	upvalues: usize,
-	params: usize,
-	locals: usize,
-	temporaries: usize,
-) -> (usize, usize) {
-	const MAX_LOCAL_COUNT: usize = 180;
+// Lua refuses to compile functions with more than 200 locals in scope
+const MAX_LOCAL_COUNT: usize = 200;
+
+// Locals declared for control flow, exceptions and spilling are not
+// registers, so some room is left for them
+const RESERVED_LOCAL_COUNT: usize = 20;
+
+// LuaJIT refuses to compile functions with more than 60 upvalues
+const MAX_UPVALUE_COUNT: usize = 60;
+
+// Outlined blocks also capture the locals declared for control flow
+// and the other outlined blocks they call
+const RESERVED_UPVALUE_COUNT: usize = 8;
+
+// Functions past this many nodes are split up, which keeps them well
+// under the limits on constants and jump distances that otherwise fail
+// with "function or expression too complex" when loaded
+const MAX_FUNCTION_SIZE: usize = 8000;
 
-	let available = MAX_LOCAL_COUNT
+// Leaves room for the upvalues, the outlined blocks defined at the start
+// of the function, and the few locals every function may declare
+fn get_available_registers(upvalues: usize, outlined: usize, params: usize) -> usize {
+	(MAX_LOCAL_COUNT - RESERVED_LOCAL_COUNT)
 		.saturating_sub(upvalues)
-		.saturating_sub(params);
+		.saturating_sub(outlined)
+		.saturating_sub(params)
+}
+
+// Outlined blocks and `try` bodies capture what they use from the function
+// as upvalues, next to the runtime names and the few locals declared for
+// control flow
+const fn get_variable_limit(upvalues: usize) -> usize {
+	MAX_UPVALUE_COUNT.saturating_sub(upvalues + RESERVED_UPVALUE_COUNT)
+}
+
+fn get_outline(ast: &FuncData, upvalues: usize) -> Outline {
+	Outline::new(ast, MAX_FUNCTION_SIZE, get_variable_limit(upvalues))
+}
+
+// Names are sanitized into identifiers and suffixed with their index,
+// so they can never collide with each other or with generated names
+pub fn to_identifier(prefix: &str, name: &str, index: usize) -> String {
+	let name: String = name
+		.chars()
+		.map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+		.collect();
+
+	format!("{prefix}_{name}_{index}")
+}
+
+/// Identifiers given to the tables, memories, globals, elements and data
+/// segments that the name section names, which are otherwise referred to
+/// by index.
+/// Memories are held in locals of their own while the rest are kept in
+/// their lists, under their name instead of their index.
+#[derive(Default)]
+pub struct ModuleNames {
+	pub table: HashMap<usize, String>,
+	pub memory: HashMap<usize, String>,
+	pub global: HashMap<usize, String>,
+	pub element: HashMap<usize, String>,
+	pub data: HashMap<usize, String>,
+}
+
+impl ModuleNames {
+	pub fn write_table(&self, index: usize, w: &mut dyn Write) -> Result<()> {
+		match self.table.get(&index) {
+			Some(name) => write!(w, "TABLE_LIST.{name}"),
+			None => write!(w, "TABLE_LIST[{index}]"),
+		}
+	}
 
-	let temporaries = available.min(temporaries);
-	let locals = available.saturating_sub(temporaries).min(locals);
+	pub fn write_memory(&self, index: usize, w: &mut dyn Write) -> Result<()> {
+		match self.memory.get(&index) {
+			Some(name) => write!(w, "{name}"),
+			None => write!(w, "memory_at_{index}"),
+		}
+	}
+
+	pub fn write_global(&self, index: usize, w: &mut dyn Write) -> Result<()> {
+		match self.global.get(&index) {
+			Some(name) => write!(w, "GLOBAL_LIST.{name}"),
+			None => write!(w, "GLOBAL_LIST[{index}]"),
+		}
+	}
+
+	pub fn write_element(&self, index: usize, w: &mut dyn Write) -> Result<()> {
+		match self.element.get(&index) {
+			Some(name) => write!(w, "ELEM_LIST.{name}"),
+			None => write!(w, "ELEM_LIST[{index}]"),
+		}
+	}
 
-	(params + locals, temporaries)
+	pub fn write_data(&self, index: usize, w: &mut dyn Write) -> Result<()> {
+		match self.data.get(&index) {
+			Some(name) => write!(w, "DATA_LIST.{name}"),
+			None => write!(w, "DATA_LIST[{index}]"),
+		}
+	}
+}
+
+/// Identifiers given to the locals and labels of a function, keyed by
+/// their index in the name section, along with those of its module.
+#[derive(Default)]
+pub struct Names {
+	pub local: HashMap<usize, String>,
+	pub label: HashMap<usize, String>,
+	pub module: Rc<ModuleNames>,
 }
 
 pub struct Manager {
 	table_map: HashMap<usize, usize>,
-	num_local: usize,
-	num_temp: usize,
+	allocation: Allocation,
+	outline: Outline,
+	hoisted_list: Vec<Vec<u8>>,
+	names: Names,
 	num_label: usize,
 	label_list: Vec<usize>,
+	label_name: HashMap<usize, String>,
+	closure_list: Vec<(usize, BTreeSet<usize>)>,
 	indentation: usize,
 }
 
 impl Manager {
-	pub fn empty() -> Self {
+	// Constant expressions only ever refer to names of the module
+	pub fn constant(module: &Rc<ModuleNames>) -> Self {
 		Self {
 			table_map: HashMap::new(),
-			num_local: 0,
-			num_temp: usize::MAX,
+			allocation: Allocation::default(),
+			outline: Outline::default(),
+			hoisted_list: Vec::new(),
+			names: Names {
+				module: Rc::clone(module),
+				..Names::default()
+			},
 			num_label: 0,
 			label_list: Vec::new(),
+			label_name: HashMap::new(),
+			closure_list: Vec::new(),
 			indentation: 0,
 		}
 	}
 
 	pub fn function(ast: &FuncData) -> Self {
+		Self::with_names(ast, Names::default())
+	}
+
+	// Named locals keep a register of their own so the name stays true
+	pub fn with_names(ast: &FuncData, names: Names) -> Self {
 		let (upvalues, memories) = localize::visit(ast);
 		let table_map = br_table::visit(ast);
-		let (num_local, num_temp) = get_pinned_registers(
-			upvalues.len() + memories.len(),
-			ast.num_param(),
-			ast.local_data().len(),
-			ast.num_stack(),
-		);
+		let upvalues = upvalues.len() + memories.len();
+		let outline = get_outline(ast, upvalues);
+		let available = get_available_registers(upvalues, outline.len(), ast.num_param());
+		let mut allocation = Allocation::new(ast, available, |var| names.local.contains_key(&var));
+
+		allocation.limit_capture(ast, get_variable_limit(upvalues));
 
 		Self {
 			table_map,
-			num_local,
-			num_temp,
+			allocation,
+			outline,
+			hoisted_list: Vec::new(),
+			names,
 			num_label: 0,
 			label_list: Vec::new(),
+			label_name: HashMap::new(),
+			closure_list: Vec::new(),
 			indentation: 0,
 		}
 	}
@@ -101,12 +219,63 @@ impl Manager {
 		!self.table_map.is_empty()
 	}
 
-	pub const fn num_local(&self) -> usize {
-		self.num_local
+	pub const fn allocation(&self) -> &Allocation {
+		&self.allocation
+	}
+
+	pub fn is_outlined(&self, block: &Block) -> bool {
+		self.outline.contains(block)
+	}
+
+	// Definitions are written apart and placed at the start of the function,
+	// outside of any loop, so they are only created once per call. Those
+	// finished first come first, so callers always see their callees.
+	pub fn hoist<T, F>(&mut self, func: F) -> Result<T>
+	where
+		F: FnOnce(&mut Self, &mut dyn Write) -> Result<T>,
+	{
+		let mut definition = Vec::new();
+		let indentation = std::mem::replace(&mut self.indentation, 1);
+		let result = func(self, &mut definition)?;
+
+		self.indentation = indentation;
+		self.hoisted_list.push(definition);
+
+		Ok(result)
+	}
+
+	pub fn take_hoisted(&mut self) -> Vec<Vec<u8>> {
+		std::mem::take(&mut self.hoisted_list)
+	}
+
+	pub fn write_register(&self, index: usize, w: &mut dyn Write) -> Result<()> {
+		let Some(var) = self.allocation.owner(index) else {
+			return write!(w, "reg_{index}");
+		};
+
+		match self.names.local.get(&var) {
+			Some(name) => write!(w, "{name}"),
+			None => write!(w, "loc_{var}"),
+		}
+	}
+
+	fn write_location(&self, location: Location, w: &mut dyn Write) -> Result<()> {
+		match location {
+			Location::Register(index) => self.write_register(index, w),
+			Location::Spill(index) => write!(w, "reg_spill[{}]", index + 1),
+		}
+	}
+
+	pub fn write_temporary(&self, var: usize, w: &mut dyn Write) -> Result<()> {
+		self.write_location(self.allocation.temporary(var), w)
+	}
+
+	pub fn write_local(&self, var: usize, w: &mut dyn Write) -> Result<()> {
+		self.write_location(self.allocation.local(var), w)
 	}
 
-	pub const fn num_temp(&self) -> usize {
-		self.num_temp
+	pub fn module_names(&self) -> &ModuleNames {
+		&self.names.module
 	}
 
 	pub fn label_list(&self) -> &[usize] {
@@ -120,10 +289,55 @@ impl Manager {
 		self.num_label - 1
 	}
 
+	// Labels of named blocks are written under their name, which stays
+	// unique as it is suffixed with the index of the label
+	pub fn push_block_label(&mut self, block: Option<&Block>) -> usize {
+		let label = self.push_label();
+		let name = block
+			.and_then(Block::label)
+			.and_then(|v| self.names.label.get(&v));
+
+		if let Some(name) = name {
+			self.label_name.insert(label, name.clone());
+		}
+
+		label
+	}
+
+	pub fn label_name(&self, label: usize) -> String {
+		self.label_name
+			.get(&label)
+			.cloned()
+			.unwrap_or_else(|| format!("continue_at_{label}"))
+	}
+
 	pub fn pop_label(&mut self) {
 		self.label_list.pop().unwrap();
 	}
 
+	// Closures start at the last pushed label, anything below
+	// it cannot be reached with a `goto` from inside
+	pub fn push_closure(&mut self) {
+		let start = self.label_list.len() - 1;
+
+		self.closure_list.push((start, BTreeSet::new()));
+	}
+
+	pub fn pop_closure(&mut self) -> BTreeSet<usize> {
+		self.closure_list.pop().unwrap().1
+	}
+
+	pub fn try_escape(&mut self, index: usize) -> bool {
+		match self.closure_list.last_mut() {
+			Some((start, list)) if index < *start => {
+				list.insert(index);
+
+				true
+			}
+			_ => false,
+		}
+	}
+
 	pub const fn indentation(&self) -> usize {
 		self.indentation
 	}
@@ -1,11 +1,19 @@
 use std::{
+	collections::HashMap,
 	io::{Result, Write},
 	ops::Range,
 };
 
-use wasm_ast::node::{
-	Block, Br, BrIf, BrTable, Call, CallIndirect, FuncData, If, LabelType, MemoryCopy, MemoryFill,
-	MemoryGrow, ResultList, SetGlobal, SetLocal, SetTemporary, Statement, StoreAt, Terminator,
+use wasm_ast::{
+	node::{
+		AtomicCmpxchg, AtomicNotify, AtomicRmw, AtomicWait, Block, Br, BrIf, BrTable, Call,
+		CallIndirect, DataDrop, ElemDrop, Expression, FuncData, If, LabelType, MemoryCopy,
+		MemoryFill, MemoryGrow, MemoryInit, ResultList, Rethrow, ReturnCall, ReturnCallIndirect,
+		SetGlobal, SetLocal, SetTemporary, Statement, StoreAt, StoreLane, TableCopy, TableFill,
+		TableGrow, TableInit, TableSet, Terminator, Throw, Try,
+	},
+	optimize::register::Location,
+	shape::LoopShape,
 };
 use wasmparser::ValType;
 
@@ -14,19 +22,33 @@ use crate::{
 };
 
 use super::{
-	expression::Condition,
+	expression::{Condition, InverseCondition},
 	manager::{Driver, Manager},
 };
 
 impl Driver for ResultList {
 	fn write(&self, mng: &mut Manager, w: &mut dyn Write) -> Result<()> {
-		write_separated(self.iter(), |t, w| t.write(mng, w), w)
+		write_separated(self.iter(), |var, w| mng.write_temporary(var, w), w)
+	}
+}
+
+// Labels outside of a protected closure are reached by returning
+// their id, which is then dispatched on after the call
+fn write_goto(index: usize, mng: &mut Manager, w: &mut dyn Write) -> Result<()> {
+	let level = mng.label_list()[index];
+
+	if mng.try_escape(index) {
+		line!(mng, w, "do return {level} end")
+	} else {
+		let name = mng.label_name(level);
+
+		line!(mng, w, "goto {name}")
 	}
 }
 
 impl Driver for Br {
 	fn write(&self, mng: &mut Manager, w: &mut dyn Write) -> Result<()> {
-		let level = *mng.label_list().iter().nth_back(self.target()).unwrap();
+		let index = mng.label_list().len() - 1 - self.target();
 
 		if !self.align().is_aligned() {
 			indentation!(mng, w)?;
@@ -36,7 +58,7 @@ impl Driver for Br {
 			writeln!(w)?;
 		}
 
-		line!(mng, w, "goto continue_at_{level}")
+		write_goto(index, mng, w)
 	}
 }
 
@@ -132,12 +154,57 @@ impl Driver for BrTable {
 	}
 }
 
+impl Driver for Throw {
+	fn write(&self, mng: &mut Manager, w: &mut dyn Write) -> Result<()> {
+		indented!(mng, w, "error(rt.exception.new(TAG_LIST[{}]", self.tag())?;
+
+		if !self.param_list().is_empty() {
+			write!(w, ", ")?;
+			self.param_list().write(mng, w)?;
+		}
+
+		writeln!(w, "), 0)")
+	}
+}
+
+impl Driver for Rethrow {
+	fn write(&self, mng: &mut Manager, w: &mut dyn Write) -> Result<()> {
+		let level = *mng.label_list().iter().nth_back(self.target()).unwrap();
+
+		line!(mng, w, "error(exception_{level}, 0)")
+	}
+}
+
+impl Driver for ReturnCall {
+	fn write(&self, mng: &mut Manager, w: &mut dyn Write) -> Result<()> {
+		indented!(mng, w, "do return FUNC_LIST[{}](", self.function())?;
+		self.param_list().write(mng, w)?;
+		writeln!(w, ") end")
+	}
+}
+
+impl Driver for ReturnCallIndirect {
+	fn write(&self, mng: &mut Manager, w: &mut dyn Write) -> Result<()> {
+		indented!(mng, w, "do return ")?;
+		mng.module_names().write_table(self.table(), w)?;
+		write!(w, ".data[")?;
+		self.index().write(mng, w)?;
+		write!(w, "](")?;
+		self.param_list().write(mng, w)?;
+		writeln!(w, ") end")
+	}
+}
+
 impl Driver for Terminator {
 	fn write(&self, mng: &mut Manager, w: &mut dyn Write) -> Result<()> {
 		match self {
 			Self::Unreachable => line!(mng, w, r#"error("out of code bounds")"#),
 			Self::Br(s) => s.write(mng, w),
 			Self::BrTable(s) => s.write(mng, w),
+			Self::Throw(s) => s.write(mng, w),
+			Self::Rethrow(s) => s.write(mng, w),
+			Self::ReturnCall(s) => s.write(mng, w),
+			Self::ReturnCallIndirect(s) => s.write(mng, w),
 		}
 	}
 }
@@ -152,31 +219,156 @@ fn write_inner_block(block: &Block, mng: &mut Manager, w: &mut dyn Write) -> Res
 	Ok(())
 }
 
+// The loop itself stands in for the branch back to its start, and
+// leaving it reaches the end of the block around it
+fn write_while(
+	block: &Block,
+	condition: &Expression,
+	code: &[Statement],
+	mng: &mut Manager,
+	w: &mut dyn Write,
+) -> Result<()> {
+	let inner_block = match block.code() {
+		[Statement::Block(v)] => Some(v),
+		_ => None,
+	};
+
+	let outer = mng.push_block_label(Some(block));
+	let inner = mng.push_block_label(inner_block);
+	let name = mng.label_name(inner);
+
+	line!(mng, w, "::{name}::")?;
+	indented!(mng, w, "while ")?;
+	InverseCondition(condition).write(mng, w)?;
+	writeln!(w, " do")?;
+	mng.indent();
+	code.iter().try_for_each(|s| s.write(mng, w))?;
+	mng.dedent();
+	line!(mng, w, "end")?;
+	mng.pop_label();
+
+	let name = mng.label_name(outer);
+
+	line!(mng, w, "::{name}::")?;
+	mng.pop_label();
+
+	Ok(())
+}
+
+fn write_repeat(
+	code: &[Statement],
+	condition: &Expression,
+	mng: &mut Manager,
+	w: &mut dyn Write,
+) -> Result<()> {
+	mng.push_label();
+
+	line!(mng, w, "repeat")?;
+	mng.indent();
+	code.iter().try_for_each(|s| s.write(mng, w))?;
+	mng.dedent();
+	indented!(mng, w, "until ")?;
+	InverseCondition(condition).write(mng, w)?;
+	writeln!(w)?;
+
+	mng.pop_label();
+
+	Ok(())
+}
+
+fn write_labeled_block(
+	block: &Block,
+	label: usize,
+	mng: &mut Manager,
+	w: &mut dyn Write,
+) -> Result<()> {
+	let name = mng.label_name(label);
+
+	match block.label_type() {
+		Some(LabelType::Forward) => {
+			write_inner_block(block, mng, w)?;
+			line!(mng, w, "::{name}::")
+		}
+		Some(LabelType::Backward) => {
+			line!(mng, w, "::{name}::")?;
+			line!(mng, w, "while true do")?;
+			mng.indent();
+			write_inner_block(block, mng, w)?;
+
+			if block.last().is_none() {
+				line!(mng, w, "break")?;
+			}
+
+			mng.dedent();
+			line!(mng, w, "end")
+		}
+		None => write_inner_block(block, mng, w),
+	}
+}
+
+// Outlined blocks become local functions defined at the start of the
+// function, where labels outside of them are reached like they are from
+// protected closures
+fn write_outlined(block: &Block, mng: &mut Manager, w: &mut dyn Write) -> Result<()> {
+	let label = mng.push_block_label(Some(block));
+	let escape_list = mng.hoist(|mng, w| {
+		line!(mng, w, "local function outlined_{label}()")?;
+		mng.indent();
+		mng.push_closure();
+		write_labeled_block(block, label, mng, w)?;
+		let escape_list = mng.pop_closure();
+		mng.dedent();
+		line!(mng, w, "end")?;
+
+		Ok(escape_list)
+	})?;
+
+	if escape_list.is_empty() {
+		line!(mng, w, "outlined_{label}()")?;
+	} else {
+		line!(mng, w, "do")?;
+		mng.indent();
+		line!(mng, w, "local escape = outlined_{label}()")?;
+
+		for (i, index) in escape_list.into_iter().enumerate() {
+			let head = if i == 0 { "if" } else { "elseif" };
+			let level = mng.label_list()[index];
+
+			line!(mng, w, "{head} escape == {level} then")?;
+			mng.indent();
+			write_goto(index, mng, w)?;
+			mng.dedent();
+		}
+
+		line!(mng, w, "end")?;
+		mng.dedent();
+		line!(mng, w, "end")?;
+	}
+
+	mng.pop_label();
+
+	Ok(())
+}
+
 impl Driver for Block {
 	fn write(&self, mng: &mut Manager, w: &mut dyn Write) -> Result<()> {
-		let label = mng.push_label();
+		if mng.is_outlined(self) {
+			return write_outlined(self, mng, w);
+		}
 
-		match self.label_type() {
-			Some(LabelType::Forward) => {
-				write_inner_block(self, mng, w)?;
-				line!(mng, w, "::continue_at_{label}::")?;
+		match LoopShape::of(self) {
+			Some(LoopShape::While { condition, code }) => {
+				return write_while(self, condition, code, mng, w);
 			}
-			Some(LabelType::Backward) => {
-				line!(mng, w, "::continue_at_{label}::")?;
-				line!(mng, w, "while true do")?;
-				mng.indent();
-				write_inner_block(self, mng, w)?;
-
-				if self.last().is_none() {
-					line!(mng, w, "break")?;
-				}
-
-				mng.dedent();
-				line!(mng, w, "end")?;
+			Some(LoopShape::Repeat { code, condition }) => {
+				return write_repeat(code, condition, mng, w);
 			}
-			None => write_inner_block(self, mng, w)?,
+			None => {}
 		}
 
+		let label = mng.push_block_label(Some(self));
+
+		write_labeled_block(self, label, mng, w)?;
 		mng.pop_label();
 
 		Ok(())
@@ -216,6 +408,114 @@ impl Driver for If {
 	}
 }
 
+fn write_handler_list(data: &Try, mng: &mut Manager, w: &mut dyn Write) -> Result<()> {
+	let level = *mng.label_list().last().unwrap();
+
+	if let Some(target) = data.delegate() {
+		// The target is relative to the parent of this `try`
+		let index = mng.label_list().len() - 2 - target.target();
+		let outer = mng.label_list()[index];
+
+		line!(mng, w, "exception_{outer} = exception_{level}")?;
+
+		return write_goto(index, mng, w);
+	}
+
+	if data.catch_list().is_empty() && data.catch_all().is_none() {
+		return line!(mng, w, "error(exception_{level}, 0)");
+	}
+
+	line!(mng, w, "local tag = rt.exception.tag_of(exception_{level})")?;
+
+	for (i, catch) in data.catch_list().iter().enumerate() {
+		let head = if i == 0 { "if" } else { "elseif" };
+
+		line!(mng, w, "{head} tag == TAG_LIST[{}] then", catch.tag())?;
+		mng.indent();
+
+		if !catch.result_list().is_empty() {
+			indentation!(mng, w)?;
+			catch.result_list().write(mng, w)?;
+			writeln!(w, " = rt.exception.unpack(exception_{level})")?;
+		}
+
+		write_inner_block(catch.code(), mng, w)?;
+		mng.dedent();
+	}
+
+	if let Some(v) = data.catch_all() {
+		let head = if data.catch_list().is_empty() {
+			"if"
+		} else {
+			"elseif"
+		};
+
+		// Traps are plain Lua errors and have no tag, so they are never caught
+		line!(mng, w, "{head} tag ~= nil then")?;
+		mng.indent();
+		write_inner_block(v, mng, w)?;
+		mng.dedent();
+	}
+
+	line!(mng, w, "else")?;
+	mng.indent();
+	line!(mng, w, "error(exception_{level}, 0)")?;
+	mng.dedent();
+	line!(mng, w, "end")
+}
+
+impl Driver for Try {
+	fn write(&self, mng: &mut Manager, w: &mut dyn Write) -> Result<()> {
+		let label = mng.push_block_label(Some(self.code()));
+		let name = mng.label_name(label);
+
+		// The body runs in a protected closure with its own copy of the label,
+		// while handlers share the one placed after them. Delegated exceptions
+		// are stored in `exception_{label}` directly.
+		line!(mng, w, "do")?;
+		mng.indent();
+		line!(mng, w, "local exception_{label}")?;
+		line!(mng, w, "local success, result = pcall(function()")?;
+		mng.indent();
+		mng.push_closure();
+		write_inner_block(self.code(), mng, w)?;
+		line!(mng, w, "::{name}::")?;
+		let escape_list = mng.pop_closure();
+		mng.dedent();
+		line!(mng, w, "end)")?;
+
+		line!(mng, w, "if not success then")?;
+		mng.indent();
+		line!(mng, w, "exception_{label} = result")?;
+		mng.dedent();
+
+		for index in escape_list {
+			let level = mng.label_list()[index];
+
+			line!(mng, w, "elseif result == {level} then")?;
+			mng.indent();
+			write_goto(index, mng, w)?;
+			mng.dedent();
+		}
+
+		line!(mng, w, "end")?;
+
+		line!(mng, w, "if exception_{label} ~= nil then")?;
+		mng.indent();
+		write_handler_list(self, mng, w)?;
+		mng.dedent();
+		line!(mng, w, "end")?;
+		line!(mng, w, "::{name}::")?;
+
+		mng.dedent();
+		line!(mng, w, "end")?;
+
+		mng.pop_label();
+
+		Ok(())
+	}
+}
+
 impl Driver for Call {
 	fn write(&self, mng: &mut Manager, w: &mut dyn Write) -> Result<()> {
 		if !self.result_list().is_empty() {
@@ -236,7 +536,8 @@ impl Driver for CallIndirect {
 			write!(w, " = ")?;
 		}
 
-		write!(w, "TABLE_LIST[{}].data[", self.table())?;
+		mng.module_names().write_table(self.table(), w)?;
+		write!(w, ".data[")?;
 		self.index().write(mng, w)?;
 		write!(w, "](")?;
 		self.param_list().write(mng, w)?;
@@ -262,7 +563,8 @@ impl Driver for SetLocal {
 
 impl Driver for SetGlobal {
 	fn write(&self, mng: &mut Manager, w: &mut dyn Write) -> Result<()> {
-		write!(w, "GLOBAL_LIST[{}].value = ", self.var())?;
+		mng.module_names().write_global(self.var(), w)?;
+		write!(w, ".value = ")?;
 		self.value().write(mng, w)
 	}
 }
@@ -272,7 +574,15 @@ impl Driver for StoreAt {
 		let name = self.store_type().into_name();
 		let memory = self.memory();
 
-		write!(w, "store_{name}(memory_at_{memory}, ")?;
+		// Atomic accesses are checked for alignment by the runtime
+		if self.is_atomic() {
+			write!(w, "rt.store.atomic_{name}(")?;
+		} else {
+			write!(w, "store_{name}(")?;
+		}
+
+		mng.module_names().write_memory(memory, w)?;
+		write!(w, ", ")?;
 
 		self.pointer().write(mng, w)?;
 
@@ -286,12 +596,108 @@ impl Driver for StoreAt {
 	}
 }
 
+impl Driver for StoreLane {
+	fn write(&self, mng: &mut Manager, w: &mut dyn Write) -> Result<()> {
+		let name = self.lane_type().into_name();
+		let memory = self.memory();
+
+		write!(w, "store_lane_{name}(")?;
+		mng.module_names().write_memory(memory, w)?;
+		write!(w, ", ")?;
+
+		self.pointer().write(mng, w)?;
+
+		if self.offset() != 0 {
+			write!(w, " + {}", self.offset())?;
+		}
+
+		write!(w, ", ")?;
+		self.vector().write(mng, w)?;
+		write!(w, ", {})", self.lane())
+	}
+}
+
+fn write_memory_address(
+	memory: usize,
+	pointer: &Expression,
+	offset: u64,
+	mng: &mut Manager,
+	w: &mut dyn Write,
+) -> Result<()> {
+	mng.module_names().write_memory(memory, w)?;
+	write!(w, ", ")?;
+	pointer.write(mng, w)?;
+
+	if offset != 0 {
+		write!(w, " + {offset}")?;
+	}
+
+	Ok(())
+}
+
+impl Driver for AtomicRmw {
+	fn write(&self, mng: &mut Manager, w: &mut dyn Write) -> Result<()> {
+		let op = self.op_type().into_name();
+		let name = self.store_type().into_name();
+
+		self.result().write(mng, w)?;
+		write!(w, " = rt.store.rmw_{op}_{name}(")?;
+		write_memory_address(self.memory(), self.pointer(), self.offset(), mng, w)?;
+		write!(w, ", ")?;
+		self.value().write(mng, w)?;
+		write!(w, ")")
+	}
+}
+
+impl Driver for AtomicCmpxchg {
+	fn write(&self, mng: &mut Manager, w: &mut dyn Write) -> Result<()> {
+		let name = self.store_type().into_name();
+
+		self.result().write(mng, w)?;
+		write!(w, " = rt.store.cmpxchg_{name}(")?;
+		write_memory_address(self.memory(), self.pointer(), self.offset(), mng, w)?;
+		write!(w, ", ")?;
+		self.expected().write(mng, w)?;
+		write!(w, ", ")?;
+		self.replacement().write(mng, w)?;
+		write!(w, ")")
+	}
+}
+
+impl Driver for AtomicWait {
+	fn write(&self, mng: &mut Manager, w: &mut dyn Write) -> Result<()> {
+		let name = self.load_type().into_name();
+
+		self.result().write(mng, w)?;
+		write!(w, " = rt.load.wait_{name}(")?;
+		write_memory_address(self.memory(), self.pointer(), self.offset(), mng, w)?;
+		write!(w, ", ")?;
+		self.expected().write(mng, w)?;
+		write!(w, ", ")?;
+		self.timeout().write(mng, w)?;
+		write!(w, ")")
+	}
+}
+
+impl Driver for AtomicNotify {
+	fn write(&self, mng: &mut Manager, w: &mut dyn Write) -> Result<()> {
+		self.result().write(mng, w)?;
+		write!(w, " = rt.store.notify(")?;
+		write_memory_address(self.memory(), self.pointer(), self.offset(), mng, w)?;
+		write!(w, ", ")?;
+		self.count().write(mng, w)?;
+		write!(w, ")")
+	}
+}
+
 impl Driver for MemoryGrow {
 	fn write(&self, mng: &mut Manager, w: &mut dyn Write) -> Result<()> {
 		let memory = self.memory();
 
 		self.result().write(mng, w)?;
-		write!(w, " = rt.allocator.grow(memory_at_{memory}, ")?;
+		write!(w, " = rt.allocator.grow(")?;
+		mng.module_names().write_memory(memory, w)?;
+		write!(w, ", ")?;
 		self.size().write(mng, w)?;
 		write!(w, ")")
 	}
@@ -302,9 +708,13 @@ impl Driver for MemoryCopy {
 		let memory_1 = self.destination().memory();
 		let memory_2 = self.source().memory();
 
-		write!(w, "rt.store.copy(memory_at_{memory_1}, ")?;
+		write!(w, "rt.store.copy(")?;
+		mng.module_names().write_memory(memory_1, w)?;
+		write!(w, ", ")?;
 		self.destination().pointer().write(mng, w)?;
-		write!(w, ", memory_at_{memory_2}, ")?;
+		write!(w, ", ")?;
+		mng.module_names().write_memory(memory_2, w)?;
+		write!(w, ", ")?;
 		self.source().pointer().write(mng, w)?;
 		write!(w, ", ")?;
 		self.size().write(mng, w)?;
@@ -316,7 +726,9 @@ impl Driver for MemoryFill {
 	fn write(&self, mng: &mut Manager, w: &mut dyn Write) -> Result<()> {
 		let memory = self.destination().memory();
 
-		write!(w, "rt.store.fill(memory_at_{memory}, ")?;
+		write!(w, "rt.store.fill(")?;
+		mng.module_names().write_memory(memory, w)?;
+		write!(w, ", ")?;
 		self.destination().pointer().write(mng, w)?;
 		write!(w, ", ")?;
 		self.size().write(mng, w)?;
@@ -326,6 +738,116 @@ impl Driver for MemoryFill {
 	}
 }
 
+impl Driver for MemoryInit {
+	fn write(&self, mng: &mut Manager, w: &mut dyn Write) -> Result<()> {
+		let memory = self.destination().memory();
+
+		write!(w, "rt.store.init(")?;
+		mng.module_names().write_memory(memory, w)?;
+		write!(w, ", ")?;
+		self.destination().pointer().write(mng, w)?;
+		write!(w, ", ")?;
+		mng.module_names().write_data(self.data(), w)?;
+		write!(w, ", ")?;
+		self.offset().write(mng, w)?;
+		write!(w, ", ")?;
+		self.size().write(mng, w)?;
+		write!(w, ")")
+	}
+}
+
+impl Driver for DataDrop {
+	fn write(&self, mng: &mut Manager, w: &mut dyn Write) -> Result<()> {
+		mng.module_names().write_data(self.data(), w)?;
+		write!(w, r#" = """#)
+	}
+}
+
+impl Driver for TableSet {
+	fn write(&self, mng: &mut Manager, w: &mut dyn Write) -> Result<()> {
+		write!(w, "rt.table.set(")?;
+		mng.module_names().write_table(self.table(), w)?;
+		write!(w, ", ")?;
+		self.index().write(mng, w)?;
+		write!(w, ", ")?;
+		self.value().write(mng, w)?;
+		write!(w, ")")
+	}
+}
+
+impl Driver for TableGrow {
+	fn write(&self, mng: &mut Manager, w: &mut dyn Write) -> Result<()> {
+		self.result().write(mng, w)?;
+		write!(w, " = rt.table.grow(")?;
+		mng.module_names().write_table(self.table(), w)?;
+		write!(w, ", ")?;
+		self.size().write(mng, w)?;
+		write!(w, ", ")?;
+		self.value().write(mng, w)?;
+		write!(w, ")")
+	}
+}
+
+impl Driver for TableFill {
+	fn write(&self, mng: &mut Manager, w: &mut dyn Write) -> Result<()> {
+		let table = self.destination().table();
+
+		write!(w, "rt.table.fill(")?;
+		mng.module_names().write_table(table, w)?;
+		write!(w, ", ")?;
+		self.destination().index().write(mng, w)?;
+		write!(w, ", ")?;
+		self.size().write(mng, w)?;
+		write!(w, ", ")?;
+		self.value().write(mng, w)?;
+		write!(w, ")")
+	}
+}
+
+impl Driver for TableCopy {
+	fn write(&self, mng: &mut Manager, w: &mut dyn Write) -> Result<()> {
+		let table_1 = self.destination().table();
+		let table_2 = self.source().table();
+
+		write!(w, "rt.table.copy(")?;
+		mng.module_names().write_table(table_1, w)?;
+		write!(w, ", ")?;
+		self.destination().index().write(mng, w)?;
+		write!(w, ", ")?;
+		mng.module_names().write_table(table_2, w)?;
+		write!(w, ", ")?;
+		self.source().index().write(mng, w)?;
+		write!(w, ", ")?;
+		self.size().write(mng, w)?;
+		write!(w, ")")
+	}
+}
+
+impl Driver for TableInit {
+	fn write(&self, mng: &mut Manager, w: &mut dyn Write) -> Result<()> {
+		let table = self.destination().table();
+
+		write!(w, "rt.table.init(")?;
+		mng.module_names().write_table(table, w)?;
+		write!(w, ", ")?;
+		self.destination().index().write(mng, w)?;
+		write!(w, ", ")?;
+		mng.module_names().write_element(self.element(), w)?;
+		write!(w, ", ")?;
+		self.offset().write(mng, w)?;
+		write!(w, ", ")?;
+		self.size().write(mng, w)?;
+		write!(w, ")")
+	}
+}
+
+impl Driver for ElemDrop {
+	fn write(&self, mng: &mut Manager, w: &mut dyn Write) -> Result<()> {
+		mng.module_names().write_element(self.element(), w)?;
+		write!(w, " = {{ n = 0 }}")
+	}
+}
+
 fn write_stat(stat: &dyn Driver, mng: &mut Manager, w: &mut dyn Write) -> Result<()> {
 	indentation!(mng, w)?;
 	stat.write(mng, w)?;
@@ -338,22 +860,36 @@ impl Driver for Statement {
 			Self::Block(s) => s.write(mng, w),
 			Self::BrIf(s) => s.write(mng, w),
 			Self::If(s) => s.write(mng, w),
+			Self::Try(s) => s.write(mng, w),
 			Self::Call(s) => write_stat(s, mng, w),
 			Self::CallIndirect(s) => write_stat(s, mng, w),
 			Self::SetTemporary(s) => write_stat(s, mng, w),
 			Self::SetLocal(s) => write_stat(s, mng, w),
 			Self::SetGlobal(s) => write_stat(s, mng, w),
 			Self::StoreAt(s) => write_stat(s, mng, w),
+			Self::StoreLane(s) => write_stat(s, mng, w),
 			Self::MemoryGrow(s) => write_stat(s, mng, w),
 			Self::MemoryCopy(s) => write_stat(s, mng, w),
 			Self::MemoryFill(s) => write_stat(s, mng, w),
+			Self::MemoryInit(s) => write_stat(s, mng, w),
+			Self::DataDrop(s) => write_stat(s, mng, w),
+			Self::AtomicRmw(s) => write_stat(s, mng, w),
+			Self::AtomicCmpxchg(s) => write_stat(s, mng, w),
+			Self::AtomicWait(s) => write_stat(s, mng, w),
+			Self::AtomicNotify(s) => write_stat(s, mng, w),
+			Self::TableSet(s) => write_stat(s, mng, w),
+			Self::TableGrow(s) => write_stat(s, mng, w),
+			Self::TableFill(s) => write_stat(s, mng, w),
+			Self::TableCopy(s) => write_stat(s, mng, w),
+			Self::TableInit(s) => write_stat(s, mng, w),
+			Self::ElemDrop(s) => write_stat(s, mng, w),
 		}
 	}
 }
 
-fn write_parameter_list(ast: &FuncData, w: &mut dyn Write) -> Result<()> {
+fn write_parameter_list(ast: &FuncData, mng: &Manager, w: &mut dyn Write) -> Result<()> {
 	write!(w, "function(")?;
-	write_separated(0..ast.num_param(), |i, w| write!(w, "loc_{i}"), w)?;
+	write_separated(0..ast.num_param(), |i, w| mng.write_local(i, w), w)?;
 	writeln!(w, ")")
 }
 
@@ -361,43 +897,46 @@ const fn type_to_zero(typ: ValType) -> &'static str {
 	match typ {
 		ValType::F32 | ValType::F64 => "0.0",
 		ValType::I64 => "0LL",
+		ValType::V128 => "v128_ZERO",
+		ValType::Ref(_) => "nil",
 		_ => "0",
 	}
 }
 
 fn write_variable_list(ast: &FuncData, mng: &Manager, w: &mut dyn Write) -> Result<()> {
-	let mut locals = ast.local_data().iter().copied();
-	let num_local = mng.num_local() - ast.num_param();
+	let allocation = mng.allocation();
+	let mut register_zero = HashMap::new();
+	let mut spill_zero = Vec::new();
 
-	for (i, typ) in locals.by_ref().enumerate().take(num_local) {
-		let index = ast.num_param() + i;
-		let zero = type_to_zero(typ);
+	for &var in allocation.initial_list() {
+		let zero = type_to_zero(ast.local_data()[var - ast.num_param()]);
 
-		line!(mng, w, "local loc_{index} = {zero}")?;
+		match allocation.local(var) {
+			Location::Register(index) => {
+				register_zero.insert(index, zero);
+			}
+			Location::Spill(index) => spill_zero.push((index, zero)),
+		}
 	}
 
-	if locals.len() != 0 {
-		indented!(mng, w, "local loc_spill = {{ ")?;
-
-		for typ in locals {
-			let zero = type_to_zero(typ);
+	for index in ast.num_param()..allocation.num_register() {
+		indented!(mng, w, "local ")?;
+		mng.write_register(index, w)?;
 
-			write!(w, "{zero}, ")?;
+		match register_zero.get(&index) {
+			Some(zero) => writeln!(w, " = {zero}")?,
+			None => writeln!(w)?,
 		}
-
-		writeln!(w, "}}")?;
 	}
 
-	let mut temporaries = 0..ast.num_stack();
-
-	for i in temporaries.by_ref().take(mng.num_temp()) {
-		line!(mng, w, "local reg_{i}")?;
-	}
+	if allocation.num_spill() != 0 {
+		let len = allocation.num_spill();
 
-	if !temporaries.is_empty() {
-		let len = temporaries.len();
+		line!(mng, w, "local reg_spill = table_new({len}, 0)")?;
 
-		line!(mng, w, "local reg_spill = table.create({len})")?;
+		for (index, zero) in spill_zero {
+			line!(mng, w, "reg_spill[{}] = {zero}", index + 1)?;
+		}
 	}
 
 	Ok(())
@@ -407,23 +946,31 @@ impl Driver for FuncData {
 	fn write(&self, mng: &mut Manager, w: &mut dyn Write) -> Result<()> {
 		mng.indent();
 
-		write_parameter_list(self, w)?;
+		write_parameter_list(self, mng, w)?;
 		write_variable_list(self, mng, w)?;
 
 		if mng.has_table() {
 			line!(mng, w, "local br_map, temp = {{}}, nil")?;
 		}
 
-		self.code().write(mng, w)?;
+		let mut body = Vec::new();
+
+		self.code().write(mng, &mut body)?;
 
 		if self.num_result() != 0 {
-			indented!(mng, w, "return ")?;
+			indented!(mng, body, "return ")?;
 
-			ResultList::new(0, self.num_result()).write(mng, w)?;
+			ResultList::new(0, self.num_result()).write(mng, &mut body)?;
 
-			writeln!(w)?;
+			writeln!(body)?;
+		}
+
+		for definition in mng.take_hoisted() {
+			w.write_all(&definition)?;
 		}
 
+		w.write_all(&body)?;
+
 		mng.dedent();
 
 		line!(mng, w, "end")
@@ -1,20 +1,40 @@
 use std::io::{ErrorKind, Result, Write};
 
-use wasm_ast::module::Module;
+use wasm_ast::{
+	module::{Module, TypeInfo},
+	optimize::Options,
+};
 
-fn load_arg_source() -> Result<Vec<u8>> {
+enum Mode {
+	Typed,
+	Readable,
+	Optimized,
+}
+
+fn load_arg_source() -> Result<(Vec<u8>, Mode)> {
 	let mut arguments = std::env::args();
 	let path = arguments
 		.next()
 		.unwrap_or_else(|| "wasm2luajit".to_string());
 
-	arguments.next().map_or_else(
+	let mut source = arguments.next();
+	let mode = match source.as_deref() {
+		Some("--readable") => Mode::Readable,
+		Some("--optimize") => Mode::Optimized,
+		_ => Mode::Typed,
+	};
+
+	if !matches!(mode, Mode::Typed) {
+		source = arguments.next();
+	}
+
+	source.map_or_else(
 		|| {
-			eprintln!("usage: {path} <file>\n");
+			eprintln!("usage: {path} [--readable | --optimize] <file>\n");
 
 			Err(ErrorKind::NotFound.into())
 		},
-		std::fs::read,
+		|source| std::fs::read(source).map(|data| (data, mode)),
 	)
 }
 
@@ -27,11 +47,21 @@ fn do_runtime(lock: &mut dyn Write) -> Result<()> {
 }
 
 fn main() -> Result<()> {
-	let data = load_arg_source()?;
-	let wasm = Module::try_from_data(&data).unwrap();
+	let (data, mode) = load_arg_source()?;
+	let wasm = Module::try_from_data(&data)?;
+	let type_info = TypeInfo::from_module(&wasm);
 
 	let lock = &mut std::io::stdout().lock();
 
 	do_runtime(lock)?;
-	codegen_luajit::from_module_untyped(&wasm, lock)
+
+	match mode {
+		Mode::Typed => codegen_luajit::from_module_typed(&wasm, &type_info, lock),
+		Mode::Readable => {
+			codegen_luajit::from_module_readable(&wasm, &type_info, &Options::default(), lock)
+		}
+		Mode::Optimized => {
+			codegen_luajit::from_module_optimized(&wasm, &type_info, &Options::all(), lock)
+		}
+	}
 }
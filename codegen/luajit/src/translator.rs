@@ -1,12 +1,17 @@
 use std::{
-	collections::BTreeSet,
+	collections::{BTreeSet, HashMap},
 	io::{Result, Write},
+	rc::Rc,
 };
 
 use wasm_ast::{
+	call_graph::CallGraph,
+	constant,
+	error::Error,
 	factory::Factory,
 	module::{External, Module, TypeInfo},
-	node::{FuncData, Statement},
+	node::{Expression, FuncData},
+	optimize::{reach::Reachable, Options},
 };
 use wasmparser::{
 	ConstExpr, Data, DataKind, Element, ElementItems, ElementKind, Export, Import, Operator,
@@ -15,7 +20,7 @@ use wasmparser::{
 
 use crate::{
 	analyzer::localize,
-	backend::manager::{Driver, Manager},
+	backend::manager::{to_identifier, write_separated, Driver, Manager, ModuleNames, Names},
 };
 
 trait AsIEName {
@@ -29,15 +34,15 @@ impl AsIEName for External {
 			Self::Table => "table_list",
 			Self::Memory => "memory_list",
 			Self::Global => "global_list",
-			Self::Tag => unimplemented!(),
+			Self::Tag => "tag_list",
 		}
 	}
 }
 
-fn reader_to_code(reader: OperatorsReader) -> Vec<Operator> {
+fn reader_to_code(reader: OperatorsReader) -> Result<Vec<Operator>> {
 	let parsed: std::result::Result<_, _> = reader.into_iter().collect();
 
-	parsed.unwrap()
+	Ok(parsed.map_err(Error::from)?)
 }
 
 fn write_named_array(name: &str, len: usize, w: &mut dyn Write) -> Result<()> {
@@ -46,20 +51,46 @@ fn write_named_array(name: &str, len: usize, w: &mut dyn Write) -> Result<()> {
 	writeln!(w, "local {name} = table_new({len}, 1)")
 }
 
-fn write_constant(init: &ConstExpr, type_info: &TypeInfo, w: &mut dyn Write) -> Result<()> {
-	let code = reader_to_code(init.get_operators_reader());
-	let func = Factory::from_type_info(type_info).create_anonymous(&code);
+fn read_constant(init: &ConstExpr, type_info: &TypeInfo) -> Result<Expression> {
+	let code = reader_to_code(init.get_operators_reader())?;
 
-	if let Some(Statement::SetTemporary(stat)) = func.code().code().last() {
-		stat.value().write(&mut Manager::empty(), w)
-	} else {
-		writeln!(w, r#"error("Valueless constant")"#)
+	Ok(constant::evaluate(&code, type_info)?)
+}
+
+fn write_constant(
+	init: &ConstExpr,
+	type_info: &TypeInfo,
+	names: &Rc<ModuleNames>,
+	w: &mut dyn Write,
+) -> Result<()> {
+	read_constant(init, type_info)?.write(&mut Manager::constant(names), w)
+}
+
+// Tables and globals are the only imports and exports that may be named
+fn write_external(
+	wanted: External,
+	index: usize,
+	names: &ModuleNames,
+	w: &mut dyn Write,
+) -> Result<()> {
+	match wanted {
+		External::Table => names.write_table(index, w),
+		External::Global => names.write_global(index, w),
+		_ => {
+			let upper = wanted.as_ie_name().to_uppercase();
+
+			write!(w, "{upper}[{index}]")
+		}
 	}
 }
 
-fn write_import_of(list: &[Import], wanted: External, w: &mut dyn Write) -> Result<()> {
+fn write_import_of(
+	list: &[Import],
+	wanted: External,
+	names: &ModuleNames,
+	w: &mut dyn Write,
+) -> Result<()> {
 	let lower = wanted.as_ie_name();
-	let upper = lower.to_uppercase();
 
 	for (i, Import { name, module, .. }) in list
 		.iter()
@@ -67,53 +98,63 @@ fn write_import_of(list: &[Import], wanted: External, w: &mut dyn Write) -> Resu
 		.enumerate()
 	{
 		write!(w, "\t")?;
-		writeln!(w, r#"{upper}[{i}] = wasm["{module}"].{lower}["{name}"]"#)?;
+		write_external(wanted, i, names, w)?;
+		writeln!(w, r#" = wasm["{module}"].{lower}["{name}"]"#)?;
 	}
 
 	Ok(())
 }
 
-fn write_export_of(list: &[Export], wanted: External, w: &mut dyn Write) -> Result<()> {
+fn write_export_of(
+	list: &[Export],
+	wanted: External,
+	names: &ModuleNames,
+	w: &mut dyn Write,
+) -> Result<()> {
 	let lower = wanted.as_ie_name();
-	let upper = lower.to_uppercase();
 
 	writeln!(w, "\t\t{lower} = {{")?;
 
 	for Export { name, index, .. } in list.iter().filter(|v| External::from(v.kind) == wanted) {
 		write!(w, "\t\t\t")?;
-		writeln!(w, r#"["{name}"] = {upper}[{index}],"#)?;
+		write!(w, r#"["{name}"] = "#)?;
+		write_external(wanted, (*index).try_into().unwrap(), names, w)?;
+		writeln!(w, ",")?;
 	}
 
 	writeln!(w, "\t\t}},")
 }
 
-fn write_import_list(list: &[Import], w: &mut dyn Write) -> Result<()> {
-	write_import_of(list, External::Func, w)?;
-	write_import_of(list, External::Table, w)?;
-	write_import_of(list, External::Memory, w)?;
-	write_import_of(list, External::Global, w)
+fn write_import_list(list: &[Import], names: &ModuleNames, w: &mut dyn Write) -> Result<()> {
+	write_import_of(list, External::Func, names, w)?;
+	write_import_of(list, External::Table, names, w)?;
+	write_import_of(list, External::Memory, names, w)?;
+	write_import_of(list, External::Global, names, w)?;
+	write_import_of(list, External::Tag, names, w)
 }
 
-fn write_export_list(list: &[Export], w: &mut dyn Write) -> Result<()> {
-	write_export_of(list, External::Func, w)?;
-	write_export_of(list, External::Table, w)?;
-	write_export_of(list, External::Memory, w)?;
-	write_export_of(list, External::Global, w)
+fn write_export_list(list: &[Export], names: &ModuleNames, w: &mut dyn Write) -> Result<()> {
+	write_export_of(list, External::Func, names, w)?;
+	write_export_of(list, External::Table, names, w)?;
+	write_export_of(list, External::Memory, names, w)?;
+	write_export_of(list, External::Global, names, w)?;
+	write_export_of(list, External::Tag, names, w)
 }
 
-fn write_table_list(wasm: &Module, w: &mut dyn Write) -> Result<()> {
+fn write_table_list(wasm: &Module, names: &ModuleNames, w: &mut dyn Write) -> Result<()> {
 	let offset = wasm.import_count(External::Table);
 	let table = wasm.table_section();
 
+	// Tables without a maximum may grow until their
+	// size no longer fits in 32 bits
 	for (i, table) in table.iter().enumerate() {
 		let index = offset + i;
 		let min = table.ty.initial;
-		let max = table.ty.maximum.unwrap_or(0xFFFF);
+		let max = table.ty.maximum.unwrap_or(0xFFFF_FFFF);
 
-		writeln!(
-			w,
-			"\tTABLE_LIST[{index}] = {{ min = {min}, max = {max}, data = {{}} }}"
-		)?;
+		write!(w, "\t")?;
+		names.write_table(index, w)?;
+		writeln!(w, " = {{ min = {min}, max = {max}, data = {{}} }}")?;
 	}
 
 	Ok(())
@@ -123,109 +164,213 @@ fn write_memory_list(wasm: &Module, w: &mut dyn Write) -> Result<()> {
 	let offset = wasm.import_count(External::Memory);
 	let memory = wasm.memory_section();
 
+	// Shared memories are treated like any other apart from being
+	// waited on, as the generated code only ever runs on a single
+	// thread, and 64-bit memories are capped to what can be addressed
+	// with 32 bits
 	for (i, ty) in memory.iter().enumerate() {
 		let index = offset + i;
 		let min = ty.initial;
-		let max = ty.maximum.unwrap_or(0xFFFF);
+		let max = ty.maximum.unwrap_or(0xFFFF).min(0x10000);
+		let shared = ty.shared;
 
-		writeln!(w, "\tMEMORY_LIST[{index}] = rt.allocator.new({min}, {max})")?;
+		writeln!(
+			w,
+			"\tMEMORY_LIST[{index}] = rt.allocator.new({min}, {max}, {shared})"
+		)?;
 	}
 
 	Ok(())
 }
 
-fn write_global_list(wasm: &Module, type_info: &TypeInfo, w: &mut dyn Write) -> Result<()> {
+fn write_global_list(
+	wasm: &Module,
+	type_info: &TypeInfo,
+	reachable: &Reachable,
+	names: &Rc<ModuleNames>,
+	w: &mut dyn Write,
+) -> Result<()> {
 	let offset = wasm.import_count(External::Global);
 	let global = wasm.global_section();
 
 	for (i, global) in global.iter().enumerate() {
 		let index = offset + i;
 
-		write!(w, "\tGLOBAL_LIST[{index}] = {{ value = ")?;
-		write_constant(&global.init_expr, type_info, w)?;
+		if !reachable.has_global(index) {
+			continue;
+		}
+
+		write!(w, "\t")?;
+		names.write_global(index, w)?;
+		write!(w, " = {{ value = ")?;
+		write_constant(&global.init_expr, type_info, names, w)?;
 		writeln!(w, " }}")?;
 	}
 
 	Ok(())
 }
 
-fn write_element_list(list: &[Element], type_info: &TypeInfo, w: &mut dyn Write) -> Result<()> {
-	for element in list {
-		let ElementKind::Active { table_index: index, offset_expr: init } = element.kind else {
-			unimplemented!("passive elements not supported")
-		};
+fn write_tag_list(wasm: &Module, w: &mut dyn Write) -> Result<()> {
+	let offset = wasm.import_count(External::Tag);
+	let tag = wasm.tag_section();
 
-		let index = index.unwrap_or(0);
+	// Tags only need a unique identity to be matched against
+	for i in 0..tag.len() {
+		let index = offset + i;
 
-		writeln!(w, "\tdo")?;
-		writeln!(w, "\t\tlocal target = TABLE_LIST[{index}].data")?;
-		write!(w, "\t\tlocal offset = ")?;
+		writeln!(w, "\tTAG_LIST[{index}] = {{}}")?;
+	}
 
-		write_constant(&init, type_info, w)?;
+	Ok(())
+}
 
-		writeln!(w)?;
-		write!(w, "\t\tlocal data = {{ ")?;
-
-		match element.items.clone() {
-			ElementItems::Functions(functions) => {
-				for index in functions {
-					let index = index.unwrap();
-					write!(w, "FUNC_LIST[{index}],")?;
-				}
-			}
-			ElementItems::Expressions(expressions) => {
-				for init in expressions {
-					let init = init.unwrap();
-					write_constant(&init, type_info, w)?;
-				}
-			}
+fn write_element_items(
+	items: &ElementItems,
+	type_info: &TypeInfo,
+	names: &Rc<ModuleNames>,
+	w: &mut dyn Write,
+) -> Result<()> {
+	match items.clone() {
+		ElementItems::Functions(functions) => write_separated(
+			functions.into_iter(),
+			|index, w| write!(w, "FUNC_LIST[{}]", index.map_err(Error::from)?),
+			w,
+		),
+		ElementItems::Expressions(expressions) => write_separated(
+			expressions.into_iter(),
+			|init, w| write_constant(&init.map_err(Error::from)?, type_info, names, w),
+			w,
+		),
+	}
+}
+
+fn write_element_list(
+	list: &[Element],
+	type_info: &TypeInfo,
+	reachable: &Reachable,
+	names: &Rc<ModuleNames>,
+	w: &mut dyn Write,
+) -> Result<()> {
+	for (i, element) in list.iter().enumerate() {
+		if !reachable.has_element(i) {
+			continue;
 		}
 
+		let len = match &element.items {
+			ElementItems::Functions(functions) => functions.count(),
+			ElementItems::Expressions(expressions) => expressions.count(),
+		};
+
+		write!(w, "\t")?;
+		names.write_element(i, w)?;
+
+		if matches!(element.kind, ElementKind::Declared) {
+			writeln!(w, " = {{ n = 0 }}")?;
+
+			continue;
+		}
+
+		write!(w, " = {{ n = {len}, ")?;
+		write_element_items(&element.items, type_info, names, w)?;
 		writeln!(w, " }}")?;
-		writeln!(w, "\t\ttable.move(data, 1, #data, offset, target)")?;
-		writeln!(w, "\tend")?;
+
+		let ElementKind::Active {
+			table_index: index,
+			offset_expr: init,
+		} = element.kind
+		else {
+			continue;
+		};
+
+		let index = index.unwrap_or(0);
+
+		write!(w, "\trt.table.init(")?;
+		names.write_table(index.try_into().unwrap(), w)?;
+		write!(w, ", ")?;
+		write_constant(&init, type_info, names, w)?;
+		write!(w, ", ")?;
+		names.write_element(i, w)?;
+		writeln!(w, ", 0, {len})")?;
+		write!(w, "\t")?;
+		names.write_element(i, w)?;
+		writeln!(w, " = {{ n = 0 }}")?;
 	}
 
 	Ok(())
 }
 
-fn write_data_list(list: &[Data], type_info: &TypeInfo, w: &mut dyn Write) -> Result<()> {
-	for data in list {
-		let (index, init) = match data.kind {
-			DataKind::Passive => unimplemented!("passive data not supported"),
-			DataKind::Active {
-				memory_index,
-				offset_expr,
-			} => (memory_index, offset_expr),
+fn write_data_list(
+	list: &[Data],
+	type_info: &TypeInfo,
+	reachable: &Reachable,
+	names: &Rc<ModuleNames>,
+	w: &mut dyn Write,
+) -> Result<()> {
+	for (i, data) in list.iter().enumerate() {
+		if !reachable.has_data(i) {
+			continue;
+		}
+
+		write!(w, "\t")?;
+		names.write_data(i, w)?;
+		writeln!(w, r#" = "{}""#, data.data.escape_ascii())?;
+
+		let DataKind::Active {
+			memory_index: index,
+			offset_expr: init,
+		} = data.kind
+		else {
+			continue;
 		};
 
-		write!(w, "\trt.store.string(MEMORY_LIST[{index}], ")?;
-		write_constant(&init, type_info, w)?;
-		writeln!(w, r#","{}")"#, data.data.escape_ascii())?;
+		write!(w, "\trt.store.init(MEMORY_LIST[{index}], ")?;
+
+		if type_info.is_memory_64(index.try_into().unwrap()) {
+			write!(w, "rt.saturate.u32_i64(")?;
+			write_constant(&init, type_info, names, w)?;
+			write!(w, ")")?;
+		} else {
+			write_constant(&init, type_info, names, w)?;
+		}
+
+		write!(w, ", ")?;
+		names.write_data(i, w)?;
+		writeln!(w, ", 0, {})", data.data.len())?;
+		write!(w, "\t")?;
+		names.write_data(i, w)?;
+		writeln!(w, r#" = """#)?;
 	}
 
 	Ok(())
 }
 
-fn build_func_list(wasm: &Module, type_info: &TypeInfo) -> Vec<FuncData> {
+fn build_func_list(
+	wasm: &Module,
+	type_info: &TypeInfo,
+	options: &Options,
+) -> wasm_ast::error::Result<Vec<FuncData>> {
 	let offset = wasm.import_count(External::Func);
-	let mut builder = Factory::from_type_info(type_info);
+	let call_graph = CallGraph::from_module(wasm)?;
+	let mut factory = Factory::from_type_info(type_info).with_call_graph(&call_graph);
+	let mut func_list = if options.skip_failed {
+		factory.create_module_partial(wasm).0
+	} else {
+		factory.create_module(wasm)?
+	};
 
-	wasm.code_section()
-		.iter()
-		.enumerate()
-		.map(|f| builder.create_indexed(f.0 + offset, f.1).unwrap())
-		.collect()
+	options.apply_list(&mut func_list, type_info, offset);
+
+	Ok(func_list)
 }
 
 fn write_local_operation(head: &str, tail: &str, w: &mut dyn Write) -> Result<()> {
 	write!(w, "local {head}_{tail} = ")?;
 
 	match (head, tail) {
-		("abs" | "ceil" | "floor" | "sqrt", _) => write!(w, "math.{head}"),
+		("abs" | "ceil" | "floor" | "sqrt", "f32" | "f64") => write!(w, "math.{head}"),
 		("rem", "i32") => write!(w, "math.fmod"),
-		("band" | "bor" | "bxor" | "bnot", _) => write!(w, "bit.{head}"),
-		("shl", _) => write!(w, "bit.lshift"),
+		("band" | "bor" | "bxor" | "bnot", "i32" | "i64") => write!(w, "bit.{head}"),
+		("shl", "i32" | "i64") => write!(w, "bit.lshift"),
 		("shr", "i32" | "i64") => write!(w, "bit.arshift"),
 		("shr", "u32" | "u64") => write!(w, "bit.rshift"),
 		("rotl", _) => write!(w, "bit.rol"),
@@ -237,11 +382,56 @@ fn write_local_operation(head: &str, tail: &str, w: &mut dyn Write) -> Result<()
 	writeln!(w)
 }
 
-fn write_localize_used(func_list: &[FuncData], w: &mut dyn Write) -> Result<BTreeSet<usize>> {
+fn constant_list<'a>(wasm: &'a Module) -> Result<Vec<ConstExpr<'a>>> {
+	let mut list: Vec<_> = wasm.global_section().iter().map(|v| v.init_expr).collect();
+
+	for element in wasm.element_section() {
+		if let ElementKind::Active { offset_expr, .. } = &element.kind {
+			list.push(*offset_expr);
+		}
+
+		if let ElementItems::Expressions(expressions) = element.items.clone() {
+			for init in expressions {
+				list.push(init.map_err(Error::from)?);
+			}
+		}
+	}
+
+	let data = wasm.data_section().iter().filter_map(|v| match &v.kind {
+		DataKind::Active { offset_expr, .. } => Some(*offset_expr),
+		DataKind::Passive => None,
+	});
+
+	list.extend(data);
+
+	Ok(list)
+}
+
+fn write_localize_used(
+	wasm: &Module,
+	type_info: &TypeInfo,
+	func_list: &[FuncData],
+	reachable: &Reachable,
+	names: &ModuleNames,
+	w: &mut dyn Write,
+) -> Result<BTreeSet<usize>> {
+	let offset = wasm.import_count(External::Func);
 	let mut loc_set = BTreeSet::new();
 	let mut mem_set = BTreeSet::new();
 
-	for (loc, mem) in func_list.iter().map(localize::visit) {
+	for init in constant_list(wasm)? {
+		let loc = localize::visit_constant(&read_constant(&init, type_info)?);
+
+		loc_set.extend(loc);
+	}
+
+	let used_list = func_list
+		.iter()
+		.enumerate()
+		.filter(|v| reachable.has_function(offset + v.0))
+		.map(|v| v.1);
+
+	for (loc, mem) in used_list.map(localize::visit) {
 		loc_set.extend(loc);
 		mem_set.extend(mem);
 	}
@@ -250,8 +440,10 @@ fn write_localize_used(func_list: &[FuncData], w: &mut dyn Write) -> Result<BTre
 		write_local_operation(loc.0, loc.1, w)?;
 	}
 
-	for mem in &mem_set {
-		writeln!(w, "local memory_at_{mem}")?;
+	for &mem in &mem_set {
+		write!(w, "local ")?;
+		names.write_memory(mem, w)?;
+		writeln!(w)?;
 	}
 
 	Ok(mem_set)
@@ -261,16 +453,115 @@ fn write_func_start(wasm: &Module, index: u32, w: &mut dyn Write) -> Result<()>
 	write!(w, "FUNC_LIST[{index}] = ")?;
 
 	wasm.name_section()
-		.get(&index)
+		.function(index)
 		.map_or_else(|| Ok(()), |name| write!(w, "--[[ {name} ]] "))
 }
 
-fn write_func_list(wasm: &Module, func_list: &[FuncData], w: &mut dyn Write) -> Result<()> {
+fn local_name_map(wasm: &Module, index: u32, ast: &FuncData) -> HashMap<usize, String> {
+	let len = ast.num_param() + ast.local_data().len();
+
+	(0..len)
+		.filter_map(|var| {
+			let name = wasm.name_section().local(index, var.try_into().unwrap())?;
+
+			Some((var, to_identifier("loc", name, var)))
+		})
+		.collect()
+}
+
+fn label_name_map(wasm: &Module, index: u32) -> HashMap<usize, String> {
+	wasm.name_section()
+		.label_list(index)
+		.map(|(label, name)| {
+			let label = label.try_into().unwrap();
+
+			(label, to_identifier("label", name, label))
+		})
+		.collect()
+}
+
+fn module_name_map<'a>(
+	len: usize,
+	name_of: impl Fn(u32) -> Option<&'a str>,
+	prefix: &str,
+) -> HashMap<usize, String> {
+	(0..len)
+		.filter_map(|index| {
+			let name = name_of(index.try_into().unwrap())?;
+
+			Some((index, to_identifier(prefix, name, index)))
+		})
+		.collect()
+}
+
+fn module_names(wasm: &Module) -> ModuleNames {
+	let names = wasm.name_section();
+
+	ModuleNames {
+		table: module_name_map(wasm.table_space(), |v| names.table(v), "table"),
+		memory: module_name_map(wasm.memory_space(), |v| names.memory(v), "memory"),
+		global: module_name_map(wasm.global_space(), |v| names.global(v), "global"),
+		element: module_name_map(wasm.element_section().len(), |v| names.element(v), "elem"),
+		data: module_name_map(wasm.data_section().len(), |v| names.data(v), "data"),
+	}
+}
+
+// Named functions are bound to a local first so that
+// their name shows up in stack traces
+fn write_readable_func(
+	wasm: &Module,
+	index: u32,
+	ast: &FuncData,
+	module: &Rc<ModuleNames>,
+	w: &mut dyn Write,
+) -> Result<()> {
+	let names = Names {
+		local: local_name_map(wasm, index, ast),
+		label: label_name_map(wasm, index),
+		module: Rc::clone(module),
+	};
+
+	let mut mng = Manager::with_names(ast, names);
+
+	let Some(name) = wasm.name_section().function(index) else {
+		write_func_start(wasm, index, w)?;
+
+		return ast.write(&mut mng, w);
+	};
+
+	let name = to_identifier("func", name, index.try_into().unwrap());
+
+	writeln!(w, "do")?;
+	write!(w, "\tlocal {name} = ")?;
+
+	mng.indent();
+	ast.write(&mut mng, w)?;
+	mng.dedent();
+
+	writeln!(w, "\tFUNC_LIST[{index}] = {name}")?;
+	writeln!(w, "end")
+}
+
+fn write_func_list(
+	wasm: &Module,
+	func_list: &[FuncData],
+	reachable: &Reachable,
+	names: Option<&Rc<ModuleNames>>,
+	w: &mut dyn Write,
+) -> Result<()> {
 	let offset = wasm.import_count(External::Func);
 
 	func_list.iter().enumerate().try_for_each(|(i, v)| {
+		if !reachable.has_function(offset + i) {
+			return Ok(());
+		}
+
 		let index = (offset + i).try_into().unwrap();
 
+		if let Some(names) = names {
+			return write_readable_func(wasm, index, v, names, w);
+		}
+
 		write_func_start(wasm, index, w)?;
 
 		v.write(&mut Manager::function(v), w)
@@ -280,23 +571,28 @@ fn write_func_list(wasm: &Module, func_list: &[FuncData], w: &mut dyn Write) ->
 fn write_module_start(
 	wasm: &Module,
 	type_info: &TypeInfo,
+	reachable: &Reachable,
 	mem_set: &BTreeSet<usize>,
+	names: &Rc<ModuleNames>,
 	w: &mut dyn Write,
 ) -> Result<()> {
 	writeln!(w, "local function run_init_code()")?;
-	write_table_list(wasm, w)?;
+	write_table_list(wasm, names, w)?;
 	write_memory_list(wasm, w)?;
-	write_global_list(wasm, type_info, w)?;
-	write_element_list(wasm.element_section(), type_info, w)?;
-	write_data_list(wasm.data_section(), type_info, w)?;
+	write_global_list(wasm, type_info, reachable, names, w)?;
+	write_tag_list(wasm, w)?;
+	write_element_list(wasm.element_section(), type_info, reachable, names, w)?;
+	write_data_list(wasm.data_section(), type_info, reachable, names, w)?;
 	writeln!(w, "end")?;
 
 	writeln!(w, "return function(wasm)")?;
-	write_import_list(wasm.import_section(), w)?;
+	write_import_list(wasm.import_section(), names, w)?;
 	writeln!(w, "\trun_init_code()")?;
 
-	for mem in mem_set {
-		writeln!(w, "\tmemory_at_{mem} = MEMORY_LIST[{mem}]")?;
+	for &mem in mem_set {
+		write!(w, "\t")?;
+		names.write_memory(mem, w)?;
+		writeln!(w, " = MEMORY_LIST[{mem}]")?;
 	}
 
 	if let Some(start) = wasm.start_section() {
@@ -304,37 +600,103 @@ fn write_module_start(
 	}
 
 	writeln!(w, "\treturn {{")?;
-	write_export_list(wasm.export_section(), w)?;
+	write_export_list(wasm.export_section(), names, w)?;
 	writeln!(w, "\t}}")?;
 	writeln!(w, "end")
 }
 
 /// # Errors
-/// Returns `Err` if writing to `Write` failed.
+/// Returns `Err` if the code could not be built or writing to `Write` failed.
 pub fn from_inst_list(code: &[Operator], type_info: &TypeInfo, w: &mut dyn Write) -> Result<()> {
-	let ast = Factory::from_type_info(type_info).create_anonymous(code);
+	let ast = Factory::from_type_info(type_info).create_anonymous(code)?;
 
 	ast.write(&mut Manager::function(&ast), w)
 }
 
-/// # Errors
-/// Returns `Err` if writing to `Write` failed.
-pub fn from_module_typed(wasm: &Module, type_info: &TypeInfo, w: &mut dyn Write) -> Result<()> {
-	let func_list = build_func_list(wasm, type_info);
-	let mem_set = write_localize_used(&func_list, w)?;
+fn write_module(
+	wasm: &Module,
+	type_info: &TypeInfo,
+	options: &Options,
+	is_readable: bool,
+	w: &mut dyn Write,
+) -> Result<()> {
+	let func_list = build_func_list(wasm, type_info, options)?;
+	let reachable = if options.remove_unreachable {
+		Reachable::new(wasm, &func_list)?
+	} else {
+		Reachable::full(wasm)
+	};
+
+	let names = Rc::new(if is_readable {
+		module_names(wasm)
+	} else {
+		ModuleNames::default()
+	});
+
+	let mem_set = write_localize_used(wasm, type_info, &func_list, &reachable, &names, w)?;
 
 	writeln!(w, "local table_new = require(\"table.new\")")?;
 	write_named_array("FUNC_LIST", wasm.function_space(), w)?;
 	write_named_array("TABLE_LIST", wasm.table_space(), w)?;
 	write_named_array("MEMORY_LIST", wasm.memory_space(), w)?;
 	write_named_array("GLOBAL_LIST", wasm.global_space(), w)?;
+	write_named_array("TAG_LIST", wasm.tag_space(), w)?;
+	write_named_array("ELEM_LIST", wasm.element_section().len(), w)?;
+	write_named_array("DATA_LIST", wasm.data_section().len(), w)?;
+
+	write_func_list(
+		wasm,
+		&func_list,
+		&reachable,
+		is_readable.then_some(&names),
+		w,
+	)?;
+	write_module_start(wasm, type_info, &reachable, &mem_set, &names, w)
+}
 
-	write_func_list(wasm, &func_list, w)?;
-	write_module_start(wasm, type_info, &mem_set, w)
+/// # Errors
+/// Returns `Err` if a function could not be built or writing to `Write` failed.
+/// Build failures are of kind `InvalidData` and wrap a `wasm_ast::error::Error`
+/// covering every function that failed.
+pub fn from_module_typed(wasm: &Module, type_info: &TypeInfo, w: &mut dyn Write) -> Result<()> {
+	write_module(wasm, type_info, &Options::default(), false, w)
+}
+
+/// Like `from_module_optimized`, but functions, locals, labels, tables,
+/// memories, elements and data segments are named after the name section
+/// entries of the module where possible.
+///
+/// # Errors
+/// Returns `Err` if a function could not be built or writing to `Write` failed.
+/// Build failures are of kind `InvalidData` and wrap a `wasm_ast::error::Error`
+/// covering every function that failed, unless `options.skip_failed` is set.
+pub fn from_module_readable(
+	wasm: &Module,
+	type_info: &TypeInfo,
+	options: &Options,
+	w: &mut dyn Write,
+) -> Result<()> {
+	write_module(wasm, type_info, options, true, w)
+}
+
+/// Like `from_module_typed`, but functions are run through the optimization
+/// passes enabled in `options` before being written.
+///
+/// # Errors
+/// Returns `Err` if a function could not be built or writing to `Write` failed.
+/// Build failures are of kind `InvalidData` and wrap a `wasm_ast::error::Error`
+/// covering every function that failed, unless `options.skip_failed` is set.
+pub fn from_module_optimized(
+	wasm: &Module,
+	type_info: &TypeInfo,
+	options: &Options,
+	w: &mut dyn Write,
+) -> Result<()> {
+	write_module(wasm, type_info, options, false, w)
 }
 
 /// # Errors
-/// Returns `Err` if writing to `Write` failed.
+/// Returns `Err` if a function could not be built or writing to `Write` failed.
 pub fn from_module_untyped(wasm: &Module, w: &mut dyn Write) -> Result<()> {
 	let type_info = TypeInfo::from_module(wasm);
 
@@ -471,6 +471,16 @@ do
 
 	saturate.u64_f64 = saturate.u64_f32
 
+	function saturate.u32_i64(num)
+		local data_1, data_2 = num_into_u32(num)
+
+		if data_2 ~= 0 then
+			return 0xFFFFFFFF
+		else
+			return data_1
+		end
+	end
+
 	function extend.i32_n8(num)
 		num = bit_and(num, 0xFF)
 
@@ -623,8 +633,6 @@ do
 	local store = {}
 	local allocator = {}
 
-	local string_sub = string.sub
-
 	local buffer_create = buffer.create
 	local buffer_to_string = buffer.tostring
 	local buffer_from_string = buffer.fromstring
@@ -780,11 +788,12 @@ do
 		buffer_write_f64(memory.data, addr, value)
 	end
 
-	function store.string(memory, addr, data, len)
-		local content = if not len or len == #data then data else string_sub(data, 1, len)
-		local temp = buffer_from_string(content)
+	function store.init(memory, addr, data, offset, len)
+		if offset + len > #data then
+			error("out of bounds memory access")
+		end
 
-		buffer_copy(memory.data, addr, temp)
+		buffer_copy(memory.data, addr, buffer_from_string(data), offset, len)
 	end
 
 	function store.copy(memory_1, addr_1, memory_2, addr_2, len)
@@ -795,10 +804,204 @@ do
 		buffer_fill(memory.data, addr, value, len)
 	end
 
+	-- Atomic accesses trap unless their address is a multiple of their size
+	local function check_aligned(addr, size)
+		if addr % size ~= 0 then
+			error("unaligned atomic")
+		end
+	end
+
+	local function atomic_load_of(load_fn, size)
+		return function(memory, addr)
+			check_aligned(addr, size)
+
+			return load_fn(memory, addr)
+		end
+	end
+
+	local function atomic_store_of(store_fn, size)
+		return function(memory, addr, value)
+			check_aligned(addr, size)
+
+			store_fn(memory, addr, value)
+		end
+	end
+
+	load.atomic_i32 = atomic_load_of(load.i32, 4)
+	load.atomic_i32_u8 = atomic_load_of(load.i32_u8, 1)
+	load.atomic_i32_u16 = atomic_load_of(load.i32_u16, 2)
+	load.atomic_i64 = atomic_load_of(load.i64, 8)
+	load.atomic_i64_u8 = atomic_load_of(load.i64_u8, 1)
+	load.atomic_i64_u16 = atomic_load_of(load.i64_u16, 2)
+	load.atomic_i64_u32 = atomic_load_of(load.i64_u32, 4)
+
+	store.atomic_i32 = atomic_store_of(store.i32, 4)
+	store.atomic_i32_n8 = atomic_store_of(store.i32_n8, 1)
+	store.atomic_i32_n16 = atomic_store_of(store.i32_n16, 2)
+	store.atomic_i64 = atomic_store_of(store.i64, 8)
+	store.atomic_i64_n8 = atomic_store_of(store.i64_n8, 1)
+	store.atomic_i64_n16 = atomic_store_of(store.i64_n16, 2)
+	store.atomic_i64_n32 = atomic_store_of(store.i64_n32, 4)
+
+	-- Memories are never shared between threads, so atomic operations
+	-- are done as plain read-modify-write sequences, which are checked
+	-- for alignment by their first load
+	local function rmw_of(load_fn, store_fn, operation)
+		return function(memory, addr, value)
+			local old = load_fn(memory, addr)
+
+			store_fn(memory, addr, operation(old, value))
+
+			return old
+		end
+	end
+
+	local function cmpxchg_of(load_fn, store_fn, is_equal, wrap)
+		return function(memory, addr, expected, replacement)
+			local old = load_fn(memory, addr)
+
+			if is_equal(old, wrap(expected)) then
+				store_fn(memory, addr, replacement)
+			end
+
+			return old
+		end
+	end
+
+	local function wrap_i32(mask)
+		return function(num)
+			return bit_and(num, mask)
+		end
+	end
+
+	local function wrap_i64(mask)
+		local num_mask = num_from_u32(mask, 0)
+
+		return function(num)
+			return Integer.bit_and(num, num_mask)
+		end
+	end
+
+	local function is_equal_i32(lhs, rhs)
+		return lhs == rhs
+	end
+
+	local function swap(_, rhs)
+		return rhs
+	end
+
+	local rmw_i32 = {
+		add = module.add.i32,
+		sub = module.sub.i32,
+		band = bit_and,
+		bor = bit_or,
+		bxor = bit_xor,
+		xchg = swap,
+	}
+
+	local rmw_i64 = {
+		add = Integer.add,
+		sub = Integer.subtract,
+		band = Integer.bit_and,
+		bor = Integer.bit_or,
+		bxor = Integer.bit_xor,
+		xchg = swap,
+	}
+
+	store.rmw_add_i32 = rmw_of(load.atomic_i32, store.i32, rmw_i32.add)
+	store.rmw_add_i32_n8 = rmw_of(load.atomic_i32_u8, store.i32_n8, rmw_i32.add)
+	store.rmw_add_i32_n16 = rmw_of(load.atomic_i32_u16, store.i32_n16, rmw_i32.add)
+	store.rmw_add_i64 = rmw_of(load.atomic_i64, store.i64, rmw_i64.add)
+	store.rmw_add_i64_n8 = rmw_of(load.atomic_i64_u8, store.i64_n8, rmw_i64.add)
+	store.rmw_add_i64_n16 = rmw_of(load.atomic_i64_u16, store.i64_n16, rmw_i64.add)
+	store.rmw_add_i64_n32 = rmw_of(load.atomic_i64_u32, store.i64_n32, rmw_i64.add)
+
+	store.rmw_sub_i32 = rmw_of(load.atomic_i32, store.i32, rmw_i32.sub)
+	store.rmw_sub_i32_n8 = rmw_of(load.atomic_i32_u8, store.i32_n8, rmw_i32.sub)
+	store.rmw_sub_i32_n16 = rmw_of(load.atomic_i32_u16, store.i32_n16, rmw_i32.sub)
+	store.rmw_sub_i64 = rmw_of(load.atomic_i64, store.i64, rmw_i64.sub)
+	store.rmw_sub_i64_n8 = rmw_of(load.atomic_i64_u8, store.i64_n8, rmw_i64.sub)
+	store.rmw_sub_i64_n16 = rmw_of(load.atomic_i64_u16, store.i64_n16, rmw_i64.sub)
+	store.rmw_sub_i64_n32 = rmw_of(load.atomic_i64_u32, store.i64_n32, rmw_i64.sub)
+
+	store.rmw_and_i32 = rmw_of(load.atomic_i32, store.i32, rmw_i32.band)
+	store.rmw_and_i32_n8 = rmw_of(load.atomic_i32_u8, store.i32_n8, rmw_i32.band)
+	store.rmw_and_i32_n16 = rmw_of(load.atomic_i32_u16, store.i32_n16, rmw_i32.band)
+	store.rmw_and_i64 = rmw_of(load.atomic_i64, store.i64, rmw_i64.band)
+	store.rmw_and_i64_n8 = rmw_of(load.atomic_i64_u8, store.i64_n8, rmw_i64.band)
+	store.rmw_and_i64_n16 = rmw_of(load.atomic_i64_u16, store.i64_n16, rmw_i64.band)
+	store.rmw_and_i64_n32 = rmw_of(load.atomic_i64_u32, store.i64_n32, rmw_i64.band)
+
+	store.rmw_or_i32 = rmw_of(load.atomic_i32, store.i32, rmw_i32.bor)
+	store.rmw_or_i32_n8 = rmw_of(load.atomic_i32_u8, store.i32_n8, rmw_i32.bor)
+	store.rmw_or_i32_n16 = rmw_of(load.atomic_i32_u16, store.i32_n16, rmw_i32.bor)
+	store.rmw_or_i64 = rmw_of(load.atomic_i64, store.i64, rmw_i64.bor)
+	store.rmw_or_i64_n8 = rmw_of(load.atomic_i64_u8, store.i64_n8, rmw_i64.bor)
+	store.rmw_or_i64_n16 = rmw_of(load.atomic_i64_u16, store.i64_n16, rmw_i64.bor)
+	store.rmw_or_i64_n32 = rmw_of(load.atomic_i64_u32, store.i64_n32, rmw_i64.bor)
+
+	store.rmw_xor_i32 = rmw_of(load.atomic_i32, store.i32, rmw_i32.bxor)
+	store.rmw_xor_i32_n8 = rmw_of(load.atomic_i32_u8, store.i32_n8, rmw_i32.bxor)
+	store.rmw_xor_i32_n16 = rmw_of(load.atomic_i32_u16, store.i32_n16, rmw_i32.bxor)
+	store.rmw_xor_i64 = rmw_of(load.atomic_i64, store.i64, rmw_i64.bxor)
+	store.rmw_xor_i64_n8 = rmw_of(load.atomic_i64_u8, store.i64_n8, rmw_i64.bxor)
+	store.rmw_xor_i64_n16 = rmw_of(load.atomic_i64_u16, store.i64_n16, rmw_i64.bxor)
+	store.rmw_xor_i64_n32 = rmw_of(load.atomic_i64_u32, store.i64_n32, rmw_i64.bxor)
+
+	store.rmw_xchg_i32 = rmw_of(load.atomic_i32, store.i32, rmw_i32.xchg)
+	store.rmw_xchg_i32_n8 = rmw_of(load.atomic_i32_u8, store.i32_n8, rmw_i32.xchg)
+	store.rmw_xchg_i32_n16 = rmw_of(load.atomic_i32_u16, store.i32_n16, rmw_i32.xchg)
+	store.rmw_xchg_i64 = rmw_of(load.atomic_i64, store.i64, rmw_i64.xchg)
+	store.rmw_xchg_i64_n8 = rmw_of(load.atomic_i64_u8, store.i64_n8, rmw_i64.xchg)
+	store.rmw_xchg_i64_n16 = rmw_of(load.atomic_i64_u16, store.i64_n16, rmw_i64.xchg)
+	store.rmw_xchg_i64_n32 = rmw_of(load.atomic_i64_u32, store.i64_n32, rmw_i64.xchg)
+
+	store.cmpxchg_i32 = cmpxchg_of(load.atomic_i32, store.i32, is_equal_i32, no_op)
+	store.cmpxchg_i32_n8 = cmpxchg_of(load.atomic_i32_u8, store.i32_n8, is_equal_i32, wrap_i32(0xFF))
+	store.cmpxchg_i32_n16 = cmpxchg_of(load.atomic_i32_u16, store.i32_n16, is_equal_i32, wrap_i32(0xFFFF))
+	store.cmpxchg_i64 = cmpxchg_of(load.atomic_i64, store.i64, Integer.is_equal, no_op)
+	store.cmpxchg_i64_n8 = cmpxchg_of(load.atomic_i64_u8, store.i64_n8, Integer.is_equal, wrap_i64(0xFF))
+	store.cmpxchg_i64_n16 = cmpxchg_of(load.atomic_i64_u16, store.i64_n16, Integer.is_equal, wrap_i64(0xFFFF))
+	store.cmpxchg_i64_n32 = cmpxchg_of(load.atomic_i64_u32, store.i64_n32, Integer.is_equal, wrap_i64(0xFFFFFFFF))
+
+	local function check_shared(memory)
+		if not memory.shared then
+			error("expected shared memory")
+		end
+	end
+
+	-- Nothing could ever notify a waiter, so it either
+	-- sees a different value or times out
+	function load.wait_i32(memory, addr, expected, _)
+		check_shared(memory)
+
+		if load.atomic_i32(memory, addr) == expected then
+			return 2
+		else
+			return 1
+		end
+	end
+
+	function load.wait_i64(memory, addr, expected, _)
+		check_shared(memory)
+
+		if Integer.is_equal(load.atomic_i64(memory, addr), expected) then
+			return 2
+		else
+			return 1
+		end
+	end
+
+	function store.notify(_, addr, _)
+		check_aligned(addr, 4)
+
+		return 0
+	end
+
 	local WASM_PAGE_SIZE = 65536
 
-	function allocator.new(min, max)
-		return { max = max, data = buffer_create(min * WASM_PAGE_SIZE) }
+	function allocator.new(min, max, shared)
+		return { max = max, shared = shared, data = buffer_create(min * WASM_PAGE_SIZE) }
 	end
 
 	function allocator.size(memory)
@@ -827,4 +1030,1134 @@ do
 	module.allocator = allocator
 end
 
+do
+	local table_ops = {}
+
+	local table_move = table.move
+
+	local function check_bounds(list, index, len)
+		if index + len > list.min then
+			error("out of bounds table access")
+		end
+	end
+
+	function table_ops.get(list, index)
+		check_bounds(list, index, 1)
+
+		return list.data[index]
+	end
+
+	function table_ops.set(list, index, value)
+		check_bounds(list, index, 1)
+
+		list.data[index] = value
+	end
+
+	function table_ops.grow(list, num, value)
+		local old = list.min
+		local new = old + num
+
+		if new > list.max then
+			return 0xFFFFFFFF
+		end
+
+		local data = list.data
+
+		for i = old, new - 1 do
+			data[i] = value
+		end
+
+		list.min = new
+
+		return old
+	end
+
+	function table_ops.fill(list, index, len, value)
+		check_bounds(list, index, len)
+
+		local data = list.data
+
+		for i = index, index + len - 1 do
+			data[i] = value
+		end
+	end
+
+	function table_ops.init(list, index, segment, offset, len)
+		check_bounds(list, index, len)
+
+		if offset + len > segment.n then
+			error("out of bounds table access")
+		end
+
+		table_move(segment, offset + 1, offset + len, index, list.data)
+	end
+
+	function table_ops.copy(list_1, index_1, list_2, index_2, len)
+		check_bounds(list_1, index_1, len)
+		check_bounds(list_2, index_2, len)
+
+		table_move(list_2.data, index_2, index_2 + len - 1, index_1, list_1.data)
+	end
+
+	module.table = table_ops
+end
+
+do
+	local exception = {}
+
+	local table_unpack = table.unpack
+
+	function exception.new(tag, ...)
+		return { tag = tag, n = select("#", ...), ... }
+	end
+
+	function exception.tag_of(data)
+		if type(data) == "table" then
+			return data.tag
+		end
+
+		return nil
+	end
+
+	function exception.unpack(data)
+		return table_unpack(data, 1, data.n)
+	end
+
+	module.exception = exception
+end
+
+do
+	local tail = {}
+
+	local table_unpack = table.unpack
+
+	-- Marks the results of a function as a tail call still to be made
+	local CONTINUE = {}
+
+	local raw_of = setmetatable({}, { __mode = "k" })
+
+	function tail.call(func, ...)
+		return CONTINUE, func, ...
+	end
+
+	local function pack(...)
+		return { n = select("#", ...), ... }
+	end
+
+	-- Tail calls are made by the loop here rather than by their caller, so
+	-- long chains of them take up no more stack than a single call. Callees
+	-- that make tail calls of their own are entered without their loop.
+	local function run(marker, ...)
+		if marker ~= CONTINUE then
+			return marker, ...
+		end
+
+		local data = pack(marker, ...)
+
+		while data[1] == CONTINUE do
+			local func = data[2]
+			local raw = raw_of[func] or func
+
+			data = pack(raw(table_unpack(data, 3, data.n)))
+		end
+
+		return table_unpack(data, 1, data.n)
+	end
+
+	function tail.entry(raw)
+		local function entry(...)
+			return run(raw(...))
+		end
+
+		raw_of[entry] = raw
+
+		return entry
+	end
+
+	module.tail = tail
+end
+
+do
+	local v128 = {}
+	local abs = {}
+	local ceil = {}
+	local floor = {}
+	local sqrt = {}
+	local splat = {}
+	local extract_lane = {}
+	local replace_lane = {}
+	local shuffle = {}
+	local swizzle = {}
+	local bitselect = {}
+	local band_not = {}
+	local any_true = {}
+	local all_true = {}
+	local bitmask = {}
+	local add_sat = {}
+	local sub_sat = {}
+	local avgr = {}
+	local q15mulr_sat = {}
+	local pmin = {}
+	local pmax = {}
+	local narrow = {}
+	local extend_low = {}
+	local extend_high = {}
+	local extadd_pairwise = {}
+	local extmul_low = {}
+	local extmul_high = {}
+	local dot = {}
+	local saturate_zero = {}
+	local convert_low = {}
+	local demote_zero = {}
+	local promote_low = {}
+	local load_lane = {}
+	local store_lane = {}
+
+	local add = module.add
+	local sub = module.sub
+	local mul = module.mul
+	local div = module.div
+	local neg = module.neg
+	local min = module.min
+	local max = module.max
+	local nearest = module.nearest
+	local popcnt = module.popcnt
+	local eq = module.eq
+	local ne = module.ne
+	local le = module.le
+	local lt = module.lt
+	local ge = module.ge
+	local gt = module.gt
+	local band = module.band
+	local bor = module.bor
+	local bxor = module.bxor
+	local bnot = module.bnot
+	local shl = module.shl
+	local shr = module.shr
+	local truncate = module.truncate
+	local saturate = module.saturate
+	local extend = module.extend
+	local convert = module.convert
+	local load = module.load
+	local store = module.store
+
+	local math_abs = math.abs
+	local math_ceil = math.ceil
+	local math_floor = math.floor
+	local math_sqrt = math.sqrt
+	local math_min = math.min
+	local math_max = math.max
+
+	local bit_not = bit32.bnot
+	local bit_arshift = bit32.arshift
+
+	local buffer_create = buffer.create
+	local buffer_copy = buffer.copy
+	local buffer_fill = buffer.fill
+
+	local buffer_read_i8 = buffer.readi8
+	local buffer_read_u8 = buffer.readu8
+	local buffer_read_i16 = buffer.readi16
+	local buffer_read_u16 = buffer.readu16
+	local buffer_read_i32 = buffer.readi32
+	local buffer_read_u32 = buffer.readu32
+	local buffer_read_f32 = buffer.readf32
+	local buffer_read_f64 = buffer.readf64
+
+	local buffer_write_u8 = buffer.writeu8
+	local buffer_write_u16 = buffer.writeu16
+	local buffer_write_u32 = buffer.writeu32
+	local buffer_write_f32 = buffer.writef32
+	local buffer_write_f64 = buffer.writef64
+
+	local num_add = Integer.add
+	local num_subtract = Integer.subtract
+	local num_multiply = Integer.multiply
+	local num_negate = Integer.negate
+	local num_is_negative = Integer.is_negative
+	local num_is_equal = Integer.is_equal
+	local num_is_less_signed = Integer.is_less_signed
+	local num_is_greater_signed = Integer.is_greater_signed
+
+	local function write_u8(data, offset, value)
+		buffer_write_u8(data, offset, bit_and(value, 0xFF))
+	end
+
+	local function write_u16(data, offset, value)
+		buffer_write_u16(data, offset, bit_and(value, 0xFFFF))
+	end
+
+	local function write_u32(data, offset, value)
+		buffer_write_u32(data, offset, bit_and(value, 0xFFFFFFFF))
+	end
+
+	local function read_i64(data, offset)
+		return num_from_u32(buffer_read_u32(data, offset), buffer_read_u32(data, offset + 4))
+	end
+
+	local function write_i64(data, offset, value)
+		local value_1, value_2 = num_into_u32(value)
+
+		buffer_write_u32(data, offset, value_1)
+		buffer_write_u32(data, offset + 4, value_2)
+	end
+
+	local function new_lane(count, read, write)
+		return { count = count, size = 16 // count, read = read, write = write }
+	end
+
+	local I8 = new_lane(16, buffer_read_i8, write_u8)
+	local U8 = new_lane(16, buffer_read_u8, write_u8)
+	local I16 = new_lane(8, buffer_read_i16, write_u16)
+	local U16 = new_lane(8, buffer_read_u16, write_u16)
+	local I32 = new_lane(4, buffer_read_i32, write_u32)
+	local U32 = new_lane(4, buffer_read_u32, write_u32)
+	local I64 = new_lane(2, read_i64, write_i64)
+	local F32 = new_lane(4, buffer_read_f32, buffer_write_f32)
+	local F64 = new_lane(2, buffer_read_f64, buffer_write_f64)
+
+	local function clamp(num, lower, upper)
+		return math_min(math_max(num, lower), upper)
+	end
+
+	local function truncate_f64(num)
+		if num >= 0 then
+			return math_floor(num)
+		else
+			return math_ceil(num)
+		end
+	end
+
+	local function lanewise_unary(lane, func)
+		local size, read, write = lane.size, lane.read, lane.write
+		local last = 16 - size
+
+		return function(value)
+			local result = buffer_create(16)
+
+			for i = 0, last, size do
+				write(result, i, func(read(value, i)))
+			end
+
+			return result
+		end
+	end
+
+	local function lanewise_binary(lane, func)
+		local size, read, write = lane.size, lane.read, lane.write
+		local last = 16 - size
+
+		return function(lhs, rhs)
+			local result = buffer_create(16)
+
+			for i = 0, last, size do
+				write(result, i, func(read(lhs, i), read(rhs, i)))
+			end
+
+			return result
+		end
+	end
+
+	local function lanewise_shift(lane, func)
+		local size, read, write = lane.size, lane.read, lane.write
+		local last = 16 - size
+		local bits = size * 8
+
+		return function(lhs, rhs)
+			local result = buffer_create(16)
+			local shift = rhs % bits
+
+			for i = 0, last, size do
+				write(result, i, func(read(lhs, i), shift))
+			end
+
+			return result
+		end
+	end
+
+	local function lanewise_compare(lane, func)
+		local size, read = lane.size, lane.read
+		local last = 16 - size
+
+		return function(lhs, rhs)
+			local result = buffer_create(16)
+
+			for i = 0, last, size do
+				if func(read(lhs, i), read(rhs, i)) then
+					buffer_fill(result, i, 0xFF, size)
+				end
+			end
+
+			return result
+		end
+	end
+
+	-- Reads `count` lanes starting at lane `first` of the source and writes
+	-- them to the lower lanes of the result, leaving the rest zeroed
+	local function lanewise_convert(from, into, count, first, func)
+		local from_size, read = from.size, from.read
+		local into_size, write = into.size, into.write
+
+		return function(value)
+			local result = buffer_create(16)
+
+			for i = 0, count - 1 do
+				write(result, i * into_size, func(read(value, (first + i) * from_size)))
+			end
+
+			return result
+		end
+	end
+
+	local function lanewise_widen(from, into, first, func)
+		local from_size, read = from.size, from.read
+		local into_size, write = into.size, into.write
+
+		return function(lhs, rhs)
+			local result = buffer_create(16)
+
+			for i = 0, into.count - 1 do
+				local offset = (first + i) * from_size
+
+				write(result, i * into_size, func(read(lhs, offset), read(rhs, offset)))
+			end
+
+			return result
+		end
+	end
+
+	local function lanewise_pairwise(from, into, func)
+		local from_size, read = from.size, from.read
+		local into_size, write = into.size, into.write
+
+		return function(lhs, rhs)
+			local result = buffer_create(16)
+
+			for i = 0, into.count - 1 do
+				local offset_1 = i * 2 * from_size
+				local offset_2 = offset_1 + from_size
+				local value = func(read(lhs, offset_1), read(rhs, offset_1))
+					+ func(read(lhs, offset_2), read(rhs, offset_2))
+
+				write(result, i * into_size, value)
+			end
+
+			return result
+		end
+	end
+
+	local function lanewise_narrow(from, into, lower, upper)
+		local from_size, read = from.size, from.read
+		local into_size, write = into.size, into.write
+		local count = from.count
+
+		return function(lhs, rhs)
+			local result = buffer_create(16)
+
+			for i = 0, count - 1 do
+				local offset = i * from_size
+
+				write(result, i * into_size, clamp(read(lhs, offset), lower, upper))
+				write(result, (i + count) * into_size, clamp(read(rhs, offset), lower, upper))
+			end
+
+			return result
+		end
+	end
+
+	local function lanewise_all_true(lane)
+		local size, read = lane.size, lane.read
+		local last = 16 - size
+
+		return function(value)
+			for i = 0, last, size do
+				if read(value, i) == 0 then
+					return 0
+				end
+			end
+
+			return 1
+		end
+	end
+
+	local function lanewise_bitmask(lane)
+		local size, read = lane.size, lane.read
+		local count = lane.count
+
+		return function(value)
+			local result = 0
+
+			for i = 0, count - 1 do
+				if read(value, i * size) < 0 then
+					result = result + 2 ^ i
+				end
+			end
+
+			return result
+		end
+	end
+
+	local function lanewise_splat(lane)
+		local size, write = lane.size, lane.write
+		local last = 16 - size
+
+		return function(value)
+			local result = buffer_create(16)
+
+			for i = 0, last, size do
+				write(result, i, value)
+			end
+
+			return result
+		end
+	end
+
+	local function lanewise_extract(lane, func)
+		local size, read = lane.size, lane.read
+
+		return function(value, index)
+			return func(read(value, index * size))
+		end
+	end
+
+	local function lanewise_replace(lane)
+		local size, write = lane.size, lane.write
+
+		return function(value, index, data)
+			local result = buffer_create(16)
+
+			buffer_copy(result, 0, value)
+			write(result, index * size, data)
+
+			return result
+		end
+	end
+
+	local function wordwise_unary(func)
+		return function(value)
+			local result = buffer_create(16)
+
+			for i = 0, 12, 4 do
+				buffer_write_u32(result, i, func(buffer_read_u32(value, i)))
+			end
+
+			return result
+		end
+	end
+
+	local function wordwise_binary(func)
+		return function(lhs, rhs)
+			local result = buffer_create(16)
+
+			for i = 0, 12, 4 do
+				buffer_write_u32(result, i, func(buffer_read_u32(lhs, i), buffer_read_u32(rhs, i)))
+			end
+
+			return result
+		end
+	end
+
+	local function add_num(lhs, rhs)
+		return lhs + rhs
+	end
+
+	local function sub_num(lhs, rhs)
+		return lhs - rhs
+	end
+
+	local function mul_num(lhs, rhs)
+		return lhs * rhs
+	end
+
+	local function div_num(lhs, rhs)
+		return lhs / rhs
+	end
+
+	local function neg_num(num)
+		return -num
+	end
+
+	local function is_equal(lhs, rhs)
+		return lhs == rhs
+	end
+
+	local function is_not_equal(lhs, rhs)
+		return lhs ~= rhs
+	end
+
+	local function is_less(lhs, rhs)
+		return lhs < rhs
+	end
+
+	local function is_less_equal(lhs, rhs)
+		return lhs <= rhs
+	end
+
+	local function is_greater(lhs, rhs)
+		return lhs > rhs
+	end
+
+	local function is_greater_equal(lhs, rhs)
+		return lhs >= rhs
+	end
+
+	local function avgr_num(lhs, rhs)
+		return (lhs + rhs + 1) // 2
+	end
+
+	local function pmin_num(lhs, rhs)
+		if rhs < lhs then
+			return rhs
+		else
+			return lhs
+		end
+	end
+
+	local function pmax_num(lhs, rhs)
+		if lhs < rhs then
+			return rhs
+		else
+			return lhs
+		end
+	end
+
+	local function saturate_between(lower, upper)
+		return function(num)
+			if num ~= num then
+				return 0
+			end
+
+			return clamp(truncate_f64(num), lower, upper)
+		end
+	end
+
+	local function from_i32(num)
+		return bit_or(num, 0)
+	end
+
+	function v128.from_u32(data_1, data_2, data_3, data_4)
+		local result = buffer_create(16)
+
+		buffer_write_u32(result, 0, data_1)
+		buffer_write_u32(result, 4, data_2)
+		buffer_write_u32(result, 8, data_3)
+		buffer_write_u32(result, 12, data_4)
+
+		return result
+	end
+
+	v128.ZERO = buffer_create(16)
+
+	splat.i8x16 = lanewise_splat(U8)
+	splat.i16x8 = lanewise_splat(U16)
+	splat.i32x4 = lanewise_splat(U32)
+	splat.i64x2 = lanewise_splat(I64)
+	splat.f32x4 = lanewise_splat(F32)
+	splat.f64x2 = lanewise_splat(F64)
+
+	extract_lane.i32_i8x16 = lanewise_extract(I8, from_i32)
+	extract_lane.i32_u8x16 = lanewise_extract(U8, no_op)
+	extract_lane.i32_i16x8 = lanewise_extract(I16, from_i32)
+	extract_lane.i32_u16x8 = lanewise_extract(U16, no_op)
+	extract_lane.i32_i32x4 = lanewise_extract(U32, no_op)
+	extract_lane.i64_i64x2 = lanewise_extract(I64, no_op)
+	extract_lane.f32_f32x4 = lanewise_extract(F32, no_op)
+	extract_lane.f64_f64x2 = lanewise_extract(F64, no_op)
+
+	replace_lane.i8x16 = lanewise_replace(U8)
+	replace_lane.i16x8 = lanewise_replace(U16)
+	replace_lane.i32x4 = lanewise_replace(U32)
+	replace_lane.i64x2 = lanewise_replace(I64)
+	replace_lane.f32x4 = lanewise_replace(F32)
+	replace_lane.f64x2 = lanewise_replace(F64)
+
+	function shuffle.i8x16(lhs, rhs, lanes)
+		local result = buffer_create(16)
+
+		for i = 0, 15 do
+			local index = lanes[i + 1]
+
+			if index < 16 then
+				buffer_write_u8(result, i, buffer_read_u8(lhs, index))
+			else
+				buffer_write_u8(result, i, buffer_read_u8(rhs, index - 16))
+			end
+		end
+
+		return result
+	end
+
+	function swizzle.i8x16(lhs, rhs)
+		local result = buffer_create(16)
+
+		for i = 0, 15 do
+			local index = buffer_read_u8(rhs, i)
+
+			if index < 16 then
+				buffer_write_u8(result, i, buffer_read_u8(lhs, index))
+			end
+		end
+
+		return result
+	end
+
+	function bitselect.v128(lhs, rhs, condition)
+		local result = buffer_create(16)
+
+		for i = 0, 12, 4 do
+			local mask = buffer_read_u32(condition, i)
+			local data_1 = bit_and(buffer_read_u32(lhs, i), mask)
+			local data_2 = bit_and(buffer_read_u32(rhs, i), bit_not(mask))
+
+			buffer_write_u32(result, i, bit_or(data_1, data_2))
+		end
+
+		return result
+	end
+
+	band.v128 = wordwise_binary(bit_and)
+	bor.v128 = wordwise_binary(bit_or)
+	bxor.v128 = wordwise_binary(bit_xor)
+	bnot.v128 = wordwise_unary(bit_not)
+
+	band_not.v128 = wordwise_binary(function(lhs, rhs)
+		return bit_and(lhs, bit_not(rhs))
+	end)
+
+	function any_true.v128(value)
+		for i = 0, 12, 4 do
+			if buffer_read_u32(value, i) ~= 0 then
+				return 1
+			end
+		end
+
+		return 0
+	end
+
+	all_true.i8x16 = lanewise_all_true(U8)
+	all_true.i16x8 = lanewise_all_true(U16)
+	all_true.i32x4 = lanewise_all_true(U32)
+
+	all_true.i64x2 = function(value)
+		for i = 0, 8, 8 do
+			if buffer_read_u32(value, i) == 0 and buffer_read_u32(value, i + 4) == 0 then
+				return 0
+			end
+		end
+
+		return 1
+	end
+
+	bitmask.i8x16 = lanewise_bitmask(I8)
+	bitmask.i16x8 = lanewise_bitmask(I16)
+	bitmask.i32x4 = lanewise_bitmask(I32)
+
+	bitmask.i64x2 = lanewise_bitmask(new_lane(2, function(data, offset)
+		return buffer_read_i32(data, offset + 4)
+	end))
+
+	abs.i8x16 = lanewise_unary(I8, math_abs)
+	abs.i16x8 = lanewise_unary(I16, math_abs)
+	abs.i32x4 = lanewise_unary(I32, math_abs)
+
+	abs.i64x2 = lanewise_unary(I64, function(num)
+		if num_is_negative(num) then
+			return num_negate(num)
+		else
+			return num
+		end
+	end)
+
+	neg.i8x16 = lanewise_unary(I8, neg_num)
+	neg.i16x8 = lanewise_unary(I16, neg_num)
+	neg.i32x4 = lanewise_unary(I32, neg_num)
+	neg.i64x2 = lanewise_unary(I64, num_negate)
+
+	popcnt.i8x16 = lanewise_unary(U8, popcnt.i32)
+
+	add.i8x16 = lanewise_binary(I8, add_num)
+	add.i16x8 = lanewise_binary(I16, add_num)
+	add.i32x4 = lanewise_binary(I32, add_num)
+	add.i64x2 = lanewise_binary(I64, num_add)
+
+	sub.i8x16 = lanewise_binary(I8, sub_num)
+	sub.i16x8 = lanewise_binary(I16, sub_num)
+	sub.i32x4 = lanewise_binary(I32, sub_num)
+	sub.i64x2 = lanewise_binary(I64, num_subtract)
+
+	mul.i16x8 = lanewise_binary(I16, mul_num)
+	mul.i32x4 = lanewise_binary(U32, mul.i32)
+	mul.i64x2 = lanewise_binary(I64, num_multiply)
+
+	add_sat.i8x16 = lanewise_binary(I8, function(lhs, rhs)
+		return clamp(lhs + rhs, -0x80, 0x7F)
+	end)
+
+	add_sat.u8x16 = lanewise_binary(U8, function(lhs, rhs)
+		return math_min(lhs + rhs, 0xFF)
+	end)
+
+	add_sat.i16x8 = lanewise_binary(I16, function(lhs, rhs)
+		return clamp(lhs + rhs, -0x8000, 0x7FFF)
+	end)
+
+	add_sat.u16x8 = lanewise_binary(U16, function(lhs, rhs)
+		return math_min(lhs + rhs, 0xFFFF)
+	end)
+
+	sub_sat.i8x16 = lanewise_binary(I8, function(lhs, rhs)
+		return clamp(lhs - rhs, -0x80, 0x7F)
+	end)
+
+	sub_sat.u8x16 = lanewise_binary(U8, function(lhs, rhs)
+		return math_max(lhs - rhs, 0)
+	end)
+
+	sub_sat.i16x8 = lanewise_binary(I16, function(lhs, rhs)
+		return clamp(lhs - rhs, -0x8000, 0x7FFF)
+	end)
+
+	sub_sat.u16x8 = lanewise_binary(U16, function(lhs, rhs)
+		return math_max(lhs - rhs, 0)
+	end)
+
+	min.i8x16 = lanewise_binary(I8, math_min)
+	min.u8x16 = lanewise_binary(U8, math_min)
+	min.i16x8 = lanewise_binary(I16, math_min)
+	min.u16x8 = lanewise_binary(U16, math_min)
+	min.i32x4 = lanewise_binary(I32, math_min)
+	min.u32x4 = lanewise_binary(U32, math_min)
+
+	max.i8x16 = lanewise_binary(I8, math_max)
+	max.u8x16 = lanewise_binary(U8, math_max)
+	max.i16x8 = lanewise_binary(I16, math_max)
+	max.u16x8 = lanewise_binary(U16, math_max)
+	max.i32x4 = lanewise_binary(I32, math_max)
+	max.u32x4 = lanewise_binary(U32, math_max)
+
+	avgr.u8x16 = lanewise_binary(U8, avgr_num)
+	avgr.u16x8 = lanewise_binary(U16, avgr_num)
+
+	q15mulr_sat.i16x8 = lanewise_binary(I16, function(lhs, rhs)
+		return clamp((lhs * rhs + 0x4000) // 0x8000, -0x8000, 0x7FFF)
+	end)
+
+	shl.i8x16 = lanewise_shift(U8, bit_lshift)
+	shl.i16x8 = lanewise_shift(U16, bit_lshift)
+	shl.i32x4 = lanewise_shift(U32, bit_lshift)
+
+	shr.i8x16 = lanewise_shift(I8, bit_arshift)
+	shr.i16x8 = lanewise_shift(I16, bit_arshift)
+	shr.i32x4 = lanewise_shift(I32, bit_arshift)
+
+	shr.u8x16 = lanewise_shift(U8, bit_rshift)
+	shr.u16x8 = lanewise_shift(U16, bit_rshift)
+	shr.u32x4 = lanewise_shift(U32, bit_rshift)
+
+	shl.i64x2 = lanewise_shift(I64, function(lhs, rhs)
+		return shl.i64(lhs, num_from_u32(rhs, 0))
+	end)
+
+	shr.i64x2 = lanewise_shift(I64, function(lhs, rhs)
+		return shr.i64(lhs, num_from_u32(rhs, 0))
+	end)
+
+	shr.u64x2 = lanewise_shift(I64, function(lhs, rhs)
+		return shr.u64(lhs, num_from_u32(rhs, 0))
+	end)
+
+	eq.i8x16 = lanewise_compare(U8, is_equal)
+	eq.i16x8 = lanewise_compare(U16, is_equal)
+	eq.i32x4 = lanewise_compare(U32, is_equal)
+	eq.i64x2 = lanewise_compare(I64, num_is_equal)
+	eq.f32x4 = lanewise_compare(F32, is_equal)
+	eq.f64x2 = lanewise_compare(F64, is_equal)
+
+	ne.i8x16 = lanewise_compare(U8, is_not_equal)
+	ne.i16x8 = lanewise_compare(U16, is_not_equal)
+	ne.i32x4 = lanewise_compare(U32, is_not_equal)
+	ne.f32x4 = lanewise_compare(F32, is_not_equal)
+	ne.f64x2 = lanewise_compare(F64, is_not_equal)
+
+	ne.i64x2 = lanewise_compare(I64, function(lhs, rhs)
+		return not num_is_equal(lhs, rhs)
+	end)
+
+	lt.i8x16 = lanewise_compare(I8, is_less)
+	lt.u8x16 = lanewise_compare(U8, is_less)
+	lt.i16x8 = lanewise_compare(I16, is_less)
+	lt.u16x8 = lanewise_compare(U16, is_less)
+	lt.i32x4 = lanewise_compare(I32, is_less)
+	lt.u32x4 = lanewise_compare(U32, is_less)
+	lt.i64x2 = lanewise_compare(I64, num_is_less_signed)
+	lt.f32x4 = lanewise_compare(F32, is_less)
+	lt.f64x2 = lanewise_compare(F64, is_less)
+
+	le.i8x16 = lanewise_compare(I8, is_less_equal)
+	le.u8x16 = lanewise_compare(U8, is_less_equal)
+	le.i16x8 = lanewise_compare(I16, is_less_equal)
+	le.u16x8 = lanewise_compare(U16, is_less_equal)
+	le.i32x4 = lanewise_compare(I32, is_less_equal)
+	le.u32x4 = lanewise_compare(U32, is_less_equal)
+	le.i64x2 = lanewise_compare(I64, le.i64)
+	le.f32x4 = lanewise_compare(F32, is_less_equal)
+	le.f64x2 = lanewise_compare(F64, is_less_equal)
+
+	gt.i8x16 = lanewise_compare(I8, is_greater)
+	gt.u8x16 = lanewise_compare(U8, is_greater)
+	gt.i16x8 = lanewise_compare(I16, is_greater)
+	gt.u16x8 = lanewise_compare(U16, is_greater)
+	gt.i32x4 = lanewise_compare(I32, is_greater)
+	gt.u32x4 = lanewise_compare(U32, is_greater)
+	gt.i64x2 = lanewise_compare(I64, num_is_greater_signed)
+	gt.f32x4 = lanewise_compare(F32, is_greater)
+	gt.f64x2 = lanewise_compare(F64, is_greater)
+
+	ge.i8x16 = lanewise_compare(I8, is_greater_equal)
+	ge.u8x16 = lanewise_compare(U8, is_greater_equal)
+	ge.i16x8 = lanewise_compare(I16, is_greater_equal)
+	ge.u16x8 = lanewise_compare(U16, is_greater_equal)
+	ge.i32x4 = lanewise_compare(I32, is_greater_equal)
+	ge.u32x4 = lanewise_compare(U32, is_greater_equal)
+	ge.i64x2 = lanewise_compare(I64, ge.i64)
+	ge.f32x4 = lanewise_compare(F32, is_greater_equal)
+	ge.f64x2 = lanewise_compare(F64, is_greater_equal)
+
+	narrow.i8x16_i16x8 = lanewise_narrow(I16, U8, -0x80, 0x7F)
+	narrow.u8x16_i16x8 = lanewise_narrow(I16, U8, 0, 0xFF)
+	narrow.i16x8_i32x4 = lanewise_narrow(I32, U16, -0x8000, 0x7FFF)
+	narrow.u16x8_i32x4 = lanewise_narrow(I32, U16, 0, 0xFFFF)
+
+	extend_low.i16x8_i8x16 = lanewise_convert(I8, U16, 8, 0, no_op)
+	extend_low.i16x8_u8x16 = lanewise_convert(U8, U16, 8, 0, no_op)
+	extend_low.i32x4_i16x8 = lanewise_convert(I16, U32, 4, 0, no_op)
+	extend_low.i32x4_u16x8 = lanewise_convert(U16, U32, 4, 0, no_op)
+	extend_low.i64x2_i32x4 = lanewise_convert(U32, I64, 2, 0, extend.i64_i32)
+	extend_low.i64x2_u32x4 = lanewise_convert(U32, I64, 2, 0, extend.i64_u32)
+
+	extend_high.i16x8_i8x16 = lanewise_convert(I8, U16, 8, 8, no_op)
+	extend_high.i16x8_u8x16 = lanewise_convert(U8, U16, 8, 8, no_op)
+	extend_high.i32x4_i16x8 = lanewise_convert(I16, U32, 4, 4, no_op)
+	extend_high.i32x4_u16x8 = lanewise_convert(U16, U32, 4, 4, no_op)
+	extend_high.i64x2_i32x4 = lanewise_convert(U32, I64, 2, 2, extend.i64_i32)
+	extend_high.i64x2_u32x4 = lanewise_convert(U32, I64, 2, 2, extend.i64_u32)
+
+	local function pairwise_sum(from, into)
+		local inner = lanewise_pairwise(from, into, no_op)
+
+		return function(value)
+			return inner(value, value)
+		end
+	end
+
+	extadd_pairwise.i16x8_i8x16 = pairwise_sum(I8, U16)
+	extadd_pairwise.i16x8_u8x16 = pairwise_sum(U8, U16)
+	extadd_pairwise.i32x4_i16x8 = pairwise_sum(I16, U32)
+	extadd_pairwise.i32x4_u16x8 = pairwise_sum(U16, U32)
+
+	local function mul_i64_i32(lhs, rhs)
+		return num_multiply(extend.i64_i32(lhs), extend.i64_i32(rhs))
+	end
+
+	local function mul_i64_u32(lhs, rhs)
+		return num_multiply(extend.i64_u32(lhs), extend.i64_u32(rhs))
+	end
+
+	extmul_low.i16x8_i8x16 = lanewise_widen(I8, U16, 0, mul_num)
+	extmul_low.i16x8_u8x16 = lanewise_widen(U8, U16, 0, mul_num)
+	extmul_low.i32x4_i16x8 = lanewise_widen(I16, U32, 0, mul_num)
+	extmul_low.i32x4_u16x8 = lanewise_widen(U16, U32, 0, mul_num)
+	extmul_low.i64x2_i32x4 = lanewise_widen(U32, I64, 0, mul_i64_i32)
+	extmul_low.i64x2_u32x4 = lanewise_widen(U32, I64, 0, mul_i64_u32)
+
+	extmul_high.i16x8_i8x16 = lanewise_widen(I8, U16, 8, mul_num)
+	extmul_high.i16x8_u8x16 = lanewise_widen(U8, U16, 8, mul_num)
+	extmul_high.i32x4_i16x8 = lanewise_widen(I16, U32, 4, mul_num)
+	extmul_high.i32x4_u16x8 = lanewise_widen(U16, U32, 4, mul_num)
+	extmul_high.i64x2_i32x4 = lanewise_widen(U32, I64, 2, mul_i64_i32)
+	extmul_high.i64x2_u32x4 = lanewise_widen(U32, I64, 2, mul_i64_u32)
+
+	dot.i32x4_i16x8 = lanewise_pairwise(I16, U32, mul_num)
+
+	abs.f32x4 = lanewise_unary(F32, math_abs)
+	abs.f64x2 = lanewise_unary(F64, math_abs)
+	neg.f32x4 = lanewise_unary(F32, neg_num)
+	neg.f64x2 = lanewise_unary(F64, neg_num)
+	ceil.f32x4 = lanewise_unary(F32, math_ceil)
+	ceil.f64x2 = lanewise_unary(F64, math_ceil)
+	floor.f32x4 = lanewise_unary(F32, math_floor)
+	floor.f64x2 = lanewise_unary(F64, math_floor)
+	truncate.f32x4 = lanewise_unary(F32, truncate.f32)
+	truncate.f64x2 = lanewise_unary(F64, truncate.f64)
+	nearest.f32x4 = lanewise_unary(F32, nearest.f32)
+	nearest.f64x2 = lanewise_unary(F64, nearest.f64)
+	sqrt.f32x4 = lanewise_unary(F32, math_sqrt)
+	sqrt.f64x2 = lanewise_unary(F64, math_sqrt)
+
+	add.f32x4 = lanewise_binary(F32, add_num)
+	add.f64x2 = lanewise_binary(F64, add_num)
+	sub.f32x4 = lanewise_binary(F32, sub_num)
+	sub.f64x2 = lanewise_binary(F64, sub_num)
+	mul.f32x4 = lanewise_binary(F32, mul_num)
+	mul.f64x2 = lanewise_binary(F64, mul_num)
+	div.f32x4 = lanewise_binary(F32, div_num)
+	div.f64x2 = lanewise_binary(F64, div_num)
+	min.f32x4 = lanewise_binary(F32, min.f32)
+	min.f64x2 = lanewise_binary(F64, min.f64)
+	max.f32x4 = lanewise_binary(F32, max.f32)
+	max.f64x2 = lanewise_binary(F64, max.f64)
+	pmin.f32x4 = lanewise_binary(F32, pmin_num)
+	pmin.f64x2 = lanewise_binary(F64, pmin_num)
+	pmax.f32x4 = lanewise_binary(F32, pmax_num)
+	pmax.f64x2 = lanewise_binary(F64, pmax_num)
+
+	saturate.i32x4_f32x4 = lanewise_convert(F32, U32, 4, 0, saturate_between(-0x80000000, 0x7FFFFFFF))
+	saturate.u32x4_f32x4 = lanewise_convert(F32, U32, 4, 0, saturate_between(0, 0xFFFFFFFF))
+	saturate_zero.i32x4_f64x2 = lanewise_convert(F64, U32, 2, 0, saturate_between(-0x80000000, 0x7FFFFFFF))
+	saturate_zero.u32x4_f64x2 = lanewise_convert(F64, U32, 2, 0, saturate_between(0, 0xFFFFFFFF))
+
+	convert.f32x4_i32x4 = lanewise_convert(I32, F32, 4, 0, no_op)
+	convert.f32x4_u32x4 = lanewise_convert(U32, F32, 4, 0, no_op)
+	convert_low.f64x2_i32x4 = lanewise_convert(I32, F64, 2, 0, no_op)
+	convert_low.f64x2_u32x4 = lanewise_convert(U32, F64, 2, 0, no_op)
+	demote_zero.f32x4_f64x2 = lanewise_convert(F64, F32, 2, 0, no_op)
+	promote_low.f64x2_f32x4 = lanewise_convert(F32, F64, 2, 0, no_op)
+
+	local function load_partial(memory, addr, len)
+		local result = buffer_create(16)
+
+		buffer_copy(result, 0, memory.data, addr, len)
+
+		return result
+	end
+
+	local function load_splat(size, splat_lane)
+		return function(memory, addr)
+			local value = load_partial(memory, addr, size)
+
+			return splat_lane(value)
+		end
+	end
+
+	function load.v128(memory, addr)
+		return load_partial(memory, addr, 16)
+	end
+
+	function load.i16x8_i8x8(memory, addr)
+		return extend_low.i16x8_i8x16(load_partial(memory, addr, 8))
+	end
+
+	function load.i16x8_u8x8(memory, addr)
+		return extend_low.i16x8_u8x16(load_partial(memory, addr, 8))
+	end
+
+	function load.i32x4_i16x4(memory, addr)
+		return extend_low.i32x4_i16x8(load_partial(memory, addr, 8))
+	end
+
+	function load.i32x4_u16x4(memory, addr)
+		return extend_low.i32x4_u16x8(load_partial(memory, addr, 8))
+	end
+
+	function load.i64x2_i32x2(memory, addr)
+		return extend_low.i64x2_i32x4(load_partial(memory, addr, 8))
+	end
+
+	function load.i64x2_u32x2(memory, addr)
+		return extend_low.i64x2_u32x4(load_partial(memory, addr, 8))
+	end
+
+	load.splat_n8 = load_splat(1, function(value)
+		return splat.i8x16(buffer_read_u8(value, 0))
+	end)
+
+	load.splat_n16 = load_splat(2, function(value)
+		return splat.i16x8(buffer_read_u16(value, 0))
+	end)
+
+	load.splat_n32 = load_splat(4, function(value)
+		return splat.i32x4(buffer_read_u32(value, 0))
+	end)
+
+	load.splat_n64 = load_splat(8, function(value)
+		buffer_copy(value, 8, value, 0, 8)
+
+		return value
+	end)
+
+	function load.zero_n32(memory, addr)
+		return load_partial(memory, addr, 4)
+	end
+
+	function load.zero_n64(memory, addr)
+		return load_partial(memory, addr, 8)
+	end
+
+	function store.v128(memory, addr, value)
+		buffer_copy(memory.data, addr, value, 0, 16)
+	end
+
+	local function lane_loader(size)
+		return function(memory, addr, value, index)
+			local result = buffer_create(16)
+
+			buffer_copy(result, 0, value)
+			buffer_copy(result, index * size, memory.data, addr, size)
+
+			return result
+		end
+	end
+
+	local function lane_storer(size)
+		return function(memory, addr, value, index)
+			buffer_copy(memory.data, addr, value, index * size, size)
+		end
+	end
+
+	load_lane.i8x16 = lane_loader(1)
+	load_lane.i16x8 = lane_loader(2)
+	load_lane.i32x4 = lane_loader(4)
+	load_lane.i64x2 = lane_loader(8)
+
+	store_lane.i8x16 = lane_storer(1)
+	store_lane.i16x8 = lane_storer(2)
+	store_lane.i32x4 = lane_storer(4)
+	store_lane.i64x2 = lane_storer(8)
+
+	module.v128 = v128
+	module.abs = abs
+	module.ceil = ceil
+	module.floor = floor
+	module.sqrt = sqrt
+	module.splat = splat
+	module.extract_lane = extract_lane
+	module.replace_lane = replace_lane
+	module.shuffle = shuffle
+	module.swizzle = swizzle
+	module.bitselect = bitselect
+	module.band_not = band_not
+	module.any_true = any_true
+	module.all_true = all_true
+	module.bitmask = bitmask
+	module.add_sat = add_sat
+	module.sub_sat = sub_sat
+	module.avgr = avgr
+	module.q15mulr_sat = q15mulr_sat
+	module.pmin = pmin
+	module.pmax = pmax
+	module.narrow = narrow
+	module.extend_low = extend_low
+	module.extend_high = extend_high
+	module.extadd_pairwise = extadd_pairwise
+	module.extmul_low = extmul_low
+	module.extmul_high = extmul_high
+	module.dot = dot
+	module.saturate_zero = saturate_zero
+	module.convert_low = convert_low
+	module.demote_zero = demote_zero
+	module.promote_low = promote_low
+	module.load_lane = load_lane
+	module.store_lane = store_lane
+end
+
 return module
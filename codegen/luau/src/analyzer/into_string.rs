@@ -1,4 +1,6 @@
-use wasm_ast::node::{BinOpType, CmpOpType, LoadType, StoreType, UnOpType};
+use wasm_ast::node::{
+	BinOpType, CmpOpType, ExtractType, LaneType, LoadType, RmwOpType, StoreType, UnOpType,
+};
 
 pub trait IntoName {
 	#[must_use]
@@ -22,6 +24,19 @@ impl IntoName for LoadType {
 			Self::I64_U16 => "i64_u16",
 			Self::I64_I32 => "i64_i32",
 			Self::I64_U32 => "i64_u32",
+			Self::V128 => "v128",
+			Self::I16X8_I8X8 => "i16x8_i8x8",
+			Self::I16X8_U8X8 => "i16x8_u8x8",
+			Self::I32X4_I16X4 => "i32x4_i16x4",
+			Self::I32X4_U16X4 => "i32x4_u16x4",
+			Self::I64X2_I32X2 => "i64x2_i32x2",
+			Self::I64X2_U32X2 => "i64x2_u32x2",
+			Self::Splat_N8 => "splat_n8",
+			Self::Splat_N16 => "splat_n16",
+			Self::Splat_N32 => "splat_n32",
+			Self::Splat_N64 => "splat_n64",
+			Self::Zero_N32 => "zero_n32",
+			Self::Zero_N64 => "zero_n64",
 		}
 	}
 }
@@ -38,6 +53,48 @@ impl IntoName for StoreType {
 			Self::I64_N8 => "i64_n8",
 			Self::I64_N16 => "i64_n16",
 			Self::I64_N32 => "i64_n32",
+			Self::V128 => "v128",
+		}
+	}
+}
+
+impl IntoName for RmwOpType {
+	fn into_name(self) -> &'static str {
+		match self {
+			Self::Add => "add",
+			Self::Sub => "sub",
+			Self::And => "and",
+			Self::Or => "or",
+			Self::Xor => "xor",
+			Self::Xchg => "xchg",
+		}
+	}
+}
+
+impl IntoName for LaneType {
+	fn into_name(self) -> &'static str {
+		match self {
+			Self::I8X16 => "i8x16",
+			Self::I16X8 => "i16x8",
+			Self::I32X4 => "i32x4",
+			Self::I64X2 => "i64x2",
+			Self::F32X4 => "f32x4",
+			Self::F64X2 => "f64x2",
+		}
+	}
+}
+
+impl IntoName for ExtractType {
+	fn into_name(self) -> &'static str {
+		match self {
+			Self::I32_I8X16 => "i32_i8x16",
+			Self::I32_U8X16 => "i32_u8x16",
+			Self::I32_I16X8 => "i32_i16x8",
+			Self::I32_U16X8 => "i32_u16x8",
+			Self::I32_I32X4 => "i32_i32x4",
+			Self::I64_I64X2 => "i64_i64x2",
+			Self::F32_F32X4 => "f32_f32x4",
+			Self::F64_F64X2 => "f64_f64x2",
 		}
 	}
 }
@@ -87,6 +144,7 @@ impl IntoNameTuple for UnOpType {
 			Self::Saturate_I64_F64 => ("saturate", "i64_f64"),
 			Self::Saturate_U64_F32 => ("saturate", "u64_f32"),
 			Self::Saturate_U64_F64 => ("saturate", "u64_f64"),
+			Self::Saturate_U32_I64 => ("saturate", "u32_i64"),
 			Self::Extend_I32_N8 => ("extend", "i32_n8"),
 			Self::Extend_I32_N16 => ("extend", "i32_n16"),
 			Self::Extend_I64_N8 => ("extend", "i64_n8"),
@@ -108,6 +166,71 @@ impl IntoNameTuple for UnOpType {
 			Self::Reinterpret_I64_F64 => ("reinterpret", "i64_f64"),
 			Self::Reinterpret_F32_I32 => ("reinterpret", "f32_i32"),
 			Self::Reinterpret_F64_I64 => ("reinterpret", "f64_i64"),
+			Self::Splat_I8X16 => ("splat", "i8x16"),
+			Self::Splat_I16X8 => ("splat", "i16x8"),
+			Self::Splat_I32X4 => ("splat", "i32x4"),
+			Self::Splat_I64X2 => ("splat", "i64x2"),
+			Self::Splat_F32X4 => ("splat", "f32x4"),
+			Self::Splat_F64X2 => ("splat", "f64x2"),
+			Self::Not_V128 => ("bnot", "v128"),
+			Self::AnyTrue_V128 => ("any_true", "v128"),
+			Self::Abs_I8X16 => ("abs", "i8x16"),
+			Self::Neg_I8X16 => ("neg", "i8x16"),
+			Self::Popcnt_I8X16 => ("popcnt", "i8x16"),
+			Self::AllTrue_I8X16 => ("all_true", "i8x16"),
+			Self::Bitmask_I8X16 => ("bitmask", "i8x16"),
+			Self::Abs_I16X8 => ("abs", "i16x8"),
+			Self::Neg_I16X8 => ("neg", "i16x8"),
+			Self::AllTrue_I16X8 => ("all_true", "i16x8"),
+			Self::Bitmask_I16X8 => ("bitmask", "i16x8"),
+			Self::Abs_I32X4 => ("abs", "i32x4"),
+			Self::Neg_I32X4 => ("neg", "i32x4"),
+			Self::AllTrue_I32X4 => ("all_true", "i32x4"),
+			Self::Bitmask_I32X4 => ("bitmask", "i32x4"),
+			Self::Abs_I64X2 => ("abs", "i64x2"),
+			Self::Neg_I64X2 => ("neg", "i64x2"),
+			Self::AllTrue_I64X2 => ("all_true", "i64x2"),
+			Self::Bitmask_I64X2 => ("bitmask", "i64x2"),
+			Self::ExtAddPairwise_I16X8_I8X16 => ("extadd_pairwise", "i16x8_i8x16"),
+			Self::ExtAddPairwise_I16X8_U8X16 => ("extadd_pairwise", "i16x8_u8x16"),
+			Self::ExtAddPairwise_I32X4_I16X8 => ("extadd_pairwise", "i32x4_i16x8"),
+			Self::ExtAddPairwise_I32X4_U16X8 => ("extadd_pairwise", "i32x4_u16x8"),
+			Self::ExtendLow_I16X8_I8X16 => ("extend_low", "i16x8_i8x16"),
+			Self::ExtendLow_I16X8_U8X16 => ("extend_low", "i16x8_u8x16"),
+			Self::ExtendHigh_I16X8_I8X16 => ("extend_high", "i16x8_i8x16"),
+			Self::ExtendHigh_I16X8_U8X16 => ("extend_high", "i16x8_u8x16"),
+			Self::ExtendLow_I32X4_I16X8 => ("extend_low", "i32x4_i16x8"),
+			Self::ExtendLow_I32X4_U16X8 => ("extend_low", "i32x4_u16x8"),
+			Self::ExtendHigh_I32X4_I16X8 => ("extend_high", "i32x4_i16x8"),
+			Self::ExtendHigh_I32X4_U16X8 => ("extend_high", "i32x4_u16x8"),
+			Self::ExtendLow_I64X2_I32X4 => ("extend_low", "i64x2_i32x4"),
+			Self::ExtendLow_I64X2_U32X4 => ("extend_low", "i64x2_u32x4"),
+			Self::ExtendHigh_I64X2_I32X4 => ("extend_high", "i64x2_i32x4"),
+			Self::ExtendHigh_I64X2_U32X4 => ("extend_high", "i64x2_u32x4"),
+			Self::Ceil_F32X4 => ("ceil", "f32x4"),
+			Self::Floor_F32X4 => ("floor", "f32x4"),
+			Self::Truncate_F32X4 => ("truncate", "f32x4"),
+			Self::Nearest_F32X4 => ("nearest", "f32x4"),
+			Self::Abs_F32X4 => ("abs", "f32x4"),
+			Self::Neg_F32X4 => ("neg", "f32x4"),
+			Self::Sqrt_F32X4 => ("sqrt", "f32x4"),
+			Self::Ceil_F64X2 => ("ceil", "f64x2"),
+			Self::Floor_F64X2 => ("floor", "f64x2"),
+			Self::Truncate_F64X2 => ("truncate", "f64x2"),
+			Self::Nearest_F64X2 => ("nearest", "f64x2"),
+			Self::Abs_F64X2 => ("abs", "f64x2"),
+			Self::Neg_F64X2 => ("neg", "f64x2"),
+			Self::Sqrt_F64X2 => ("sqrt", "f64x2"),
+			Self::Saturate_I32X4_F32X4 => ("saturate", "i32x4_f32x4"),
+			Self::Saturate_U32X4_F32X4 => ("saturate", "u32x4_f32x4"),
+			Self::SaturateZero_I32X4_F64X2 => ("saturate_zero", "i32x4_f64x2"),
+			Self::SaturateZero_U32X4_F64X2 => ("saturate_zero", "u32x4_f64x2"),
+			Self::Convert_F32X4_I32X4 => ("convert", "f32x4_i32x4"),
+			Self::Convert_F32X4_U32X4 => ("convert", "f32x4_u32x4"),
+			Self::ConvertLow_F64X2_I32X4 => ("convert_low", "f64x2_i32x4"),
+			Self::ConvertLow_F64X2_U32X4 => ("convert_low", "f64x2_u32x4"),
+			Self::DemoteZero_F32X4_F64X2 => ("demote_zero", "f32x4_f64x2"),
+			Self::PromoteLow_F64X2_F32X4 => ("promote_low", "f64x2_f32x4"),
 		}
 	}
 }
@@ -159,6 +282,138 @@ impl IntoNameTuple for BinOpType {
 			Self::Min_F64 => ("min", "f64"),
 			Self::Max_F64 => ("max", "f64"),
 			Self::Copysign_F64 => ("copysign", "f64"),
+			Self::Swizzle_I8X16 => ("swizzle", "i8x16"),
+			Self::Eq_I8X16 => ("eq", "i8x16"),
+			Self::Ne_I8X16 => ("ne", "i8x16"),
+			Self::LtS_I8X16 => ("lt", "i8x16"),
+			Self::LtU_I8X16 => ("lt", "u8x16"),
+			Self::GtS_I8X16 => ("gt", "i8x16"),
+			Self::GtU_I8X16 => ("gt", "u8x16"),
+			Self::LeS_I8X16 => ("le", "i8x16"),
+			Self::LeU_I8X16 => ("le", "u8x16"),
+			Self::GeS_I8X16 => ("ge", "i8x16"),
+			Self::GeU_I8X16 => ("ge", "u8x16"),
+			Self::Eq_I16X8 => ("eq", "i16x8"),
+			Self::Ne_I16X8 => ("ne", "i16x8"),
+			Self::LtS_I16X8 => ("lt", "i16x8"),
+			Self::LtU_I16X8 => ("lt", "u16x8"),
+			Self::GtS_I16X8 => ("gt", "i16x8"),
+			Self::GtU_I16X8 => ("gt", "u16x8"),
+			Self::LeS_I16X8 => ("le", "i16x8"),
+			Self::LeU_I16X8 => ("le", "u16x8"),
+			Self::GeS_I16X8 => ("ge", "i16x8"),
+			Self::GeU_I16X8 => ("ge", "u16x8"),
+			Self::Eq_I32X4 => ("eq", "i32x4"),
+			Self::Ne_I32X4 => ("ne", "i32x4"),
+			Self::LtS_I32X4 => ("lt", "i32x4"),
+			Self::LtU_I32X4 => ("lt", "u32x4"),
+			Self::GtS_I32X4 => ("gt", "i32x4"),
+			Self::GtU_I32X4 => ("gt", "u32x4"),
+			Self::LeS_I32X4 => ("le", "i32x4"),
+			Self::LeU_I32X4 => ("le", "u32x4"),
+			Self::GeS_I32X4 => ("ge", "i32x4"),
+			Self::GeU_I32X4 => ("ge", "u32x4"),
+			Self::Eq_I64X2 => ("eq", "i64x2"),
+			Self::Ne_I64X2 => ("ne", "i64x2"),
+			Self::LtS_I64X2 => ("lt", "i64x2"),
+			Self::GtS_I64X2 => ("gt", "i64x2"),
+			Self::LeS_I64X2 => ("le", "i64x2"),
+			Self::GeS_I64X2 => ("ge", "i64x2"),
+			Self::Eq_F32X4 => ("eq", "f32x4"),
+			Self::Ne_F32X4 => ("ne", "f32x4"),
+			Self::Lt_F32X4 => ("lt", "f32x4"),
+			Self::Gt_F32X4 => ("gt", "f32x4"),
+			Self::Le_F32X4 => ("le", "f32x4"),
+			Self::Ge_F32X4 => ("ge", "f32x4"),
+			Self::Eq_F64X2 => ("eq", "f64x2"),
+			Self::Ne_F64X2 => ("ne", "f64x2"),
+			Self::Lt_F64X2 => ("lt", "f64x2"),
+			Self::Gt_F64X2 => ("gt", "f64x2"),
+			Self::Le_F64X2 => ("le", "f64x2"),
+			Self::Ge_F64X2 => ("ge", "f64x2"),
+			Self::And_V128 => ("band", "v128"),
+			Self::AndNot_V128 => ("band_not", "v128"),
+			Self::Or_V128 => ("bor", "v128"),
+			Self::Xor_V128 => ("bxor", "v128"),
+			Self::Narrow_I8X16_I16X8 => ("narrow", "i8x16_i16x8"),
+			Self::Narrow_U8X16_I16X8 => ("narrow", "u8x16_i16x8"),
+			Self::Narrow_I16X8_I32X4 => ("narrow", "i16x8_i32x4"),
+			Self::Narrow_U16X8_I32X4 => ("narrow", "u16x8_i32x4"),
+			Self::Shl_I8X16 => ("shl", "i8x16"),
+			Self::ShrS_I8X16 => ("shr", "i8x16"),
+			Self::ShrU_I8X16 => ("shr", "u8x16"),
+			Self::Add_I8X16 => ("add", "i8x16"),
+			Self::AddSatS_I8X16 => ("add_sat", "i8x16"),
+			Self::AddSatU_I8X16 => ("add_sat", "u8x16"),
+			Self::Sub_I8X16 => ("sub", "i8x16"),
+			Self::SubSatS_I8X16 => ("sub_sat", "i8x16"),
+			Self::SubSatU_I8X16 => ("sub_sat", "u8x16"),
+			Self::MinS_I8X16 => ("min", "i8x16"),
+			Self::MinU_I8X16 => ("min", "u8x16"),
+			Self::MaxS_I8X16 => ("max", "i8x16"),
+			Self::MaxU_I8X16 => ("max", "u8x16"),
+			Self::AvgrU_I8X16 => ("avgr", "u8x16"),
+			Self::Shl_I16X8 => ("shl", "i16x8"),
+			Self::ShrS_I16X8 => ("shr", "i16x8"),
+			Self::ShrU_I16X8 => ("shr", "u16x8"),
+			Self::Add_I16X8 => ("add", "i16x8"),
+			Self::AddSatS_I16X8 => ("add_sat", "i16x8"),
+			Self::AddSatU_I16X8 => ("add_sat", "u16x8"),
+			Self::Sub_I16X8 => ("sub", "i16x8"),
+			Self::SubSatS_I16X8 => ("sub_sat", "i16x8"),
+			Self::SubSatU_I16X8 => ("sub_sat", "u16x8"),
+			Self::Mul_I16X8 => ("mul", "i16x8"),
+			Self::MinS_I16X8 => ("min", "i16x8"),
+			Self::MinU_I16X8 => ("min", "u16x8"),
+			Self::MaxS_I16X8 => ("max", "i16x8"),
+			Self::MaxU_I16X8 => ("max", "u16x8"),
+			Self::AvgrU_I16X8 => ("avgr", "u16x8"),
+			Self::Shl_I32X4 => ("shl", "i32x4"),
+			Self::ShrS_I32X4 => ("shr", "i32x4"),
+			Self::ShrU_I32X4 => ("shr", "u32x4"),
+			Self::Add_I32X4 => ("add", "i32x4"),
+			Self::Sub_I32X4 => ("sub", "i32x4"),
+			Self::Mul_I32X4 => ("mul", "i32x4"),
+			Self::MinS_I32X4 => ("min", "i32x4"),
+			Self::MinU_I32X4 => ("min", "u32x4"),
+			Self::MaxS_I32X4 => ("max", "i32x4"),
+			Self::MaxU_I32X4 => ("max", "u32x4"),
+			Self::Shl_I64X2 => ("shl", "i64x2"),
+			Self::ShrS_I64X2 => ("shr", "i64x2"),
+			Self::ShrU_I64X2 => ("shr", "u64x2"),
+			Self::Add_I64X2 => ("add", "i64x2"),
+			Self::Sub_I64X2 => ("sub", "i64x2"),
+			Self::Mul_I64X2 => ("mul", "i64x2"),
+			Self::Q15MulrSat_I16X8 => ("q15mulr_sat", "i16x8"),
+			Self::ExtMulLow_I16X8_I8X16 => ("extmul_low", "i16x8_i8x16"),
+			Self::ExtMulLow_I16X8_U8X16 => ("extmul_low", "i16x8_u8x16"),
+			Self::ExtMulHigh_I16X8_I8X16 => ("extmul_high", "i16x8_i8x16"),
+			Self::ExtMulHigh_I16X8_U8X16 => ("extmul_high", "i16x8_u8x16"),
+			Self::ExtMulLow_I32X4_I16X8 => ("extmul_low", "i32x4_i16x8"),
+			Self::ExtMulLow_I32X4_U16X8 => ("extmul_low", "i32x4_u16x8"),
+			Self::ExtMulHigh_I32X4_I16X8 => ("extmul_high", "i32x4_i16x8"),
+			Self::ExtMulHigh_I32X4_U16X8 => ("extmul_high", "i32x4_u16x8"),
+			Self::ExtMulLow_I64X2_I32X4 => ("extmul_low", "i64x2_i32x4"),
+			Self::ExtMulLow_I64X2_U32X4 => ("extmul_low", "i64x2_u32x4"),
+			Self::ExtMulHigh_I64X2_I32X4 => ("extmul_high", "i64x2_i32x4"),
+			Self::ExtMulHigh_I64X2_U32X4 => ("extmul_high", "i64x2_u32x4"),
+			Self::Dot_I32X4_I16X8 => ("dot", "i32x4_i16x8"),
+			Self::Add_F32X4 => ("add", "f32x4"),
+			Self::Sub_F32X4 => ("sub", "f32x4"),
+			Self::Mul_F32X4 => ("mul", "f32x4"),
+			Self::Div_F32X4 => ("div", "f32x4"),
+			Self::Min_F32X4 => ("min", "f32x4"),
+			Self::Max_F32X4 => ("max", "f32x4"),
+			Self::PMin_F32X4 => ("pmin", "f32x4"),
+			Self::PMax_F32X4 => ("pmax", "f32x4"),
+			Self::Add_F64X2 => ("add", "f64x2"),
+			Self::Sub_F64X2 => ("sub", "f64x2"),
+			Self::Mul_F64X2 => ("mul", "f64x2"),
+			Self::Div_F64X2 => ("div", "f64x2"),
+			Self::Min_F64X2 => ("min", "f64x2"),
+			Self::Max_F64X2 => ("max", "f64x2"),
+			Self::PMin_F64X2 => ("pmin", "f64x2"),
+			Self::PMax_F64X2 => ("pmax", "f64x2"),
 		}
 	}
 }
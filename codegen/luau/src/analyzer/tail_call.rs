@@ -0,0 +1,28 @@
+use wasm_ast::{
+	node::{FuncData, ReturnCall, ReturnCallIndirect},
+	visit::{Driver, Visitor},
+};
+
+struct Visit {
+	has_tail_call: bool,
+}
+
+impl Visitor for Visit {
+	fn visit_return_call(&mut self, _: &ReturnCall) {
+		self.has_tail_call = true;
+	}
+
+	fn visit_return_call_indirect(&mut self, _: &ReturnCallIndirect) {
+		self.has_tail_call = true;
+	}
+}
+
+pub fn visit(ast: &FuncData) -> bool {
+	let mut visit = Visit {
+		has_tail_call: false,
+	};
+
+	ast.accept(&mut visit);
+
+	visit.has_tail_call
+}
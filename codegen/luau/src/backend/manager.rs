@@ -1,11 +1,16 @@
 use std::{
 	collections::HashMap,
 	io::{Result, Write},
+	rc::Rc,
 };
 
-use wasm_ast::node::{BrTable, FuncData, LabelType};
+use wasm_ast::{
+	node::{Block, BrTable, FuncData},
+	optimize::register::{Allocation, Location},
+	outline::Outline,
+};
 
-use crate::analyzer::{br_target, localize};
+use crate::analyzer::{br_target, localize, tail_call};
 
 #[macro_export]
 macro_rules! indentation {
@@ -32,60 +37,190 @@ macro_rules! line {
 	}};
 }
 
-fn get_pinned_registers(
-	upvalues: usize,
-	params: usize,
-	locals: usize,
-	temporaries: usize,
-) -> (usize, usize) {
-	const MAX_LOCAL_COUNT: usize = 180;
+// Lua refuses to compile functions with more than 200 locals in scope
+const MAX_LOCAL_COUNT: usize = 200;
+
+// Locals declared for control flow, exceptions and spilling are not
+// registers, so some room is left for them
+const RESERVED_LOCAL_COUNT: usize = 20;
+
+// Luau refuses to compile functions with more than 200 upvalues
+const MAX_UPVALUE_COUNT: usize = 200;
+
+// Outlined blocks also capture the locals declared for control flow
+// and the other outlined blocks they call
+const RESERVED_UPVALUE_COUNT: usize = 8;
+
+// Functions past this many nodes are split up, which keeps them well
+// under the limits on constants and jump distances that otherwise fail
+// with "function or expression too complex" when loaded
+const MAX_FUNCTION_SIZE: usize = 8000;
 
-	let available = MAX_LOCAL_COUNT
+// Leaves room for the upvalues, the outlined blocks defined at the start
+// of the function, and the few locals every function may declare
+fn get_available_registers(upvalues: usize, outlined: usize, params: usize) -> usize {
+	(MAX_LOCAL_COUNT - RESERVED_LOCAL_COUNT)
 		.saturating_sub(upvalues)
-		.saturating_sub(params);
+		.saturating_sub(outlined)
+		.saturating_sub(params)
+}
+
+// Outlined blocks and `try` bodies capture what they use from the function
+// as upvalues, next to the runtime names and the few locals declared for
+// control flow
+const fn get_variable_limit(upvalues: usize) -> usize {
+	MAX_UPVALUE_COUNT.saturating_sub(upvalues + RESERVED_UPVALUE_COUNT)
+}
+
+fn get_outline(ast: &FuncData, upvalues: usize) -> Outline {
+	Outline::new(ast, MAX_FUNCTION_SIZE, get_variable_limit(upvalues))
+}
+
+// Names are sanitized into identifiers and suffixed with their index,
+// so they can never collide with each other or with generated names
+pub fn to_identifier(prefix: &str, name: &str, index: usize) -> String {
+	let name: String = name
+		.chars()
+		.map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+		.collect();
 
-	let temporaries = available.min(temporaries);
-	let locals = available.saturating_sub(temporaries).min(locals);
+	format!("{prefix}_{name}_{index}")
+}
 
-	(params + locals, temporaries)
+/// Identifiers given to the tables, memories, globals, elements and data
+/// segments that the name section names, which are otherwise referred to
+/// by index.
+/// Memories are held in locals of their own while the rest are kept in
+/// their lists, under their name instead of their index.
+#[derive(Default)]
+pub struct ModuleNames {
+	pub table: HashMap<usize, String>,
+	pub memory: HashMap<usize, String>,
+	pub global: HashMap<usize, String>,
+	pub element: HashMap<usize, String>,
+	pub data: HashMap<usize, String>,
+}
+
+impl ModuleNames {
+	pub fn write_table(&self, index: usize, w: &mut dyn Write) -> Result<()> {
+		match self.table.get(&index) {
+			Some(name) => write!(w, "TABLE_LIST.{name}"),
+			None => write!(w, "TABLE_LIST[{index}]"),
+		}
+	}
+
+	pub fn write_memory(&self, index: usize, w: &mut dyn Write) -> Result<()> {
+		match self.memory.get(&index) {
+			Some(name) => write!(w, "{name}"),
+			None => write!(w, "memory_at_{index}"),
+		}
+	}
+
+	pub fn write_global(&self, index: usize, w: &mut dyn Write) -> Result<()> {
+		match self.global.get(&index) {
+			Some(name) => write!(w, "GLOBAL_LIST.{name}"),
+			None => write!(w, "GLOBAL_LIST[{index}]"),
+		}
+	}
+
+	pub fn write_element(&self, index: usize, w: &mut dyn Write) -> Result<()> {
+		match self.element.get(&index) {
+			Some(name) => write!(w, "ELEM_LIST.{name}"),
+			None => write!(w, "ELEM_LIST[{index}]"),
+		}
+	}
+
+	pub fn write_data(&self, index: usize, w: &mut dyn Write) -> Result<()> {
+		match self.data.get(&index) {
+			Some(name) => write!(w, "DATA_LIST.{name}"),
+			None => write!(w, "DATA_LIST[{index}]"),
+		}
+	}
+}
+
+/// Identifiers given to the locals and labels of a function, keyed by
+/// their index in the name section, along with those of its module.
+#[derive(Default)]
+pub struct Names {
+	pub local: HashMap<usize, String>,
+	pub label: HashMap<usize, String>,
+	pub module: Rc<ModuleNames>,
+}
+
+/// How a label is written, and so how branches reach it.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Label {
+	/// Written without a loop, as nothing branches to it
+	Plain,
+	/// Written as a loop that is left with `break`
+	Forward,
+	/// Written as a loop that is restarted with `continue`
+	Backward,
+	/// Ends where the loop of the next label does, so it is reached
+	/// by leaving that loop with `break`
+	Exit,
 }
 
 pub struct Manager {
 	table_map: HashMap<usize, usize>,
 	has_branch: bool,
-	num_local: usize,
-	num_temp: usize,
-	label_list: Vec<Option<LabelType>>,
+	has_tail_call: bool,
+	allocation: Allocation,
+	outline: Outline,
+	num_outlined: usize,
+	hoisted_list: Vec<Vec<u8>>,
+	names: Names,
+	label_list: Vec<Label>,
 	indentation: usize,
 }
 
 impl Manager {
-	pub fn empty() -> Self {
+	// Constant expressions only ever refer to names of the module
+	pub fn constant(module: &Rc<ModuleNames>) -> Self {
 		Self {
 			table_map: HashMap::new(),
 			has_branch: false,
-			num_local: 0,
-			num_temp: usize::MAX,
+			has_tail_call: false,
+			allocation: Allocation::default(),
+			outline: Outline::default(),
+			num_outlined: 0,
+			hoisted_list: Vec::new(),
+			names: Names {
+				module: Rc::clone(module),
+				..Names::default()
+			},
 			label_list: Vec::new(),
 			indentation: 0,
 		}
 	}
 
 	pub fn function(ast: &FuncData) -> Self {
+		Self::with_names(ast, Names::default())
+	}
+
+	// Named locals keep a register of their own so the name stays true
+	pub fn with_names(ast: &FuncData, names: Names) -> Self {
 		let (upvalues, memories) = localize::visit(ast);
 		let (table_map, has_branch) = br_target::visit(ast);
-		let (num_local, num_temp) = get_pinned_registers(
-			upvalues.len() + memories.len(),
-			ast.num_param(),
-			ast.local_data().len(),
-			ast.num_stack(),
-		);
+		let upvalues = upvalues.len() + memories.len();
+		let outline = get_outline(ast, upvalues);
+		let available = get_available_registers(upvalues, outline.len(), ast.num_param());
+		let mut allocation = Allocation::new(ast, available, |var| names.local.contains_key(&var));
+
+		allocation.limit_capture(ast, get_variable_limit(upvalues));
+
+		// Branches out of outlined blocks always go through `desired`
+		let has_branch = has_branch || !outline.is_empty();
 
 		Self {
 			table_map,
 			has_branch,
-			num_local,
-			num_temp,
+			has_tail_call: tail_call::visit(ast),
+			allocation,
+			outline,
+			num_outlined: 0,
+			hoisted_list: Vec::new(),
+			names,
 			label_list: Vec::new(),
 			indentation: 0,
 		}
@@ -105,19 +240,106 @@ impl Manager {
 		self.has_branch
 	}
 
-	pub const fn num_local(&self) -> usize {
-		self.num_local
+	pub const fn has_tail_call(&self) -> bool {
+		self.has_tail_call
+	}
+
+	pub const fn allocation(&self) -> &Allocation {
+		&self.allocation
+	}
+
+	pub fn is_outlined(&self, block: &Block) -> bool {
+		self.outline.contains(block)
+	}
+
+	pub fn next_outlined(&mut self) -> usize {
+		self.num_outlined += 1;
+
+		self.num_outlined - 1
+	}
+
+	// Definitions are written apart and placed at the start of the function,
+	// outside of any loop, so they are only created once per call. Those
+	// finished first come first, so callers always see their callees.
+	pub fn hoist<T, F>(&mut self, func: F) -> Result<T>
+	where
+		F: FnOnce(&mut Self, &mut dyn Write) -> Result<T>,
+	{
+		let mut definition = Vec::new();
+		let indentation = std::mem::replace(&mut self.indentation, 1);
+		let result = func(self, &mut definition)?;
+
+		self.indentation = indentation;
+		self.hoisted_list.push(definition);
+
+		Ok(result)
+	}
+
+	pub fn take_hoisted(&mut self) -> Vec<Vec<u8>> {
+		std::mem::take(&mut self.hoisted_list)
+	}
+
+	pub fn write_register(&self, index: usize, w: &mut dyn Write) -> Result<()> {
+		let Some(var) = self.allocation.owner(index) else {
+			return write!(w, "reg_{index}");
+		};
+
+		match self.names.local.get(&var) {
+			Some(name) => write!(w, "{name}"),
+			None => write!(w, "loc_{var}"),
+		}
+	}
+
+	fn write_location(&self, location: Location, w: &mut dyn Write) -> Result<()> {
+		match location {
+			Location::Register(index) => self.write_register(index, w),
+			Location::Spill(index) => write!(w, "reg_spill[{}]", index + 1),
+		}
 	}
 
-	pub const fn num_temp(&self) -> usize {
-		self.num_temp
+	pub fn write_temporary(&self, var: usize, w: &mut dyn Write) -> Result<()> {
+		self.write_location(self.allocation.temporary(var), w)
 	}
 
-	pub fn label_list(&self) -> &[Option<LabelType>] {
+	pub fn write_local(&self, var: usize, w: &mut dyn Write) -> Result<()> {
+		self.write_location(self.allocation.local(var), w)
+	}
+
+	pub fn module_names(&self) -> &ModuleNames {
+		&self.names.module
+	}
+
+	pub fn label_name(&self, block: &Block) -> Option<&str> {
+		let label = block.label()?;
+
+		self.names.label.get(&label).map(String::as_str)
+	}
+
+	pub fn label_list(&self) -> &[Label] {
 		&self.label_list
 	}
 
-	pub fn push_label(&mut self, label: Option<LabelType>) {
+	// Finds the label of the innermost loop being written
+	pub fn loop_index(&self) -> Option<usize> {
+		self.label_list
+			.iter()
+			.rposition(|v| matches!(v, Label::Forward | Label::Backward))
+	}
+
+	// Labels of the innermost loop, or ending right after it, can be
+	// jumped to directly instead of going through `desired`
+	pub fn direct_jump(&self, index: usize) -> Option<&'static str> {
+		let last = self.loop_index()?;
+
+		match self.label_list[index] {
+			Label::Forward if index == last => Some("break"),
+			Label::Backward if index == last => Some("continue"),
+			Label::Exit if index + 1 == last => Some("break"),
+			_ => None,
+		}
+	}
+
+	pub fn push_label(&mut self, label: Label) {
 		self.label_list.push(label);
 	}
 
@@ -5,7 +5,10 @@ pub static NUMERIC: &str = if cfg!(feature = "vector") {
 	include_str!("../runtime/numeric_tb.lua")
 };
 
-pub use translator::{from_inst_list, from_module_typed, from_module_untyped};
+pub use translator::{
+	from_inst_list, from_module_optimized, from_module_readable, from_module_typed,
+	from_module_untyped,
+};
 
 mod analyzer;
 mod backend;
@@ -0,0 +1,126 @@
+use wasm_ast::{
+	call_graph::{CallGraph, Effect},
+	module::Module,
+};
+
+use common::to_bytes;
+
+mod common;
+
+fn call_graph(source: &str) -> CallGraph {
+	let bytes = to_bytes(source);
+	let wasm = Module::try_from_data(&bytes).unwrap();
+
+	CallGraph::from_module(&wasm).unwrap()
+}
+
+const MEMORY_WRITE: Effect = Effect {
+	global_write: false,
+	memory_write: true,
+	table_write: false,
+};
+
+#[test]
+fn pure_leaf() {
+	let graph = call_graph(
+		r#"
+		(module
+			(memory 1)
+			(global $count (mut i32) (i32.const 0))
+			(func $leaf (param i32) (result i32)
+				(i32.add
+					(i32.load (local.get 0))
+					(global.get $count)))
+			(func $caller (result i32)
+				(call $leaf (i32.const 0))))
+		"#,
+	);
+
+	// Reading state is not an effect
+	assert!(graph.effect(0).is_pure());
+	assert!(graph.effect(1).is_pure());
+	assert!(graph.callee_list(0).is_empty());
+	assert_eq!(graph.callee_list(1), [0]);
+	assert_eq!(graph.caller_list(0), [1]);
+}
+
+#[test]
+fn memory_writer() {
+	let graph = call_graph(
+		r#"
+		(module
+			(memory 1)
+			(func $writer
+				(i32.store8 (i32.const 0) (i32.const 1)))
+			(func $caller
+				(call $writer)
+				(call $writer))
+			(func $bystander
+				(drop (memory.size))))
+		"#,
+	);
+
+	assert_eq!(graph.effect(0), MEMORY_WRITE);
+	assert_eq!(graph.effect(1), MEMORY_WRITE);
+	assert!(graph.effect(2).is_pure());
+
+	// Calls are listed once no matter how often they are made
+	assert_eq!(graph.callee_list(1), [0]);
+}
+
+// Effects found anywhere in a cycle reach every function in it, along
+// with anything that calls into it
+#[test]
+fn recursive_cycle() {
+	let graph = call_graph(
+		r#"
+		(module
+			(global $depth (mut i32) (i32.const 0))
+			(func $even (param i32)
+				(if (local.get 0)
+					(then (call $odd (i32.sub (local.get 0) (i32.const 1))))))
+			(func $odd (param i32)
+				(global.set $depth (local.get 0))
+				(call $even (local.get 0)))
+			(func $entry
+				(call $even (i32.const 10)))
+			(func $self (param i32)
+				(if (local.get 0)
+					(then (call $self (i32.sub (local.get 0) (i32.const 1)))))))
+		"#,
+	);
+
+	let global_write = Effect {
+		global_write: true,
+		..Effect::default()
+	};
+
+	assert_eq!(graph.effect(0), global_write);
+	assert_eq!(graph.effect(1), global_write);
+	assert_eq!(graph.effect(2), global_write);
+	assert!(graph.effect(3).is_pure());
+
+	assert_eq!(graph.caller_list(0), [1, 2]);
+	assert_eq!(graph.callee_list(3), [3]);
+	assert_eq!(graph.caller_list(3), [3]);
+}
+
+#[test]
+fn imports_and_indirect_calls_change_everything() {
+	let graph = call_graph(
+		r#"
+		(module
+			(import "env" "log" (func $log))
+			(type $t (func))
+			(table 1 funcref)
+			(func $logger
+				(call $log))
+			(func $dispatch
+				(call_indirect (type $t) (i32.const 0))))
+		"#,
+	);
+
+	assert_eq!(graph.effect(0), Effect::ALL);
+	assert_eq!(graph.effect(1), Effect::ALL);
+	assert_eq!(graph.effect(2), Effect::ALL);
+}
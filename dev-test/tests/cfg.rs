@@ -0,0 +1,135 @@
+use wasm_ast::{
+	cfg::Graph,
+	node::{Block, FuncData, Statement},
+};
+
+use common::build;
+
+mod common;
+
+fn nested_block(stat: &Statement) -> &Block {
+	let Statement::Block(block) = stat else {
+		panic!("expected a block, got {stat:?}");
+	};
+
+	block
+}
+
+// Each loop counts down its own local, with the inner one running
+// inside every iteration of the outer one
+static NESTED_LOOP: &str = r#"
+(module
+	(func (param i32 i32) (result i32) (local i32)
+		(loop $outer
+			(local.set 2 (i32.add (local.get 2) (i32.const 1)))
+			(loop $inner
+				(local.set 1 (i32.sub (local.get 1) (i32.const 1)))
+				(br_if $inner (local.get 1)))
+			(local.set 0 (i32.sub (local.get 0) (i32.const 1)))
+			(br_if $outer (local.get 0)))
+		(local.get 2)))
+"#;
+
+struct Position {
+	outer: usize,
+	inner: usize,
+	after_inner: usize,
+	after_outer: usize,
+}
+
+impl Position {
+	fn of(graph: &Graph, ast: &FuncData) -> Self {
+		let outer = nested_block(&ast.code().code()[0]);
+		let inner = nested_block(&outer.code()[1]);
+
+		Self {
+			outer: graph.block_of(&outer.code()[0]),
+			inner: graph.block_of(&inner.code()[0]),
+			after_inner: graph.block_of(&outer.code()[2]),
+			after_outer: graph.block_of(&ast.code().code()[1]),
+		}
+	}
+}
+
+#[test]
+fn nested_loop_headers() {
+	let ast = build(NESTED_LOOP, 0);
+	let graph = Graph::new(&ast);
+	let at = Position::of(&graph, &ast);
+
+	assert!(graph.is_loop_header(at.outer));
+	assert!(graph.is_loop_header(at.inner));
+	assert!(!graph.is_loop_header(at.after_inner));
+	assert!(!graph.is_loop_header(at.after_outer));
+
+	assert_eq!(graph.loop_of(at.inner), Some(at.inner));
+	assert_eq!(graph.loop_of(at.after_inner), Some(at.outer));
+	assert_eq!(graph.loop_of(at.after_outer), None);
+	assert_eq!(graph.loop_parent(at.inner), Some(at.outer));
+	assert_eq!(graph.loop_parent(at.outer), None);
+
+	assert_eq!(graph.loop_depth(at.inner), 2);
+	assert_eq!(graph.loop_depth(at.after_inner), 1);
+	assert_eq!(graph.loop_depth(at.after_outer), 0);
+}
+
+#[test]
+fn nested_loop_dominators() {
+	let ast = build(NESTED_LOOP, 0);
+	let graph = Graph::new(&ast);
+	let at = Position::of(&graph, &ast);
+
+	// The code after each loop is only reached by falling out of the
+	// `br_if` at its end
+	assert!(graph.dominates(at.inner, at.after_inner));
+	assert!(graph.dominates(at.after_inner, at.after_outer));
+	assert_eq!(graph.immediate_dominator(at.inner), Some(at.outer));
+	assert!(graph.dominated_list(at.outer).contains(&at.inner));
+
+	for id in [at.outer, at.inner, at.after_inner, at.after_outer] {
+		assert!(graph.dominates(Graph::ENTRY, id));
+		assert!(graph.dominates(at.outer, id));
+		assert!(graph.dominates(id, id));
+	}
+
+	// Back edges never make a block dominate its loop header
+	assert!(!graph.dominates(at.inner, at.outer));
+	assert!(!graph.dominates(at.after_inner, at.inner));
+	assert!(!graph.dominates(at.after_outer, at.outer));
+}
+
+#[test]
+fn branches_out_of_loops() {
+	let ast = build(
+		r#"
+		(module
+			(func (param i32) (result i32)
+				(block $exit
+					(loop $again
+						(br_if $exit (i32.eqz (local.get 0)))
+						(local.set 0 (i32.sub (local.get 0) (i32.const 1)))
+						(br $again)))
+				(local.get 0)))
+		"#,
+		0,
+	);
+
+	let graph = Graph::new(&ast);
+	let exit = nested_block(&ast.code().code()[0]);
+	let again = nested_block(&exit.code()[0]);
+
+	let header = graph.block_of(&again.code()[0]);
+	let body = graph.block_of(&again.code()[1]);
+	let after = graph.block_of(&ast.code().code()[1]);
+
+	assert!(graph.is_loop_header(header));
+	assert_eq!(graph.loop_of(body), Some(header));
+	assert_eq!(graph.block_of_last(again.last().unwrap()), body);
+
+	// Leaving through `br_if` is the only way out, so the loop header
+	// dominates the code after the loop, which is outside of it
+	assert_eq!(graph.immediate_dominator(after), Some(header));
+	assert_eq!(graph.loop_of(after), None);
+	assert!(graph.block_list()[body].successors().contains(&header));
+	assert!(graph.block_list()[header].predecessors().contains(&body));
+}
@@ -0,0 +1,44 @@
+// Shared by the test files, which each use only some of these
+#![allow(dead_code)]
+
+use wasm_ast::{
+	factory::Factory,
+	module::{Module, TypeInfo},
+	node::FuncData,
+};
+use wast::{parser::ParseBuffer, Wat};
+
+pub fn to_bytes(source: &str) -> Vec<u8> {
+	let lexed = ParseBuffer::new(source).unwrap();
+	let mut parsed: Wat = wast::parser::parse(&lexed).unwrap();
+
+	parsed.encode().unwrap()
+}
+
+// Builds the function at `index` of the code section of a module
+pub fn build(source: &str, index: usize) -> FuncData {
+	let bytes = to_bytes(source);
+	let wasm = Module::try_from_data(&bytes).unwrap();
+	let type_info = TypeInfo::from_module(&wasm);
+
+	Factory::from_type_info(&type_info)
+		.create_indexed(index, &wasm.code_section()[index])
+		.unwrap()
+}
+
+// Trees are compared through what they serialize to, which also keeps
+// the payloads of NaN constants as floats are written by their bits
+pub fn assert_round_trip(bytes: &[u8]) {
+	let wasm = Module::try_from_data(bytes).unwrap();
+	let type_info = TypeInfo::from_module(&wasm);
+	let func_list = Factory::from_type_info(&type_info)
+		.create_module(&wasm)
+		.unwrap();
+
+	for data in func_list {
+		let serialized = serde_json::to_string(&data).unwrap();
+		let round_trip: FuncData = serde_json::from_str(&serialized).unwrap();
+
+		assert_eq!(serialized, serde_json::to_string(&round_trip).unwrap());
+	}
+}
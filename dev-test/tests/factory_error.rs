@@ -0,0 +1,277 @@
+use std::io::{Result, Write};
+
+use wasm_ast::{
+	error::{Error, IndexSpace, Location},
+	factory::Factory,
+	module::{supported_features, Module, TypeInfo},
+	node::Terminator,
+	optimize::Options,
+};
+use wasmparser::{BlockType, MemArg, Operator, WasmFeatures};
+
+use common::to_bytes;
+
+mod common;
+
+fn build_anonymous(source: &str, list: &[Operator]) -> Error {
+	let bytes = to_bytes(source);
+	let wasm = Module::try_from_data(&bytes).unwrap();
+	let type_info = TypeInfo::from_module(&wasm);
+
+	Factory::from_type_info(&type_info)
+		.create_anonymous(list)
+		.unwrap_err()
+}
+
+const fn at_operator(offset: usize) -> Location {
+	Location {
+		function: None,
+		offset,
+	}
+}
+
+#[test]
+fn stack_underflow() {
+	let error = build_anonymous("(module)", &[Operator::I32Add, Operator::End]);
+
+	assert!(matches!(
+		error,
+		Error::Invalid {
+			function: None,
+			offset: 0,
+			..
+		}
+	));
+}
+
+#[test]
+fn stack_underflow_in_block() {
+	let list = [
+		Operator::I32Const { value: 1 },
+		Operator::Block {
+			blockty: BlockType::Empty,
+		},
+		Operator::Drop,
+		Operator::End,
+		Operator::End,
+	];
+
+	let error = build_anonymous("(module)", &list);
+
+	assert!(matches!(error, Error::Invalid { offset: 2, .. }));
+}
+
+#[test]
+fn unmatched_end() {
+	let list = [Operator::End, Operator::End];
+	let error = build_anonymous("(module)", &list);
+
+	assert!(matches!(error, Error::MalformedNesting { location } if location == at_operator(0)));
+}
+
+#[test]
+fn else_without_if() {
+	let list = [Operator::Else, Operator::End];
+	let error = build_anonymous("(module)", &list);
+
+	assert!(matches!(error, Error::MalformedNesting { location } if location == at_operator(0)));
+}
+
+#[test]
+fn local_out_of_range() {
+	let list = [Operator::LocalGet { local_index: 3 }, Operator::End];
+	let error = build_anonymous("(module)", &list);
+
+	assert!(matches!(
+		error,
+		Error::IndexOutOfRange {
+			location,
+			space: IndexSpace::Local,
+			index: 3,
+		} if location == at_operator(0)
+	));
+}
+
+#[test]
+fn label_out_of_range() {
+	let list = [
+		Operator::Nop,
+		Operator::Br { relative_depth: 2 },
+		Operator::End,
+	];
+	let error = build_anonymous("(module)", &list);
+
+	assert!(matches!(
+		error,
+		Error::IndexOutOfRange {
+			location,
+			space: IndexSpace::Label,
+			index: 2,
+		} if location == at_operator(1)
+	));
+}
+
+#[test]
+fn block_type_out_of_range() {
+	let list = [
+		Operator::Block {
+			blockty: BlockType::FuncType(5),
+		},
+		Operator::End,
+		Operator::End,
+	];
+
+	let error = build_anonymous("(module (type (func)))", &list);
+
+	assert!(matches!(
+		error,
+		Error::IndexOutOfRange {
+			location,
+			space: IndexSpace::Type,
+			index: 5,
+		} if location == at_operator(0)
+	));
+}
+
+#[test]
+fn memory_out_of_range() {
+	let memarg = MemArg {
+		align: 2,
+		max_align: 2,
+		offset: 0,
+		memory: 1,
+	};
+
+	let list = [
+		Operator::I32Const { value: 0 },
+		Operator::I32Load { memarg },
+		Operator::End,
+	];
+
+	let error = build_anonymous("(module (memory 1))", &list);
+
+	assert!(matches!(
+		error,
+		Error::IndexOutOfRange {
+			location,
+			space: IndexSpace::Memory,
+			index: 1,
+		} if location == at_operator(1)
+	));
+}
+
+#[test]
+fn function_out_of_range() {
+	let list = [Operator::RefFunc { function_index: 1 }, Operator::End];
+	let error = build_anonymous("(module (func))", &list);
+
+	assert!(matches!(
+		error,
+		Error::IndexOutOfRange {
+			space: IndexSpace::Function,
+			index: 1,
+			..
+		}
+	));
+}
+
+// Relaxed SIMD passes validation when enabled but cannot be translated,
+// so every function using it fails while the others still build
+static PARTLY_FAILING: &str = r#"
+(module
+	(func (param v128) (result v128)
+		local.get 0
+		i32x4.relaxed_trunc_f32x4_s)
+	(func (result i32)
+		i32.const 1)
+	(func (param v128) (result v128)
+		local.get 0
+		i32x4.relaxed_trunc_f32x4_u))
+"#;
+
+fn with_relaxed_simd(bytes: &[u8]) -> Module<'_> {
+	let features = WasmFeatures {
+		relaxed_simd: true,
+		..supported_features()
+	};
+
+	Module::try_from_data_with_features(bytes, features).unwrap()
+}
+
+#[test]
+fn every_failing_function_is_reported() {
+	let bytes = to_bytes(PARTLY_FAILING);
+	let wasm = with_relaxed_simd(&bytes);
+	let type_info = TypeInfo::from_module(&wasm);
+	let mut builder = Factory::from_type_info(&type_info);
+
+	assert!(builder.create_indexed(1, &wasm.code_section()[1]).is_ok());
+
+	let Err(Error::List(list)) = builder.create_module(&wasm) else {
+		panic!("both failing functions should be reported");
+	};
+
+	assert_eq!(list.len(), 2);
+
+	for (error, index) in list.iter().zip([0, 2]) {
+		let range = wasm.code_section()[index].range();
+		let location = error.location().unwrap();
+
+		assert!(matches!(error, Error::UnsupportedOperator { .. }));
+		assert_eq!(location.function, Some(index));
+		assert!(range.contains(&location.offset));
+	}
+}
+
+#[test]
+fn failing_functions_are_replaced() {
+	let bytes = to_bytes(PARTLY_FAILING);
+	let wasm = with_relaxed_simd(&bytes);
+	let type_info = TypeInfo::from_module(&wasm);
+	let (func_list, error_list) = Factory::from_type_info(&type_info).create_module_partial(&wasm);
+
+	assert_eq!(func_list.len(), 3);
+	assert_eq!(error_list.len(), 2);
+
+	for index in [0, 2] {
+		let ast = &func_list[index];
+
+		assert_eq!(ast.param_data().len(), 1);
+		assert_eq!(ast.num_result(), 1);
+		assert!(ast.code().code().is_empty());
+		assert!(matches!(ast.code().last(), Some(Terminator::Unreachable)));
+	}
+
+	assert!(func_list[1].code().last().is_none());
+}
+
+#[test]
+fn failing_functions_do_not_stop_translation() {
+	type Translator = fn(&Module, &TypeInfo, &Options, &mut dyn Write) -> Result<()>;
+
+	let bytes = to_bytes(PARTLY_FAILING);
+	let wasm = with_relaxed_simd(&bytes);
+	let type_info = TypeInfo::from_module(&wasm);
+	let options = Options {
+		skip_failed: true,
+		..Options::default()
+	};
+
+	let translator_list: [Translator; 2] = [
+		codegen_luau::from_module_optimized,
+		codegen_luajit::from_module_optimized,
+	];
+
+	for translator in translator_list {
+		let mut output = Vec::new();
+
+		assert!(translator(&wasm, &type_info, &Options::default(), &mut Vec::new()).is_err());
+
+		translator(&wasm, &type_info, &options, &mut output).unwrap();
+
+		let output = String::from_utf8(output).unwrap();
+
+		assert_eq!(output.matches(r#"error("out of code bounds")"#).count(), 2);
+		assert!(output.contains("FUNC_LIST[1] ="));
+	}
+}
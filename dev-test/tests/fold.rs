@@ -0,0 +1,246 @@
+use wasm_ast::{
+	node::{BinOpType, CmpOpType, Expression, Statement, UnOpType, Value},
+	optimize::fold,
+};
+
+use common::build;
+
+mod common;
+
+// Folds the only function of a module and returns the expression that
+// its result is set to
+fn fold_result(source: &str) -> Expression {
+	let mut ast = build(source, 0);
+
+	fold::run(&mut ast);
+
+	match ast.code().code().last() {
+		Some(Statement::SetTemporary(v)) => v.value().clone(),
+		_ => panic!("result should be set last"),
+	}
+}
+
+fn as_i32(value: Option<Value>) -> i32 {
+	let Some(Value::I32(value)) = value else {
+		panic!("expected an i32, got {value:?}");
+	};
+
+	value
+}
+
+fn as_i64(value: Option<Value>) -> i64 {
+	let Some(Value::I64(value)) = value else {
+		panic!("expected an i64, got {value:?}");
+	};
+
+	value
+}
+
+fn as_f32(value: Option<Value>) -> f32 {
+	let Some(Value::F32(value)) = value else {
+		panic!("expected an f32, got {value:?}");
+	};
+
+	value
+}
+
+fn as_f64(value: Option<Value>) -> f64 {
+	let Some(Value::F64(value)) = value else {
+		panic!("expected an f64, got {value:?}");
+	};
+
+	value
+}
+
+#[test]
+fn integer_wrap() {
+	let add = fold::bin_op(BinOpType::Add_I32, Value::I32(i32::MAX), Value::I32(1));
+	let sub = fold::bin_op(BinOpType::Sub_I64, Value::I64(i64::MIN), Value::I64(1));
+	let mul = fold::bin_op(BinOpType::Mul_I32, Value::I32(0x1000_0000), Value::I32(16));
+	let neg = fold::bin_op(BinOpType::Sub_I32, Value::I32(0), Value::I32(i32::MIN));
+
+	assert_eq!(as_i32(add), i32::MIN);
+	assert_eq!(as_i64(sub), i64::MAX);
+	assert_eq!(as_i32(mul), 0);
+	assert_eq!(as_i32(neg), i32::MIN);
+
+	let wrap = fold::un_op(UnOpType::Wrap_I32_I64, Value::I64(0x1_8000_0001));
+
+	assert_eq!(as_i32(wrap), i32::MIN + 1);
+}
+
+#[test]
+fn unsigned_operations() {
+	let div = fold::bin_op(BinOpType::DivU_I32, Value::I32(-1), Value::I32(2));
+	let rem = fold::bin_op(BinOpType::RemU_I64, Value::I64(-1), Value::I64(10));
+	let less = fold::cmp_op(CmpOpType::LtU_I32, Value::I32(1), Value::I32(-1));
+
+	assert_eq!(as_i32(div), i32::MAX);
+	assert_eq!(as_i64(rem), 5);
+	assert_eq!(as_i32(less), 1);
+}
+
+#[test]
+fn division_traps_are_kept() {
+	let trapping = [
+		(BinOpType::DivS_I32, Value::I32(1), Value::I32(0)),
+		(BinOpType::DivU_I32, Value::I32(1), Value::I32(0)),
+		(BinOpType::RemS_I32, Value::I32(1), Value::I32(0)),
+		(BinOpType::RemU_I32, Value::I32(1), Value::I32(0)),
+		(BinOpType::DivS_I32, Value::I32(i32::MIN), Value::I32(-1)),
+		(BinOpType::DivS_I64, Value::I64(1), Value::I64(0)),
+		(BinOpType::DivU_I64, Value::I64(1), Value::I64(0)),
+		(BinOpType::RemS_I64, Value::I64(1), Value::I64(0)),
+		(BinOpType::RemU_I64, Value::I64(1), Value::I64(0)),
+		(BinOpType::DivS_I64, Value::I64(i64::MIN), Value::I64(-1)),
+	];
+
+	for (op_type, lhs, rhs) in trapping {
+		assert!(
+			fold::bin_op(op_type, lhs, rhs).is_none(),
+			"{op_type:?} folded"
+		);
+	}
+
+	// The remainder of the most negative value by negative one is zero
+	// rather than an overflow
+	let rem_32 = fold::bin_op(BinOpType::RemS_I32, Value::I32(i32::MIN), Value::I32(-1));
+	let rem_64 = fold::bin_op(BinOpType::RemS_I64, Value::I64(i64::MIN), Value::I64(-1));
+
+	assert_eq!(as_i32(rem_32), 0);
+	assert_eq!(as_i64(rem_64), 0);
+}
+
+#[test]
+fn division_traps_stay_in_function() {
+	let divide =
+		fold_result("(module (func (result i32) (i32.div_s (i32.const 1) (i32.const 0))))");
+	let overflow = fold_result(
+		"(module (func (result i64) (i64.div_s (i64.const 0x8000000000000000) (i64.const -1))))",
+	);
+	let truncate = fold_result("(module (func (result i32) (i32.trunc_f32_s (f32.const nan))))");
+
+	assert!(matches!(divide, Expression::BinOp(_)));
+	assert!(matches!(overflow, Expression::BinOp(_)));
+	assert!(matches!(truncate, Expression::UnOp(_)));
+
+	let folded = fold_result("(module (func (result i32) (i32.add (i32.const -1) (i32.const 2))))");
+
+	assert!(matches!(folded, Expression::Value(Value::I32(1))));
+}
+
+#[test]
+fn shift_counts_are_masked() {
+	let shl = fold::bin_op(BinOpType::Shl_I32, Value::I32(1), Value::I32(33));
+	let shr_s = fold::bin_op(BinOpType::ShrS_I32, Value::I32(i32::MIN), Value::I32(-1));
+	let shr_u = fold::bin_op(BinOpType::ShrU_I64, Value::I64(-1), Value::I64(64 + 60));
+	let rotl = fold::bin_op(BinOpType::Rotl_I32, Value::I32(1), Value::I32(32 + 4));
+	let rotr = fold::bin_op(BinOpType::Rotr_I64, Value::I64(1), Value::I64(-1));
+
+	assert_eq!(as_i32(shl), 2);
+	assert_eq!(as_i32(shr_s), -1);
+	assert_eq!(as_i64(shr_u), 15);
+	assert_eq!(as_i32(rotl), 16);
+	assert_eq!(as_i64(rotr), 2);
+}
+
+#[test]
+fn nan_propagates() {
+	let nan = f64::from_bits(0x7FF8_0000_0000_0001);
+	let add = fold::bin_op(BinOpType::Add_F64, Value::F64(nan), Value::F64(1.0));
+	let min = fold::bin_op(BinOpType::Min_F32, Value::F32(1.0), Value::F32(f32::NAN));
+	let max = fold::bin_op(BinOpType::Max_F64, Value::F64(nan), Value::F64(1.0));
+
+	assert!(as_f64(add).is_nan());
+	assert!(as_f32(min).is_nan());
+	assert!(as_f64(max).is_nan());
+
+	let equal = fold::cmp_op(CmpOpType::Eq_F64, Value::F64(nan), Value::F64(nan));
+	let not_equal = fold::cmp_op(
+		CmpOpType::Ne_F32,
+		Value::F32(f32::NAN),
+		Value::F32(f32::NAN),
+	);
+
+	assert_eq!(as_i32(equal), 0);
+	assert_eq!(as_i32(not_equal), 1);
+
+	// Negation and the absolute value only touch the sign bit
+	let neg = fold::un_op(UnOpType::Neg_F64, Value::F64(nan));
+	let abs = fold::un_op(UnOpType::Abs_F32, Value::F32(-f32::NAN));
+
+	assert_eq!(as_f64(neg).to_bits(), 0xFFF8_0000_0000_0001);
+	assert_eq!(as_f32(abs).to_bits(), f32::NAN.to_bits());
+}
+
+#[test]
+fn signed_zero() {
+	let min = fold::bin_op(BinOpType::Min_F64, Value::F64(0.0), Value::F64(-0.0));
+	let max = fold::bin_op(BinOpType::Max_F32, Value::F32(-0.0), Value::F32(0.0));
+	let copysign = fold::bin_op(BinOpType::Copysign_F64, Value::F64(1.0), Value::F64(-0.0));
+	let neg = fold::un_op(UnOpType::Neg_F32, Value::F32(0.0));
+
+	assert_eq!(as_f64(min).to_bits(), (-0.0_f64).to_bits());
+	assert_eq!(as_f32(max).to_bits(), 0.0_f32.to_bits());
+	assert_eq!(as_f64(copysign).to_bits(), (-1.0_f64).to_bits());
+	assert_eq!(as_f32(neg).to_bits(), (-0.0_f32).to_bits());
+
+	let equal = fold::cmp_op(CmpOpType::Eq_F32, Value::F32(0.0), Value::F32(-0.0));
+
+	assert_eq!(as_i32(equal), 1);
+}
+
+#[test]
+fn nearest_rounds_ties_to_even() {
+	let list = [
+		(0.5, 0.0),
+		(1.5, 2.0),
+		(2.5, 2.0),
+		(-0.5, -0.0),
+		(-1.5, -2.0),
+		(-2.5, -2.0),
+		(0.49, 0.0),
+		(-0.3, -0.0),
+		(4_503_599_627_370_495.5, 4_503_599_627_370_496.0),
+		(f64::INFINITY, f64::INFINITY),
+	];
+
+	for (value, expected) in list {
+		let result = fold::un_op(UnOpType::Nearest_F64, Value::F64(value));
+
+		assert_eq!(as_f64(result).to_bits(), f64::to_bits(expected), "{value}");
+	}
+
+	let list = [(0.5, 0.0), (3.5, 4.0), (-2.5, -2.0), (-0.5, -0.0)];
+
+	for (value, expected) in list {
+		let result = fold::un_op(UnOpType::Nearest_F32, Value::F32(value));
+
+		assert_eq!(as_f32(result).to_bits(), f32::to_bits(expected), "{value}");
+	}
+
+	let nan = fold::un_op(UnOpType::Nearest_F64, Value::F64(f64::NAN));
+
+	assert!(as_f64(nan).is_nan());
+}
+
+#[test]
+fn float_conversion_traps_are_kept() {
+	let trapping = [
+		(UnOpType::Truncate_I32_F32, Value::F32(f32::NAN)),
+		(UnOpType::Truncate_I32_F64, Value::F64(2_147_483_648.0)),
+		(UnOpType::Truncate_U32_F64, Value::F64(-1.0)),
+		(UnOpType::Truncate_I64_F64, Value::F64(f64::INFINITY)),
+		(UnOpType::Truncate_U64_F32, Value::F32(-1.0)),
+	];
+
+	for (op_type, rhs) in trapping {
+		assert!(fold::un_op(op_type, rhs).is_none(), "{op_type:?} folded");
+	}
+
+	let truncate = fold::un_op(UnOpType::Truncate_U32_F64, Value::F64(-0.9));
+	let saturate = fold::un_op(UnOpType::Saturate_I32_F32, Value::F32(f32::NAN));
+
+	assert_eq!(as_i32(truncate), 0);
+	assert_eq!(as_i32(saturate), 0);
+}
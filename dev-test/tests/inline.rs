@@ -0,0 +1,124 @@
+use wasm_ast::{
+	factory::Factory,
+	module::{Module, TypeInfo},
+	node::{Block, FuncData, Statement},
+	optimize::inline,
+};
+
+use common::to_bytes;
+
+mod common;
+
+// The callee leaves its inner block with `br_if`, returns early from
+// inside it, and otherwise branches out of the outer block with a value,
+// so its labels, alignments and variables must all be remapped
+static BRANCHING: &str = r#"
+(module
+	(func $callee (param i32 i32) (result i32) (local i64)
+		(i32.const 100)
+		(block $outer (result i32)
+			(block $inner
+				(br_if $inner (local.get 0))
+				(return (i32.const 7)))
+			(local.set 2 (i64.extend_i32_u (local.get 1)))
+			(i32.const 5)
+			(br $outer (i32.wrap_i64 (local.get 2))))
+		i32.add)
+	(func $caller (param i32) (result i32) (local f32 i32)
+		(local.set 2 (i32.const 3))
+		(block $kept
+			(br_if $kept (local.get 0)))
+		(i32.add
+			(local.get 2)
+			(call $callee (local.get 0) (i32.const 2)))))
+"#;
+
+fn inline_all(source: &str, threshold: usize) -> Vec<FuncData> {
+	let bytes = to_bytes(source);
+	let wasm = Module::try_from_data(&bytes).unwrap();
+	let type_info = TypeInfo::from_module(&wasm);
+	let mut builder = Factory::from_type_info(&type_info);
+	let mut func_list: Vec<_> = wasm
+		.code_section()
+		.iter()
+		.enumerate()
+		.map(|(i, v)| builder.create_indexed(i, v).unwrap())
+		.collect();
+
+	inline::run(&mut func_list, &type_info, 0, threshold);
+
+	func_list
+}
+
+fn assert_no_label(block: &Block) {
+	assert_eq!(block.label(), None);
+
+	for stat in block.code() {
+		if let Statement::Block(v) = stat {
+			assert_no_label(v);
+		}
+	}
+}
+
+#[test]
+fn inlines_branches_and_early_return() {
+	let func_list = inline_all(BRANCHING, 1000);
+	let output = func_list[1].to_string();
+
+	// Parameters and the extra local take new locals after those of the
+	// caller, with the local zeroed before the body
+	assert!(output.contains("locals [F32, I32, I32, I32, I64]"));
+	assert!(output.contains("loc_3 = loc_0\n\tloc_4 = 2_i32\n\tloc_5 = 0_i64\n"));
+	assert!(output.contains("br_if loc_3 @0"));
+	assert!(output.contains("loc_5 = (Extend_I64_U32 loc_4)"));
+
+	// Temporaries, including those moved by branches, start after the
+	// ones of the caller
+	assert!(output.contains("tmp_2 = 100_i32"));
+	assert!(output.contains("br @2 [2] <- [3]"));
+	assert!(output.contains("br @0 [3] <- [4]"));
+	assert!(output.contains("tmp_2 = (Add_I32 tmp_2 tmp_3)"));
+	assert!(output.contains("tmp_1 = tmp_2\n\ttmp_0 = (Add_I32 loc_2 tmp_1)"));
+	assert!(output.contains("temporaries [I32, I32, I32, I32, I32]"));
+	assert!(!output.contains("call"));
+}
+
+#[test]
+fn inlined_blocks_drop_labels() {
+	let func_list = inline_all(BRANCHING, 1000);
+	let code = func_list[1].code().code();
+
+	let Statement::Block(kept) = &code[1] else {
+		panic!("caller block should come first");
+	};
+
+	let Some(Statement::Block(inlined)) = code.iter().rev().nth(2) else {
+		panic!("inlined body should be a block");
+	};
+
+	assert_eq!(kept.label(), Some(0));
+	assert_no_label(inlined);
+}
+
+#[test]
+fn large_or_calling_functions_are_kept() {
+	let source = r#"
+		(module
+			(func $leaf (result i32)
+				(i32.const 1))
+			(func $branch (result i32)
+				(call $leaf))
+			(func $root (result i32)
+				(i32.add (call $branch) (call $leaf))))
+	"#;
+
+	let func_list = inline_all(source, 1000);
+	let output = func_list[2].to_string();
+
+	assert!(output.contains("call 1()"));
+	assert!(!output.contains("call 0()"));
+
+	let func_list = inline_all(source, 1);
+
+	assert!(func_list[2].to_string().contains("call 0()"));
+}
@@ -25,13 +25,30 @@ do
 		end
 	end
 
+	local function lane_checked(name, size)
+		local old_load = assert(rt.load_lane[name], "Missing load lane function " .. name)
+		local old_store = assert(rt.store_lane[name], "Missing store lane function " .. name)
+
+		rt.load_lane[name] = function(memory, addr, value, index)
+			assert(is_valid_address(memory, addr, size), "Invalid memory read")
+
+			return old_load(memory, addr, value, index)
+		end
+
+		rt.store_lane[name] = function(memory, addr, value, index)
+			assert(is_valid_address(memory, addr, size), "Invalid memory write")
+
+			return old_store(memory, addr, value, index)
+		end
+	end
+
 	do
-		local old = rt.store.string
+		local old = rt.store.init
 
-		function rt.store.string(memory, addr, data, len)
-			assert(is_valid_address(memory, addr, #data), "Invalid memory write")
+		function rt.store.init(memory, addr, data, offset, len)
+			assert(is_valid_address(memory, addr, len), "Invalid memory write")
 
-			return old(memory, addr, data, len)
+			return old(memory, addr, data, offset, len)
 		end
 	end
 
@@ -49,6 +66,19 @@ do
 	load_checked("i64", 8)
 	load_checked("f32", 4)
 	load_checked("f64", 8)
+	load_checked("v128", 16)
+	load_checked("i16x8_i8x8", 8)
+	load_checked("i16x8_u8x8", 8)
+	load_checked("i32x4_i16x4", 8)
+	load_checked("i32x4_u16x4", 8)
+	load_checked("i64x2_i32x2", 8)
+	load_checked("i64x2_u32x2", 8)
+	load_checked("splat_n8", 1)
+	load_checked("splat_n16", 2)
+	load_checked("splat_n32", 4)
+	load_checked("splat_n64", 8)
+	load_checked("zero_n32", 4)
+	load_checked("zero_n64", 8)
 
 	store_checked("i32_n8", 1)
 	store_checked("i32_n16", 2)
@@ -59,8 +89,16 @@ do
 	store_checked("i64", 8)
 	store_checked("f32", 4)
 	store_checked("f64", 8)
+	store_checked("v128", 16)
+
+	lane_checked("i8x16", 1)
+	lane_checked("i16x8", 2)
+	lane_checked("i32x4", 4)
+	lane_checked("i64x2", 8)
 end
 
+local ffi = require("ffi")
+
 local loaded = {}
 local linked = {}
 
@@ -69,6 +107,23 @@ local LUA_NAN_CANONICAL = -(0 / 0)
 local LUA_NAN_DEFAULT = -(0 / 0)
 local LUA_INFINITY = math.huge
 
+local REF_NULL = "ref.null"
+local REF_FUNC = "ref.func"
+
+local function ref_extern(index)
+	return "ref.extern " .. index
+end
+
+local function is_ref_equal(lhs, rhs)
+	if rhs == REF_NULL then
+		return lhs == nil
+	elseif rhs == REF_FUNC then
+		return type(lhs) == "function"
+	end
+
+	return false
+end
+
 local function is_number_equal(lhs, rhs)
 	if type(lhs) ~= "number" or type(rhs) ~= "number" then
 		return false
@@ -77,13 +132,55 @@ local function is_number_equal(lhs, rhs)
 	return math.abs(lhs - rhs) < 0.00001 or string.format("%.3g", lhs) == string.format("%.3g", rhs)
 end
 
+local V128_LANE_LIST = {
+	i8x16 = "i8",
+	i16x8 = "i16",
+	i32x4 = "i32",
+	f32x4 = "f32",
+	f64x2 = "f64",
+}
+
+local function v128_pattern(shape, lanes)
+	return { shape = shape, lanes = lanes }
+end
+
+local function is_v128_equal(lhs, rhs)
+	if not ffi.istype("union V128", lhs) or type(rhs) ~= "table" or rhs.shape == nil then
+		return false
+	end
+
+	local source = lhs[V128_LANE_LIST[rhs.shape]]
+
+	for i, v in ipairs(rhs.lanes) do
+		local data = source[i - 1]
+
+		if data ~= v and not (data ~= data and v ~= v) and not is_number_equal(data, v) then
+			return false
+		end
+	end
+
+	return true
+end
+
+local function to_string(data)
+	if type(data) == "table" and data.shape ~= nil then
+		return "v128 " .. data.shape .. " { " .. table.concat(data.lanes, ", ") .. " }"
+	elseif ffi.istype("union V128", data) then
+		local source = data.u32
+
+		return string.format("v128 %08x %08x %08x %08x", source[0], source[1], source[2], source[3])
+	end
+
+	return tostring(data)
+end
+
 local function assert_eq(lhs, rhs, level)
-	if lhs == rhs or is_number_equal(lhs, rhs) then
+	if is_ref_equal(lhs, rhs) or is_v128_equal(lhs, rhs) or lhs == rhs or is_number_equal(lhs, rhs) then
 		return
 	end
 
-	lhs = tostring(lhs)
-	rhs = tostring(rhs)
+	lhs = to_string(lhs)
+	rhs = to_string(rhs)
 	level = (level or 1) + 1
 
 	error(lhs .. " ~= " .. rhs, level)
@@ -94,8 +191,8 @@ local function assert_neq(lhs, rhs, level)
 		return
 	end
 
-	lhs = tostring(lhs)
-	rhs = tostring(rhs)
+	lhs = to_string(lhs)
+	rhs = to_string(rhs)
 	level = (level or 1) + 1
 
 	error(lhs .. " == " .. rhs, level)
@@ -140,6 +237,6 @@ linked.spectest = {
 		global_i32 = { value = 666 },
 		global_i64 = { value = 666LL },
 	},
-	table_list = { table = { data = {} } },
+	table_list = { table = { min = 10, max = 20, data = {} } },
 	memory_list = { memory = rt.allocator.new(1, 2) },
 }
@@ -3,7 +3,10 @@ use std::{
 	path::PathBuf,
 };
 
-use wasm_ast::module::{Module, TypeInfo};
+use wasm_ast::{
+	module::{Module, TypeInfo},
+	optimize::Options,
+};
 use wast::{
 	core::{WastArgCore, WastRetCore},
 	WastArg, WastExecute, WastInvoke, WastRet, Wat,
@@ -24,6 +27,9 @@ impl LuaJIT {
 			WastArg::Core(WastArgCore::I64(v)) => write!(w, "{v}LL"),
 			WastArg::Core(WastArgCore::F32(v)) => target::write_f32(f32::from_bits(v.bits), w),
 			WastArg::Core(WastArgCore::F64(v)) => target::write_f64(f64::from_bits(v.bits), w),
+			WastArg::Core(WastArgCore::V128(v)) => target::write_v128(v, w),
+			WastArg::Core(WastArgCore::RefNull(_)) => write!(w, "nil"),
+			WastArg::Core(WastArgCore::RefExtern(v)) => write!(w, "ref_extern({v})"),
 			_ => panic!("Unsupported expression"),
 		}
 	}
@@ -34,6 +40,10 @@ impl LuaJIT {
 			WastRet::Core(WastRetCore::I64(v)) => write!(w, "{v}LL"),
 			WastRet::Core(WastRetCore::F32(v)) => target::write_f32_nan(v, w),
 			WastRet::Core(WastRetCore::F64(v)) => target::write_f64_nan(v, w),
+			WastRet::Core(WastRetCore::V128(v)) => target::write_v128_pattern(v, w),
+			WastRet::Core(WastRetCore::RefNull(_)) => write!(w, "REF_NULL"),
+			WastRet::Core(WastRetCore::RefExtern(v)) => write!(w, "ref_extern({v})"),
+			WastRet::Core(WastRetCore::RefFunc(_)) => write!(w, "REF_FUNC"),
 			_ => panic!("Unsupported expression"),
 		}
 	}
@@ -142,11 +152,16 @@ impl Target for LuaJIT {
 		writeln!(w, "{ASSERTION}")
 	}
 
-	fn write_module(data: &Module, name: Option<&str>, w: &mut dyn Write) -> Result<()> {
+	fn write_module(
+		data: &Module,
+		options: &Options,
+		name: Option<&str>,
+		w: &mut dyn Write,
+	) -> Result<()> {
 		let type_info = TypeInfo::from_module(data);
 
 		writeln!(w, r#"loaded["temp"] = (function()"#)?;
-		codegen_luajit::from_module_typed(data, &type_info, w)?;
+		codegen_luajit::from_module_optimized(data, &type_info, options, w)?;
 		writeln!(w, "end)()(linked)")?;
 
 		if let Some(name) = name {
@@ -157,66 +172,178 @@ impl Target for LuaJIT {
 	}
 }
 
-static DO_NOT_RUN: [&str; 58] = [
-	"names.wast",
-	"skip-stack-guard-page.wast",
-	"simd_address.wast",
-	"simd_align.wast",
-	"simd_bit_shift.wast",
-	"simd_bitwise.wast",
-	"simd_boolean.wast",
-	"simd_const.wast",
-	"simd_conversions.wast",
-	"simd_f32x4_arith.wast",
-	"simd_f32x4_cmp.wast",
-	"simd_f32x4_pmin_pmax.wast",
-	"simd_f32x4_rounding.wast",
-	"simd_f32x4.wast",
-	"simd_f64x2_arith.wast",
-	"simd_f64x2_cmp.wast",
-	"simd_f64x2_pmin_pmax.wast",
-	"simd_f64x2_rounding.wast",
-	"simd_f64x2.wast",
-	"simd_i16x8_arith.wast",
-	"simd_i16x8_arith2.wast",
-	"simd_i16x8_cmp.wast",
-	"simd_i16x8_extadd_pairwise_i8x16.wast",
-	"simd_i16x8_extmul_i8x16.wast",
-	"simd_i16x8_q15mulr_sat_s.wast",
-	"simd_i16x8_sat_arith.wast",
-	"simd_i32x4_arith.wast",
-	"simd_i32x4_arith2.wast",
-	"simd_i32x4_cmp.wast",
-	"simd_i32x4_dot_i16x8.wast",
-	"simd_i32x4_extadd_pairwise_i16x8.wast",
-	"simd_i32x4_extmul_i16x8.wast",
-	"simd_i32x4_trunc_sat_f32x4.wast",
-	"simd_i32x4_trunc_sat_f64x2.wast",
-	"simd_i64x2_arith.wast",
-	"simd_i64x2_arith2.wast",
-	"simd_i64x2_cmp.wast",
-	"simd_i64x2_extmul_i32x4.wast",
-	"simd_i8x16_arith.wast",
-	"simd_i8x16_arith2.wast",
-	"simd_i8x16_cmp.wast",
-	"simd_i8x16_sat_arith.wast",
-	"simd_int_to_int_extend.wast",
-	"simd_lane.wast",
-	"simd_load_extend.wast",
-	"simd_load_splat.wast",
-	"simd_load_zero.wast",
-	"simd_load.wast",
-	"simd_load16_lane.wast",
-	"simd_load32_lane.wast",
-	"simd_load64_lane.wast",
-	"simd_load8_lane.wast",
-	"simd_splat.wast",
-	"simd_store.wast",
-	"simd_store16_lane.wast",
-	"simd_store32_lane.wast",
-	"simd_store64_lane.wast",
-	"simd_store8_lane.wast",
-];
+static DO_NOT_RUN: [&str; 2] = ["names.wast", "skip-stack-guard-page.wast"];
+
+// LuaJIT reuses the frame of the caller for tail calls, so
+// recursion through them can go as deep as it needs to
+static TAIL_CALL: &str = r#"
+(module
+	(type $t (func (param i64) (result i64)))
+	(table funcref (elem $count))
+	(func $even (export "even") (param i64) (result i32)
+		(if (result i32) (i64.eqz (local.get 0))
+			(then (i32.const 1))
+			(else (return_call $odd (i64.sub (local.get 0) (i64.const 1))))))
+	(func $odd (export "odd") (param i64) (result i32)
+		(if (result i32) (i64.eqz (local.get 0))
+			(then (i32.const 0))
+			(else (return_call $even (i64.sub (local.get 0) (i64.const 1))))))
+	(func $count (export "count") (param i64) (result i64)
+		(if (result i64) (i64.eqz (local.get 0))
+			(then (local.get 0))
+			(else (return_call_indirect (type $t)
+				(i64.sub (local.get 0) (i64.const 1))
+				(i32.const 0)))))
+	(global $left (mut i32) (i32.const 0))
+	(func $drain (export "drain") (param i32) (result i32)
+		(global.set $left (local.get 0))
+		(return_call $step))
+	(func $step (result i32)
+		(if (i32.eqz (global.get $left))
+			(then (return (i32.const 7))))
+		(global.set $left (i32.sub (global.get $left) (i32.const 1)))
+		(return_call $step))
+	(func $plain (param i64) (result i64)
+		(i64.add (local.get 0) (i64.const 1)))
+	(func (export "last") (param i64) (result i64)
+		(return_call $plain (local.get 0))))
+
+(assert_return (invoke "even" (i64.const 1000000)) (i32.const 1))
+(assert_return (invoke "odd" (i64.const 1000001)) (i32.const 1))
+(assert_return (invoke "count" (i64.const 1000000)) (i64.const 0))
+(assert_return (invoke "drain" (i32.const 1000000)) (i32.const 7))
+(assert_return (invoke "last" (i64.const 1)) (i64.const 2))
+"#;
+
+// Indirect calls and table accesses each name the table they use, and
+// typed `select` works on any value type, references included
+static MULTI_TABLE: &str = r#"
+(module
+	(type $unary (func (param i32) (result i32)))
+	(table $first funcref (elem $double))
+	(table $second funcref (elem $negate $double))
+	(table $refs 2 externref)
+	(func $double (param i32) (result i32)
+		(i32.mul (local.get 0) (i32.const 2)))
+	(func $negate (param i32) (result i32)
+		(i32.sub (i32.const 0) (local.get 0)))
+	(func (export "call_first") (param i32 i32) (result i32)
+		(call_indirect $first (type $unary) (local.get 0) (local.get 1)))
+	(func (export "call_second") (param i32 i32) (result i32)
+		(call_indirect $second (type $unary) (local.get 0) (local.get 1)))
+	(func (export "copy") (param i32 i32)
+		(table.set $first (local.get 0) (table.get $second (local.get 1))))
+	(func (export "get") (param i32) (result externref)
+		(table.get $refs (local.get 0)))
+	(func (export "set") (param i32 externref)
+		(table.set $refs (local.get 0) (local.get 1)))
+	(func (export "pick") (param i32 i64 i64) (result i64)
+		(select (result i64) (local.get 1) (local.get 2) (local.get 0)))
+	(func (export "pick_ref") (param i32 externref externref) (result externref)
+		(select (result externref) (local.get 1) (local.get 2) (local.get 0))))
+
+(assert_return (invoke "call_first" (i32.const 5) (i32.const 0)) (i32.const 10))
+(assert_return (invoke "call_second" (i32.const 5) (i32.const 0)) (i32.const -5))
+(assert_return (invoke "call_second" (i32.const 5) (i32.const 1)) (i32.const 10))
+(assert_trap (invoke "call_first" (i32.const 5) (i32.const 1)) "undefined element")
+(invoke "copy" (i32.const 0) (i32.const 0))
+(assert_return (invoke "call_first" (i32.const 5) (i32.const 0)) (i32.const -5))
+(assert_return (invoke "get" (i32.const 1)) (ref.null extern))
+(invoke "set" (i32.const 1) (ref.extern 7))
+(assert_return (invoke "get" (i32.const 1)) (ref.extern 7))
+(assert_return (invoke "get" (i32.const 0)) (ref.null extern))
+(assert_trap (invoke "get" (i32.const 2)) "out of bounds table access")
+(assert_return (invoke "pick" (i32.const 1) (i64.const 3) (i64.const 4)) (i64.const 3))
+(assert_return (invoke "pick" (i32.const 0) (i64.const 3) (i64.const 4)) (i64.const 4))
+(assert_return (invoke "pick_ref" (i32.const 1) (ref.extern 1) (ref.extern 2)) (ref.extern 1))
+(assert_return (invoke "pick_ref" (i32.const 0) (ref.extern 1) (ref.extern 2)) (ref.extern 2))
+"#;
+
+// Every atomic access traps on addresses that are not a multiple of its
+// size, and waiting is only allowed on shared memories
+static ATOMIC: &str = r#"
+(module
+	(memory 1 1 shared)
+	(func (export "load") (param i32) (result i32)
+		(i32.atomic.load (local.get 0)))
+	(func (export "load_offset") (param i32) (result i32)
+		(i32.atomic.load offset=2 (local.get 0)))
+	(func (export "load16") (param i32) (result i32)
+		(i32.atomic.load16_u (local.get 0)))
+	(func (export "store") (param i32 i64)
+		(i64.atomic.store (local.get 0) (local.get 1)))
+	(func (export "add") (param i32 i32) (result i32)
+		(i32.atomic.rmw.add (local.get 0) (local.get 1)))
+	(func (export "cmpxchg") (param i32 i64 i64) (result i64)
+		(i64.atomic.rmw.cmpxchg (local.get 0) (local.get 1) (local.get 2)))
+	(func (export "wait") (param i32 i32) (result i32)
+		(memory.atomic.wait32 (local.get 0) (local.get 1) (i64.const 0)))
+	(func (export "notify") (param i32) (result i32)
+		(memory.atomic.notify (local.get 0) (i32.const 1))))
+
+(assert_return (invoke "load" (i32.const 4)) (i32.const 0))
+(assert_trap (invoke "load" (i32.const 2)) "unaligned atomic")
+(assert_return (invoke "load_offset" (i32.const 2)) (i32.const 0))
+(assert_trap (invoke "load_offset" (i32.const 0)) "unaligned atomic")
+(assert_return (invoke "load16" (i32.const 2)) (i32.const 0))
+(assert_trap (invoke "load16" (i32.const 1)) "unaligned atomic")
+(invoke "store" (i32.const 8) (i64.const 5))
+(assert_trap (invoke "store" (i32.const 4) (i64.const 5)) "unaligned atomic")
+(assert_return (invoke "add" (i32.const 8) (i32.const 1)) (i32.const 5))
+(assert_trap (invoke "add" (i32.const 6) (i32.const 1)) "unaligned atomic")
+(assert_return (invoke "cmpxchg" (i32.const 8) (i64.const 6) (i64.const 7)) (i64.const 6))
+(assert_return (invoke "load" (i32.const 8)) (i32.const 7))
+(assert_trap (invoke "cmpxchg" (i32.const 12) (i64.const 0) (i64.const 1)) "unaligned atomic")
+(assert_return (invoke "wait" (i32.const 0) (i32.const 1)) (i32.const 1))
+(assert_return (invoke "wait" (i32.const 0) (i32.const 0)) (i32.const 2))
+(assert_trap (invoke "wait" (i32.const 2) (i32.const 0)) "unaligned atomic")
+(assert_return (invoke "notify" (i32.const 0)) (i32.const 0))
+(assert_trap (invoke "notify" (i32.const 1)) "unaligned atomic")
+
+(module
+	(memory 1)
+	(func (export "wait32") (result i32)
+		(memory.atomic.wait32 (i32.const 0) (i32.const 0) (i64.const 0)))
+	(func (export "wait64") (result i32)
+		(memory.atomic.wait64 (i32.const 0) (i64.const 0) (i64.const 0)))
+	(func (export "notify") (result i32)
+		(memory.atomic.notify (i32.const 0) (i32.const 1))))
+
+(assert_trap (invoke "wait32") "expected shared memory")
+(assert_trap (invoke "wait64") "expected shared memory")
+(assert_return (invoke "notify") (i32.const 0))
+"#;
+
+// The `try` body reads more locals than a closure may capture, so the
+// ones past the limit are spilled into a table it captures instead
+fn wide_try(count: usize) -> String {
+	let local = "i32 ".repeat(count);
+	let set: String = (1..=count)
+		.map(|i| format!("(local.set {i} (i32.add (local.get 0) (i32.const {i})))"))
+		.collect();
+	let sum: String = (1..=count)
+		.map(|i| format!("(local.get {i}) i32.add "))
+		.collect();
+	let total = (1..=count).sum::<usize>();
+
+	format!(
+		r#"
+(module
+	(func (export "sum") (param i32) (result i32) (local {local})
+		{set}
+		(try (result i32)
+			(do
+				(local.get 0)
+				{sum})
+			(catch_all
+				(i32.const -1)))))
+
+(assert_return (invoke "sum" (i32.const 0)) (i32.const {total}))
+(assert_return (invoke "sum" (i32.const 1)) (i32.const {}))
+"#,
+		total + count + 1
+	)
+}
 
 #[test_generator::test_resources("dev-test/spec/*.wast")]
 fn translate_file(path: PathBuf) {
@@ -229,5 +356,46 @@ fn translate_file(path: PathBuf) {
 
 	let source = std::fs::read_to_string(path).unwrap();
 
-	LuaJIT::test(name, &source).unwrap();
+	LuaJIT::test(name, &source, &Options::default()).unwrap();
+}
+
+// Every pass, including inlining, must leave the results of the
+// spec tests unchanged
+#[test_generator::test_resources("dev-test/spec/*.wast")]
+fn translate_file_optimized(path: PathBuf) {
+	let path = path.strip_prefix("dev-test/").unwrap();
+	let name = path.file_name().unwrap().to_str().unwrap();
+
+	if DO_NOT_RUN.contains(&name) {
+		return;
+	}
+
+	let source = std::fs::read_to_string(path).unwrap();
+
+	LuaJIT::test(&format!("optimized_{name}"), &source, &Options::all()).unwrap();
+}
+
+#[test]
+fn tail_call_depth() {
+	LuaJIT::test("tail_call_depth", TAIL_CALL, &Options::default()).unwrap();
+}
+
+#[test]
+fn multiple_tables() {
+	LuaJIT::test("multiple_tables", MULTI_TABLE, &Options::default()).unwrap();
+	LuaJIT::test("optimized_multiple_tables", MULTI_TABLE, &Options::all()).unwrap();
+}
+
+#[test]
+fn atomic_traps() {
+	LuaJIT::test("atomic_traps", ATOMIC, &Options::default()).unwrap();
+	LuaJIT::test("optimized_atomic_traps", ATOMIC, &Options::all()).unwrap();
+}
+
+#[test]
+fn wide_try_body() {
+	let source = wide_try(70);
+
+	LuaJIT::test("wide_try_body", &source, &Options::default()).unwrap();
+	LuaJIT::test("optimized_wide_try_body", &source, &Options::all()).unwrap();
 }
@@ -0,0 +1,192 @@
+use std::fmt::Write;
+
+use wasm_ast::{
+	module::{Module, TypeInfo},
+	node::{Block, FuncData, Statement},
+	optimize::Options,
+	outline::Outline,
+};
+
+use common::{build, to_bytes};
+
+mod common;
+
+// A loop around several blocks of arithmetic, large enough together
+// that the loop has to be split up to fit
+fn oversized() -> String {
+	let mut source = String::from(
+		"(module (func (export \"run\") (param i32) (result i32) (local i32 i32)\n(loop $again\n",
+	);
+
+	for block in 0..4 {
+		source.push_str("(block\n");
+
+		for i in 0..700 {
+			let local = 1 + (block + i) % 2;
+
+			writeln!(
+				source,
+				"(local.set {local} (i32.add (local.get {local}) (i32.const {i})))"
+			)
+			.unwrap();
+		}
+
+		source.push_str(")\n");
+	}
+
+	source.push_str("(br_if $again (local.tee 0 (i32.sub (local.get 0) (i32.const 1)))))\n");
+	source.push_str("(i32.add (local.get 1) (local.get 2))))");
+
+	source
+}
+
+fn translate(
+	translator: fn(&Module, &TypeInfo, &Options, &mut Vec<u8>) -> std::io::Result<()>,
+) -> String {
+	let bytes = to_bytes(&oversized());
+	let wasm = Module::try_from_data(&bytes).unwrap();
+	let type_info = TypeInfo::from_module(&wasm);
+	let mut output = Vec::new();
+
+	translator(&wasm, &type_info, &Options::default(), &mut output).unwrap();
+
+	String::from_utf8(output).unwrap()
+}
+
+// Definitions are written once at the start of the function, before the
+// loop they are called from, and never inside of it
+fn assert_hoisted(output: &str, loop_start: &str) {
+	let line_list: Vec<_> = output.lines().collect();
+	let is_definition = |line: &&str| line.contains("local function outlined_");
+
+	let definition_list: Vec<_> = (0..line_list.len())
+		.filter(|&i| is_definition(&line_list[i]))
+		.collect();
+
+	let first_loop = line_list
+		.iter()
+		.position(|v| v.trim_start().starts_with(loop_start))
+		.unwrap();
+
+	assert_eq!(definition_list.len(), 3);
+
+	for index in definition_list {
+		assert!(index < first_loop);
+		assert!(line_list[index].starts_with("\tlocal function"));
+
+		let name = line_list[index]
+			.trim_start()
+			.trim_start_matches("local function ");
+
+		let call = line_list
+			.iter()
+			.rposition(|v| v.trim_start() == name)
+			.unwrap();
+
+		assert!(call > first_loop);
+		assert!(line_list[call].starts_with("\t\t"));
+	}
+}
+
+#[test]
+fn oversized_function_is_outlined() {
+	let ast = build(&oversized(), 0);
+	let outline = Outline::new(&ast, 8000, 100);
+
+	let Statement::Block(body) = &ast.code().code()[0] else {
+		panic!("function should start with its loop");
+	};
+
+	let nested: Vec<_> = body
+		.code()
+		.iter()
+		.filter_map(|v| match v {
+			Statement::Block(v) => Some(v),
+			_ => None,
+		})
+		.collect();
+
+	// The largest blocks are taken out until the loop is well within
+	// the limit, which leaves the loop itself small enough to keep
+	assert_eq!(nested.len(), 4);
+	assert_eq!(outline.len(), 3);
+	assert_eq!(nested.iter().filter(|v| outline.contains(v)).count(), 3);
+	assert!(!outline.contains(body));
+
+	// Nothing is outlined in functions that are small enough already
+	assert!(Outline::new(&ast, 100_000, 100).is_empty());
+}
+
+#[test]
+fn luau_outlined_functions_are_hoisted() {
+	let output = translate(|wasm, type_info, options, w| {
+		codegen_luau::from_module_optimized(wasm, type_info, options, w)
+	});
+
+	assert_hoisted(&output, "repeat");
+}
+
+#[test]
+fn luajit_outlined_functions_are_hoisted() {
+	let output = translate(|wasm, type_info, options, w| {
+		codegen_luajit::from_module_optimized(wasm, type_info, options, w)
+	});
+
+	assert_hoisted(&output, "repeat");
+}
+
+fn catch_all_block(ast: &FuncData, index: usize) -> &Block {
+	let Some(Statement::Try(try_)) = ast
+		.code()
+		.code()
+		.iter()
+		.find(|v| matches!(v, Statement::Try(_)))
+	else {
+		panic!("function should have a `try`");
+	};
+
+	let Statement::Block(block) = &try_.catch_all().unwrap().code()[index] else {
+		panic!("handler should be made of blocks");
+	};
+
+	block
+}
+
+// Caught exceptions are held in locals of the handler, which outlined
+// blocks are not defined in
+#[test]
+fn rethrowing_blocks_are_kept() {
+	let ast = build(
+		r#"
+		(module
+			(tag $error)
+			(func (param i32) (result i32)
+				(try
+					(do
+						(throw $error))
+					(catch_all
+						(block
+							(local.set 0 (i32.add (local.get 0) (i32.const 1)))
+							(local.set 0 (i32.mul (local.get 0) (i32.const 2)))
+							(local.set 0 (i32.xor (local.get 0) (i32.const 3)))
+							(br_if 0 (local.get 0))
+							(rethrow 1))
+						(block
+							(local.set 0 (i32.add (local.get 0) (i32.const 4)))
+							(local.set 0 (i32.mul (local.get 0) (i32.const 5)))
+							(local.set 0 (i32.xor (local.get 0) (i32.const 6)))
+							(try
+								(do
+									(throw $error))
+								(catch_all
+									(rethrow 0))))))
+				(local.get 0)))
+		"#,
+		0,
+	);
+
+	let outline = Outline::new(&ast, 10, 100);
+
+	assert!(!outline.contains(catch_all_block(&ast, 0)));
+	assert!(outline.contains(catch_all_block(&ast, 1)));
+}
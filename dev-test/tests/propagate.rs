@@ -0,0 +1,174 @@
+use wasm_ast::optimize::{liveness::Liveness, propagate};
+
+use common::build;
+
+mod common;
+
+fn propagate(source: &str) -> String {
+	let mut ast = build(source, 0);
+
+	propagate::run(&mut ast);
+
+	ast.to_string()
+}
+
+// The block forces the sum into a temporary, which is then read once
+// by the statement after it
+#[test]
+fn moves_value_past_unrelated_block() {
+	let output = propagate(
+		r#"
+		(module
+			(func (param i32 i32) (result i32)
+				(i32.add (local.get 0) (local.get 1))
+				(block (br_if 0 (local.get 1)))
+				(i32.const 1)
+				i32.add))
+		"#,
+	);
+
+	assert!(output.contains("tmp_0 = (Add_I32 (Add_I32 loc_0 loc_1) 1_i32)"));
+	assert!(!output.contains("tmp_0 = (Add_I32 loc_0 loc_1)"));
+}
+
+#[test]
+fn keeps_value_before_write_it_depends_on() {
+	let output = propagate(
+		r#"
+		(module
+			(memory 1)
+			(func (param i32 i32) (result i32)
+				(i32.load (local.get 0))
+				(block (i32.store (local.get 1) (i32.const 1)))
+				(i32.const 1)
+				i32.add))
+		"#,
+	);
+
+	let load = output.find("tmp_0 = (load I32").unwrap();
+	let store = output.find("store I32").unwrap();
+
+	assert!(load < store);
+	assert!(output.contains("tmp_0 = (Add_I32 tmp_0 1_i32)"));
+}
+
+// Nested blocks are simplified before the block around them, and both
+// must find the live sets of their own statements
+#[test]
+fn moves_values_in_nested_and_outer_blocks() {
+	let output = propagate(
+		r#"
+		(module
+			(func (param i32 i32) (result i32) (local i32)
+				(i32.add (local.get 0) (local.get 1))
+				(block
+					(i32.mul (local.get 0) (local.get 1))
+					(block (br_if 0 (local.get 1)))
+					(local.set 2))
+				(local.get 2)
+				i32.add))
+		"#,
+	);
+
+	assert!(output.contains("loc_2 = (Mul_I32 loc_0 loc_1)"));
+	assert!(output.contains("tmp_0 = (Add_I32 (Add_I32 loc_0 loc_1) loc_2)"));
+	assert!(!output.contains("tmp_1"));
+}
+
+#[test]
+fn keeps_trapping_value_in_order() {
+	let output = propagate(
+		r#"
+		(module
+			(func (param i32 i32) (result i32)
+				(i32.div_s (local.get 0) (local.get 1))
+				(block (br_if 0 (local.get 1)))
+				(i32.const 1)
+				i32.add))
+		"#,
+	);
+
+	assert!(output.contains("tmp_0 = (DivS_I32 loc_0 loc_1)"));
+	assert!(output.contains("tmp_0 = (Add_I32 tmp_0 1_i32)"));
+}
+
+// Once the dead write to the parameter is gone, the division is right
+// before its reader, but a `Select` arm may never be evaluated
+#[test]
+fn keeps_trapping_value_out_of_select_arm() {
+	let output = propagate(
+		r#"
+		(module
+			(func (param i32 i32) (result i32)
+				(i32.div_s (local.get 0) (local.get 1))
+				(local.set 0 (i32.const 5))
+				(i32.const 0)
+				(local.get 1)
+				select))
+		"#,
+	);
+
+	assert!(!output.contains("loc_0 = 5_i32"));
+	assert!(output.contains("tmp_0 = (DivS_I32 loc_0 loc_1)"));
+	assert!(output.contains("tmp_0 = (select loc_1 tmp_0 0_i32)"));
+}
+
+#[test]
+fn moves_trapping_value_into_select_condition() {
+	let output = propagate(
+		r#"
+		(module
+			(func (param i32 i32) (result i32)
+				(i32.const 0)
+				(local.get 1)
+				(i32.div_s (local.get 0) (local.get 1))
+				(local.set 0 (i32.const 5))
+				select))
+		"#,
+	);
+
+	assert!(output.contains("tmp_0 = (select (DivS_I32 loc_0 loc_1) 0_i32 loc_1)"));
+}
+
+#[test]
+fn moves_pure_value_into_select_arm() {
+	let output = propagate(
+		r#"
+		(module
+			(func (param i32 i32) (result i32)
+				(i32.add (local.get 0) (local.get 1))
+				(local.set 0 (i32.const 5))
+				(i32.const 0)
+				(local.get 1)
+				select))
+		"#,
+	);
+
+	assert!(output.contains("tmp_0 = (select loc_1 (Add_I32 loc_0 loc_1) 0_i32)"));
+}
+
+#[test]
+fn liveness_is_found_by_position() {
+	let ast = build(
+		r#"
+		(module
+			(func (param i32) (result i32) (local i32)
+				(loop
+					(local.set 0 (i32.add (local.get 1) (local.get 0)))
+					(br_if 0 (local.get 0)))
+				(local.get 1)))
+		"#,
+		0,
+	);
+
+	let liveness = Liveness::new(&ast);
+	let body = &liveness.nested(0)[0];
+
+	// The local is read in the loop before any write to it
+	assert!(liveness.entry().local.contains(&1));
+
+	// Both are live across the back edge of the loop
+	assert!(body.point(0).after.local.contains(&0));
+	assert!(body.point(1).middle.local.contains(&1));
+	assert!(liveness.point(1).after.temporary.contains(&0));
+}
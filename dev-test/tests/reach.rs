@@ -0,0 +1,161 @@
+use wasm_ast::{
+	factory::Factory,
+	module::{Module, TypeInfo},
+	optimize::reach::Reachable,
+};
+
+use common::to_bytes;
+
+mod common;
+
+fn reach(source: &str) -> Reachable {
+	let bytes = to_bytes(source);
+	let wasm = Module::try_from_data(&bytes).unwrap();
+	let type_info = TypeInfo::from_module(&wasm);
+	let func_list = Factory::from_type_info(&type_info)
+		.create_module(&wasm)
+		.unwrap();
+
+	Reachable::new(&wasm, &func_list).unwrap()
+}
+
+#[test]
+fn exports_are_roots() {
+	let reachable = reach(
+		r#"
+		(module
+			(global $kept (mut i32) (i32.const 0))
+			(global $dropped i32 (i32.const 1))
+			(func $callee)
+			(func $unused)
+			(func (export "run")
+				(call $callee))
+			(export "kept" (global $kept)))
+		"#,
+	);
+
+	assert!(reachable.has_function(0));
+	assert!(!reachable.has_function(1));
+	assert!(reachable.has_function(2));
+	assert!(reachable.has_global(0));
+	assert!(!reachable.has_global(1));
+}
+
+#[test]
+fn start_function_is_a_root() {
+	let reachable = reach(
+		r#"
+		(module
+			(global $count (mut i32) (i32.const 0))
+			(func $start
+				(global.set $count (i32.const 1)))
+			(func $unused)
+			(start $start))
+		"#,
+	);
+
+	assert!(reachable.has_function(0));
+	assert!(!reachable.has_function(1));
+	assert!(reachable.has_global(0));
+}
+
+#[test]
+fn global_initializers_are_followed() {
+	let reachable = reach(
+		r#"
+		(module
+			(import "env" "base" (global $base i32))
+			(global $table funcref (ref.func $target))
+			(global $copy i32 (global.get $base))
+			(global $unused funcref (ref.func $other))
+			(func $target)
+			(func $other)
+			(func (export "run") (result i32)
+				(global.get $copy))
+			(export "table" (global $table)))
+		"#,
+	);
+
+	assert!(reachable.has_global(0));
+	assert!(reachable.has_global(1));
+	assert!(reachable.has_global(2));
+	assert!(!reachable.has_global(3));
+	assert!(reachable.has_function(0));
+	assert!(!reachable.has_function(1));
+}
+
+#[test]
+fn imports_are_always_used() {
+	let reachable = reach(
+		r#"
+		(module
+			(import "env" "log" (func $log (param i32)))
+			(import "env" "seed" (global $seed i32))
+			(func $unused
+				(call $log (global.get $seed))))
+		"#,
+	);
+
+	assert!(reachable.has_function(0));
+	assert!(reachable.has_global(0));
+	assert!(!reachable.has_function(1));
+}
+
+#[test]
+fn active_segments_are_roots() {
+	let reachable = reach(
+		r#"
+		(module
+			(memory 1)
+			(table 1 funcref)
+			(func $in_table)
+			(elem (i32.const 0) $in_table)
+			(data (i32.const 0) "active"))
+		"#,
+	);
+
+	assert!(reachable.has_function(0));
+	assert!(reachable.has_element(0));
+	assert!(reachable.has_data(0));
+}
+
+#[test]
+fn passive_segments_need_a_user() {
+	let reachable = reach(
+		r#"
+		(module
+			(memory 1)
+			(table 1 funcref)
+			(func $initialized)
+			(func $pruned)
+			(func $dropped)
+			(func $declared)
+			(elem $used funcref (ref.func $initialized))
+			(elem $unused func $pruned)
+			(elem $only_dropped func $dropped)
+			(elem declare func $declared)
+			(data $copied "copied")
+			(data $unread "unread")
+			(data $discarded "discarded")
+			(func (export "run")
+				(table.init $used (i32.const 0) (i32.const 0) (i32.const 1))
+				(elem.drop $only_dropped)
+				(memory.init $copied (i32.const 0) (i32.const 0) (i32.const 1))
+				(data.drop $discarded)))
+		"#,
+	);
+
+	assert!(reachable.has_element(0));
+	assert!(!reachable.has_element(1));
+	assert!(reachable.has_element(2));
+	assert!(!reachable.has_element(3));
+	assert!(reachable.has_data(0));
+	assert!(!reachable.has_data(1));
+	assert!(reachable.has_data(2));
+
+	// Functions of declared segments are only used through `ref.func`
+	assert!(reachable.has_function(0));
+	assert!(!reachable.has_function(1));
+	assert!(reachable.has_function(2));
+	assert!(!reachable.has_function(3));
+}
@@ -0,0 +1,112 @@
+use wasm_ast::{
+	module::{Module, TypeInfo},
+	optimize::Options,
+};
+
+use common::to_bytes;
+
+mod common;
+
+static NAMED: &str = r#"
+(module
+	(global $base (import "env" "base") i32)
+	(global $hit-count (mut i32) (global.get $base))
+	(memory $heap 1)
+	(table $fns 2 funcref)
+	(elem $init (i32.const 0) $add $add)
+	(data $greeting (i32.const 0) "hi")
+	(data $sign-off "bye")
+	(func $add (export "add") (param $lhs i32) (param $rhs i32) (result i32)
+		(local $sum i32)
+		(global.set $hit-count (i32.add (global.get $hit-count) (i32.const 1)))
+		(block $done
+			(loop $again
+				(local.set $sum (i32.add (local.get $lhs) (local.get $rhs)))
+				(br_if $again (i32.eqz (local.get $sum)))
+				(br_if $done (local.get $sum))))
+		(memory.init $sign-off (i32.const 4) (i32.const 0) (i32.const 3))
+		(i32.store $heap (i32.const 0) (local.get $sum))
+		(call_indirect $fns (param i32 i32) (result i32)
+			(local.get $sum)
+			(local.get $sum)
+			(i32.const 1))))
+"#;
+
+fn translate(
+	translator: fn(&Module, &TypeInfo, &Options, &mut Vec<u8>) -> std::io::Result<()>,
+) -> String {
+	let bytes = to_bytes(NAMED);
+	let wasm = Module::try_from_data(&bytes).unwrap();
+	let type_info = TypeInfo::from_module(&wasm);
+	let mut output = Vec::new();
+
+	translator(&wasm, &type_info, &Options::default(), &mut output).unwrap();
+
+	String::from_utf8(output).unwrap()
+}
+
+fn assert_module_names(output: &str) {
+	let expected = [
+		"func_add_0",
+		"loc_lhs_0",
+		"loc_rhs_1",
+		"loc_sum_2",
+		"local memory_heap_0",
+		"store_i32(memory_heap_0, ",
+		"TABLE_LIST.table_fns_0 = ",
+		"TABLE_LIST.table_fns_0.data[",
+		"ELEM_LIST.elem_init_0 = ",
+		"DATA_LIST.data_greeting_0 = ",
+		"DATA_LIST.data_sign_off_1 = ",
+		"rt.store.init(memory_heap_0, 4, DATA_LIST.data_sign_off_1, ",
+		"GLOBAL_LIST.global_base_0 = ",
+		"GLOBAL_LIST.global_hit_count_1 = { value = GLOBAL_LIST.global_base_0.value }",
+		"GLOBAL_LIST.global_hit_count_1.value = ",
+	];
+
+	for name in expected {
+		assert!(output.contains(name), "`{name}` missing from output");
+	}
+
+	assert!(!output.contains("memory_at_0"));
+	assert!(!output.contains("reg_1"));
+	assert!(!output.contains("GLOBAL_LIST["));
+}
+
+#[test]
+fn luau_named_output() {
+	let output = translate(|wasm, type_info, options, w| {
+		codegen_luau::from_module_readable(wasm, type_info, options, w)
+	});
+
+	assert_module_names(&output);
+	assert!(output.contains("-- label_done_0"));
+	assert!(output.contains("-- label_again_1"));
+}
+
+#[test]
+fn luajit_named_output() {
+	let output = translate(|wasm, type_info, options, w| {
+		codegen_luajit::from_module_readable(wasm, type_info, options, w)
+	});
+
+	assert_module_names(&output);
+	assert!(output.contains("::label_done_0::"));
+	assert!(output.contains("::label_again_1::"));
+	assert!(output.contains("goto label_again_1"));
+}
+
+#[test]
+fn unnamed_output_is_unchanged() {
+	let bytes = to_bytes("(module (memory 1) (func (i32.store (i32.const 0) (i32.const 1))))");
+	let wasm = Module::try_from_data(&bytes).unwrap();
+	let type_info = TypeInfo::from_module(&wasm);
+	let mut readable = Vec::new();
+	let mut typed = Vec::new();
+
+	codegen_luajit::from_module_readable(&wasm, &type_info, &Options::default(), &mut readable)
+		.unwrap();
+	codegen_luajit::from_module_typed(&wasm, &type_info, &mut typed).unwrap();
+
+	assert_eq!(readable, typed);
+}
@@ -0,0 +1,189 @@
+use wasm_ast::{
+	node::Statement,
+	optimize::register::{Allocation, Location},
+};
+
+use common::build;
+
+mod common;
+
+// Keeps several sums live at once so that they cannot share registers
+static MANY_LIVE: &str = r#"
+(module
+	(func (param i32 i32 i32) (result i32) (local i32 i32 i32 i32)
+		(local.set 3 (i32.add (local.get 0) (local.get 1)))
+		(local.set 4 (i32.add (local.get 1) (local.get 2)))
+		(local.set 5 (i32.add (local.get 2) (local.get 0)))
+		(local.set 6 (i32.mul (local.get 3) (local.get 4)))
+		(i32.add
+			(i32.add (local.get 3) (local.get 4))
+			(i32.add (local.get 5) (local.get 6)))))
+"#;
+
+#[test]
+fn parameters_keep_their_registers() {
+	let ast = build(MANY_LIVE, 0);
+
+	for available in [0, 2, 100] {
+		let allocation = Allocation::new(&ast, available, |_| false);
+
+		for var in 0..3 {
+			assert_eq!(allocation.local(var), Location::Register(var));
+			assert_eq!(allocation.owner(var), Some(var));
+		}
+
+		for var in 3..7 {
+			assert!(!matches!(allocation.local(var), Location::Register(0..=2)));
+		}
+	}
+}
+
+#[test]
+fn spills_once_registers_run_out() {
+	let ast = build(MANY_LIVE, 0);
+	let roomy = Allocation::new(&ast, 100, |_| false);
+
+	assert_eq!(roomy.num_spill(), 0);
+	assert!(roomy.num_register() <= 3 + 100);
+
+	let tight = Allocation::new(&ast, 2, |_| false);
+	let spilled = (3..7)
+		.filter(|&var| matches!(tight.local(var), Location::Spill(_)))
+		.count();
+
+	assert_eq!(tight.num_register(), 3 + 2);
+	assert!(tight.num_spill() > 0);
+	assert!(spilled > 0);
+
+	// Every spill slot is within the table of spilled values
+	for var in 3..7 {
+		if let Location::Spill(index) = tight.local(var) {
+			assert!(index < tight.num_spill());
+		}
+	}
+}
+
+#[test]
+fn named_locals_own_their_registers() {
+	let ast = build(MANY_LIVE, 0);
+	let allocation = Allocation::new(&ast, 100, |var| var == 5);
+
+	let Location::Register(register) = allocation.local(5) else {
+		panic!("named local should not be spilled");
+	};
+
+	assert_eq!(allocation.owner(register), Some(5));
+
+	for var in (0..7).filter(|&v| v != 5) {
+		assert_ne!(allocation.local(var), Location::Register(register));
+	}
+}
+
+#[test]
+fn locals_read_before_written_start_at_zero() {
+	let ast = build(
+		r#"
+		(module
+			(func (param i32) (result i32) (local i32 i32 i32)
+				(local.set 2 (i32.const 7))
+				(loop $again
+					(local.set 1 (i32.add (local.get 1) (local.get 2)))
+					(br_if $again (local.get 0)))
+				(local.set 3 (local.get 1))
+				(local.get 3)))
+		"#,
+		0,
+	);
+
+	let allocation = Allocation::new(&ast, 100, |_| false);
+
+	// The parameter is set by the caller, the second local is written
+	// before it is read, and so is the last one
+	assert_eq!(allocation.initial_list(), [1]);
+}
+
+// The caught value is written when the handler starts, so it must not
+// overwrite the local that is read after the handler
+#[test]
+fn catch_results_interfere_with_live_values() {
+	let ast = build(
+		r#"
+		(module
+			(tag $error (param i32))
+			(func (param i32) (result i32) (local i32)
+				(local.set 1 (i32.mul (local.get 0) (i32.const 3)))
+				(try (result i32)
+					(do
+						(throw $error (local.get 0)))
+					(catch $error))
+				(local.get 1)
+				i32.add))
+		"#,
+		0,
+	);
+
+	let Some(Statement::Try(try_)) = ast
+		.code()
+		.code()
+		.iter()
+		.find(|v| matches!(v, Statement::Try(_)))
+	else {
+		panic!("function should have a `try`");
+	};
+
+	let result_list: Vec<_> = try_.catch_list()[0].result_list().iter().collect();
+
+	assert_eq!(result_list.len(), 1);
+
+	for available in [1, 100] {
+		let allocation = Allocation::new(&ast, available, |_| false);
+		let result = allocation.temporary(result_list[0]);
+
+		assert_ne!(result, allocation.local(0));
+		assert_ne!(result, allocation.local(1));
+	}
+}
+
+// A `try` body is a closure, so what it mentions past the limit is spilled
+// into the table it captures as a single upvalue
+#[test]
+fn try_bodies_capture_at_most_the_limit() {
+	let ast = build(
+		r#"
+		(module
+			(func (param i32) (result i32) (local i32 i32 i32 i32)
+				(local.set 1 (i32.add (local.get 0) (i32.const 1)))
+				(local.set 2 (i32.add (local.get 0) (i32.const 2)))
+				(local.set 3 (i32.add (local.get 0) (i32.const 3)))
+				(local.set 4 (i32.add (local.get 0) (i32.const 4)))
+				(try (result i32)
+					(do
+						(i32.add
+							(i32.add (local.get 1) (local.get 2))
+							(i32.add (local.get 3) (local.get 4))))
+					(catch_all
+						(local.get 0)))))
+		"#,
+		0,
+	);
+
+	let mut allocation = Allocation::new(&ast, 100, |_| false);
+
+	assert_eq!(allocation.num_spill(), 0);
+
+	allocation.limit_capture(&ast, 2);
+
+	let kept = (1..5)
+		.filter(|&var| matches!(allocation.local(var), Location::Register(_)))
+		.count();
+
+	assert_eq!(allocation.local(0), Location::Register(0));
+	assert!(kept <= 2);
+	assert!(allocation.num_spill() > 0);
+
+	for var in 1..5 {
+		if let Location::Spill(index) = allocation.local(var) {
+			assert!(index < allocation.num_spill());
+		}
+	}
+}
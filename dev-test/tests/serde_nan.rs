@@ -0,0 +1,24 @@
+use common::{assert_round_trip, build, to_bytes};
+
+mod common;
+
+static NAN: &str = r#"
+(module
+	(func (result f32)
+		f32.const nan:0x200000)
+	(func (result f64)
+		f64.const -nan
+		f64.const 1.5
+		f64.add))
+"#;
+
+// NaN never equals itself, so this only passes if float constants
+// are stored by their bits
+#[test]
+fn round_trip_nan() {
+	assert_round_trip(&to_bytes(NAN));
+
+	let serialized = serde_json::to_string(&build(NAN, 0)).unwrap();
+
+	assert!(serialized.contains(&0x7FA0_0000_u32.to_string()));
+}
@@ -0,0 +1,32 @@
+use std::path::PathBuf;
+
+use wast::{parser::ParseBuffer, QuoteWat, Wast, WastDirective, Wat};
+
+use common::assert_round_trip;
+
+mod common;
+
+static DO_NOT_RUN: [&str; 2] = ["names.wast", "skip-stack-guard-page.wast"];
+
+#[test_generator::test_resources("dev-test/spec/*.wast")]
+fn round_trip_file(path: PathBuf) {
+	let path = path.strip_prefix("dev-test/").unwrap();
+	let name = path.file_name().unwrap().to_str().unwrap();
+
+	if DO_NOT_RUN.contains(&name) {
+		return;
+	}
+
+	let source = std::fs::read_to_string(path).unwrap();
+
+	let lexed = ParseBuffer::new(&source).expect("Failed to tokenize");
+	let parsed: Wast = wast::parser::parse(&lexed).unwrap();
+
+	for variant in parsed.directives {
+		if let WastDirective::Wat(QuoteWat::Wat(Wat::Module(mut ast))) = variant {
+			let bytes = ast.encode().unwrap();
+
+			assert_round_trip(&bytes);
+		}
+	}
+}
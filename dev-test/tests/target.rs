@@ -5,7 +5,7 @@ use std::{
 	process::Command,
 };
 
-use wasm_ast::module::Module as AstModule;
+use wasm_ast::{module::Module as AstModule, optimize::Options};
 use wast::{
 	core::Module as WaModule, parser::ParseBuffer, token::Id, QuoteWat, Wast, WastDirective,
 	WastExecute, WastInvoke, WastRet, Wat,
@@ -44,6 +44,75 @@ macro_rules! impl_write_number_nan {
 impl_write_number_nan!(write_f32, write_f32_nan, f32, wast::token::Float32);
 impl_write_number_nan!(write_f64, write_f64_nan, f64, wast::token::Float64);
 
+#[allow(clippy::missing_errors_doc)]
+pub fn write_v128(data: &wast::core::V128Const, w: &mut dyn Write) -> Result<()> {
+	let data = data.to_le_bytes();
+	let data_1 = u32::from_le_bytes(data[0..4].try_into().unwrap());
+	let data_2 = u32::from_le_bytes(data[4..8].try_into().unwrap());
+	let data_3 = u32::from_le_bytes(data[8..12].try_into().unwrap());
+	let data_4 = u32::from_le_bytes(data[12..16].try_into().unwrap());
+
+	write!(
+		w,
+		"rt.v128.from_u32({data_1}, {data_2}, {data_3}, {data_4})"
+	)
+}
+
+fn write_lane_list<T: std::fmt::Display>(shape: &str, list: &[T], w: &mut dyn Write) -> Result<()> {
+	write!(w, r#"v128_pattern("{shape}", {{ "#)?;
+
+	list.iter().try_for_each(|v| write!(w, "{v}, "))?;
+
+	write!(w, "}})")
+}
+
+// 64 bit lanes are compared as pairs of 32 bit lanes so that
+// the assertions do not need to know how integers are represented
+#[allow(clippy::missing_errors_doc)]
+pub fn write_v128_pattern(data: &wast::core::V128Pattern, w: &mut dyn Write) -> Result<()> {
+	use wast::core::V128Pattern;
+
+	match data {
+		V128Pattern::I8x16(list) => write_lane_list("i8x16", list, w),
+		V128Pattern::I16x8(list) => write_lane_list("i16x8", list, w),
+		V128Pattern::I32x4(list) => write_lane_list("i32x4", list, w),
+		V128Pattern::I64x2(list) => {
+			let list: Vec<_> = list
+				.iter()
+				.flat_map(|v| {
+					let data = v.to_le_bytes();
+					let data_1 = i32::from_le_bytes(data[0..4].try_into().unwrap());
+					let data_2 = i32::from_le_bytes(data[4..8].try_into().unwrap());
+
+					[data_1, data_2]
+				})
+				.collect();
+
+			write_lane_list("i32x4", &list, w)
+		}
+		V128Pattern::F32x4(list) => {
+			write!(w, r#"v128_pattern("f32x4", {{ "#)?;
+
+			list.iter().try_for_each(|v| {
+				write_f32_nan(v, w)?;
+				write!(w, ", ")
+			})?;
+
+			write!(w, "}})")
+		}
+		V128Pattern::F64x2(list) => {
+			write!(w, r#"v128_pattern("f64x2", {{ "#)?;
+
+			list.iter().try_for_each(|v| {
+				write_f64_nan(v, w)?;
+				write!(w, ", ")
+			})?;
+
+			write!(w, "}})")
+		}
+	}
+}
+
 #[allow(clippy::missing_const_for_fn)]
 fn try_into_ast_module(data: QuoteWat) -> Option<WaModule> {
 	if let QuoteWat::Wat(Wat::Module(data)) = data {
@@ -77,9 +146,14 @@ pub trait Target: Sized {
 
 	fn write_runtime(w: &mut dyn Write) -> Result<()>;
 
-	fn write_module(data: &AstModule, name: Option<&str>, w: &mut dyn Write) -> Result<()>;
+	fn write_module(
+		data: &AstModule,
+		options: &Options,
+		name: Option<&str>,
+		w: &mut dyn Write,
+	) -> Result<()>;
 
-	fn write_variant(variant: WastDirective, w: &mut dyn Write) -> Result<()> {
+	fn write_variant(variant: WastDirective, options: &Options, w: &mut dyn Write) -> Result<()> {
 		match variant {
 			WastDirective::Wat(data) => {
 				let mut ast = try_into_ast_module(data).expect("Must be a module");
@@ -88,7 +162,7 @@ pub trait Target: Sized {
 				let data = AstModule::try_from_data(&bytes).unwrap();
 				let name = ast.id.as_ref().map(Id::name);
 
-				Self::write_module(&data, name, w)?;
+				Self::write_module(&data, options, name, w)?;
 			}
 			WastDirective::Register { name, module, .. } => {
 				let pre = get_name_from_id(module);
@@ -127,7 +201,7 @@ pub trait Target: Sized {
 		}
 	}
 
-	fn run_generation(source: &str) -> Result<Vec<u8>> {
+	fn run_generation(source: &str, options: &Options) -> Result<Vec<u8>> {
 		let lexed = ParseBuffer::new(source).expect("Failed to tokenize");
 		let parsed: Wast = wast::parser::parse(&lexed).unwrap();
 
@@ -136,14 +210,14 @@ pub trait Target: Sized {
 		Self::write_runtime(&mut data)?;
 
 		for variant in parsed.directives {
-			Self::write_variant(variant, &mut data)?;
+			Self::write_variant(variant, options, &mut data)?;
 		}
 
 		Ok(data)
 	}
 
-	fn test(name: &str, source: &str) -> Result<()> {
-		let data = Self::run_generation(source)?;
+	fn test(name: &str, source: &str, options: &Options) -> Result<()> {
+		let data = Self::run_generation(source, options)?;
 		let temp = PathBuf::from(env!("CARGO_TARGET_TMPDIR"))
 			.join(name)
 			.with_extension("wast.lua");
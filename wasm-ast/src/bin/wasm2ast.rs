@@ -0,0 +1,83 @@
+use std::io::{ErrorKind, Result, Write};
+
+use wasm_ast::{
+	factory::Factory,
+	module::{External, Module, TypeInfo},
+};
+
+fn load_arg_source() -> Result<Vec<u8>> {
+	let mut arguments = std::env::args();
+	let path = arguments.next().unwrap_or_else(|| "wasm2ast".to_string());
+
+	arguments.next().map_or_else(
+		|| {
+			eprintln!("usage: {path} <file>\n");
+
+			Err(ErrorKind::NotFound.into())
+		},
+		std::fs::read,
+	)
+}
+
+fn do_sections(wasm: &Module, w: &mut dyn Write) -> Result<()> {
+	writeln!(w, "types {}", wasm.type_section().len())?;
+	writeln!(w, "functions {}", wasm.function_space())?;
+	writeln!(w, "tables {}", wasm.table_space())?;
+	writeln!(w, "memories {}", wasm.memory_space())?;
+	writeln!(w, "globals {}", wasm.global_space())?;
+	writeln!(w, "tags {}", wasm.tag_space())?;
+	writeln!(w, "elements {}", wasm.element_section().len())?;
+	writeln!(w, "data {}", wasm.data_section().len())?;
+
+	if let Some(start) = wasm.start_section() {
+		writeln!(w, "start {start}")?;
+	}
+
+	writeln!(w)
+}
+
+fn do_imports(wasm: &Module, w: &mut dyn Write) -> Result<()> {
+	for v in wasm.import_section() {
+		writeln!(w, "import {:?} {:?} {:?}", v.module, v.name, v.ty)?;
+	}
+
+	for v in wasm.export_section() {
+		writeln!(w, "export {:?} {:?} {}", v.name, v.kind, v.index)?;
+	}
+
+	writeln!(w)
+}
+
+fn do_functions(wasm: &Module, type_info: &TypeInfo, w: &mut dyn Write) -> Result<()> {
+	let offset = wasm.import_count(External::Func);
+	let mut builder = Factory::from_type_info(type_info);
+
+	for (i, v) in wasm.code_section().iter().enumerate() {
+		let index = offset + i;
+		let func = builder.create_indexed(index, v)?;
+		let name = u32::try_from(index)
+			.ok()
+			.and_then(|v| wasm.name_section().function(v));
+
+		match name {
+			Some(name) => writeln!(w, "function {index} {name:?}")?,
+			None => writeln!(w, "function {index}")?,
+		}
+
+		writeln!(w, "{func}\n")?;
+	}
+
+	Ok(())
+}
+
+fn main() -> Result<()> {
+	let data = load_arg_source()?;
+	let wasm = Module::try_from_data(&data)?;
+	let type_info = TypeInfo::from_module(&wasm);
+
+	let lock = &mut std::io::stdout().lock();
+
+	do_sections(&wasm, lock)?;
+	do_imports(&wasm, lock)?;
+	do_functions(&wasm, &type_info, lock)
+}
@@ -0,0 +1,167 @@
+use wasmparser::{FunctionBody, Operator};
+
+use crate::{
+	error::Result,
+	module::{External, Module},
+	node::{RmwOpType, StoreType},
+};
+
+/// The kinds of module state that running a function may change.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Effect {
+	pub global_write: bool,
+	pub memory_write: bool,
+	pub table_write: bool,
+}
+
+impl Effect {
+	/// Assumed for anything that cannot be looked into.
+	pub const ALL: Self = Self {
+		global_write: true,
+		memory_write: true,
+		table_write: true,
+	};
+
+	#[must_use]
+	pub const fn is_pure(self) -> bool {
+		!self.global_write && !self.memory_write && !self.table_write
+	}
+
+	// Returns whether anything new was added
+	fn merge(&mut self, other: Self) -> bool {
+		let old = *self;
+
+		self.global_write |= other.global_write;
+		self.memory_write |= other.memory_write;
+		self.table_write |= other.table_write;
+
+		*self != old
+	}
+
+	fn add_operator(&mut self, op: &Operator) {
+		match op {
+			Operator::GlobalSet { .. } => self.global_write = true,
+			Operator::I32AtomicRmwCmpxchg { .. }
+			| Operator::I64AtomicRmwCmpxchg { .. }
+			| Operator::I32AtomicRmw8CmpxchgU { .. }
+			| Operator::I32AtomicRmw16CmpxchgU { .. }
+			| Operator::I64AtomicRmw8CmpxchgU { .. }
+			| Operator::I64AtomicRmw16CmpxchgU { .. }
+			| Operator::I64AtomicRmw32CmpxchgU { .. }
+			| Operator::V128Store8Lane { .. }
+			| Operator::V128Store16Lane { .. }
+			| Operator::V128Store32Lane { .. }
+			| Operator::V128Store64Lane { .. }
+			| Operator::MemoryGrow { .. }
+			| Operator::MemoryCopy { .. }
+			| Operator::MemoryFill { .. }
+			| Operator::MemoryInit { .. } => self.memory_write = true,
+			Operator::TableSet { .. }
+			| Operator::TableGrow { .. }
+			| Operator::TableFill { .. }
+			| Operator::TableCopy { .. }
+			| Operator::TableInit { .. } => self.table_write = true,
+			Operator::CallIndirect { .. } | Operator::ReturnCallIndirect { .. } => {
+				self.merge(Self::ALL);
+			}
+			op => {
+				self.memory_write |=
+					StoreType::try_from(op).is_ok() || RmwOpType::try_from(op).is_ok();
+			}
+		}
+	}
+}
+
+/// The direct calls between the functions of a module, along with the
+/// state each function may change when called, including through the
+/// functions it calls. Imported functions and indirect calls are assumed
+/// to change everything. Functions are numbered by their index in the
+/// function space.
+pub struct CallGraph {
+	callee_list: Vec<Vec<usize>>,
+	caller_list: Vec<Vec<usize>>,
+	effect_list: Vec<Effect>,
+}
+
+impl CallGraph {
+	/// # Errors
+	///
+	/// Returns an error if a function body cannot be read.
+	pub fn from_module(wasm: &Module) -> Result<Self> {
+		let len = wasm.function_space();
+		let offset = wasm.import_count(External::Func);
+
+		let mut graph = Self {
+			callee_list: vec![Vec::new(); len],
+			caller_list: vec![Vec::new(); len],
+			effect_list: vec![Effect::ALL; offset],
+		};
+
+		for (i, func) in wasm.code_section().iter().enumerate() {
+			let effect = graph.load_function(offset + i, func)?;
+
+			graph.effect_list.push(effect);
+		}
+
+		graph.load_transitive_effect();
+
+		Ok(graph)
+	}
+
+	/// Returns the functions called directly by a function, in the order
+	/// they first appear.
+	#[must_use]
+	pub fn callee_list(&self, index: usize) -> &[usize] {
+		&self.callee_list[index]
+	}
+
+	/// Returns the functions that call a function directly.
+	#[must_use]
+	pub fn caller_list(&self, index: usize) -> &[usize] {
+		&self.caller_list[index]
+	}
+
+	#[must_use]
+	pub fn effect(&self, index: usize) -> Effect {
+		self.effect_list[index]
+	}
+
+	fn add_call(&mut self, caller: usize, callee: usize) {
+		if self.callee_list[caller].contains(&callee) {
+			return;
+		}
+
+		self.callee_list[caller].push(callee);
+		self.caller_list[callee].push(caller);
+	}
+
+	fn load_function(&mut self, index: usize, func: &FunctionBody) -> Result<Effect> {
+		let mut effect = Effect::default();
+
+		for op in func.get_operators_reader()? {
+			match op? {
+				Operator::Call { function_index } | Operator::ReturnCall { function_index } => {
+					self.add_call(index, function_index.try_into().unwrap());
+				}
+				op => effect.add_operator(&op),
+			}
+		}
+
+		Ok(effect)
+	}
+
+	// Effects flow from callees to their callers until nothing changes
+	fn load_transitive_effect(&mut self) {
+		let mut pending: Vec<_> = (0..self.effect_list.len()).collect();
+
+		while let Some(callee) = pending.pop() {
+			let effect = self.effect_list[callee];
+
+			for &caller in &self.caller_list[callee] {
+				if self.effect_list[caller].merge(effect) {
+					pending.push(caller);
+				}
+			}
+		}
+	}
+}
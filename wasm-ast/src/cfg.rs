@@ -0,0 +1,547 @@
+use std::collections::HashMap;
+
+use crate::node::{Block, BrIf, Catch, FuncData, If, LabelType, Statement, Terminator, Try};
+
+/// How control leaves a basic block once its code has run.
+#[derive(Clone, Copy)]
+pub enum Exit<'a> {
+	/// Control falls through to the only successor, if any.
+	Jump,
+	/// The condition picks between the two arms or the code after them.
+	If(&'a If),
+	/// The condition picks between the branch target and the code after it.
+	BrIf(&'a BrIf),
+	Terminator(&'a Terminator),
+}
+
+/// A run of straight line statements. Statements that nest other
+/// blocks are never part of the code, as they are split into the
+/// basic blocks of their bodies instead.
+pub struct BasicBlock<'a> {
+	code: Vec<&'a Statement>,
+	exit: Exit<'a>,
+	predecessors: Vec<usize>,
+	successors: Vec<usize>,
+}
+
+impl<'a> BasicBlock<'a> {
+	const fn new() -> Self {
+		Self {
+			code: Vec::new(),
+			exit: Exit::Jump,
+			predecessors: Vec::new(),
+			successors: Vec::new(),
+		}
+	}
+
+	#[must_use]
+	pub fn code(&self) -> &[&'a Statement] {
+		&self.code
+	}
+
+	#[must_use]
+	pub const fn exit(&self) -> Exit<'a> {
+		self.exit
+	}
+
+	#[must_use]
+	pub fn predecessors(&self) -> &[usize] {
+		&self.predecessors
+	}
+
+	#[must_use]
+	pub fn successors(&self) -> &[usize] {
+		&self.successors
+	}
+}
+
+/// The control flow graph of a function, along with its dominator tree
+/// and loop nesting. Basic block `0` is the entry and basic block `1` is
+/// an empty exit reached by returning, tail calling, or throwing out of
+/// the function.
+///
+/// Any basic block that calls or throws has an edge to each handler
+/// that could catch the exception, or to the exit if none could. Basic
+/// blocks that can never be reached have no dominator and belong to
+/// no loop.
+pub struct Graph<'a> {
+	block_list: Vec<BasicBlock<'a>>,
+	position_map: HashMap<usize, usize>,
+	dominator: Vec<Option<usize>>,
+	dominated_list: Vec<Vec<usize>>,
+	loop_header: Vec<Option<usize>>,
+	loop_parent: Vec<Option<usize>>,
+}
+
+impl<'a> Graph<'a> {
+	pub const ENTRY: usize = 0;
+	pub const EXIT: usize = 1;
+
+	#[must_use]
+	pub fn new(ast: &'a FuncData) -> Self {
+		let mut builder = Builder {
+			block_list: vec![BasicBlock::new(), BasicBlock::new()],
+			position_map: HashMap::new(),
+			label_list: Vec::new(),
+			handler: vec![Self::EXIT],
+			current: Self::ENTRY,
+		};
+
+		if builder.block(ast.code(), Self::EXIT) {
+			builder.add_edge(builder.current, Self::EXIT);
+		}
+
+		let mut graph = Self {
+			block_list: builder.block_list,
+			position_map: builder.position_map,
+			dominator: Vec::new(),
+			dominated_list: Vec::new(),
+			loop_header: Vec::new(),
+			loop_parent: Vec::new(),
+		};
+
+		graph.load_dominator_list();
+		graph.load_loop_list();
+
+		graph
+	}
+
+	#[must_use]
+	pub fn block_list(&self) -> &[BasicBlock<'a>] {
+		&self.block_list
+	}
+
+	/// Returns the basic block in which a statement of the function
+	/// starts running.
+	#[must_use]
+	pub fn block_of(&self, stat: &Statement) -> usize {
+		let id = stat as *const _ as usize;
+
+		self.position_map[&id]
+	}
+
+	/// Returns the basic block that a terminator of the function ends.
+	#[must_use]
+	pub fn block_of_last(&self, last: &Terminator) -> usize {
+		let id = last as *const _ as usize;
+
+		self.position_map[&id]
+	}
+
+	#[must_use]
+	pub fn immediate_dominator(&self, id: usize) -> Option<usize> {
+		self.dominator[id]
+	}
+
+	/// Returns the children of a basic block in the dominator tree.
+	#[must_use]
+	pub fn dominated_list(&self, id: usize) -> &[usize] {
+		&self.dominated_list[id]
+	}
+
+	/// Tells whether every path from the entry to `other` passes
+	/// through `id`. Every reachable block dominates itself.
+	#[must_use]
+	pub fn dominates(&self, id: usize, other: usize) -> bool {
+		if !self.is_reachable(other) {
+			return false;
+		}
+
+		let mut current = Some(other);
+
+		while let Some(v) = current {
+			if v == id {
+				return true;
+			}
+
+			current = self.dominator[v];
+		}
+
+		false
+	}
+
+	#[must_use]
+	pub fn is_reachable(&self, id: usize) -> bool {
+		id == Self::ENTRY || self.dominator[id].is_some()
+	}
+
+	#[must_use]
+	pub fn is_loop_header(&self, id: usize) -> bool {
+		self.loop_header[id] == Some(id)
+	}
+
+	/// Returns the header of the innermost loop holding a basic block,
+	/// where a header is held by its own loop.
+	#[must_use]
+	pub fn loop_of(&self, id: usize) -> Option<usize> {
+		self.loop_header[id]
+	}
+
+	/// Returns the header of the loop directly around the loop
+	/// with the given header.
+	#[must_use]
+	pub fn loop_parent(&self, header: usize) -> Option<usize> {
+		self.loop_parent[header]
+	}
+
+	/// Returns how many loops hold a basic block.
+	#[must_use]
+	pub fn loop_depth(&self, id: usize) -> usize {
+		let mut depth = 0;
+		let mut current = self.loop_header[id];
+
+		while let Some(v) = current {
+			depth += 1;
+			current = self.loop_parent[v];
+		}
+
+		depth
+	}
+
+	fn find_post_order(&self) -> Vec<usize> {
+		let mut visited = vec![false; self.block_list.len()];
+		let mut order = Vec::with_capacity(self.block_list.len());
+		let mut pending = vec![(Self::ENTRY, 0)];
+
+		visited[Self::ENTRY] = true;
+
+		while let Some((id, next)) = pending.pop() {
+			match self.block_list[id].successors.get(next) {
+				Some(&succ) => {
+					pending.push((id, next + 1));
+
+					if !std::mem::replace(&mut visited[succ], true) {
+						pending.push((succ, 0));
+					}
+				}
+				None => order.push(id),
+			}
+		}
+
+		order
+	}
+
+	// Uses the iterative algorithm by Cooper, Harvey, and Kennedy
+	fn load_dominator_list(&mut self) {
+		let mut order = self.find_post_order();
+		let mut number = vec![usize::MAX; self.block_list.len()];
+
+		for (i, &id) in order.iter().enumerate() {
+			number[id] = i;
+		}
+
+		order.reverse();
+
+		let mut dominator = vec![None; self.block_list.len()];
+		let mut changed = true;
+
+		dominator[Self::ENTRY] = Some(Self::ENTRY);
+
+		while changed {
+			changed = false;
+
+			for &id in &order[1..] {
+				let mut found = None;
+
+				for &pred in &self.block_list[id].predecessors {
+					if dominator[pred].is_none() {
+						continue;
+					}
+
+					found = Some(found.map_or(pred, |mut other| {
+						let mut pred = pred;
+
+						while pred != other {
+							while number[pred] < number[other] {
+								pred = dominator[pred].unwrap();
+							}
+
+							while number[other] < number[pred] {
+								other = dominator[other].unwrap();
+							}
+						}
+
+						pred
+					}));
+				}
+
+				if found != dominator[id] {
+					dominator[id] = found;
+					changed = true;
+				}
+			}
+		}
+
+		dominator[Self::ENTRY] = None;
+
+		let mut dominated_list = vec![Vec::new(); self.block_list.len()];
+
+		for &id in &order {
+			if let Some(parent) = dominator[id] {
+				dominated_list[parent].push(id);
+			}
+		}
+
+		self.dominator = dominator;
+		self.dominated_list = dominated_list;
+	}
+
+	// Finds the natural loop of each back edge, which nest properly as
+	// structured control flow is always reducible
+	fn load_loop_list(&mut self) {
+		let len = self.block_list.len();
+		let mut body_list: Vec<(usize, Vec<bool>, usize)> = Vec::new();
+
+		for header in 0..len {
+			let mut body = vec![false; len];
+			let mut pending: Vec<_> = self.block_list[header]
+				.predecessors
+				.iter()
+				.copied()
+				.filter(|&pred| self.dominates(header, pred))
+				.collect();
+
+			if pending.is_empty() {
+				continue;
+			}
+
+			body[header] = true;
+
+			while let Some(id) = pending.pop() {
+				if !std::mem::replace(&mut body[id], true) {
+					let list = &self.block_list[id].predecessors;
+
+					pending.extend(list.iter().filter(|&&v| self.is_reachable(v)));
+				}
+			}
+
+			let size = body.iter().filter(|&&v| v).count();
+
+			body_list.push((header, body, size));
+		}
+
+		body_list.sort_by_key(|v| v.2);
+
+		self.loop_header = (0..len)
+			.map(|id| body_list.iter().find(|v| v.1[id]).map(|v| v.0))
+			.collect();
+
+		self.loop_parent = vec![None; len];
+
+		for (header, ..) in &body_list {
+			self.loop_parent[*header] = body_list
+				.iter()
+				.find(|v| v.0 != *header && v.1[*header])
+				.map(|v| v.0);
+		}
+	}
+}
+
+struct Label {
+	target: usize,
+	handler: Vec<usize>,
+}
+
+struct Builder<'a> {
+	block_list: Vec<BasicBlock<'a>>,
+	position_map: HashMap<usize, usize>,
+	label_list: Vec<Label>,
+	handler: Vec<usize>,
+	current: usize,
+}
+
+impl<'a> Builder<'a> {
+	fn add_block(&mut self) -> usize {
+		self.block_list.push(BasicBlock::new());
+		self.block_list.len() - 1
+	}
+
+	fn add_edge(&mut self, from: usize, to: usize) {
+		if self.block_list[from].successors.contains(&to) {
+			return;
+		}
+
+		self.block_list[from].successors.push(to);
+		self.block_list[to].predecessors.push(from);
+	}
+
+	fn add_handler_edge(&mut self) {
+		for i in 0..self.handler.len() {
+			self.add_edge(self.current, self.handler[i]);
+		}
+	}
+
+	fn start_block(&mut self) -> usize {
+		let next = self.add_block();
+
+		self.add_edge(self.current, next);
+		self.current = next;
+
+		next
+	}
+
+	fn get_target(&self, target: usize) -> usize {
+		let index = self.label_list.len() - 1 - target;
+
+		self.label_list[index].target
+	}
+
+	fn terminator(&mut self, last: &'a Terminator) {
+		let id = last as *const _ as usize;
+
+		self.position_map.insert(id, self.current);
+		self.block_list[self.current].exit = Exit::Terminator(last);
+
+		match last {
+			Terminator::Unreachable => {}
+			Terminator::Br(v) => self.add_edge(self.current, self.get_target(v.target())),
+			Terminator::BrTable(v) => {
+				for br in v.data() {
+					self.add_edge(self.current, self.get_target(br.target()));
+				}
+
+				self.add_edge(self.current, self.get_target(v.default().target()));
+			}
+			Terminator::Throw(_) | Terminator::Rethrow(_) => self.add_handler_edge(),
+			Terminator::ReturnCall(_) | Terminator::ReturnCallIndirect(_) => {
+				self.add_edge(self.current, Graph::EXIT);
+			}
+		}
+	}
+
+	// Returns whether control can fall off the end of the block
+	fn block(&mut self, block: &'a Block, target: usize) -> bool {
+		self.label_list.push(Label {
+			target,
+			handler: self.handler.clone(),
+		});
+
+		for stat in block.code() {
+			self.statement(stat);
+		}
+
+		if let Some(v) = block.last() {
+			self.terminator(v);
+		}
+
+		self.label_list.pop();
+
+		block.last().is_none()
+	}
+
+	// Arms start in their own basic blocks and meet again in `after`
+	fn arm(&mut self, block: &'a Block, from: usize, after: usize) {
+		self.current = self.add_block();
+		self.add_edge(from, self.current);
+
+		if self.block(block, after) {
+			self.add_edge(self.current, after);
+		}
+	}
+
+	fn block_forward(&mut self, block: &'a Block) {
+		let after = self.add_block();
+
+		if self.block(block, after) {
+			self.add_edge(self.current, after);
+		}
+
+		self.current = after;
+	}
+
+	fn block_backward(&mut self, block: &'a Block) {
+		let header = self.start_block();
+
+		if self.block(block, header) {
+			self.start_block();
+		} else {
+			self.current = self.add_block();
+		}
+	}
+
+	fn if_(&mut self, if_: &'a If) {
+		let from = self.current;
+		let after = self.add_block();
+
+		self.block_list[from].exit = Exit::If(if_);
+		self.arm(if_.on_true(), from, after);
+
+		match if_.on_false() {
+			Some(v) => self.arm(v, from, after),
+			None => self.add_edge(from, after),
+		}
+
+		self.current = after;
+	}
+
+	fn br_if(&mut self, br_if: &'a BrIf) {
+		let target = self.get_target(br_if.target().target());
+
+		self.block_list[self.current].exit = Exit::BrIf(br_if);
+		self.add_edge(self.current, target);
+		self.start_block();
+	}
+
+	// Handlers that do not match pass the exception on outward, and
+	// delegation sends it to the handlers of the given `try` body
+	fn try_(&mut self, try_: &'a Try) {
+		let from = self.current;
+		let after = self.add_block();
+		let outer = self.handler.clone();
+
+		let code_list: Vec<_> = try_
+			.catch_list()
+			.iter()
+			.map(Catch::code)
+			.chain(try_.catch_all())
+			.map(|v| (self.add_block(), v))
+			.collect();
+
+		let mut handler: Vec<_> = code_list.iter().map(|v| v.0).collect();
+
+		if try_.catch_all().is_none() {
+			let rest = try_.delegate().map_or(&outer, |v| {
+				let index = self.label_list.len() - 1 - v.target();
+
+				&self.label_list[index].handler
+			});
+
+			handler.extend(rest);
+		}
+
+		self.handler = handler;
+		self.arm(try_.code(), from, after);
+		self.handler = outer;
+
+		for (entry, code) in code_list {
+			self.current = entry;
+
+			if self.block(code, after) {
+				self.add_edge(self.current, after);
+			}
+		}
+
+		self.current = after;
+	}
+
+	fn statement(&mut self, stat: &'a Statement) {
+		let id = stat as *const _ as usize;
+
+		self.position_map.insert(id, self.current);
+
+		match stat {
+			Statement::Block(v) if v.label_type() == Some(LabelType::Backward) => {
+				self.block_backward(v);
+			}
+			Statement::Block(v) => self.block_forward(v),
+			Statement::If(v) => self.if_(v),
+			Statement::BrIf(v) => self.br_if(v),
+			Statement::Try(v) => self.try_(v),
+			Statement::Call(_) | Statement::CallIndirect(_) => {
+				self.block_list[self.current].code.push(stat);
+				self.add_handler_edge();
+			}
+			_ => self.block_list[self.current].code.push(stat),
+		}
+	}
+}
@@ -0,0 +1,163 @@
+use wasmparser::Operator;
+
+use crate::{
+	error::{Error, IndexSpace, Location, Result},
+	module::TypeInfo,
+	node::{BinOp, BinOpType, Expression, GetGlobal, RefFunc, RefNull, Value},
+	optimize::fold,
+};
+
+fn fold_bin_op(op_type: BinOpType, lhs: Expression, rhs: Expression) -> Expression {
+	if let (Expression::Value(a), Expression::Value(b)) = (&lhs, &rhs) {
+		if let Some(value) = fold::bin_op(op_type, *a, *b) {
+			return Expression::Value(value);
+		}
+	}
+
+	Expression::BinOp(BinOp {
+		op_type,
+		lhs: lhs.into(),
+		rhs: rhs.into(),
+	})
+}
+
+struct Evaluator<'a> {
+	type_info: &'a TypeInfo<'a>,
+	stack: Vec<Expression>,
+	offset: usize,
+}
+
+impl Evaluator<'_> {
+	const fn malformed(&self) -> Error {
+		Error::MalformedConstant {
+			location: self.location(),
+		}
+	}
+
+	fn unsupported(&self, op: &Operator) -> Error {
+		Error::UnsupportedOperator {
+			location: self.location(),
+			operator: format!("{op:?}"),
+		}
+	}
+
+	const fn location(&self) -> Location {
+		Location {
+			function: None,
+			offset: self.offset,
+		}
+	}
+
+	// Indices can only fail to fit on targets narrower than 32 bits
+	fn to_index(&self, space: IndexSpace, index: u32) -> Result<usize> {
+		usize::try_from(index).map_err(|_| Error::Invalid {
+			function: None,
+			offset: self.offset,
+			message: format!("{space} index {index} is too large"),
+		})
+	}
+
+	fn pop(&mut self) -> Result<Expression> {
+		self.stack.pop().ok_or_else(|| self.malformed())
+	}
+
+	fn push_constant<T: Into<Value>>(&mut self, value: T) {
+		self.stack.push(Expression::Value(value.into()));
+	}
+
+	fn push_bin_op(&mut self, op_type: BinOpType) -> Result<()> {
+		let rhs = self.pop()?;
+		let lhs = self.pop()?;
+
+		self.stack.push(fold_bin_op(op_type, lhs, rhs));
+
+		Ok(())
+	}
+
+	fn add_instruction(&mut self, op: &Operator) -> Result<()> {
+		match *op {
+			Operator::I32Const { value } => self.push_constant(value),
+			Operator::I64Const { value } => self.push_constant(value),
+			Operator::F32Const { value } => self.push_constant(value.bits()),
+			Operator::F64Const { value } => self.push_constant(value.bits()),
+			Operator::V128Const { value } => {
+				self.push_constant(u128::from_le_bytes(*value.bytes()));
+			}
+			Operator::RefNull { hty } => {
+				let data = Expression::RefNull(RefNull {
+					ref_type: hty.into(),
+				});
+
+				self.stack.push(data);
+			}
+			Operator::RefFunc { function_index } => {
+				let function = self.to_index(IndexSpace::Function, function_index)?;
+				let data = Expression::RefFunc(RefFunc { function });
+
+				self.stack.push(data);
+			}
+			Operator::GlobalGet { global_index } => {
+				let var = self.to_index(IndexSpace::Global, global_index)?;
+				let ty = self
+					.type_info
+					.global_type(var)
+					.ok_or_else(|| Error::IndexOutOfRange {
+						location: self.location(),
+						space: IndexSpace::Global,
+						index: var,
+					})?;
+				let data = Expression::GetGlobal(GetGlobal { var, ty });
+
+				self.stack.push(data);
+			}
+			Operator::I32Add
+			| Operator::I32Sub
+			| Operator::I32Mul
+			| Operator::I64Add
+			| Operator::I64Sub
+			| Operator::I64Mul => {
+				let op_type = BinOpType::try_from(op).map_err(|()| self.unsupported(op))?;
+
+				self.push_bin_op(op_type)?;
+			}
+			_ => return Err(self.unsupported(op)),
+		}
+
+		Ok(())
+	}
+}
+
+/// Evaluates a constant expression into a single `Expression`. Arithmetic
+/// on known values is folded, while reads of globals are kept as-is to be
+/// resolved when the module is instantiated.
+///
+/// # Errors
+///
+/// Returns an error if the expression contains operators not allowed in
+/// constant expressions, reads a global that does not exist, or does not
+/// produce exactly one value. Offsets are given as operator positions.
+pub fn evaluate(list: &[Operator], type_info: &TypeInfo) -> Result<Expression> {
+	let mut evaluator = Evaluator {
+		type_info,
+		stack: Vec::new(),
+		offset: 0,
+	};
+
+	for (i, op) in list.iter().enumerate() {
+		evaluator.offset = i;
+
+		if matches!(op, Operator::End) {
+			break;
+		}
+
+		evaluator.add_instruction(op)?;
+	}
+
+	let value = evaluator.pop()?;
+
+	if evaluator.stack.is_empty() {
+		Ok(value)
+	} else {
+		Err(evaluator.malformed())
+	}
+}
@@ -0,0 +1,161 @@
+use std::fmt::{Display, Formatter};
+
+use wasmparser::BinaryReaderError;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Location {
+	pub function: Option<usize>,
+	pub offset: usize,
+}
+
+impl Display for Location {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		match self.function {
+			Some(function) => write!(f, "function {function} at offset {:#x}", self.offset),
+			None => write!(f, "constant expression at operator {}", self.offset),
+		}
+	}
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IndexSpace {
+	Type,
+	Function,
+	Label,
+	Tag,
+	Local,
+	Global,
+	Table,
+	Memory,
+	Data,
+	Element,
+}
+
+impl Display for IndexSpace {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		let name = match self {
+			Self::Type => "type",
+			Self::Function => "function",
+			Self::Label => "label",
+			Self::Tag => "tag",
+			Self::Local => "local",
+			Self::Global => "global",
+			Self::Table => "table",
+			Self::Memory => "memory",
+			Self::Data => "data",
+			Self::Element => "element",
+		};
+
+		f.write_str(name)
+	}
+}
+
+#[derive(Debug)]
+pub enum Error {
+	Reader(BinaryReaderError),
+	UnsupportedOperator {
+		location: Location,
+		operator: String,
+	},
+	MalformedNesting {
+		location: Location,
+	},
+	MalformedConstant {
+		location: Location,
+	},
+	IndexOutOfRange {
+		location: Location,
+		space: IndexSpace,
+		index: usize,
+	},
+	Invalid {
+		function: Option<usize>,
+		offset: usize,
+		message: String,
+	},
+	List(Vec<Error>),
+}
+
+impl Error {
+	#[must_use]
+	pub const fn location(&self) -> Option<Location> {
+		match self {
+			Self::Reader(_) | Self::Invalid { function: None, .. } | Self::List(_) => None,
+			Self::UnsupportedOperator { location, .. }
+			| Self::MalformedNesting { location }
+			| Self::MalformedConstant { location }
+			| Self::IndexOutOfRange { location, .. } => Some(*location),
+			Self::Invalid {
+				function: Some(function),
+				offset,
+				..
+			} => Some(Location {
+				function: Some(*function),
+				offset: *offset,
+			}),
+		}
+	}
+}
+
+impl Display for Error {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Self::Reader(error) => error.fmt(f),
+			Self::UnsupportedOperator { location, operator } => {
+				write!(f, "unsupported operator `{operator}` in {location}")
+			}
+			Self::MalformedNesting { location } => {
+				write!(f, "malformed block nesting in {location}")
+			}
+			Self::MalformedConstant { location } => {
+				write!(f, "malformed value stack in {location}")
+			}
+			Self::IndexOutOfRange {
+				location,
+				space,
+				index,
+			} => write!(f, "{space} index {index} out of range in {location}"),
+			Self::Invalid {
+				function: Some(function),
+				offset,
+				message,
+			} => write!(
+				f,
+				"invalid function {function} at offset {offset:#x}: {message}"
+			),
+			Self::Invalid {
+				function: None,
+				offset,
+				message,
+			} => write!(f, "invalid module at offset {offset:#x}: {message}"),
+			Self::List(list) => {
+				write!(f, "{} functions failed to build", list.len())?;
+
+				list.iter().try_for_each(|error| write!(f, "\n{error}"))
+			}
+		}
+	}
+}
+
+impl std::error::Error for Error {
+	fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+		match self {
+			Self::Reader(error) => Some(error),
+			_ => None,
+		}
+	}
+}
+
+impl From<BinaryReaderError> for Error {
+	fn from(value: BinaryReaderError) -> Self {
+		Self::Reader(value)
+	}
+}
+
+impl From<Error> for std::io::Error {
+	fn from(value: Error) -> Self {
+		Self::new(std::io::ErrorKind::InvalidData, value)
+	}
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
@@ -1,29 +1,59 @@
-use wasmparser::{BlockType, FunctionBody, MemArg, Operator, Result};
+use wasmparser::{BlockType, FunctionBody, MemArg, Operator, ValType};
 
 use crate::{
-	module::{read_checked, read_checked_locals, TypeInfo},
+	call_graph::{CallGraph, Effect},
+	error::{Error, IndexSpace, Location, Result},
+	module::{read_checked, read_checked_locals, External, Module, TypeInfo},
 	node::{
-		BinOp, BinOpType, Block, Br, BrIf, BrTable, Call, CallIndirect, CmpOp, CmpOpType,
-		Expression, FuncData, GetGlobal, If, LabelType, LoadAt, LoadType, Local, MemoryArgument,
-		MemoryCopy, MemoryFill, MemoryGrow, MemorySize, Select, SetGlobal, SetLocal, Statement,
-		StoreAt, StoreType, Terminator, UnOp, UnOpType, Value,
+		Align, AtomicCmpxchg, AtomicNotify, AtomicRmw, AtomicWait, BinOp, BinOpType, BitSelect,
+		Block, Br, BrIf, BrTable, Call, CallIndirect, Catch, CmpOp, CmpOpType, DataDrop, ElemDrop,
+		Expression, ExtractLane, ExtractType, FuncData, GetGlobal, If, LabelType, LaneType, LoadAt,
+		LoadLane, LoadType, Local, MemoryArgument, MemoryCopy, MemoryFill, MemoryGrow, MemoryInit,
+		MemorySize, RefFunc, RefIsNull, RefNull, RefType, ReplaceLane, ResultList, Rethrow,
+		ReturnCall, ReturnCallIndirect, RmwOpType, Select, SetGlobal, SetLocal, Shuffle, Statement,
+		StoreAt, StoreLane, StoreType, TableArgument, TableCopy, TableFill, TableGet, TableGrow,
+		TableInit, TableSet, TableSize, Terminator, Throw, Try, UnOp, UnOpType, Value,
 	},
-	stack::{ReadGet, Stack},
+	stack::{find_temporary_data, ReadGet, Stack},
 };
 
 #[derive(Clone, Copy)]
-enum BlockVariant {
+enum BlockVariant<'a> {
 	Forward,
 	Backward,
 	If,
 	Else,
+	Try,
+	Catch {
+		tag: Option<usize>,
+		param_type: &'a [ValType],
+	},
 }
 
 enum BlockData {
-	Forward { num_result: usize },
-	Backward { num_param: usize },
-	If { num_result: usize, ty: BlockType },
-	Else { num_result: usize },
+	Forward {
+		num_result: usize,
+	},
+	Backward {
+		num_param: usize,
+	},
+	If {
+		num_result: usize,
+		ty: BlockType,
+	},
+	Else {
+		num_result: usize,
+	},
+	Try {
+		num_result: usize,
+		ty: BlockType,
+	},
+	Catch {
+		num_result: usize,
+		ty: BlockType,
+		tag: Option<usize>,
+		result_list: ResultList,
+	},
 }
 
 impl Default for BlockData {
@@ -35,14 +65,37 @@ impl Default for BlockData {
 impl From<BlockData> for LabelType {
 	fn from(data: BlockData) -> Self {
 		match data {
-			BlockData::Forward { .. } | BlockData::If { .. } | BlockData::Else { .. } => {
-				Self::Forward
-			}
+			BlockData::Forward { .. }
+			| BlockData::If { .. }
+			| BlockData::Else { .. }
+			| BlockData::Try { .. }
+			| BlockData::Catch { .. } => Self::Forward,
 			BlockData::Backward { .. } => Self::Backward,
 		}
 	}
 }
 
+// Memory64 addresses are `i64` values, so they get converted to
+// plain numbers before being used
+fn into_address(pointer: Expression, is_64: bool) -> Expression {
+	if is_64 {
+		Expression::UnOp(UnOp {
+			op_type: UnOpType::Saturate_U32_I64,
+			rhs: pointer.into(),
+		})
+	} else {
+		pointer
+	}
+}
+
+#[derive(Clone, Copy)]
+struct MemoryAccess {
+	memory: usize,
+	offset: u64,
+	is_64: bool,
+	is_atomic: bool,
+}
+
 #[derive(Default)]
 struct StatList {
 	stack: Stack,
@@ -51,6 +104,7 @@ struct StatList {
 
 	block_data: BlockData,
 	has_reference: bool,
+	label: Option<usize>,
 }
 
 impl StatList {
@@ -62,58 +116,256 @@ impl StatList {
 		self.stack.leak_into(&mut self.code, |_| true);
 	}
 
-	fn leak_pre_call(&mut self) {
+	// Only the state that the callee may change has to be read first
+	fn leak_pre_call(&mut self, effect: Effect) {
 		self.stack.leak_into(&mut self.code, |node| {
-			ReadGet::run(node, |_| false, |_| true, |_| true)
+			ReadGet::run(
+				node,
+				|_| false,
+				|_| effect.global_write,
+				|_| effect.memory_write,
+				|_| effect.table_write,
+			)
 		});
 	}
 
 	fn leak_local_write(&mut self, id: usize) {
 		self.stack.leak_into(&mut self.code, |node| {
-			ReadGet::run(node, |var| var.var() == id, |_| false, |_| false)
+			ReadGet::run(node, |var| var.var() == id, |_| false, |_| false, |_| false)
 		});
 	}
 
 	fn leak_global_write(&mut self, id: usize) {
 		self.stack.leak_into(&mut self.code, |node| {
-			ReadGet::run(node, |_| false, |var| var.var() == id, |_| false)
+			ReadGet::run(node, |_| false, |var| var.var() == id, |_| false, |_| false)
 		});
 	}
 
 	fn leak_memory_write(&mut self, id: usize) {
 		self.stack.leak_into(&mut self.code, |node| {
-			ReadGet::run(node, |_| false, |_| false, |var| var.memory() == id)
+			ReadGet::run(node, |_| false, |_| false, |var| var == id, |_| false)
+		});
+	}
+
+	fn leak_table_write(&mut self, id: usize) {
+		self.stack.leak_into(&mut self.code, |node| {
+			ReadGet::run(node, |_| false, |_| false, |_| false, |var| var == id)
 		});
 	}
 
-	fn push_load(&mut self, load_type: LoadType, memarg: MemArg) {
-		let memory = memarg.memory.try_into().unwrap();
-		let offset = memarg.offset.try_into().unwrap();
+	fn pop_address(&mut self, access: MemoryAccess) -> Option<Box<Expression>> {
+		let pointer = self.stack.pop()?;
+
+		Some(into_address(pointer, access.is_64).into())
+	}
+
+	fn push_load(&mut self, load_type: LoadType, access: MemoryAccess) -> Option<()> {
+		let MemoryAccess {
+			memory,
+			offset,
+			is_atomic,
+			..
+		} = access;
 
 		let data = Expression::LoadAt(LoadAt {
 			load_type,
 			memory,
 			offset,
-			pointer: self.stack.pop().into(),
+			pointer: self.pop_address(access)?,
+			is_atomic,
 		});
 
 		self.stack.push(data);
+
+		Some(())
 	}
 
-	fn add_store(&mut self, store_type: StoreType, memarg: MemArg) {
-		let memory = memarg.memory.try_into().unwrap();
-		let offset = memarg.offset.try_into().unwrap();
+	fn add_store(&mut self, store_type: StoreType, access: MemoryAccess) -> Option<()> {
+		let MemoryAccess {
+			memory,
+			offset,
+			is_atomic,
+			..
+		} = access;
 
 		let data = Statement::StoreAt(StoreAt {
 			store_type,
 			memory,
 			offset,
-			value: self.stack.pop().into(),
-			pointer: self.stack.pop().into(),
+			value: self.stack.pop()?.into(),
+			pointer: self.pop_address(access)?,
+			is_atomic,
+		});
+
+		self.leak_memory_write(memory);
+		self.code.push(data);
+
+		Some(())
+	}
+
+	fn add_atomic_rmw(
+		&mut self,
+		op_type: RmwOpType,
+		store_type: StoreType,
+		access: MemoryAccess,
+	) -> Option<()> {
+		let MemoryAccess { memory, offset, .. } = access;
+
+		let value = self.stack.pop()?.into();
+		let pointer = self.pop_address(access)?;
+		let result = self.stack.push_temporary(store_type.value_type());
+
+		let data = Statement::AtomicRmw(AtomicRmw {
+			op_type,
+			store_type,
+			memory,
+			offset,
+			result,
+			pointer,
+			value,
+		});
+
+		self.leak_memory_write(memory);
+		self.code.push(data);
+
+		Some(())
+	}
+
+	fn add_atomic_cmpxchg(&mut self, store_type: StoreType, access: MemoryAccess) -> Option<()> {
+		let MemoryAccess { memory, offset, .. } = access;
+
+		let replacement = self.stack.pop()?.into();
+		let expected = self.stack.pop()?.into();
+		let pointer = self.pop_address(access)?;
+		let result = self.stack.push_temporary(store_type.value_type());
+
+		let data = Statement::AtomicCmpxchg(AtomicCmpxchg {
+			store_type,
+			memory,
+			offset,
+			result,
+			pointer,
+			expected,
+			replacement,
+		});
+
+		self.leak_memory_write(memory);
+		self.code.push(data);
+
+		Some(())
+	}
+
+	fn add_atomic_wait(&mut self, load_type: LoadType, access: MemoryAccess) -> Option<()> {
+		let MemoryAccess { memory, offset, .. } = access;
+
+		let timeout = self.stack.pop()?.into();
+		let expected = self.stack.pop()?.into();
+		let pointer = self.pop_address(access)?;
+		let result = self.stack.push_temporary(ValType::I32);
+
+		let data = Statement::AtomicWait(AtomicWait {
+			load_type,
+			memory,
+			offset,
+			result,
+			pointer,
+			expected,
+			timeout,
+		});
+
+		self.code.push(data);
+
+		Some(())
+	}
+
+	fn add_atomic_notify(&mut self, access: MemoryAccess) -> Option<()> {
+		let MemoryAccess { memory, offset, .. } = access;
+
+		let count = self.stack.pop()?.into();
+		let pointer = self.pop_address(access)?;
+		let result = self.stack.push_temporary(ValType::I32);
+
+		let data = Statement::AtomicNotify(AtomicNotify {
+			memory,
+			offset,
+			result,
+			pointer,
+			count,
+		});
+
+		self.code.push(data);
+
+		Some(())
+	}
+
+	fn push_load_lane(
+		&mut self,
+		lane_type: LaneType,
+		access: MemoryAccess,
+		lane: u8,
+	) -> Option<()> {
+		let MemoryAccess { memory, offset, .. } = access;
+
+		let data = Expression::LoadLane(LoadLane {
+			lane_type,
+			memory,
+			offset,
+			lane,
+			vector: self.stack.pop()?.into(),
+			pointer: self.pop_address(access)?,
+		});
+
+		self.stack.push(data);
+
+		Some(())
+	}
+
+	fn add_store_lane(
+		&mut self,
+		lane_type: LaneType,
+		access: MemoryAccess,
+		lane: u8,
+	) -> Option<()> {
+		let MemoryAccess { memory, offset, .. } = access;
+
+		let data = Statement::StoreLane(StoreLane {
+			lane_type,
+			memory,
+			offset,
+			lane,
+			vector: self.stack.pop()?.into(),
+			pointer: self.pop_address(access)?,
 		});
 
 		self.leak_memory_write(memory);
 		self.code.push(data);
+
+		Some(())
+	}
+
+	fn push_extract_lane(&mut self, op_type: ExtractType, lane: u8) -> Option<()> {
+		let data = Expression::ExtractLane(ExtractLane {
+			op_type,
+			lane,
+			vector: self.stack.pop()?.into(),
+		});
+
+		self.stack.push(data);
+
+		Some(())
+	}
+
+	fn push_replace_lane(&mut self, lane_type: LaneType, lane: u8) -> Option<()> {
+		let data = Expression::ReplaceLane(ReplaceLane {
+			lane_type,
+			lane,
+			value: self.stack.pop()?.into(),
+			vector: self.stack.pop()?.into(),
+		});
+
+		self.stack.push(data);
+
+		Some(())
 	}
 
 	fn push_constant<T: Into<Value>>(&mut self, value: T) {
@@ -122,69 +374,76 @@ impl StatList {
 		self.stack.push(value);
 	}
 
-	fn push_un_op(&mut self, op_type: UnOpType) {
+	fn push_un_op(&mut self, op_type: UnOpType) -> Option<()> {
 		let data = Expression::UnOp(UnOp {
 			op_type,
-			rhs: self.stack.pop().into(),
+			rhs: self.stack.pop()?.into(),
 		});
 
 		self.stack.push(data);
+
+		Some(())
 	}
 
-	fn push_bin_op(&mut self, op_type: BinOpType) {
+	fn push_bin_op(&mut self, op_type: BinOpType) -> Option<()> {
 		let data = Expression::BinOp(BinOp {
 			op_type,
-			rhs: self.stack.pop().into(),
-			lhs: self.stack.pop().into(),
+			rhs: self.stack.pop()?.into(),
+			lhs: self.stack.pop()?.into(),
 		});
 
 		self.stack.push(data);
+
+		Some(())
 	}
 
-	fn push_cmp_op(&mut self, op_type: CmpOpType) {
+	fn push_cmp_op(&mut self, op_type: CmpOpType) -> Option<()> {
 		let data = Expression::CmpOp(CmpOp {
 			op_type,
-			rhs: self.stack.pop().into(),
-			lhs: self.stack.pop().into(),
+			rhs: self.stack.pop()?.into(),
+			lhs: self.stack.pop()?.into(),
 		});
 
 		self.stack.push(data);
+
+		Some(())
 	}
 
 	// Eqz is the only unary comparison so it's "emulated"
 	// using a constant operand
-	fn try_add_equal_zero(&mut self, op: &Operator) -> bool {
+	fn try_add_equal_zero(&mut self, op: &Operator) -> Option<bool> {
 		match op {
 			Operator::I32Eqz => {
 				self.push_constant(0_i32);
-				self.push_cmp_op(CmpOpType::Eq_I32);
+				self.push_cmp_op(CmpOpType::Eq_I32)?;
 
-				true
+				Some(true)
 			}
 			Operator::I64Eqz => {
 				self.push_constant(0_i64);
-				self.push_cmp_op(CmpOpType::Eq_I64);
+				self.push_cmp_op(CmpOpType::Eq_I64)?;
 
-				true
+				Some(true)
 			}
-			_ => false,
+			_ => Some(false),
 		}
 	}
 
-	// Try to generate a simple operation
-	fn try_add_operation(&mut self, op: &Operator) -> bool {
+	// Try to generate a simple operation, giving `None` if there
+	// are not enough values on the stack for it
+	fn try_add_operation(&mut self, op: &Operator) -> Option<bool> {
 		if let Ok(op_type) = UnOpType::try_from(op) {
-			self.push_un_op(op_type);
+			self.push_un_op(op_type)?;
 
-			true
+			Some(true)
 		} else if let Ok(op_type) = BinOpType::try_from(op) {
-			self.push_bin_op(op_type);
+			self.push_bin_op(op_type)?;
 
-			true
+			Some(true)
 		} else if let Ok(op_type) = CmpOpType::try_from(op) {
-			self.push_cmp_op(op_type);
+			self.push_cmp_op(op_type)?;
 
-			true
+			Some(true)
 		} else {
 			self.try_add_equal_zero(op)
 		}
@@ -202,6 +461,7 @@ impl From<StatList> for Block {
 
 		Self {
 			label_type,
+			label: stat.label,
 			code: stat.code,
 			last: stat.last,
 		}
@@ -210,11 +470,17 @@ impl From<StatList> for Block {
 
 pub struct Factory<'a> {
 	type_info: &'a TypeInfo<'a>,
+	call_graph: Option<&'a CallGraph>,
+	local_type: Vec<ValType>,
 
 	pending: Vec<StatList>,
 	target: StatList,
 
 	nested_unreachable: usize,
+	num_label: usize,
+
+	function: Option<usize>,
+	offset: usize,
 }
 
 impl<'a> Factory<'a> {
@@ -222,81 +488,412 @@ impl<'a> Factory<'a> {
 	pub fn from_type_info(type_info: &'a TypeInfo<'a>) -> Self {
 		Self {
 			type_info,
+			call_graph: None,
+			local_type: Vec::new(),
 			pending: Vec::new(),
 			target: StatList::new(),
 			nested_unreachable: 0,
+			num_label: 0,
+			function: None,
+			offset: 0,
 		}
 	}
 
+	/// Uses the effects of each function to avoid reading values early
+	/// around calls to functions that cannot change them. Without it,
+	/// every call is assumed to change everything.
 	#[must_use]
-	pub fn create_anonymous(&mut self, list: &[Operator]) -> FuncData {
-		let data = self.build_stat_list(list, 1);
+	pub fn with_call_graph(mut self, call_graph: &'a CallGraph) -> Self {
+		self.call_graph = Some(call_graph);
+		self
+	}
 
-		FuncData {
+	/// # Errors
+	///
+	/// Returns an error if the code contains unsupported operators
+	/// or is malformed. Offsets are given as operator positions.
+	pub fn create_anonymous(&mut self, list: &[Operator]) -> Result<FuncData> {
+		let iter = list.iter().enumerate().map(|(i, op)| (op, i));
+
+		self.function = None;
+		self.local_type.clear();
+
+		let data = self.build_stat_list(iter, 1)?;
+		let num_stack = data.stack.capacity;
+		let code = data.into();
+
+		Ok(FuncData {
+			param_data: Vec::new(),
 			local_data: Vec::new(),
+			temporary_data: find_temporary_data(&code, num_stack),
 			num_result: 1,
-			num_param: 0,
-			num_stack: data.stack.capacity,
-			code: data.into(),
-		}
+			code,
+		})
 	}
 
 	/// # Errors
 	///
-	/// Returns an error if the function is malformed.
+	/// Returns an error if the function contains unsupported operators,
+	/// is malformed, or cannot be read.
 	pub fn create_indexed(&mut self, index: usize, func: &FunctionBody) -> Result<FuncData> {
-		let code = read_checked(func.get_operators_reader()?)?;
+		let code = read_checked(func.get_operators_reader()?.into_iter_with_offsets())?;
 		let local_data = read_checked_locals(func.get_locals_reader()?)?;
 
-		let (num_param, num_result) = self.type_info.by_func_index(index);
-		let data = self.build_stat_list(&code, num_result);
+		self.function = Some(index);
+		self.offset = func.range().start;
+
+		let ty = self
+			.type_info
+			.by_func_index(index)
+			.ok_or_else(|| self.out_of_range(IndexSpace::Function, index))?;
+
+		self.local_type.clear();
+		self.local_type
+			.extend(ty.params().iter().chain(&local_data));
+
+		let iter = code.iter().map(|(op, offset)| (op, *offset));
+		let data = self.build_stat_list(iter, ty.results().len())?;
+		// Results are returned from the first temporaries even when every
+		// path ends in a terminator and never writes them
+		let num_stack = data.stack.capacity.max(ty.results().len());
+		let code = data.into();
 
 		Ok(FuncData {
+			param_data: ty.params().to_vec(),
 			local_data,
-			num_result,
-			num_param,
-			num_stack: data.stack.capacity,
-			code: data.into(),
+			temporary_data: find_temporary_data(&code, num_stack),
+			num_result: ty.results().len(),
+			code,
 		})
 	}
 
-	fn start_block(&mut self, ty: BlockType, variant: BlockVariant) {
-		let (num_param, num_result) = self.type_info.by_block_type(ty);
+	/// Builds every function defined by a module in order, putting a
+	/// function that traps when called in place of each one that fails
+	/// to build. The errors of those that failed are returned alongside.
+	pub fn create_module_partial(&mut self, wasm: &Module) -> (Vec<FuncData>, Vec<Error>) {
+		let offset = wasm.import_count(External::Func);
+		let mut func_list = Vec::new();
+		let mut error_list = Vec::new();
+
+		for (i, func) in wasm.code_section().iter().enumerate() {
+			match self.create_indexed(offset + i, func) {
+				Ok(data) => func_list.push(data),
+				Err(error) => {
+					func_list.push(self.create_trapping(offset + i));
+					error_list.push(error);
+				}
+			}
+		}
+
+		(func_list, error_list)
+	}
+
+	/// Builds every function defined by a module in order. Building
+	/// carries on past functions that fail so that all of their errors
+	/// are reported at once.
+	///
+	/// # Errors
+	///
+	/// Returns the error of the function that failed to build, or an
+	/// `Error::List` holding each error if several did.
+	pub fn create_module(&mut self, wasm: &Module) -> Result<Vec<FuncData>> {
+		let (func_list, mut error_list) = self.create_module_partial(wasm);
+
+		match error_list.len() {
+			0 => Ok(func_list),
+			1 => Err(error_list.remove(0)),
+			_ => Err(Error::List(error_list)),
+		}
+	}
+
+	// Stand-ins keep the type of the function so that callers are unchanged,
+	// and like any function hold their results in the first temporaries
+	fn create_trapping(&self, index: usize) -> FuncData {
+		let (param_data, result_data) = self
+			.type_info
+			.by_func_index(index)
+			.map_or_else(Default::default, |ty| {
+				(ty.params().to_vec(), ty.results().to_vec())
+			});
+
+		FuncData {
+			param_data,
+			local_data: Vec::new(),
+			temporary_data: result_data.iter().copied().map(Some).collect(),
+			num_result: result_data.len(),
+			code: Block::new(None, Vec::new(), Some(Terminator::Unreachable)),
+		}
+	}
+
+	const fn location(&self) -> Location {
+		Location {
+			function: self.function,
+			offset: self.offset,
+		}
+	}
+
+	const fn out_of_range(&self, space: IndexSpace, index: usize) -> Error {
+		Error::IndexOutOfRange {
+			location: self.location(),
+			space,
+			index,
+		}
+	}
+
+	const fn malformed_nesting(&self) -> Error {
+		Error::MalformedNesting {
+			location: self.location(),
+		}
+	}
+
+	fn invalid(&self, message: String) -> Error {
+		Error::Invalid {
+			function: self.function,
+			offset: self.offset,
+			message,
+		}
+	}
+
+	fn unsupported(&self, op: &Operator) -> Error {
+		Error::UnsupportedOperator {
+			location: self.location(),
+			operator: format!("{op:?}"),
+		}
+	}
+
+	fn stack_underflow(&self) -> Error {
+		self.invalid("value stack underflow".to_string())
+	}
+
+	// Indices can only fail to fit on targets narrower than 32 bits
+	fn to_index(&self, space: IndexSpace, index: u32) -> Result<usize> {
+		usize::try_from(index)
+			.map_err(|_| self.invalid(format!("{space} index {index} is too large")))
+	}
+
+	fn to_label(&self, index: u32) -> Result<usize> {
+		self.to_index(IndexSpace::Label, index)
+	}
+
+	fn pop(&mut self) -> Result<Expression> {
+		self.target
+			.stack
+			.pop()
+			.ok_or_else(|| self.stack_underflow())
+	}
+
+	fn pop_list(&mut self, len: usize) -> Result<Vec<Expression>> {
+		let list: Option<Vec<_>> = self.target.stack.pop_len(len).map(Iterator::collect);
+
+		list.ok_or_else(|| self.stack_underflow())
+	}
+
+	// Statements that pop their operands themselves give `None` when
+	// the stack runs out
+	fn check_stack(&self, result: Option<()>) -> Result<()> {
+		result.ok_or_else(|| self.stack_underflow())
+	}
+
+	fn by_block_type(&self, ty: BlockType) -> Result<(Vec<ValType>, Vec<ValType>)> {
+		match ty {
+			BlockType::Empty => Ok((Vec::new(), Vec::new())),
+			BlockType::Type(ty) => Ok((Vec::new(), vec![ty])),
+			BlockType::FuncType(index) => {
+				let index = self.to_index(IndexSpace::Type, index)?;
+				let ty = self
+					.type_info
+					.by_type_index(index)
+					.ok_or_else(|| self.out_of_range(IndexSpace::Type, index))?;
+
+				Ok((ty.params().to_vec(), ty.results().to_vec()))
+			}
+		}
+	}
+
+	fn by_local_index(&self, index: usize) -> Result<ValType> {
+		self.local_type
+			.get(index)
+			.copied()
+			.ok_or_else(|| self.out_of_range(IndexSpace::Local, index))
+	}
+
+	fn by_global_index(&self, index: usize) -> Result<ValType> {
+		self.type_info
+			.global_type(index)
+			.ok_or_else(|| self.out_of_range(IndexSpace::Global, index))
+	}
+
+	fn by_memory_index(&self, index: usize) -> Result<bool> {
+		self.type_info
+			.by_memory_index(index)
+			.ok_or_else(|| self.out_of_range(IndexSpace::Memory, index))
+	}
+
+	fn to_table(&self, index: u32) -> Result<usize> {
+		let index = self.to_index(IndexSpace::Table, index)?;
+
+		self.by_table_index(index)?;
+
+		Ok(index)
+	}
+
+	fn by_table_index(&self, index: usize) -> Result<RefType> {
+		let ty = self
+			.type_info
+			.table_type(index)
+			.ok_or_else(|| self.out_of_range(IndexSpace::Table, index))?;
+
+		if ty == ValType::EXTERNREF {
+			Ok(RefType::Extern)
+		} else {
+			Ok(RefType::Func)
+		}
+	}
+
+	fn start_block(&mut self, ty: BlockType, variant: BlockVariant) -> Result<()> {
+		let (param_type, result_type) = self.by_block_type(ty)?;
+		let mut num_param = param_type.len();
+		let num_result = result_type.len();
 		let mut old = std::mem::take(&mut self.target);
 
 		old.leak_all();
 
+		if !matches!(variant, BlockVariant::Else | BlockVariant::Catch { .. }) {
+			self.target.label = Some(self.num_label);
+			self.num_label += 1;
+		}
+
 		self.target.block_data = match variant {
 			BlockVariant::Forward => BlockData::Forward { num_result },
 			BlockVariant::Backward => BlockData::Backward { num_param },
 			BlockVariant::If => BlockData::If { num_result, ty },
 			BlockVariant::Else => {
-				old.stack.pop_len(num_result).for_each(drop);
-				old.stack.push_temporaries(num_param);
+				old.stack
+					.pop_len(num_result)
+					.ok_or_else(|| self.stack_underflow())?
+					.for_each(drop);
+				old.stack.push_temporaries(&param_type);
 
 				BlockData::Else { num_result }
 			}
+			BlockVariant::Try => BlockData::Try { num_result, ty },
+			BlockVariant::Catch { tag, param_type } => {
+				old.stack
+					.pop_len(num_result)
+					.ok_or_else(|| self.stack_underflow())?
+					.for_each(drop);
+
+				let result_list = old.stack.push_temporaries(param_type);
+
+				num_param = param_type.len();
+
+				BlockData::Catch {
+					num_result,
+					ty,
+					tag,
+					result_list,
+				}
+			}
 		};
 
-		self.target.stack = old.stack.split_last(num_param, num_result);
+		self.target.stack = old
+			.stack
+			.split_last(num_param, num_result)
+			.ok_or_else(|| self.stack_underflow())?;
 
-		old.stack.push_temporaries(num_result);
+		old.stack.push_temporaries(&result_type);
 
 		self.pending.push(old);
+
+		Ok(())
 	}
 
-	fn start_else(&mut self) {
+	fn start_else(&mut self) -> Result<()> {
 		let BlockData::If { ty, .. } = self.target.block_data else {
-			unreachable!()
+			return Err(self.malformed_nesting());
+		};
+
+		let label = self.target.label;
+
+		self.target.leak_all();
+		self.end_block()?;
+		self.start_block(ty, BlockVariant::Else)?;
+		self.target.label = label;
+
+		Ok(())
+	}
+
+	// Handlers receive the values of the caught tag rather than
+	// the parameters of the `try` block itself
+	fn start_catch(&mut self, tag: Option<usize>) -> Result<()> {
+		let (BlockData::Try { ty, .. } | BlockData::Catch { ty, .. }) = self.target.block_data
+		else {
+			return Err(self.malformed_nesting());
+		};
+
+		let param_type = match tag {
+			Some(tag) => self
+				.type_info
+				.by_tag_index(tag)
+				.ok_or_else(|| self.out_of_range(IndexSpace::Tag, tag))?,
+			None => &[],
+		};
+
+		let label = self.target.label;
+
+		self.target.leak_all();
+		self.end_block()?;
+		self.start_block(ty, BlockVariant::Catch { tag, param_type })?;
+		self.target.label = label;
+
+		Ok(())
+	}
+
+	// Delegation is resolved to the closest enclosing `try` body at or
+	// above the given label, or to the caller if there is none
+	fn end_delegate(&mut self, target: usize) -> Result<()> {
+		let BlockData::Try { .. } = self.target.block_data else {
+			return Err(self.malformed_nesting());
 		};
 
 		self.target.leak_all();
-		self.end_block();
-		self.start_block(ty, BlockVariant::Else);
+		self.end_block()?;
+
+		if self.get_relative_block(target).is_none() {
+			return Err(self.out_of_range(IndexSpace::Label, target));
+		}
+
+		let mut delegate = None;
+		let mut target = target;
+
+		while let Some(block) = self.get_relative_block(target) {
+			if let BlockData::Try { .. } = block.block_data {
+				let align = Align {
+					new: 0,
+					old: 0,
+					length: 0,
+				};
+
+				block.has_reference = true;
+				delegate = Some(Br { target, align });
+
+				break;
+			}
+
+			target += 1;
+		}
+
+		let Some(Statement::Try(last)) = self.target.code.last_mut() else {
+			return Err(self.malformed_nesting());
+		};
+
+		last.delegate = delegate;
+
+		Ok(())
 	}
 
-	fn end_block(&mut self) {
-		let old = self.pending.pop().unwrap();
+	fn end_block(&mut self) -> Result<()> {
+		let old = self.pending.pop().ok_or_else(|| self.malformed_nesting())?;
 		let now = std::mem::replace(&mut self.target, old);
 
 		self.target.stack.capacity = now.stack.capacity;
@@ -304,41 +901,70 @@ impl<'a> Factory<'a> {
 		let stat = match now.block_data {
 			BlockData::Forward { .. } | BlockData::Backward { .. } => Statement::Block(now.into()),
 			BlockData::If { .. } => Statement::If(If {
-				condition: self.target.stack.pop().into(),
+				condition: self.pop()?.into(),
 				on_true: Box::new(now.into()),
 				on_false: None,
 			}),
 			BlockData::Else { .. } => {
-				let Statement::If(last) = self.target.code.last_mut().unwrap() else {
-					unreachable!()
+				let Some(Statement::If(last)) = self.target.code.last_mut() else {
+					return Err(self.malformed_nesting());
 				};
 
 				last.on_false = Some(Box::new(now.into()));
 
-				return;
+				return Ok(());
+			}
+			BlockData::Try { .. } => Statement::Try(Try {
+				code: Box::new(now.into()),
+				catch_list: Vec::new(),
+				catch_all: None,
+				delegate: None,
+			}),
+			BlockData::Catch {
+				tag, result_list, ..
+			} => {
+				let Some(Statement::Try(last)) = self.target.code.last_mut() else {
+					return Err(self.malformed_nesting());
+				};
+
+				match tag {
+					Some(tag) => last.catch_list.push(Catch {
+						tag,
+						result_list,
+						code: now.into(),
+					}),
+					None => last.catch_all = Some(Box::new(now.into())),
+				}
+
+				return Ok(());
 			}
 		};
 
 		self.target.code.push(stat);
+
+		Ok(())
 	}
 
-	fn get_relative_block(&mut self, index: usize) -> &mut StatList {
+	fn get_relative_block(&mut self, index: usize) -> Option<&mut StatList> {
 		if index == 0 {
-			&mut self.target
+			Some(&mut self.target)
 		} else {
-			let index = self.pending.len() - index;
+			let index = self.pending.len().checked_sub(index)?;
 
-			&mut self.pending[index]
+			self.pending.get_mut(index)
 		}
 	}
 
-	fn get_br_terminator(&mut self, target: usize) -> Br {
-		let block = self.get_relative_block(target);
+	fn get_br_terminator(&mut self, target: usize) -> Result<Br> {
+		let error = self.out_of_range(IndexSpace::Label, target);
+		let block = self.get_relative_block(target).ok_or(error)?;
 		let previous = block.stack.previous;
 		let result = match block.block_data {
 			BlockData::Forward { num_result }
 			| BlockData::If { num_result, .. }
-			| BlockData::Else { num_result } => num_result,
+			| BlockData::Else { num_result }
+			| BlockData::Try { num_result, .. }
+			| BlockData::Catch { num_result, .. } => num_result,
 			BlockData::Backward { num_param } => num_param,
 		};
 
@@ -346,16 +972,22 @@ impl<'a> Factory<'a> {
 
 		let align = self.target.stack.get_br_alignment(previous, result);
 
-		Br { target, align }
+		Ok(Br { target, align })
 	}
 
-	fn add_call(&mut self, function: usize) {
-		let (num_param, num_result) = self.type_info.by_func_index(function);
-		let param_list = self.target.stack.pop_len(num_param).collect();
+	fn add_call(&mut self, function: usize) -> Result<()> {
+		let ty = self
+			.type_info
+			.by_func_index(function)
+			.ok_or_else(|| self.out_of_range(IndexSpace::Function, function))?;
+		let param_list = self.pop_list(ty.params().len())?;
+		let effect = self
+			.call_graph
+			.map_or(Effect::ALL, |graph| graph.effect(function));
 
-		self.target.leak_pre_call();
+		self.target.leak_pre_call(effect);
 
-		let result_list = self.target.stack.push_temporaries(num_result);
+		let result_list = self.target.stack.push_temporaries(ty.results());
 
 		let data = Statement::Call(Call {
 			function,
@@ -364,16 +996,21 @@ impl<'a> Factory<'a> {
 		});
 
 		self.target.code.push(data);
+
+		Ok(())
 	}
 
-	fn add_call_indirect(&mut self, ty: usize, table: usize) {
-		let (num_param, num_result) = self.type_info.by_type_index(ty);
-		let index = self.target.stack.pop().into();
-		let param_list = self.target.stack.pop_len(num_param).collect();
+	fn add_call_indirect(&mut self, ty: usize, table: usize) -> Result<()> {
+		let ty = self
+			.type_info
+			.by_type_index(ty)
+			.ok_or_else(|| self.out_of_range(IndexSpace::Type, ty))?;
+		let index = self.pop()?.into();
+		let param_list = self.pop_list(ty.params().len())?;
 
-		self.target.leak_pre_call();
+		self.target.leak_pre_call(Effect::ALL);
 
-		let result_list = self.target.stack.push_temporaries(num_result);
+		let result_list = self.target.stack.push_temporaries(ty.results());
 
 		let data = Statement::CallIndirect(CallIndirect {
 			table,
@@ -383,35 +1020,263 @@ impl<'a> Factory<'a> {
 		});
 
 		self.target.code.push(data);
+
+		Ok(())
+	}
+
+	fn memory_access(&self, memarg: MemArg) -> Result<MemoryAccess> {
+		let memory = self.to_index(IndexSpace::Memory, memarg.memory)?;
+
+		Ok(MemoryAccess {
+			memory,
+			offset: memarg.offset,
+			is_64: self.by_memory_index(memory)?,
+			is_atomic: false,
+		})
+	}
+
+	fn push_load(&mut self, load_type: LoadType, memarg: MemArg) -> Result<()> {
+		let access = self.memory_access(memarg)?;
+		let result = self.target.push_load(load_type, access);
+
+		self.check_stack(result)
+	}
+
+	fn add_store(&mut self, store_type: StoreType, memarg: MemArg) -> Result<()> {
+		let access = self.memory_access(memarg)?;
+		let result = self.target.add_store(store_type, access);
+
+		self.check_stack(result)
+	}
+
+	fn push_atomic_load(&mut self, load_type: LoadType, memarg: MemArg) -> Result<()> {
+		let access = MemoryAccess {
+			is_atomic: true,
+			..self.memory_access(memarg)?
+		};
+		let result = self.target.push_load(load_type, access);
+
+		self.check_stack(result)
+	}
+
+	fn add_atomic_store(&mut self, store_type: StoreType, memarg: MemArg) -> Result<()> {
+		let access = MemoryAccess {
+			is_atomic: true,
+			..self.memory_access(memarg)?
+		};
+		let result = self.target.add_store(store_type, access);
+
+		self.check_stack(result)
+	}
+
+	fn push_load_lane(&mut self, lane_type: LaneType, memarg: MemArg, lane: u8) -> Result<()> {
+		let access = self.memory_access(memarg)?;
+		let result = self.target.push_load_lane(lane_type, access, lane);
+
+		self.check_stack(result)
+	}
+
+	fn add_store_lane(&mut self, lane_type: LaneType, memarg: MemArg, lane: u8) -> Result<()> {
+		let access = self.memory_access(memarg)?;
+		let result = self.target.add_store_lane(lane_type, access, lane);
+
+		self.check_stack(result)
+	}
+
+	fn add_atomic_rmw(
+		&mut self,
+		op_type: RmwOpType,
+		store_type: StoreType,
+		memarg: MemArg,
+	) -> Result<()> {
+		let access = self.memory_access(memarg)?;
+		let result = self.target.add_atomic_rmw(op_type, store_type, access);
+
+		self.check_stack(result)
+	}
+
+	fn add_atomic_cmpxchg(&mut self, store_type: StoreType, memarg: MemArg) -> Result<()> {
+		let access = self.memory_access(memarg)?;
+		let result = self.target.add_atomic_cmpxchg(store_type, access);
+
+		self.check_stack(result)
+	}
+
+	fn add_atomic_wait(&mut self, load_type: LoadType, memarg: MemArg) -> Result<()> {
+		let access = self.memory_access(memarg)?;
+		let result = self.target.add_atomic_wait(load_type, access);
+
+		self.check_stack(result)
+	}
+
+	fn add_atomic_notify(&mut self, memarg: MemArg) -> Result<()> {
+		let access = self.memory_access(memarg)?;
+		let result = self.target.add_atomic_notify(access);
+
+		self.check_stack(result)
+	}
+
+	fn add_return(&mut self) -> Result<()> {
+		let target = self.pending.len();
+		let term = Terminator::Br(self.get_br_terminator(target)?);
+
+		self.target.set_terminator(term);
+		self.nested_unreachable += 1;
+
+		Ok(())
+	}
+
+	// Protected `try` bodies run in their own closure, so a tail call there
+	// would both be caught by the handlers and return from the wrong function
+	fn is_in_try(&self) -> bool {
+		std::iter::once(&self.target)
+			.chain(&self.pending)
+			.any(|v| matches!(v.block_data, BlockData::Try { .. }))
+	}
+
+	fn add_return_call(&mut self, function: usize) -> Result<()> {
+		if self.is_in_try() {
+			self.add_call(function)?;
+
+			return self.add_return();
+		}
+
+		let num_param = self
+			.type_info
+			.by_func_index(function)
+			.ok_or_else(|| self.out_of_range(IndexSpace::Function, function))?
+			.params()
+			.len();
+		let param_list = self.pop_list(num_param)?;
+
+		let term = Terminator::ReturnCall(ReturnCall {
+			function,
+			param_list,
+		});
+
+		self.target.set_terminator(term);
+		self.nested_unreachable += 1;
+
+		Ok(())
+	}
+
+	fn add_return_call_indirect(&mut self, ty: usize, table: usize) -> Result<()> {
+		if self.is_in_try() {
+			self.add_call_indirect(ty, table)?;
+
+			return self.add_return();
+		}
+
+		let num_param = self
+			.type_info
+			.by_type_index(ty)
+			.ok_or_else(|| self.out_of_range(IndexSpace::Type, ty))?
+			.params()
+			.len();
+		let index = self.pop()?.into();
+		let param_list = self.pop_list(num_param)?;
+
+		let term = Terminator::ReturnCallIndirect(ReturnCallIndirect {
+			table,
+			index,
+			param_list,
+		});
+
+		self.target.set_terminator(term);
+		self.nested_unreachable += 1;
+
+		Ok(())
+	}
+
+	fn add_throw(&mut self, tag: usize) -> Result<()> {
+		let num_param = self
+			.type_info
+			.by_tag_index(tag)
+			.ok_or_else(|| self.out_of_range(IndexSpace::Tag, tag))?
+			.len();
+		let param_list = self.pop_list(num_param)?;
+
+		let term = Terminator::Throw(Throw { tag, param_list });
+
+		self.target.set_terminator(term);
+		self.nested_unreachable += 1;
+
+		Ok(())
+	}
+
+	fn add_rethrow(&mut self, target: usize) -> Result<()> {
+		let error = self.out_of_range(IndexSpace::Label, target);
+		let block = self.get_relative_block(target).ok_or(error)?;
+
+		let BlockData::Catch { .. } = block.block_data else {
+			return Err(self.malformed_nesting());
+		};
+
+		let term = Terminator::Rethrow(Rethrow { target });
+
+		self.target.set_terminator(term);
+		self.nested_unreachable += 1;
+
+		Ok(())
 	}
 
 	#[cold]
-	fn drop_unreachable(&mut self, op: &Operator) {
-		match op {
-			Operator::Block { .. } | Operator::Loop { .. } | Operator::If { .. } => {
+	fn drop_unreachable(&mut self, op: &Operator) -> Result<()> {
+		match *op {
+			Operator::Block { .. }
+			| Operator::Loop { .. }
+			| Operator::If { .. }
+			| Operator::Try { .. } => {
 				self.nested_unreachable += 1;
+				self.num_label += 1;
 			}
 			Operator::Else if self.nested_unreachable == 1 => {
 				self.nested_unreachable -= 1;
 
-				self.start_else();
+				self.start_else()?;
+			}
+			Operator::Catch { tag_index } if self.nested_unreachable == 1 => {
+				self.nested_unreachable -= 1;
+
+				let tag = self.to_index(IndexSpace::Tag, tag_index)?;
+
+				self.start_catch(Some(tag))?;
+			}
+			Operator::CatchAll if self.nested_unreachable == 1 => {
+				self.nested_unreachable -= 1;
+
+				self.start_catch(None)?;
+			}
+			Operator::Delegate { relative_depth } if self.nested_unreachable == 1 => {
+				self.nested_unreachable -= 1;
+
+				let target = self.to_label(relative_depth)?;
+
+				self.end_delegate(target)?;
+			}
+			Operator::Delegate { .. } => {
+				self.nested_unreachable -= 1;
 			}
 			Operator::End if self.nested_unreachable == 1 => {
 				self.nested_unreachable -= 1;
 
-				self.end_block();
+				self.end_block()?;
 			}
 			Operator::End => {
 				self.nested_unreachable -= 1;
 			}
 			_ => {}
 		}
+
+		Ok(())
 	}
 
 	#[allow(clippy::too_many_lines)]
-	fn add_instruction(&mut self, op: &Operator) {
-		if self.target.try_add_operation(op) {
-			return;
+	fn add_instruction(&mut self, op: &Operator) -> Result<()> {
+		match self.target.try_add_operation(op) {
+			Some(true) => return Ok(()),
+			Some(false) => {}
+			None => return Err(self.stack_underflow()),
 		}
 
 		match *op {
@@ -422,50 +1287,85 @@ impl<'a> Factory<'a> {
 			}
 			Operator::Nop => {}
 			Operator::Block { blockty } => {
-				self.start_block(blockty, BlockVariant::Forward);
+				self.start_block(blockty, BlockVariant::Forward)?;
 			}
 			Operator::Loop { blockty } => {
-				self.start_block(blockty, BlockVariant::Backward);
+				self.start_block(blockty, BlockVariant::Backward)?;
 			}
 			Operator::If { blockty } => {
-				let cond = self.target.stack.pop();
+				let cond = self.pop()?;
+
+				self.start_block(blockty, BlockVariant::If)?;
+
+				let Some(last) = self.pending.last_mut() else {
+					return Err(self.malformed_nesting());
+				};
 
-				self.start_block(blockty, BlockVariant::If);
-				self.pending.last_mut().unwrap().stack.push(cond);
+				last.stack.push(cond);
 			}
 			Operator::Else => {
-				self.start_else();
+				self.start_else()?;
+			}
+			Operator::Try { blockty } => {
+				self.start_block(blockty, BlockVariant::Try)?;
+			}
+			Operator::Catch { tag_index } => {
+				let tag = self.to_index(IndexSpace::Tag, tag_index)?;
+
+				self.start_catch(Some(tag))?;
+			}
+			Operator::CatchAll => {
+				self.start_catch(None)?;
+			}
+			Operator::Delegate { relative_depth } => {
+				let target = self.to_label(relative_depth)?;
+
+				self.end_delegate(target)?;
+			}
+			Operator::Throw { tag_index } => {
+				let tag = self.to_index(IndexSpace::Tag, tag_index)?;
+
+				self.add_throw(tag)?;
+			}
+			Operator::Rethrow { relative_depth } => {
+				let target = self.to_label(relative_depth)?;
+
+				self.add_rethrow(target)?;
 			}
 			Operator::End => {
 				self.target.leak_all();
-				self.end_block();
+				self.end_block()?;
 			}
 			Operator::Br { relative_depth } => {
-				let target = relative_depth.try_into().unwrap();
-				let term = Terminator::Br(self.get_br_terminator(target));
+				let target = self.to_label(relative_depth)?;
+				let term = Terminator::Br(self.get_br_terminator(target)?);
 
 				self.target.set_terminator(term);
 				self.nested_unreachable += 1;
 			}
 			Operator::BrIf { relative_depth } => {
-				let target = relative_depth.try_into().unwrap();
+				let target = self.to_label(relative_depth)?;
 				let data = Statement::BrIf(BrIf {
-					condition: self.target.stack.pop().into(),
-					target: self.get_br_terminator(target),
+					condition: self.pop()?.into(),
+					target: self.get_br_terminator(target)?,
 				});
 
 				self.target.leak_all();
 				self.target.code.push(data);
 			}
 			Operator::BrTable { ref targets } => {
-				let condition = self.target.stack.pop().into();
+				let condition = self.pop()?.into();
 				let data = targets
 					.targets()
-					.map(Result::unwrap)
-					.map(|v| self.get_br_terminator(v.try_into().unwrap()))
-					.collect();
+					.map(|v| {
+						let target = self.to_label(v?)?;
+
+						self.get_br_terminator(target)
+					})
+					.collect::<Result<_>>()?;
 
-				let default = self.get_br_terminator(targets.default().try_into().unwrap());
+				let default = self.to_label(targets.default())?;
+				let default = self.get_br_terminator(default)?;
 
 				let term = Terminator::BrTable(BrTable {
 					condition,
@@ -477,60 +1377,74 @@ impl<'a> Factory<'a> {
 				self.nested_unreachable += 1;
 			}
 			Operator::Return => {
-				let target = self.pending.len();
-				let term = Terminator::Br(self.get_br_terminator(target));
-
-				self.target.set_terminator(term);
-				self.nested_unreachable += 1;
+				self.add_return()?;
 			}
 			Operator::Call { function_index } => {
-				let index = function_index.try_into().unwrap();
+				let index = self.to_index(IndexSpace::Function, function_index)?;
 
-				self.add_call(index);
+				self.add_call(index)?;
 			}
 			Operator::CallIndirect {
 				type_index,
-				table_byte,
+				table_index,
 				..
 			} => {
-				let index = type_index.try_into().unwrap();
+				let index = self.to_index(IndexSpace::Type, type_index)?;
+				let table = self.to_table(table_index)?;
+
+				self.add_call_indirect(index, table)?;
+			}
+			Operator::ReturnCall { function_index } => {
+				let index = self.to_index(IndexSpace::Function, function_index)?;
+
+				self.add_return_call(index)?;
+			}
+			Operator::ReturnCallIndirect {
+				type_index,
+				table_index,
+			} => {
+				let index = self.to_index(IndexSpace::Type, type_index)?;
+				let table = self.to_table(table_index)?;
 
-				self.add_call_indirect(index, table_byte.into());
+				self.add_return_call_indirect(index, table)?;
 			}
 			Operator::Drop => {
-				self.target.stack.pop();
+				self.pop()?;
 			}
-			Operator::Select => {
+			Operator::Select | Operator::TypedSelect { .. } => {
 				let data = Expression::Select(Select {
-					condition: self.target.stack.pop().into(),
-					on_false: self.target.stack.pop().into(),
-					on_true: self.target.stack.pop().into(),
+					condition: self.pop()?.into(),
+					on_false: self.pop()?.into(),
+					on_true: self.pop()?.into(),
 				});
 
 				self.target.stack.push(data);
 			}
 			Operator::LocalGet { local_index } => {
-				let var = local_index.try_into().unwrap();
-				let data = Expression::GetLocal(Local { var });
+				let var = self.to_index(IndexSpace::Local, local_index)?;
+				let ty = self.by_local_index(var)?;
+				let data = Expression::GetLocal(Local { var, ty });
 
 				self.target.stack.push(data);
 			}
 			Operator::LocalSet { local_index } => {
-				let var = local_index.try_into().unwrap();
+				let var = self.to_index(IndexSpace::Local, local_index)?;
+				let ty = self.by_local_index(var)?;
 				let data = Statement::SetLocal(SetLocal {
-					var: Local { var },
-					value: self.target.stack.pop().into(),
+					var: Local { var, ty },
+					value: self.pop()?.into(),
 				});
 
 				self.target.leak_local_write(var);
 				self.target.code.push(data);
 			}
 			Operator::LocalTee { local_index } => {
-				let var = local_index.try_into().unwrap();
-				let get = Expression::GetLocal(Local { var });
+				let var = self.to_index(IndexSpace::Local, local_index)?;
+				let ty = self.by_local_index(var)?;
+				let get = Expression::GetLocal(Local { var, ty });
 				let set = Statement::SetLocal(SetLocal {
-					var: Local { var },
-					value: self.target.stack.pop().into(),
+					var: Local { var, ty },
+					value: self.pop()?.into(),
 				});
 
 				self.target.leak_local_write(var);
@@ -538,54 +1452,252 @@ impl<'a> Factory<'a> {
 				self.target.code.push(set);
 			}
 			Operator::GlobalGet { global_index } => {
-				let var = global_index.try_into().unwrap();
-				let data = Expression::GetGlobal(GetGlobal { var });
+				let var = self.to_index(IndexSpace::Global, global_index)?;
+				let ty = self.by_global_index(var)?;
+				let data = Expression::GetGlobal(GetGlobal { var, ty });
 
 				self.target.stack.push(data);
 			}
 			Operator::GlobalSet { global_index } => {
-				let var = global_index.try_into().unwrap();
+				let var = self.to_index(IndexSpace::Global, global_index)?;
+
+				self.by_global_index(var)?;
+
 				let data = Statement::SetGlobal(SetGlobal {
 					var,
-					value: self.target.stack.pop().into(),
+					value: self.pop()?.into(),
 				});
 
 				self.target.leak_global_write(var);
 				self.target.code.push(data);
 			}
-			Operator::I32Load { memarg } => self.target.push_load(LoadType::I32, memarg),
-			Operator::I64Load { memarg } => self.target.push_load(LoadType::I64, memarg),
-			Operator::F32Load { memarg } => self.target.push_load(LoadType::F32, memarg),
-			Operator::F64Load { memarg } => self.target.push_load(LoadType::F64, memarg),
-			Operator::I32Load8S { memarg } => self.target.push_load(LoadType::I32_I8, memarg),
-			Operator::I32Load8U { memarg } => self.target.push_load(LoadType::I32_U8, memarg),
-			Operator::I32Load16S { memarg } => self.target.push_load(LoadType::I32_I16, memarg),
-			Operator::I32Load16U { memarg } => self.target.push_load(LoadType::I32_U16, memarg),
-			Operator::I64Load8S { memarg } => self.target.push_load(LoadType::I64_I8, memarg),
-			Operator::I64Load8U { memarg } => self.target.push_load(LoadType::I64_U8, memarg),
-			Operator::I64Load16S { memarg } => self.target.push_load(LoadType::I64_I16, memarg),
-			Operator::I64Load16U { memarg } => self.target.push_load(LoadType::I64_U16, memarg),
-			Operator::I64Load32S { memarg } => self.target.push_load(LoadType::I64_I32, memarg),
-			Operator::I64Load32U { memarg } => self.target.push_load(LoadType::I64_U32, memarg),
-			Operator::I32Store { memarg } => self.target.add_store(StoreType::I32, memarg),
-			Operator::I64Store { memarg } => self.target.add_store(StoreType::I64, memarg),
-			Operator::F32Store { memarg } => self.target.add_store(StoreType::F32, memarg),
-			Operator::F64Store { memarg } => self.target.add_store(StoreType::F64, memarg),
-			Operator::I32Store8 { memarg } => self.target.add_store(StoreType::I32_N8, memarg),
-			Operator::I32Store16 { memarg } => self.target.add_store(StoreType::I32_N16, memarg),
-			Operator::I64Store8 { memarg } => self.target.add_store(StoreType::I64_N8, memarg),
-			Operator::I64Store16 { memarg } => self.target.add_store(StoreType::I64_N16, memarg),
-			Operator::I64Store32 { memarg } => self.target.add_store(StoreType::I64_N32, memarg),
+			Operator::I32Load { memarg } => self.push_load(LoadType::I32, memarg)?,
+			Operator::I64Load { memarg } => self.push_load(LoadType::I64, memarg)?,
+			Operator::F32Load { memarg } => self.push_load(LoadType::F32, memarg)?,
+			Operator::F64Load { memarg } => self.push_load(LoadType::F64, memarg)?,
+			Operator::I32Load8S { memarg } => self.push_load(LoadType::I32_I8, memarg)?,
+			Operator::I32Load8U { memarg } => self.push_load(LoadType::I32_U8, memarg)?,
+			Operator::I32Load16S { memarg } => self.push_load(LoadType::I32_I16, memarg)?,
+			Operator::I32Load16U { memarg } => self.push_load(LoadType::I32_U16, memarg)?,
+			Operator::I64Load8S { memarg } => self.push_load(LoadType::I64_I8, memarg)?,
+			Operator::I64Load8U { memarg } => self.push_load(LoadType::I64_U8, memarg)?,
+			Operator::I64Load16S { memarg } => self.push_load(LoadType::I64_I16, memarg)?,
+			Operator::I64Load16U { memarg } => self.push_load(LoadType::I64_U16, memarg)?,
+			Operator::I64Load32S { memarg } => self.push_load(LoadType::I64_I32, memarg)?,
+			Operator::I64Load32U { memarg } => self.push_load(LoadType::I64_U32, memarg)?,
+			Operator::I32Store { memarg } => self.add_store(StoreType::I32, memarg)?,
+			Operator::I64Store { memarg } => self.add_store(StoreType::I64, memarg)?,
+			Operator::F32Store { memarg } => self.add_store(StoreType::F32, memarg)?,
+			Operator::F64Store { memarg } => self.add_store(StoreType::F64, memarg)?,
+			Operator::I32Store8 { memarg } => self.add_store(StoreType::I32_N8, memarg)?,
+			Operator::I32Store16 { memarg } => self.add_store(StoreType::I32_N16, memarg)?,
+			Operator::I64Store8 { memarg } => self.add_store(StoreType::I64_N8, memarg)?,
+			Operator::I64Store16 { memarg } => self.add_store(StoreType::I64_N16, memarg)?,
+			Operator::I64Store32 { memarg } => self.add_store(StoreType::I64_N32, memarg)?,
+			Operator::V128Load { memarg } => self.push_load(LoadType::V128, memarg)?,
+			Operator::V128Load8x8S { memarg } => self.push_load(LoadType::I16X8_I8X8, memarg)?,
+			Operator::V128Load8x8U { memarg } => self.push_load(LoadType::I16X8_U8X8, memarg)?,
+			Operator::V128Load16x4S { memarg } => {
+				self.push_load(LoadType::I32X4_I16X4, memarg)?;
+			}
+			Operator::V128Load16x4U { memarg } => {
+				self.push_load(LoadType::I32X4_U16X4, memarg)?;
+			}
+			Operator::V128Load32x2S { memarg } => {
+				self.push_load(LoadType::I64X2_I32X2, memarg)?;
+			}
+			Operator::V128Load32x2U { memarg } => {
+				self.push_load(LoadType::I64X2_U32X2, memarg)?;
+			}
+			Operator::V128Load8Splat { memarg } => self.push_load(LoadType::Splat_N8, memarg)?,
+			Operator::V128Load16Splat { memarg } => {
+				self.push_load(LoadType::Splat_N16, memarg)?;
+			}
+			Operator::V128Load32Splat { memarg } => {
+				self.push_load(LoadType::Splat_N32, memarg)?;
+			}
+			Operator::V128Load64Splat { memarg } => {
+				self.push_load(LoadType::Splat_N64, memarg)?;
+			}
+			Operator::V128Load32Zero { memarg } => self.push_load(LoadType::Zero_N32, memarg)?,
+			Operator::V128Load64Zero { memarg } => self.push_load(LoadType::Zero_N64, memarg)?,
+			Operator::V128Store { memarg } => self.add_store(StoreType::V128, memarg)?,
+			Operator::V128Load8Lane { memarg, lane } => {
+				self.push_load_lane(LaneType::I8X16, memarg, lane)?;
+			}
+			Operator::V128Load16Lane { memarg, lane } => {
+				self.push_load_lane(LaneType::I16X8, memarg, lane)?;
+			}
+			Operator::V128Load32Lane { memarg, lane } => {
+				self.push_load_lane(LaneType::I32X4, memarg, lane)?;
+			}
+			Operator::V128Load64Lane { memarg, lane } => {
+				self.push_load_lane(LaneType::I64X2, memarg, lane)?;
+			}
+			Operator::V128Store8Lane { memarg, lane } => {
+				self.add_store_lane(LaneType::I8X16, memarg, lane)?;
+			}
+			Operator::V128Store16Lane { memarg, lane } => {
+				self.add_store_lane(LaneType::I16X8, memarg, lane)?;
+			}
+			Operator::V128Store32Lane { memarg, lane } => {
+				self.add_store_lane(LaneType::I32X4, memarg, lane)?;
+			}
+			Operator::V128Store64Lane { memarg, lane } => {
+				self.add_store_lane(LaneType::I64X2, memarg, lane)?;
+			}
+			Operator::I32AtomicLoad { memarg } => self.push_atomic_load(LoadType::I32, memarg)?,
+			Operator::I64AtomicLoad { memarg } => self.push_atomic_load(LoadType::I64, memarg)?,
+			Operator::I32AtomicLoad8U { memarg } => {
+				self.push_atomic_load(LoadType::I32_U8, memarg)?
+			}
+			Operator::I32AtomicLoad16U { memarg } => {
+				self.push_atomic_load(LoadType::I32_U16, memarg)?
+			}
+			Operator::I64AtomicLoad8U { memarg } => {
+				self.push_atomic_load(LoadType::I64_U8, memarg)?
+			}
+			Operator::I64AtomicLoad16U { memarg } => {
+				self.push_atomic_load(LoadType::I64_U16, memarg)?
+			}
+			Operator::I64AtomicLoad32U { memarg } => {
+				self.push_atomic_load(LoadType::I64_U32, memarg)?
+			}
+			Operator::I32AtomicStore { memarg } => self.add_atomic_store(StoreType::I32, memarg)?,
+			Operator::I64AtomicStore { memarg } => self.add_atomic_store(StoreType::I64, memarg)?,
+			Operator::I32AtomicStore8 { memarg } => {
+				self.add_atomic_store(StoreType::I32_N8, memarg)?
+			}
+			Operator::I32AtomicStore16 { memarg } => {
+				self.add_atomic_store(StoreType::I32_N16, memarg)?
+			}
+			Operator::I64AtomicStore8 { memarg } => {
+				self.add_atomic_store(StoreType::I64_N8, memarg)?
+			}
+			Operator::I64AtomicStore16 { memarg } => {
+				self.add_atomic_store(StoreType::I64_N16, memarg)?
+			}
+			Operator::I64AtomicStore32 { memarg } => {
+				self.add_atomic_store(StoreType::I64_N32, memarg)?
+			}
+			Operator::I32AtomicRmwAdd { memarg }
+			| Operator::I32AtomicRmwSub { memarg }
+			| Operator::I32AtomicRmwAnd { memarg }
+			| Operator::I32AtomicRmwOr { memarg }
+			| Operator::I32AtomicRmwXor { memarg }
+			| Operator::I32AtomicRmwXchg { memarg } => {
+				let op_type = RmwOpType::try_from(op).map_err(|()| self.unsupported(op))?;
+
+				self.add_atomic_rmw(op_type, StoreType::I32, memarg)?;
+			}
+			Operator::I64AtomicRmwAdd { memarg }
+			| Operator::I64AtomicRmwSub { memarg }
+			| Operator::I64AtomicRmwAnd { memarg }
+			| Operator::I64AtomicRmwOr { memarg }
+			| Operator::I64AtomicRmwXor { memarg }
+			| Operator::I64AtomicRmwXchg { memarg } => {
+				let op_type = RmwOpType::try_from(op).map_err(|()| self.unsupported(op))?;
+
+				self.add_atomic_rmw(op_type, StoreType::I64, memarg)?;
+			}
+			Operator::I32AtomicRmw8AddU { memarg }
+			| Operator::I32AtomicRmw8SubU { memarg }
+			| Operator::I32AtomicRmw8AndU { memarg }
+			| Operator::I32AtomicRmw8OrU { memarg }
+			| Operator::I32AtomicRmw8XorU { memarg }
+			| Operator::I32AtomicRmw8XchgU { memarg } => {
+				let op_type = RmwOpType::try_from(op).map_err(|()| self.unsupported(op))?;
+
+				self.add_atomic_rmw(op_type, StoreType::I32_N8, memarg)?;
+			}
+			Operator::I32AtomicRmw16AddU { memarg }
+			| Operator::I32AtomicRmw16SubU { memarg }
+			| Operator::I32AtomicRmw16AndU { memarg }
+			| Operator::I32AtomicRmw16OrU { memarg }
+			| Operator::I32AtomicRmw16XorU { memarg }
+			| Operator::I32AtomicRmw16XchgU { memarg } => {
+				let op_type = RmwOpType::try_from(op).map_err(|()| self.unsupported(op))?;
+
+				self.add_atomic_rmw(op_type, StoreType::I32_N16, memarg)?;
+			}
+			Operator::I64AtomicRmw8AddU { memarg }
+			| Operator::I64AtomicRmw8SubU { memarg }
+			| Operator::I64AtomicRmw8AndU { memarg }
+			| Operator::I64AtomicRmw8OrU { memarg }
+			| Operator::I64AtomicRmw8XorU { memarg }
+			| Operator::I64AtomicRmw8XchgU { memarg } => {
+				let op_type = RmwOpType::try_from(op).map_err(|()| self.unsupported(op))?;
+
+				self.add_atomic_rmw(op_type, StoreType::I64_N8, memarg)?;
+			}
+			Operator::I64AtomicRmw16AddU { memarg }
+			| Operator::I64AtomicRmw16SubU { memarg }
+			| Operator::I64AtomicRmw16AndU { memarg }
+			| Operator::I64AtomicRmw16OrU { memarg }
+			| Operator::I64AtomicRmw16XorU { memarg }
+			| Operator::I64AtomicRmw16XchgU { memarg } => {
+				let op_type = RmwOpType::try_from(op).map_err(|()| self.unsupported(op))?;
+
+				self.add_atomic_rmw(op_type, StoreType::I64_N16, memarg)?;
+			}
+			Operator::I64AtomicRmw32AddU { memarg }
+			| Operator::I64AtomicRmw32SubU { memarg }
+			| Operator::I64AtomicRmw32AndU { memarg }
+			| Operator::I64AtomicRmw32OrU { memarg }
+			| Operator::I64AtomicRmw32XorU { memarg }
+			| Operator::I64AtomicRmw32XchgU { memarg } => {
+				let op_type = RmwOpType::try_from(op).map_err(|()| self.unsupported(op))?;
+
+				self.add_atomic_rmw(op_type, StoreType::I64_N32, memarg)?;
+			}
+			Operator::I32AtomicRmwCmpxchg { memarg } => {
+				self.add_atomic_cmpxchg(StoreType::I32, memarg)?;
+			}
+			Operator::I64AtomicRmwCmpxchg { memarg } => {
+				self.add_atomic_cmpxchg(StoreType::I64, memarg)?;
+			}
+			Operator::I32AtomicRmw8CmpxchgU { memarg } => {
+				self.add_atomic_cmpxchg(StoreType::I32_N8, memarg)?;
+			}
+			Operator::I32AtomicRmw16CmpxchgU { memarg } => {
+				self.add_atomic_cmpxchg(StoreType::I32_N16, memarg)?;
+			}
+			Operator::I64AtomicRmw8CmpxchgU { memarg } => {
+				self.add_atomic_cmpxchg(StoreType::I64_N8, memarg)?;
+			}
+			Operator::I64AtomicRmw16CmpxchgU { memarg } => {
+				self.add_atomic_cmpxchg(StoreType::I64_N16, memarg)?;
+			}
+			Operator::I64AtomicRmw32CmpxchgU { memarg } => {
+				self.add_atomic_cmpxchg(StoreType::I64_N32, memarg)?;
+			}
+			Operator::MemoryAtomicWait32 { memarg } => {
+				self.add_atomic_wait(LoadType::I32, memarg)?;
+			}
+			Operator::MemoryAtomicWait64 { memarg } => {
+				self.add_atomic_wait(LoadType::I64, memarg)?;
+			}
+			Operator::MemoryAtomicNotify { memarg } => self.add_atomic_notify(memarg)?,
+			// There is only ever one thread, so ordering is already guaranteed
+			Operator::AtomicFence => {}
 			Operator::MemorySize { mem, .. } => {
-				let memory = mem.try_into().unwrap();
+				let memory = self.to_index(IndexSpace::Memory, mem)?;
+				let is_64 = self.by_memory_index(memory)?;
 				let data = Expression::MemorySize(MemorySize { memory });
 
 				self.target.stack.push(data);
+
+				if is_64 {
+					let result = self.target.push_un_op(UnOpType::Extend_I64_U32);
+
+					self.check_stack(result)?;
+				}
 			}
 			Operator::MemoryGrow { mem, .. } => {
-				let size = self.target.stack.pop().into();
-				let result = self.target.stack.push_temporary();
-				let memory = mem.try_into().unwrap();
+				let memory = self.to_index(IndexSpace::Memory, mem)?;
+				let is_64 = self.by_memory_index(memory)?;
+				let size = into_address(self.pop()?, is_64).into();
+				let result = self.target.stack.push_temporary(ValType::I32);
 
 				let data = Statement::MemoryGrow(MemoryGrow {
 					memory,
@@ -595,18 +1707,30 @@ impl<'a> Factory<'a> {
 
 				self.target.leak_memory_write(memory);
 				self.target.code.push(data);
+
+				if is_64 {
+					let result = self.target.push_un_op(UnOpType::Extend_I64_I32);
+
+					self.check_stack(result)?;
+				}
 			}
 			Operator::MemoryCopy { dst_mem, src_mem } => {
-				let size = self.target.stack.pop().into();
+				let dst_mem = self.to_index(IndexSpace::Memory, dst_mem)?;
+				let src_mem = self.to_index(IndexSpace::Memory, src_mem)?;
+				let dst_64 = self.by_memory_index(dst_mem)?;
+				let src_64 = self.by_memory_index(src_mem)?;
+
+				// The size is only 64 bits wide if both memories are
+				let size = into_address(self.pop()?, dst_64 && src_64).into();
 
 				let source = MemoryArgument {
-					memory: src_mem.try_into().unwrap(),
-					pointer: self.target.stack.pop().into(),
+					memory: src_mem,
+					pointer: into_address(self.pop()?, src_64).into(),
 				};
 
 				let destination = MemoryArgument {
-					memory: dst_mem.try_into().unwrap(),
-					pointer: self.target.stack.pop().into(),
+					memory: dst_mem,
+					pointer: into_address(self.pop()?, dst_64).into(),
 				};
 
 				self.target.leak_memory_write(source.memory);
@@ -621,12 +1745,15 @@ impl<'a> Factory<'a> {
 				self.target.code.push(data);
 			}
 			Operator::MemoryFill { mem } => {
-				let size = self.target.stack.pop().into();
-				let value = self.target.stack.pop().into();
+				let memory = self.to_index(IndexSpace::Memory, mem)?;
+				let is_64 = self.by_memory_index(memory)?;
+
+				let size = into_address(self.pop()?, is_64).into();
+				let value = self.pop()?.into();
 
 				let destination = MemoryArgument {
-					memory: mem.try_into().unwrap(),
-					pointer: self.target.stack.pop().into(),
+					memory,
+					pointer: into_address(self.pop()?, is_64).into(),
 				};
 
 				self.target.leak_memory_write(destination.memory);
@@ -639,23 +1766,269 @@ impl<'a> Factory<'a> {
 
 				self.target.code.push(data);
 			}
+			Operator::MemoryInit { data_index, mem } => {
+				let memory = self.to_index(IndexSpace::Memory, mem)?;
+				let is_64 = self.by_memory_index(memory)?;
+				let size = self.pop()?.into();
+				let offset = self.pop()?.into();
+
+				let destination = MemoryArgument {
+					memory,
+					pointer: into_address(self.pop()?, is_64).into(),
+				};
+
+				self.target.leak_memory_write(destination.memory);
+
+				let data = Statement::MemoryInit(MemoryInit {
+					destination,
+					data: self.to_index(IndexSpace::Data, data_index)?,
+					offset,
+					size,
+				});
+
+				self.target.code.push(data);
+			}
+			Operator::DataDrop { data_index } => {
+				let data = Statement::DataDrop(DataDrop {
+					data: self.to_index(IndexSpace::Data, data_index)?,
+				});
+
+				self.target.code.push(data);
+			}
+			Operator::RefNull { hty } => {
+				let data = Expression::RefNull(RefNull {
+					ref_type: hty.into(),
+				});
+
+				self.target.stack.push(data);
+			}
+			Operator::RefIsNull => {
+				let data = Expression::RefIsNull(RefIsNull {
+					value: self.pop()?.into(),
+				});
+
+				self.target.stack.push(data);
+			}
+			Operator::RefFunc { function_index } => {
+				let function = self.to_index(IndexSpace::Function, function_index)?;
+
+				if self.type_info.by_func_index(function).is_none() {
+					return Err(self.out_of_range(IndexSpace::Function, function));
+				}
+
+				let data = Expression::RefFunc(RefFunc { function });
+
+				self.target.stack.push(data);
+			}
+			Operator::TableGet { table } => {
+				let table = self.to_table(table)?;
+				let data = Expression::TableGet(TableGet {
+					table,
+					ref_type: self.by_table_index(table)?,
+					index: self.pop()?.into(),
+				});
+
+				self.target.stack.push(data);
+			}
+			Operator::TableSet { table } => {
+				let table = self.to_table(table)?;
+				let data = Statement::TableSet(TableSet {
+					table,
+					value: self.pop()?.into(),
+					index: self.pop()?.into(),
+				});
+
+				self.target.leak_table_write(table);
+				self.target.code.push(data);
+			}
+			Operator::TableSize { table } => {
+				let table = self.to_table(table)?;
+				let data = Expression::TableSize(TableSize { table });
+
+				self.target.stack.push(data);
+			}
+			Operator::TableGrow { table } => {
+				let size = self.pop()?.into();
+				let value = self.pop()?.into();
+				let result = self.target.stack.push_temporary(ValType::I32);
+				let table = self.to_table(table)?;
+
+				let data = Statement::TableGrow(TableGrow {
+					table,
+					result,
+					size,
+					value,
+				});
+
+				self.target.leak_table_write(table);
+				self.target.code.push(data);
+			}
+			Operator::TableFill { table } => {
+				let size = self.pop()?.into();
+				let value = self.pop()?.into();
+
+				let destination = TableArgument {
+					table: self.to_table(table)?,
+					index: self.pop()?.into(),
+				};
+
+				self.target.leak_table_write(destination.table);
+
+				let data = Statement::TableFill(TableFill {
+					destination,
+					size,
+					value,
+				});
+
+				self.target.code.push(data);
+			}
+			Operator::TableCopy {
+				dst_table,
+				src_table,
+			} => {
+				let size = self.pop()?.into();
+
+				let source = TableArgument {
+					table: self.to_table(src_table)?,
+					index: self.pop()?.into(),
+				};
+
+				let destination = TableArgument {
+					table: self.to_table(dst_table)?,
+					index: self.pop()?.into(),
+				};
+
+				self.target.leak_table_write(destination.table);
+
+				let data = Statement::TableCopy(TableCopy {
+					destination,
+					source,
+					size,
+				});
+
+				self.target.code.push(data);
+			}
+			Operator::TableInit { elem_index, table } => {
+				let size = self.pop()?.into();
+				let offset = self.pop()?.into();
+
+				let destination = TableArgument {
+					table: self.to_table(table)?,
+					index: self.pop()?.into(),
+				};
+
+				self.target.leak_table_write(destination.table);
+
+				let data = Statement::TableInit(TableInit {
+					destination,
+					element: self.to_index(IndexSpace::Element, elem_index)?,
+					offset,
+					size,
+				});
+
+				self.target.code.push(data);
+			}
+			Operator::ElemDrop { elem_index } => {
+				let data = Statement::ElemDrop(ElemDrop {
+					element: self.to_index(IndexSpace::Element, elem_index)?,
+				});
+
+				self.target.code.push(data);
+			}
 			Operator::I32Const { value } => self.target.push_constant(value),
 			Operator::I64Const { value } => self.target.push_constant(value),
 			Operator::F32Const { value } => self.target.push_constant(value.bits()),
 			Operator::F64Const { value } => self.target.push_constant(value.bits()),
-			_ => panic!("Unsupported instruction: {op:?}"),
+			Operator::V128Const { value } => {
+				self.target
+					.push_constant(u128::from_le_bytes(*value.bytes()));
+			}
+			Operator::I8x16Shuffle { lanes } => {
+				let data = Expression::Shuffle(Shuffle {
+					lanes,
+					rhs: self.pop()?.into(),
+					lhs: self.pop()?.into(),
+				});
+
+				self.target.stack.push(data);
+			}
+			Operator::V128Bitselect => {
+				let data = Expression::BitSelect(BitSelect {
+					condition: self.pop()?.into(),
+					on_false: self.pop()?.into(),
+					on_true: self.pop()?.into(),
+				});
+
+				self.target.stack.push(data);
+			}
+			Operator::I8x16ExtractLaneS { lane }
+			| Operator::I8x16ExtractLaneU { lane }
+			| Operator::I16x8ExtractLaneS { lane }
+			| Operator::I16x8ExtractLaneU { lane }
+			| Operator::I32x4ExtractLane { lane }
+			| Operator::I64x2ExtractLane { lane }
+			| Operator::F32x4ExtractLane { lane }
+			| Operator::F64x2ExtractLane { lane } => {
+				let op_type = ExtractType::try_from(op).map_err(|()| self.unsupported(op))?;
+				let result = self.target.push_extract_lane(op_type, lane);
+
+				self.check_stack(result)?;
+			}
+			Operator::I8x16ReplaceLane { lane } => {
+				let result = self.target.push_replace_lane(LaneType::I8X16, lane);
+
+				self.check_stack(result)?;
+			}
+			Operator::I16x8ReplaceLane { lane } => {
+				let result = self.target.push_replace_lane(LaneType::I16X8, lane);
+
+				self.check_stack(result)?;
+			}
+			Operator::I32x4ReplaceLane { lane } => {
+				let result = self.target.push_replace_lane(LaneType::I32X4, lane);
+
+				self.check_stack(result)?;
+			}
+			Operator::I64x2ReplaceLane { lane } => {
+				let result = self.target.push_replace_lane(LaneType::I64X2, lane);
+
+				self.check_stack(result)?;
+			}
+			Operator::F32x4ReplaceLane { lane } => {
+				let result = self.target.push_replace_lane(LaneType::F32X4, lane);
+
+				self.check_stack(result)?;
+			}
+			Operator::F64x2ReplaceLane { lane } => {
+				let result = self.target.push_replace_lane(LaneType::F64X2, lane);
+
+				self.check_stack(result)?;
+			}
+			_ => return Err(self.unsupported(op)),
 		}
+
+		Ok(())
 	}
 
-	fn build_stat_list(&mut self, list: &[Operator], num_result: usize) -> StatList {
+	fn build_stat_list<'b, I>(&mut self, list: I, num_result: usize) -> Result<StatList>
+	where
+		I: ExactSizeIterator<Item = (&'b Operator<'b>, usize)>,
+	{
+		let len = list.len().saturating_sub(1);
+
+		self.target = StatList::new();
 		self.target.block_data = BlockData::Forward { num_result };
+		self.pending.clear();
 		self.nested_unreachable = 0;
+		self.num_label = 0;
+
+		for (op, offset) in list.take(len) {
+			self.offset = offset;
 
-		for op in list.iter().take(list.len() - 1) {
 			if self.nested_unreachable == 0 {
-				self.add_instruction(op);
+				self.add_instruction(op)?;
 			} else {
-				self.drop_unreachable(op);
+				self.drop_unreachable(op)?;
 			}
 		}
 
@@ -663,6 +2036,6 @@ impl<'a> Factory<'a> {
 			self.target.leak_all();
 		}
 
-		std::mem::take(&mut self.target)
+		Ok(std::mem::take(&mut self.target))
 	}
 }
@@ -1,6 +1,16 @@
+pub mod call_graph;
+pub mod cfg;
+pub mod constant;
+pub mod error;
 pub mod factory;
 pub mod module;
 pub mod node;
+pub mod optimize;
+pub mod outline;
+pub mod print;
+pub mod shape;
 pub mod visit;
 
+#[cfg(feature = "serde")]
+mod serialize;
 mod stack;
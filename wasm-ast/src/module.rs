@@ -1,10 +1,14 @@
 use std::collections::HashMap;
 
 use wasmparser::{
-	BlockType, Data, Element, Export, ExternalKind, FunctionBody, Global, Import, LocalsReader,
-	MemoryType, Name, NameSectionReader, Parser, Payload, Result, Table, Type, TypeRef, ValType,
+	BinaryReaderError, Data, Element, Export, ExternalKind, FuncType, FuncValidatorAllocations,
+	FunctionBody, Global, Import, IndirectNameMap, LocalsReader, MemoryType, Name, NameMap,
+	NameSectionReader, Parser, Payload, Result, Table, TagType, Type, TypeRef, ValType,
+	ValidPayload, Validator, WasmFeatures,
 };
 
+use crate::error::Error;
+
 #[derive(PartialEq, Eq, Clone, Copy)]
 pub enum External {
 	Func,
@@ -53,6 +57,148 @@ pub(crate) fn read_checked_locals(reader: LocalsReader) -> Result<Vec<ValType>>
 	})
 }
 
+fn read_name_map<'a>(map: NameMap<'a>) -> Result<HashMap<u32, &'a str>> {
+	map.into_iter()
+		.map(|v| v.map(|naming| (naming.index, naming.name)))
+		.collect()
+}
+
+fn read_indirect_name_map<'a>(
+	map: IndirectNameMap<'a>,
+) -> Result<HashMap<u32, HashMap<u32, &'a str>>> {
+	map.into_iter()
+		.map(|v| {
+			let indirect = v?;
+
+			Ok((indirect.index, read_name_map(indirect.names)?))
+		})
+		.collect()
+}
+
+#[derive(Default)]
+pub struct NameSection<'a> {
+	module: Option<&'a str>,
+	function_map: HashMap<u32, &'a str>,
+	local_map: HashMap<u32, HashMap<u32, &'a str>>,
+	label_map: HashMap<u32, HashMap<u32, &'a str>>,
+	table_map: HashMap<u32, &'a str>,
+	memory_map: HashMap<u32, &'a str>,
+	global_map: HashMap<u32, &'a str>,
+	element_map: HashMap<u32, &'a str>,
+	data_map: HashMap<u32, &'a str>,
+}
+
+impl<'a> NameSection<'a> {
+	fn load_name(&mut self, name: Name<'a>) -> Result<()> {
+		match name {
+			Name::Module { name, .. } => self.module = Some(name),
+			Name::Function(map) => self.function_map = read_name_map(map)?,
+			Name::Local(map) => self.local_map = read_indirect_name_map(map)?,
+			Name::Label(map) => self.label_map = read_indirect_name_map(map)?,
+			Name::Table(map) => self.table_map = read_name_map(map)?,
+			Name::Memory(map) => self.memory_map = read_name_map(map)?,
+			Name::Global(map) => self.global_map = read_name_map(map)?,
+			Name::Element(map) => self.element_map = read_name_map(map)?,
+			Name::Data(map) => self.data_map = read_name_map(map)?,
+			_ => {}
+		}
+
+		Ok(())
+	}
+
+	#[must_use]
+	pub const fn module(&self) -> Option<&'a str> {
+		self.module
+	}
+
+	#[must_use]
+	pub fn function(&self, index: u32) -> Option<&'a str> {
+		self.function_map.get(&index).copied()
+	}
+
+	#[must_use]
+	pub fn local(&self, function: u32, index: u32) -> Option<&'a str> {
+		self.local_map.get(&function)?.get(&index).copied()
+	}
+
+	/// Labels are indexed by the order in which their `block`, `loop`,
+	/// `if` and `try` instructions appear in the function body.
+	#[must_use]
+	pub fn label(&self, function: u32, index: u32) -> Option<&'a str> {
+		self.label_map.get(&function)?.get(&index).copied()
+	}
+
+	/// Returns every named label of a function along with its index.
+	pub fn label_list(&self, function: u32) -> impl Iterator<Item = (u32, &'a str)> + '_ {
+		self.label_map
+			.get(&function)
+			.into_iter()
+			.flat_map(|map| map.iter().map(|(&index, &name)| (index, name)))
+	}
+
+	#[must_use]
+	pub fn table(&self, index: u32) -> Option<&'a str> {
+		self.table_map.get(&index).copied()
+	}
+
+	#[must_use]
+	pub fn memory(&self, index: u32) -> Option<&'a str> {
+		self.memory_map.get(&index).copied()
+	}
+
+	#[must_use]
+	pub fn global(&self, index: u32) -> Option<&'a str> {
+		self.global_map.get(&index).copied()
+	}
+
+	#[must_use]
+	pub fn element(&self, index: u32) -> Option<&'a str> {
+		self.element_map.get(&index).copied()
+	}
+
+	#[must_use]
+	pub fn data(&self, index: u32) -> Option<&'a str> {
+		self.data_map.get(&index).copied()
+	}
+}
+
+/// Returns the features that both backends are able to translate,
+/// which modules are validated against by default.
+#[must_use]
+pub fn supported_features() -> WasmFeatures {
+	// Everything enabled by default is supported, along with these
+	WasmFeatures {
+		threads: true,
+		multi_memory: true,
+		exceptions: true,
+		memory64: true,
+		extended_const: true,
+		..WasmFeatures::default()
+	}
+}
+
+fn validate_payload(validator: &mut Validator, payload: &Payload) -> crate::error::Result<()> {
+	let into_error = |function, error: BinaryReaderError| Error::Invalid {
+		function,
+		offset: error.offset(),
+		message: error.message().to_string(),
+	};
+
+	match validator
+		.payload(payload)
+		.map_err(|e| into_error(None, e))?
+	{
+		ValidPayload::Func(func, body) => {
+			let mut func = func.into_validator(FuncValidatorAllocations::default());
+			let function = func.index().try_into().unwrap();
+
+			func.validate(&body)
+				.map_err(|e| into_error(Some(function), e))
+		}
+		_ => Ok(()),
+	}
+}
+
 pub struct Module<'a> {
 	type_section: Vec<Type>,
 	import_section: Vec<Import<'a>>,
@@ -60,12 +206,13 @@ pub struct Module<'a> {
 	table_section: Vec<Table<'a>>,
 	memory_section: Vec<MemoryType>,
 	global_section: Vec<Global<'a>>,
+	tag_section: Vec<TagType>,
 	export_section: Vec<Export<'a>>,
 	element_section: Vec<Element<'a>>,
 	data_section: Vec<Data<'a>>,
 	code_section: Vec<FunctionBody<'a>>,
 
-	name_section: HashMap<u32, &'a str>,
+	name_section: NameSection<'a>,
 
 	start_section: Option<u32>,
 }
@@ -73,8 +220,21 @@ pub struct Module<'a> {
 impl<'a> Module<'a> {
 	/// # Errors
 	///
-	/// Returns a `BinaryReaderError` if any module section is malformed.
-	pub fn try_from_data(data: &'a [u8]) -> Result<Self> {
+	/// Returns an `Error` if any module section is malformed or if the
+	/// module is invalid under the features given by `supported_features`.
+	pub fn try_from_data(data: &'a [u8]) -> crate::error::Result<Self> {
+		Self::try_from_data_with_features(data, supported_features())
+	}
+
+	/// # Errors
+	///
+	/// Returns an `Error` if any module section is malformed or if the
+	/// module is invalid under the given features.
+	pub fn try_from_data_with_features(
+		data: &'a [u8],
+		features: WasmFeatures,
+	) -> crate::error::Result<Self> {
+		let mut validator = Validator::new_with_features(features);
 		let mut temp = Module {
 			type_section: Vec::new(),
 			import_section: Vec::new(),
@@ -82,27 +242,33 @@ impl<'a> Module<'a> {
 			table_section: Vec::new(),
 			memory_section: Vec::new(),
 			global_section: Vec::new(),
+			tag_section: Vec::new(),
 			export_section: Vec::new(),
 			element_section: Vec::new(),
 			data_section: Vec::new(),
 			code_section: Vec::new(),
-			name_section: HashMap::new(),
+			name_section: NameSection::default(),
 			start_section: None,
 		};
 
-		temp.load_data(data)?;
+		temp.load_data(data, &mut validator)?;
 		Ok(temp)
 	}
 
-	fn load_data(&mut self, data: &'a [u8]) -> Result<()> {
+	fn load_data(&mut self, data: &'a [u8], validator: &mut Validator) -> crate::error::Result<()> {
 		for payload in Parser::new(0).parse_all(data) {
-			match payload? {
+			let payload = payload?;
+
+			validate_payload(validator, &payload)?;
+
+			match payload {
 				Payload::TypeSection(v) => self.type_section = read_checked(v)?,
 				Payload::ImportSection(v) => self.import_section = read_checked(v)?,
 				Payload::FunctionSection(v) => self.func_section = read_checked(v)?,
 				Payload::TableSection(v) => self.table_section = read_checked(v)?,
 				Payload::MemorySection(v) => self.memory_section = read_checked(v)?,
 				Payload::GlobalSection(v) => self.global_section = read_checked(v)?,
+				Payload::TagSection(v) => self.tag_section = read_checked(v)?,
 				Payload::ExportSection(v) => self.export_section = read_checked(v)?,
 				Payload::ElementSection(v) => self.element_section = read_checked(v)?,
 				Payload::DataSection(v) => self.data_section = read_checked(v)?,
@@ -114,12 +280,7 @@ impl<'a> Module<'a> {
 				}
 				Payload::CustomSection(v) if v.name() == "name" => {
 					for name in NameSectionReader::new(v.data(), v.data_offset()) {
-						if let Name::Function(map) = name? {
-							let mut iter = map.into_iter();
-							while let Some(Ok(elem)) = iter.next() {
-								self.name_section.insert(elem.index, elem.name);
-							}
-						}
+						self.name_section.load_name(name?)?;
 					}
 				}
 				_ => {}
@@ -156,6 +317,11 @@ impl<'a> Module<'a> {
 		self.import_count(External::Global) + self.global_section.len()
 	}
 
+	#[must_use]
+	pub fn tag_space(&self) -> usize {
+		self.import_count(External::Tag) + self.tag_section.len()
+	}
+
 	#[must_use]
 	pub fn type_section(&self) -> &[Type] {
 		&self.type_section
@@ -186,6 +352,11 @@ impl<'a> Module<'a> {
 		&self.global_section
 	}
 
+	#[must_use]
+	pub fn tag_section(&self) -> &[TagType] {
+		&self.tag_section
+	}
+
 	#[must_use]
 	pub fn export_section(&self) -> &[Export] {
 		&self.export_section
@@ -207,7 +378,7 @@ impl<'a> Module<'a> {
 	}
 
 	#[must_use]
-	pub const fn name_section(&self) -> &HashMap<u32, &'a str> {
+	pub const fn name_section(&self) -> &NameSection<'a> {
 		&self.name_section
 	}
 
@@ -220,6 +391,10 @@ impl<'a> Module<'a> {
 pub struct TypeInfo<'a> {
 	type_list: &'a [Type],
 	func_list: Vec<usize>,
+	table_list: Vec<ValType>,
+	memory_list: Vec<bool>,
+	global_list: Vec<ValType>,
+	tag_list: Vec<usize>,
 }
 
 impl<'a> TypeInfo<'a> {
@@ -228,10 +403,18 @@ impl<'a> TypeInfo<'a> {
 		let mut temp = Self {
 			type_list: &wasm.type_section,
 			func_list: Vec::new(),
+			table_list: Vec::new(),
+			memory_list: Vec::new(),
+			global_list: Vec::new(),
+			tag_list: Vec::new(),
 		};
 
 		temp.load_import_list(&wasm.import_section);
 		temp.load_func_list(&wasm.func_section);
+		temp.load_table_list(&wasm.table_section);
+		temp.load_memory_list(&wasm.memory_section);
+		temp.load_global_list(&wasm.global_section);
+		temp.load_tag_list(&wasm.tag_section);
 		temp
 	}
 
@@ -246,6 +429,38 @@ impl<'a> TypeInfo<'a> {
 			.map(|v| usize::try_from(v).unwrap());
 
 		self.func_list.extend(iter);
+
+		let iter = list.iter().copied().filter_map(|v| match v.ty {
+			TypeRef::Table(v) => Some(ValType::Ref(v.element_type)),
+			_ => None,
+		});
+
+		self.table_list.extend(iter);
+
+		let iter = list.iter().copied().filter_map(|v| match v.ty {
+			TypeRef::Memory(v) => Some(v.memory64),
+			_ => None,
+		});
+
+		self.memory_list.extend(iter);
+
+		let iter = list.iter().copied().filter_map(|v| match v.ty {
+			TypeRef::Global(v) => Some(v.content_type),
+			_ => None,
+		});
+
+		self.global_list.extend(iter);
+
+		let iter = list
+			.iter()
+			.copied()
+			.filter_map(|v| match v.ty {
+				TypeRef::Tag(v) => Some(v.func_type_idx),
+				_ => None,
+			})
+			.map(|v| usize::try_from(v).unwrap());
+
+		self.tag_list.extend(iter);
 	}
 
 	fn load_func_list(&mut self, list: &[u32]) {
@@ -254,29 +469,75 @@ impl<'a> TypeInfo<'a> {
 		self.func_list.extend(iter);
 	}
 
-	pub(crate) fn by_type_index(&self, index: usize) -> (usize, usize) {
-		let Type::Func(ty) = &self.type_list[index] else {
-			unreachable!("type at func index must be a func type");
+	fn load_table_list(&mut self, list: &[Table]) {
+		let iter = list.iter().map(|v| ValType::Ref(v.ty.element_type));
+
+		self.table_list.extend(iter);
+	}
+
+	fn load_memory_list(&mut self, list: &[MemoryType]) {
+		let iter = list.iter().map(|v| v.memory64);
+
+		self.memory_list.extend(iter);
+	}
+
+	fn load_global_list(&mut self, list: &[Global]) {
+		let iter = list.iter().map(|v| v.ty.content_type);
+
+		self.global_list.extend(iter);
+	}
+
+	fn load_tag_list(&mut self, list: &[TagType]) {
+		let iter = list
+			.iter()
+			.map(|v| usize::try_from(v.func_type_idx).unwrap());
+
+		self.tag_list.extend(iter);
+	}
+
+	#[must_use]
+	pub fn is_memory_64(&self, index: usize) -> bool {
+		self.memory_list.get(index).copied().unwrap_or_default()
+	}
+
+	#[must_use]
+	pub fn func_type(&self, index: usize) -> Option<&'a FuncType> {
+		self.by_func_index(index)
+	}
+
+	/// The type of the elements held by a table.
+	#[must_use]
+	pub fn table_type(&self, index: usize) -> Option<ValType> {
+		self.table_list.get(index).copied()
+	}
+
+	/// The type of the value held by a global.
+	#[must_use]
+	pub fn global_type(&self, index: usize) -> Option<ValType> {
+		self.global_list.get(index).copied()
+	}
+
+	pub(crate) fn by_type_index(&self, index: usize) -> Option<&'a FuncType> {
+		let Some(Type::Func(ty)) = self.type_list.get(index) else {
+			return None;
 		};
 
-		(ty.params().len(), ty.results().len())
+		Some(ty)
 	}
 
-	pub(crate) fn by_func_index(&self, index: usize) -> (usize, usize) {
-		let adjusted = self.func_list[index];
+	pub(crate) fn by_func_index(&self, index: usize) -> Option<&'a FuncType> {
+		let adjusted = *self.func_list.get(index)?;
 
 		self.by_type_index(adjusted)
 	}
 
-	pub(crate) fn by_block_type(&self, ty: BlockType) -> (usize, usize) {
-		match ty {
-			BlockType::Empty => (0, 0),
-			BlockType::Type(_) => (0, 1),
-			BlockType::FuncType(i) => {
-				let id = i.try_into().unwrap();
+	pub(crate) fn by_memory_index(&self, index: usize) -> Option<bool> {
+		self.memory_list.get(index).copied()
+	}
+
+	pub(crate) fn by_tag_index(&self, index: usize) -> Option<&'a [ValType]> {
+		let adjusted = *self.tag_list.get(index)?;
 
-				self.by_type_index(id)
-			}
-		}
+		self.by_type_index(adjusted).map(FuncType::params)
 	}
 }
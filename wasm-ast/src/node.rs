@@ -1,7 +1,8 @@
-use wasmparser::{Operator, ValType};
+use wasmparser::{HeapType, Operator, ValType};
 
 #[allow(non_camel_case_types)]
-#[derive(Clone, Copy)]
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum LoadType {
 	I32,
 	I64,
@@ -17,6 +18,38 @@ pub enum LoadType {
 	I64_U16,
 	I64_I32,
 	I64_U32,
+	V128,
+	I16X8_I8X8,
+	I16X8_U8X8,
+	I32X4_I16X4,
+	I32X4_U16X4,
+	I64X2_I32X2,
+	I64X2_U32X2,
+	Splat_N8,
+	Splat_N16,
+	Splat_N32,
+	Splat_N64,
+	Zero_N32,
+	Zero_N64,
+}
+
+impl LoadType {
+	#[must_use]
+	pub const fn result_type(self) -> ValType {
+		match self {
+			Self::I32 | Self::I32_I8 | Self::I32_U8 | Self::I32_I16 | Self::I32_U16 => ValType::I32,
+			Self::I64
+			| Self::I64_I8
+			| Self::I64_U8
+			| Self::I64_I16
+			| Self::I64_U16
+			| Self::I64_I32
+			| Self::I64_U32 => ValType::I64,
+			Self::F32 => ValType::F32,
+			Self::F64 => ValType::F64,
+			_ => ValType::V128,
+		}
+	}
 }
 
 impl TryFrom<&Operator<'_>> for LoadType {
@@ -38,6 +71,26 @@ impl TryFrom<&Operator<'_>> for LoadType {
 			Operator::I64Load16U { .. } => Self::I64_U16,
 			Operator::I64Load32S { .. } => Self::I64_I32,
 			Operator::I64Load32U { .. } => Self::I64_U32,
+			Operator::V128Load { .. } => Self::V128,
+			Operator::V128Load8x8S { .. } => Self::I16X8_I8X8,
+			Operator::V128Load8x8U { .. } => Self::I16X8_U8X8,
+			Operator::V128Load16x4S { .. } => Self::I32X4_I16X4,
+			Operator::V128Load16x4U { .. } => Self::I32X4_U16X4,
+			Operator::V128Load32x2S { .. } => Self::I64X2_I32X2,
+			Operator::V128Load32x2U { .. } => Self::I64X2_U32X2,
+			Operator::V128Load8Splat { .. } => Self::Splat_N8,
+			Operator::V128Load16Splat { .. } => Self::Splat_N16,
+			Operator::V128Load32Splat { .. } => Self::Splat_N32,
+			Operator::V128Load64Splat { .. } => Self::Splat_N64,
+			Operator::V128Load32Zero { .. } => Self::Zero_N32,
+			Operator::V128Load64Zero { .. } => Self::Zero_N64,
+			Operator::I32AtomicLoad { .. } => Self::I32,
+			Operator::I64AtomicLoad { .. } => Self::I64,
+			Operator::I32AtomicLoad8U { .. } => Self::I32_U8,
+			Operator::I32AtomicLoad16U { .. } => Self::I32_U16,
+			Operator::I64AtomicLoad8U { .. } => Self::I64_U8,
+			Operator::I64AtomicLoad16U { .. } => Self::I64_U16,
+			Operator::I64AtomicLoad32U { .. } => Self::I64_U32,
 			_ => return Err(()),
 		};
 
@@ -46,7 +99,8 @@ impl TryFrom<&Operator<'_>> for LoadType {
 }
 
 #[allow(non_camel_case_types)]
-#[derive(Clone, Copy)]
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum StoreType {
 	I32,
 	I64,
@@ -57,6 +111,20 @@ pub enum StoreType {
 	I64_N8,
 	I64_N16,
 	I64_N32,
+	V128,
+}
+
+impl StoreType {
+	#[must_use]
+	pub const fn value_type(self) -> ValType {
+		match self {
+			Self::I32 | Self::I32_N8 | Self::I32_N16 => ValType::I32,
+			Self::I64 | Self::I64_N8 | Self::I64_N16 | Self::I64_N32 => ValType::I64,
+			Self::F32 => ValType::F32,
+			Self::F64 => ValType::F64,
+			Self::V128 => ValType::V128,
+		}
+	}
 }
 
 impl TryFrom<&Operator<'_>> for StoreType {
@@ -73,6 +141,79 @@ impl TryFrom<&Operator<'_>> for StoreType {
 			Operator::I64Store8 { .. } => Self::I64_N8,
 			Operator::I64Store16 { .. } => Self::I64_N16,
 			Operator::I64Store32 { .. } => Self::I64_N32,
+			Operator::V128Store { .. } => Self::V128,
+			Operator::I32AtomicStore { .. } => Self::I32,
+			Operator::I64AtomicStore { .. } => Self::I64,
+			Operator::I32AtomicStore8 { .. } => Self::I32_N8,
+			Operator::I32AtomicStore16 { .. } => Self::I32_N16,
+			Operator::I64AtomicStore8 { .. } => Self::I64_N8,
+			Operator::I64AtomicStore16 { .. } => Self::I64_N16,
+			Operator::I64AtomicStore32 { .. } => Self::I64_N32,
+			_ => return Err(()),
+		};
+
+		Ok(result)
+	}
+}
+
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum RmwOpType {
+	Add,
+	Sub,
+	And,
+	Or,
+	Xor,
+	Xchg,
+}
+
+impl TryFrom<&Operator<'_>> for RmwOpType {
+	type Error = ();
+
+	fn try_from(inst: &Operator) -> Result<Self, Self::Error> {
+		let result = match inst {
+			Operator::I32AtomicRmwAdd { .. }
+			| Operator::I64AtomicRmwAdd { .. }
+			| Operator::I32AtomicRmw8AddU { .. }
+			| Operator::I32AtomicRmw16AddU { .. }
+			| Operator::I64AtomicRmw8AddU { .. }
+			| Operator::I64AtomicRmw16AddU { .. }
+			| Operator::I64AtomicRmw32AddU { .. } => Self::Add,
+			Operator::I32AtomicRmwSub { .. }
+			| Operator::I64AtomicRmwSub { .. }
+			| Operator::I32AtomicRmw8SubU { .. }
+			| Operator::I32AtomicRmw16SubU { .. }
+			| Operator::I64AtomicRmw8SubU { .. }
+			| Operator::I64AtomicRmw16SubU { .. }
+			| Operator::I64AtomicRmw32SubU { .. } => Self::Sub,
+			Operator::I32AtomicRmwAnd { .. }
+			| Operator::I64AtomicRmwAnd { .. }
+			| Operator::I32AtomicRmw8AndU { .. }
+			| Operator::I32AtomicRmw16AndU { .. }
+			| Operator::I64AtomicRmw8AndU { .. }
+			| Operator::I64AtomicRmw16AndU { .. }
+			| Operator::I64AtomicRmw32AndU { .. } => Self::And,
+			Operator::I32AtomicRmwOr { .. }
+			| Operator::I64AtomicRmwOr { .. }
+			| Operator::I32AtomicRmw8OrU { .. }
+			| Operator::I32AtomicRmw16OrU { .. }
+			| Operator::I64AtomicRmw8OrU { .. }
+			| Operator::I64AtomicRmw16OrU { .. }
+			| Operator::I64AtomicRmw32OrU { .. } => Self::Or,
+			Operator::I32AtomicRmwXor { .. }
+			| Operator::I64AtomicRmwXor { .. }
+			| Operator::I32AtomicRmw8XorU { .. }
+			| Operator::I32AtomicRmw16XorU { .. }
+			| Operator::I64AtomicRmw8XorU { .. }
+			| Operator::I64AtomicRmw16XorU { .. }
+			| Operator::I64AtomicRmw32XorU { .. } => Self::Xor,
+			Operator::I32AtomicRmwXchg { .. }
+			| Operator::I64AtomicRmwXchg { .. }
+			| Operator::I32AtomicRmw8XchgU { .. }
+			| Operator::I32AtomicRmw16XchgU { .. }
+			| Operator::I64AtomicRmw8XchgU { .. }
+			| Operator::I64AtomicRmw16XchgU { .. }
+			| Operator::I64AtomicRmw32XchgU { .. } => Self::Xchg,
 			_ => return Err(()),
 		};
 
@@ -83,7 +224,8 @@ impl TryFrom<&Operator<'_>> for StoreType {
 // Order of mnemonics is:
 // operation_result_parameter
 #[allow(non_camel_case_types)]
-#[derive(Clone, Copy)]
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum UnOpType {
 	Clz_I32,
 	Ctz_I32,
@@ -122,6 +264,7 @@ pub enum UnOpType {
 	Saturate_I64_F64,
 	Saturate_U64_F32,
 	Saturate_U64_F64,
+	Saturate_U32_I64,
 	Extend_I32_N8,
 	Extend_I32_N16,
 	Extend_I64_N8,
@@ -143,6 +286,148 @@ pub enum UnOpType {
 	Reinterpret_I64_F64,
 	Reinterpret_F32_I32,
 	Reinterpret_F64_I64,
+	Splat_I8X16,
+	Splat_I16X8,
+	Splat_I32X4,
+	Splat_I64X2,
+	Splat_F32X4,
+	Splat_F64X2,
+	Not_V128,
+	AnyTrue_V128,
+	Abs_I8X16,
+	Neg_I8X16,
+	Popcnt_I8X16,
+	AllTrue_I8X16,
+	Bitmask_I8X16,
+	Abs_I16X8,
+	Neg_I16X8,
+	AllTrue_I16X8,
+	Bitmask_I16X8,
+	Abs_I32X4,
+	Neg_I32X4,
+	AllTrue_I32X4,
+	Bitmask_I32X4,
+	Abs_I64X2,
+	Neg_I64X2,
+	AllTrue_I64X2,
+	Bitmask_I64X2,
+	ExtAddPairwise_I16X8_I8X16,
+	ExtAddPairwise_I16X8_U8X16,
+	ExtAddPairwise_I32X4_I16X8,
+	ExtAddPairwise_I32X4_U16X8,
+	ExtendLow_I16X8_I8X16,
+	ExtendLow_I16X8_U8X16,
+	ExtendHigh_I16X8_I8X16,
+	ExtendHigh_I16X8_U8X16,
+	ExtendLow_I32X4_I16X8,
+	ExtendLow_I32X4_U16X8,
+	ExtendHigh_I32X4_I16X8,
+	ExtendHigh_I32X4_U16X8,
+	ExtendLow_I64X2_I32X4,
+	ExtendLow_I64X2_U32X4,
+	ExtendHigh_I64X2_I32X4,
+	ExtendHigh_I64X2_U32X4,
+	Ceil_F32X4,
+	Floor_F32X4,
+	Truncate_F32X4,
+	Nearest_F32X4,
+	Abs_F32X4,
+	Neg_F32X4,
+	Sqrt_F32X4,
+	Ceil_F64X2,
+	Floor_F64X2,
+	Truncate_F64X2,
+	Nearest_F64X2,
+	Abs_F64X2,
+	Neg_F64X2,
+	Sqrt_F64X2,
+	Saturate_I32X4_F32X4,
+	Saturate_U32X4_F32X4,
+	SaturateZero_I32X4_F64X2,
+	SaturateZero_U32X4_F64X2,
+	Convert_F32X4_I32X4,
+	Convert_F32X4_U32X4,
+	ConvertLow_F64X2_I32X4,
+	ConvertLow_F64X2_U32X4,
+	DemoteZero_F32X4_F64X2,
+	PromoteLow_F64X2_F32X4,
+}
+
+impl UnOpType {
+	#[must_use]
+	pub const fn result_type(self) -> ValType {
+		match self {
+			Self::Clz_I32
+			| Self::Ctz_I32
+			| Self::Popcnt_I32
+			| Self::Wrap_I32_I64
+			| Self::Truncate_I32_F32
+			| Self::Truncate_I32_F64
+			| Self::Truncate_U32_F32
+			| Self::Truncate_U32_F64
+			| Self::Saturate_I32_F32
+			| Self::Saturate_I32_F64
+			| Self::Saturate_U32_F32
+			| Self::Saturate_U32_F64
+			| Self::Saturate_U32_I64
+			| Self::Extend_I32_N8
+			| Self::Extend_I32_N16
+			| Self::Reinterpret_I32_F32
+			| Self::AnyTrue_V128
+			| Self::AllTrue_I8X16
+			| Self::Bitmask_I8X16
+			| Self::AllTrue_I16X8
+			| Self::Bitmask_I16X8
+			| Self::AllTrue_I32X4
+			| Self::Bitmask_I32X4
+			| Self::AllTrue_I64X2
+			| Self::Bitmask_I64X2 => ValType::I32,
+			Self::Clz_I64
+			| Self::Ctz_I64
+			| Self::Popcnt_I64
+			| Self::Truncate_I64_F32
+			| Self::Truncate_I64_F64
+			| Self::Truncate_U64_F32
+			| Self::Truncate_U64_F64
+			| Self::Saturate_I64_F32
+			| Self::Saturate_I64_F64
+			| Self::Saturate_U64_F32
+			| Self::Saturate_U64_F64
+			| Self::Extend_I64_N8
+			| Self::Extend_I64_N16
+			| Self::Extend_I64_N32
+			| Self::Extend_I64_I32
+			| Self::Extend_I64_U32
+			| Self::Reinterpret_I64_F64 => ValType::I64,
+			Self::Abs_F32
+			| Self::Neg_F32
+			| Self::Ceil_F32
+			| Self::Floor_F32
+			| Self::Truncate_F32
+			| Self::Nearest_F32
+			| Self::Sqrt_F32
+			| Self::Convert_F32_I32
+			| Self::Convert_F32_U32
+			| Self::Convert_F32_I64
+			| Self::Convert_F32_U64
+			| Self::Demote_F32_F64
+			| Self::Reinterpret_F32_I32 => ValType::F32,
+			Self::Abs_F64
+			| Self::Neg_F64
+			| Self::Ceil_F64
+			| Self::Floor_F64
+			| Self::Truncate_F64
+			| Self::Nearest_F64
+			| Self::Sqrt_F64
+			| Self::Convert_F64_I32
+			| Self::Convert_F64_U32
+			| Self::Convert_F64_I64
+			| Self::Convert_F64_U64
+			| Self::Promote_F64_F32
+			| Self::Reinterpret_F64_I64 => ValType::F64,
+			_ => ValType::V128,
+		}
+	}
 }
 
 impl TryFrom<&Operator<'_>> for UnOpType {
@@ -208,6 +493,71 @@ impl TryFrom<&Operator<'_>> for UnOpType {
 			Operator::I64ReinterpretF64 => Self::Reinterpret_I64_F64,
 			Operator::F32ReinterpretI32 => Self::Reinterpret_F32_I32,
 			Operator::F64ReinterpretI64 => Self::Reinterpret_F64_I64,
+			Operator::I8x16Splat => Self::Splat_I8X16,
+			Operator::I16x8Splat => Self::Splat_I16X8,
+			Operator::I32x4Splat => Self::Splat_I32X4,
+			Operator::I64x2Splat => Self::Splat_I64X2,
+			Operator::F32x4Splat => Self::Splat_F32X4,
+			Operator::F64x2Splat => Self::Splat_F64X2,
+			Operator::V128Not => Self::Not_V128,
+			Operator::V128AnyTrue => Self::AnyTrue_V128,
+			Operator::I8x16Abs => Self::Abs_I8X16,
+			Operator::I8x16Neg => Self::Neg_I8X16,
+			Operator::I8x16Popcnt => Self::Popcnt_I8X16,
+			Operator::I8x16AllTrue => Self::AllTrue_I8X16,
+			Operator::I8x16Bitmask => Self::Bitmask_I8X16,
+			Operator::I16x8Abs => Self::Abs_I16X8,
+			Operator::I16x8Neg => Self::Neg_I16X8,
+			Operator::I16x8AllTrue => Self::AllTrue_I16X8,
+			Operator::I16x8Bitmask => Self::Bitmask_I16X8,
+			Operator::I32x4Abs => Self::Abs_I32X4,
+			Operator::I32x4Neg => Self::Neg_I32X4,
+			Operator::I32x4AllTrue => Self::AllTrue_I32X4,
+			Operator::I32x4Bitmask => Self::Bitmask_I32X4,
+			Operator::I64x2Abs => Self::Abs_I64X2,
+			Operator::I64x2Neg => Self::Neg_I64X2,
+			Operator::I64x2AllTrue => Self::AllTrue_I64X2,
+			Operator::I64x2Bitmask => Self::Bitmask_I64X2,
+			Operator::I16x8ExtAddPairwiseI8x16S => Self::ExtAddPairwise_I16X8_I8X16,
+			Operator::I16x8ExtAddPairwiseI8x16U => Self::ExtAddPairwise_I16X8_U8X16,
+			Operator::I32x4ExtAddPairwiseI16x8S => Self::ExtAddPairwise_I32X4_I16X8,
+			Operator::I32x4ExtAddPairwiseI16x8U => Self::ExtAddPairwise_I32X4_U16X8,
+			Operator::I16x8ExtendLowI8x16S => Self::ExtendLow_I16X8_I8X16,
+			Operator::I16x8ExtendLowI8x16U => Self::ExtendLow_I16X8_U8X16,
+			Operator::I16x8ExtendHighI8x16S => Self::ExtendHigh_I16X8_I8X16,
+			Operator::I16x8ExtendHighI8x16U => Self::ExtendHigh_I16X8_U8X16,
+			Operator::I32x4ExtendLowI16x8S => Self::ExtendLow_I32X4_I16X8,
+			Operator::I32x4ExtendLowI16x8U => Self::ExtendLow_I32X4_U16X8,
+			Operator::I32x4ExtendHighI16x8S => Self::ExtendHigh_I32X4_I16X8,
+			Operator::I32x4ExtendHighI16x8U => Self::ExtendHigh_I32X4_U16X8,
+			Operator::I64x2ExtendLowI32x4S => Self::ExtendLow_I64X2_I32X4,
+			Operator::I64x2ExtendLowI32x4U => Self::ExtendLow_I64X2_U32X4,
+			Operator::I64x2ExtendHighI32x4S => Self::ExtendHigh_I64X2_I32X4,
+			Operator::I64x2ExtendHighI32x4U => Self::ExtendHigh_I64X2_U32X4,
+			Operator::F32x4Ceil => Self::Ceil_F32X4,
+			Operator::F32x4Floor => Self::Floor_F32X4,
+			Operator::F32x4Trunc => Self::Truncate_F32X4,
+			Operator::F32x4Nearest => Self::Nearest_F32X4,
+			Operator::F32x4Abs => Self::Abs_F32X4,
+			Operator::F32x4Neg => Self::Neg_F32X4,
+			Operator::F32x4Sqrt => Self::Sqrt_F32X4,
+			Operator::F64x2Ceil => Self::Ceil_F64X2,
+			Operator::F64x2Floor => Self::Floor_F64X2,
+			Operator::F64x2Trunc => Self::Truncate_F64X2,
+			Operator::F64x2Nearest => Self::Nearest_F64X2,
+			Operator::F64x2Abs => Self::Abs_F64X2,
+			Operator::F64x2Neg => Self::Neg_F64X2,
+			Operator::F64x2Sqrt => Self::Sqrt_F64X2,
+			Operator::I32x4TruncSatF32x4S => Self::Saturate_I32X4_F32X4,
+			Operator::I32x4TruncSatF32x4U => Self::Saturate_U32X4_F32X4,
+			Operator::I32x4TruncSatF64x2SZero => Self::SaturateZero_I32X4_F64X2,
+			Operator::I32x4TruncSatF64x2UZero => Self::SaturateZero_U32X4_F64X2,
+			Operator::F32x4ConvertI32x4S => Self::Convert_F32X4_I32X4,
+			Operator::F32x4ConvertI32x4U => Self::Convert_F32X4_U32X4,
+			Operator::F64x2ConvertLowI32x4S => Self::ConvertLow_F64X2_I32X4,
+			Operator::F64x2ConvertLowI32x4U => Self::ConvertLow_F64X2_U32X4,
+			Operator::F32x4DemoteF64x2Zero => Self::DemoteZero_F32X4_F64X2,
+			Operator::F64x2PromoteLowF32x4 => Self::PromoteLow_F64X2_F32X4,
 			_ => return Err(()),
 		};
 
@@ -216,7 +566,8 @@ impl TryFrom<&Operator<'_>> for UnOpType {
 }
 
 #[allow(non_camel_case_types)]
-#[derive(Clone, Copy)]
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum BinOpType {
 	Add_I32,
 	Sub_I32,
@@ -262,6 +613,191 @@ pub enum BinOpType {
 	Min_F64,
 	Max_F64,
 	Copysign_F64,
+	Swizzle_I8X16,
+	Eq_I8X16,
+	Ne_I8X16,
+	LtS_I8X16,
+	LtU_I8X16,
+	GtS_I8X16,
+	GtU_I8X16,
+	LeS_I8X16,
+	LeU_I8X16,
+	GeS_I8X16,
+	GeU_I8X16,
+	Eq_I16X8,
+	Ne_I16X8,
+	LtS_I16X8,
+	LtU_I16X8,
+	GtS_I16X8,
+	GtU_I16X8,
+	LeS_I16X8,
+	LeU_I16X8,
+	GeS_I16X8,
+	GeU_I16X8,
+	Eq_I32X4,
+	Ne_I32X4,
+	LtS_I32X4,
+	LtU_I32X4,
+	GtS_I32X4,
+	GtU_I32X4,
+	LeS_I32X4,
+	LeU_I32X4,
+	GeS_I32X4,
+	GeU_I32X4,
+	Eq_I64X2,
+	Ne_I64X2,
+	LtS_I64X2,
+	GtS_I64X2,
+	LeS_I64X2,
+	GeS_I64X2,
+	Eq_F32X4,
+	Ne_F32X4,
+	Lt_F32X4,
+	Gt_F32X4,
+	Le_F32X4,
+	Ge_F32X4,
+	Eq_F64X2,
+	Ne_F64X2,
+	Lt_F64X2,
+	Gt_F64X2,
+	Le_F64X2,
+	Ge_F64X2,
+	And_V128,
+	AndNot_V128,
+	Or_V128,
+	Xor_V128,
+	Narrow_I8X16_I16X8,
+	Narrow_U8X16_I16X8,
+	Narrow_I16X8_I32X4,
+	Narrow_U16X8_I32X4,
+	Shl_I8X16,
+	ShrS_I8X16,
+	ShrU_I8X16,
+	Add_I8X16,
+	AddSatS_I8X16,
+	AddSatU_I8X16,
+	Sub_I8X16,
+	SubSatS_I8X16,
+	SubSatU_I8X16,
+	MinS_I8X16,
+	MinU_I8X16,
+	MaxS_I8X16,
+	MaxU_I8X16,
+	AvgrU_I8X16,
+	Shl_I16X8,
+	ShrS_I16X8,
+	ShrU_I16X8,
+	Add_I16X8,
+	AddSatS_I16X8,
+	AddSatU_I16X8,
+	Sub_I16X8,
+	SubSatS_I16X8,
+	SubSatU_I16X8,
+	Mul_I16X8,
+	MinS_I16X8,
+	MinU_I16X8,
+	MaxS_I16X8,
+	MaxU_I16X8,
+	AvgrU_I16X8,
+	Shl_I32X4,
+	ShrS_I32X4,
+	ShrU_I32X4,
+	Add_I32X4,
+	Sub_I32X4,
+	Mul_I32X4,
+	MinS_I32X4,
+	MinU_I32X4,
+	MaxS_I32X4,
+	MaxU_I32X4,
+	Shl_I64X2,
+	ShrS_I64X2,
+	ShrU_I64X2,
+	Add_I64X2,
+	Sub_I64X2,
+	Mul_I64X2,
+	Q15MulrSat_I16X8,
+	ExtMulLow_I16X8_I8X16,
+	ExtMulLow_I16X8_U8X16,
+	ExtMulHigh_I16X8_I8X16,
+	ExtMulHigh_I16X8_U8X16,
+	ExtMulLow_I32X4_I16X8,
+	ExtMulLow_I32X4_U16X8,
+	ExtMulHigh_I32X4_I16X8,
+	ExtMulHigh_I32X4_U16X8,
+	ExtMulLow_I64X2_I32X4,
+	ExtMulLow_I64X2_U32X4,
+	ExtMulHigh_I64X2_I32X4,
+	ExtMulHigh_I64X2_U32X4,
+	Dot_I32X4_I16X8,
+	Add_F32X4,
+	Sub_F32X4,
+	Mul_F32X4,
+	Div_F32X4,
+	Min_F32X4,
+	Max_F32X4,
+	PMin_F32X4,
+	PMax_F32X4,
+	Add_F64X2,
+	Sub_F64X2,
+	Mul_F64X2,
+	Div_F64X2,
+	Min_F64X2,
+	Max_F64X2,
+	PMin_F64X2,
+	PMax_F64X2,
+}
+
+impl BinOpType {
+	#[must_use]
+	pub const fn result_type(self) -> ValType {
+		match self {
+			Self::Add_I32
+			| Self::Sub_I32
+			| Self::Mul_I32
+			| Self::DivS_I32
+			| Self::DivU_I32
+			| Self::RemS_I32
+			| Self::RemU_I32
+			| Self::And_I32
+			| Self::Or_I32
+			| Self::Xor_I32
+			| Self::Shl_I32
+			| Self::ShrS_I32
+			| Self::ShrU_I32
+			| Self::Rotl_I32
+			| Self::Rotr_I32 => ValType::I32,
+			Self::Add_I64
+			| Self::Sub_I64
+			| Self::Mul_I64
+			| Self::DivS_I64
+			| Self::DivU_I64
+			| Self::RemS_I64
+			| Self::RemU_I64
+			| Self::And_I64
+			| Self::Or_I64
+			| Self::Xor_I64
+			| Self::Shl_I64
+			| Self::ShrS_I64
+			| Self::ShrU_I64
+			| Self::Rotl_I64
+			| Self::Rotr_I64 => ValType::I64,
+			Self::Add_F32
+			| Self::Sub_F32
+			| Self::Mul_F32
+			| Self::Div_F32
+			| Self::Min_F32
+			| Self::Max_F32
+			| Self::Copysign_F32 => ValType::F32,
+			Self::Add_F64
+			| Self::Sub_F64
+			| Self::Mul_F64
+			| Self::Div_F64
+			| Self::Min_F64
+			| Self::Max_F64
+			| Self::Copysign_F64 => ValType::F64,
+			_ => ValType::V128,
+		}
+	}
 }
 
 impl TryFrom<&Operator<'_>> for BinOpType {
@@ -313,6 +849,138 @@ impl TryFrom<&Operator<'_>> for BinOpType {
 			Operator::F64Min => Self::Min_F64,
 			Operator::F64Max => Self::Max_F64,
 			Operator::F64Copysign => Self::Copysign_F64,
+			Operator::I8x16Swizzle => Self::Swizzle_I8X16,
+			Operator::I8x16Eq => Self::Eq_I8X16,
+			Operator::I8x16Ne => Self::Ne_I8X16,
+			Operator::I8x16LtS => Self::LtS_I8X16,
+			Operator::I8x16LtU => Self::LtU_I8X16,
+			Operator::I8x16GtS => Self::GtS_I8X16,
+			Operator::I8x16GtU => Self::GtU_I8X16,
+			Operator::I8x16LeS => Self::LeS_I8X16,
+			Operator::I8x16LeU => Self::LeU_I8X16,
+			Operator::I8x16GeS => Self::GeS_I8X16,
+			Operator::I8x16GeU => Self::GeU_I8X16,
+			Operator::I16x8Eq => Self::Eq_I16X8,
+			Operator::I16x8Ne => Self::Ne_I16X8,
+			Operator::I16x8LtS => Self::LtS_I16X8,
+			Operator::I16x8LtU => Self::LtU_I16X8,
+			Operator::I16x8GtS => Self::GtS_I16X8,
+			Operator::I16x8GtU => Self::GtU_I16X8,
+			Operator::I16x8LeS => Self::LeS_I16X8,
+			Operator::I16x8LeU => Self::LeU_I16X8,
+			Operator::I16x8GeS => Self::GeS_I16X8,
+			Operator::I16x8GeU => Self::GeU_I16X8,
+			Operator::I32x4Eq => Self::Eq_I32X4,
+			Operator::I32x4Ne => Self::Ne_I32X4,
+			Operator::I32x4LtS => Self::LtS_I32X4,
+			Operator::I32x4LtU => Self::LtU_I32X4,
+			Operator::I32x4GtS => Self::GtS_I32X4,
+			Operator::I32x4GtU => Self::GtU_I32X4,
+			Operator::I32x4LeS => Self::LeS_I32X4,
+			Operator::I32x4LeU => Self::LeU_I32X4,
+			Operator::I32x4GeS => Self::GeS_I32X4,
+			Operator::I32x4GeU => Self::GeU_I32X4,
+			Operator::I64x2Eq => Self::Eq_I64X2,
+			Operator::I64x2Ne => Self::Ne_I64X2,
+			Operator::I64x2LtS => Self::LtS_I64X2,
+			Operator::I64x2GtS => Self::GtS_I64X2,
+			Operator::I64x2LeS => Self::LeS_I64X2,
+			Operator::I64x2GeS => Self::GeS_I64X2,
+			Operator::F32x4Eq => Self::Eq_F32X4,
+			Operator::F32x4Ne => Self::Ne_F32X4,
+			Operator::F32x4Lt => Self::Lt_F32X4,
+			Operator::F32x4Gt => Self::Gt_F32X4,
+			Operator::F32x4Le => Self::Le_F32X4,
+			Operator::F32x4Ge => Self::Ge_F32X4,
+			Operator::F64x2Eq => Self::Eq_F64X2,
+			Operator::F64x2Ne => Self::Ne_F64X2,
+			Operator::F64x2Lt => Self::Lt_F64X2,
+			Operator::F64x2Gt => Self::Gt_F64X2,
+			Operator::F64x2Le => Self::Le_F64X2,
+			Operator::F64x2Ge => Self::Ge_F64X2,
+			Operator::V128And => Self::And_V128,
+			Operator::V128AndNot => Self::AndNot_V128,
+			Operator::V128Or => Self::Or_V128,
+			Operator::V128Xor => Self::Xor_V128,
+			Operator::I8x16NarrowI16x8S => Self::Narrow_I8X16_I16X8,
+			Operator::I8x16NarrowI16x8U => Self::Narrow_U8X16_I16X8,
+			Operator::I16x8NarrowI32x4S => Self::Narrow_I16X8_I32X4,
+			Operator::I16x8NarrowI32x4U => Self::Narrow_U16X8_I32X4,
+			Operator::I8x16Shl => Self::Shl_I8X16,
+			Operator::I8x16ShrS => Self::ShrS_I8X16,
+			Operator::I8x16ShrU => Self::ShrU_I8X16,
+			Operator::I8x16Add => Self::Add_I8X16,
+			Operator::I8x16AddSatS => Self::AddSatS_I8X16,
+			Operator::I8x16AddSatU => Self::AddSatU_I8X16,
+			Operator::I8x16Sub => Self::Sub_I8X16,
+			Operator::I8x16SubSatS => Self::SubSatS_I8X16,
+			Operator::I8x16SubSatU => Self::SubSatU_I8X16,
+			Operator::I8x16MinS => Self::MinS_I8X16,
+			Operator::I8x16MinU => Self::MinU_I8X16,
+			Operator::I8x16MaxS => Self::MaxS_I8X16,
+			Operator::I8x16MaxU => Self::MaxU_I8X16,
+			Operator::I8x16AvgrU => Self::AvgrU_I8X16,
+			Operator::I16x8Shl => Self::Shl_I16X8,
+			Operator::I16x8ShrS => Self::ShrS_I16X8,
+			Operator::I16x8ShrU => Self::ShrU_I16X8,
+			Operator::I16x8Add => Self::Add_I16X8,
+			Operator::I16x8AddSatS => Self::AddSatS_I16X8,
+			Operator::I16x8AddSatU => Self::AddSatU_I16X8,
+			Operator::I16x8Sub => Self::Sub_I16X8,
+			Operator::I16x8SubSatS => Self::SubSatS_I16X8,
+			Operator::I16x8SubSatU => Self::SubSatU_I16X8,
+			Operator::I16x8Mul => Self::Mul_I16X8,
+			Operator::I16x8MinS => Self::MinS_I16X8,
+			Operator::I16x8MinU => Self::MinU_I16X8,
+			Operator::I16x8MaxS => Self::MaxS_I16X8,
+			Operator::I16x8MaxU => Self::MaxU_I16X8,
+			Operator::I16x8AvgrU => Self::AvgrU_I16X8,
+			Operator::I32x4Shl => Self::Shl_I32X4,
+			Operator::I32x4ShrS => Self::ShrS_I32X4,
+			Operator::I32x4ShrU => Self::ShrU_I32X4,
+			Operator::I32x4Add => Self::Add_I32X4,
+			Operator::I32x4Sub => Self::Sub_I32X4,
+			Operator::I32x4Mul => Self::Mul_I32X4,
+			Operator::I32x4MinS => Self::MinS_I32X4,
+			Operator::I32x4MinU => Self::MinU_I32X4,
+			Operator::I32x4MaxS => Self::MaxS_I32X4,
+			Operator::I32x4MaxU => Self::MaxU_I32X4,
+			Operator::I64x2Shl => Self::Shl_I64X2,
+			Operator::I64x2ShrS => Self::ShrS_I64X2,
+			Operator::I64x2ShrU => Self::ShrU_I64X2,
+			Operator::I64x2Add => Self::Add_I64X2,
+			Operator::I64x2Sub => Self::Sub_I64X2,
+			Operator::I64x2Mul => Self::Mul_I64X2,
+			Operator::I16x8Q15MulrSatS => Self::Q15MulrSat_I16X8,
+			Operator::I16x8ExtMulLowI8x16S => Self::ExtMulLow_I16X8_I8X16,
+			Operator::I16x8ExtMulLowI8x16U => Self::ExtMulLow_I16X8_U8X16,
+			Operator::I16x8ExtMulHighI8x16S => Self::ExtMulHigh_I16X8_I8X16,
+			Operator::I16x8ExtMulHighI8x16U => Self::ExtMulHigh_I16X8_U8X16,
+			Operator::I32x4ExtMulLowI16x8S => Self::ExtMulLow_I32X4_I16X8,
+			Operator::I32x4ExtMulLowI16x8U => Self::ExtMulLow_I32X4_U16X8,
+			Operator::I32x4ExtMulHighI16x8S => Self::ExtMulHigh_I32X4_I16X8,
+			Operator::I32x4ExtMulHighI16x8U => Self::ExtMulHigh_I32X4_U16X8,
+			Operator::I64x2ExtMulLowI32x4S => Self::ExtMulLow_I64X2_I32X4,
+			Operator::I64x2ExtMulLowI32x4U => Self::ExtMulLow_I64X2_U32X4,
+			Operator::I64x2ExtMulHighI32x4S => Self::ExtMulHigh_I64X2_I32X4,
+			Operator::I64x2ExtMulHighI32x4U => Self::ExtMulHigh_I64X2_U32X4,
+			Operator::I32x4DotI16x8S => Self::Dot_I32X4_I16X8,
+			Operator::F32x4Add => Self::Add_F32X4,
+			Operator::F32x4Sub => Self::Sub_F32X4,
+			Operator::F32x4Mul => Self::Mul_F32X4,
+			Operator::F32x4Div => Self::Div_F32X4,
+			Operator::F32x4Min => Self::Min_F32X4,
+			Operator::F32x4Max => Self::Max_F32X4,
+			Operator::F32x4PMin => Self::PMin_F32X4,
+			Operator::F32x4PMax => Self::PMax_F32X4,
+			Operator::F64x2Add => Self::Add_F64X2,
+			Operator::F64x2Sub => Self::Sub_F64X2,
+			Operator::F64x2Mul => Self::Mul_F64X2,
+			Operator::F64x2Div => Self::Div_F64X2,
+			Operator::F64x2Min => Self::Min_F64X2,
+			Operator::F64x2Max => Self::Max_F64X2,
+			Operator::F64x2PMin => Self::PMin_F64X2,
+			Operator::F64x2PMax => Self::PMax_F64X2,
 			_ => {
 				return Err(());
 			}
@@ -323,7 +991,8 @@ impl TryFrom<&Operator<'_>> for BinOpType {
 }
 
 #[allow(non_camel_case_types)]
-#[derive(Clone, Copy)]
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum CmpOpType {
 	Eq_I32,
 	Ne_I32,
@@ -405,6 +1074,8 @@ impl TryFrom<&Operator<'_>> for CmpOpType {
 	}
 }
 
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Select {
 	pub(crate) condition: Box<Expression>,
 	pub(crate) on_true: Box<Expression>,
@@ -412,6 +1083,15 @@ pub struct Select {
 }
 
 impl Select {
+	#[must_use]
+	pub fn new(condition: Expression, on_true: Expression, on_false: Expression) -> Self {
+		Self {
+			condition: condition.into(),
+			on_true: on_true.into(),
+			on_false: on_false.into(),
+		}
+	}
+
 	#[must_use]
 	pub const fn condition(&self) -> &Expression {
 		&self.condition
@@ -426,52 +1106,127 @@ impl Select {
 	pub const fn on_false(&self) -> &Expression {
 		&self.on_false
 	}
+
+	pub fn condition_mut(&mut self) -> &mut Expression {
+		&mut self.condition
+	}
+
+	pub fn on_true_mut(&mut self) -> &mut Expression {
+		&mut self.on_true
+	}
+
+	pub fn on_false_mut(&mut self) -> &mut Expression {
+		&mut self.on_false
+	}
 }
 
-#[derive(Clone, Copy)]
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Temporary {
 	pub(crate) var: usize,
+	#[cfg_attr(feature = "serde", serde(with = "crate::serialize::val_type"))]
+	pub(crate) ty: ValType,
 }
 
 impl Temporary {
+	#[must_use]
+	pub const fn new(var: usize, ty: ValType) -> Self {
+		Self { var, ty }
+	}
+
 	#[must_use]
 	pub const fn var(self) -> usize {
 		self.var
 	}
+
+	#[must_use]
+	pub const fn ty(self) -> ValType {
+		self.ty
+	}
 }
 
-#[derive(Clone, Copy)]
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Local {
 	pub(crate) var: usize,
+	#[cfg_attr(feature = "serde", serde(with = "crate::serialize::val_type"))]
+	pub(crate) ty: ValType,
 }
 
 impl Local {
+	#[must_use]
+	pub const fn new(var: usize, ty: ValType) -> Self {
+		Self { var, ty }
+	}
+
 	#[must_use]
 	pub const fn var(self) -> usize {
 		self.var
 	}
+
+	#[must_use]
+	pub const fn ty(self) -> ValType {
+		self.ty
+	}
 }
 
-#[derive(Clone, Copy)]
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct GetGlobal {
 	pub(crate) var: usize,
+	#[cfg_attr(feature = "serde", serde(with = "crate::serialize::val_type"))]
+	pub(crate) ty: ValType,
 }
 
 impl GetGlobal {
+	#[must_use]
+	pub const fn new(var: usize, ty: ValType) -> Self {
+		Self { var, ty }
+	}
+
 	#[must_use]
 	pub const fn var(self) -> usize {
 		self.var
 	}
+
+	#[must_use]
+	pub const fn ty(self) -> ValType {
+		self.ty
+	}
 }
 
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct LoadAt {
 	pub(crate) load_type: LoadType,
 	pub(crate) memory: usize,
-	pub(crate) offset: u32,
+	pub(crate) offset: u64,
 	pub(crate) pointer: Box<Expression>,
+	pub(crate) is_atomic: bool,
 }
 
 impl LoadAt {
+	#[must_use]
+	pub fn new(load_type: LoadType, memory: usize, offset: u64, pointer: Expression) -> Self {
+		Self {
+			load_type,
+			memory,
+			offset,
+			pointer: pointer.into(),
+			is_atomic: false,
+		}
+	}
+
+	/// Makes the load atomic, so that it traps when its address is not
+	/// a multiple of its size.
+	#[must_use]
+	pub fn into_atomic(self) -> Self {
+		Self {
+			is_atomic: true,
+			..self
+		}
+	}
+
 	#[must_use]
 	pub const fn load_type(&self) -> LoadType {
 		self.load_type
@@ -483,7 +1238,7 @@ impl LoadAt {
 	}
 
 	#[must_use]
-	pub const fn offset(&self) -> u32 {
+	pub const fn offset(&self) -> u64 {
 		self.offset
 	}
 
@@ -491,483 +1246,2266 @@ impl LoadAt {
 	pub const fn pointer(&self) -> &Expression {
 		&self.pointer
 	}
+
+	#[must_use]
+	pub const fn is_atomic(&self) -> bool {
+		self.is_atomic
+	}
+
+	pub fn pointer_mut(&mut self) -> &mut Expression {
+		&mut self.pointer
+	}
 }
 
-#[derive(Clone, Copy)]
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct MemorySize {
 	pub(crate) memory: usize,
 }
 
 impl MemorySize {
+	#[must_use]
+	pub const fn new(memory: usize) -> Self {
+		Self { memory }
+	}
+
 	#[must_use]
 	pub const fn memory(&self) -> usize {
 		self.memory
 	}
 }
 
-#[derive(Clone, Copy)]
-pub enum Value {
-	I32(i32),
-	I64(i64),
-	F32(f32),
-	F64(f64),
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum RefType {
+	Func,
+	Extern,
 }
 
-impl From<i32> for Value {
-	fn from(value: i32) -> Self {
-		Self::I32(value)
+impl From<RefType> for ValType {
+	fn from(value: RefType) -> Self {
+		match value {
+			RefType::Func => Self::FUNCREF,
+			RefType::Extern => Self::EXTERNREF,
+		}
 	}
 }
 
-impl From<i64> for Value {
-	fn from(value: i64) -> Self {
-		Self::I64(value)
+impl From<HeapType> for RefType {
+	fn from(value: HeapType) -> Self {
+		match value {
+			HeapType::Extern => Self::Extern,
+			_ => Self::Func,
+		}
 	}
 }
 
-impl From<u32> for Value {
-	fn from(value: u32) -> Self {
-		Self::F32(f32::from_bits(value))
-	}
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RefNull {
+	pub(crate) ref_type: RefType,
 }
 
-impl From<u64> for Value {
-	fn from(value: u64) -> Self {
-		Self::F64(f64::from_bits(value))
+impl RefNull {
+	#[must_use]
+	pub const fn new(ref_type: RefType) -> Self {
+		Self { ref_type }
+	}
+
+	#[must_use]
+	pub const fn ref_type(&self) -> RefType {
+		self.ref_type
 	}
 }
 
-pub struct UnOp {
-	pub(crate) op_type: UnOpType,
-	pub(crate) rhs: Box<Expression>,
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RefIsNull {
+	pub(crate) value: Box<Expression>,
 }
 
-impl UnOp {
+impl RefIsNull {
 	#[must_use]
-	pub const fn op_type(&self) -> UnOpType {
-		self.op_type
+	pub fn new(value: Expression) -> Self {
+		Self {
+			value: value.into(),
+		}
 	}
 
 	#[must_use]
-	pub const fn rhs(&self) -> &Expression {
-		&self.rhs
+	pub const fn value(&self) -> &Expression {
+		&self.value
 	}
-}
 
-pub struct BinOp {
-	pub(crate) op_type: BinOpType,
-	pub(crate) lhs: Box<Expression>,
-	pub(crate) rhs: Box<Expression>,
+	pub fn value_mut(&mut self) -> &mut Expression {
+		&mut self.value
+	}
 }
 
-impl BinOp {
-	#[must_use]
-	pub const fn op_type(&self) -> BinOpType {
-		self.op_type
-	}
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RefFunc {
+	pub(crate) function: usize,
+}
 
+impl RefFunc {
 	#[must_use]
-	pub const fn lhs(&self) -> &Expression {
-		&self.lhs
+	pub const fn new(function: usize) -> Self {
+		Self { function }
 	}
 
 	#[must_use]
-	pub const fn rhs(&self) -> &Expression {
-		&self.rhs
+	pub const fn function(&self) -> usize {
+		self.function
 	}
 }
 
-pub struct CmpOp {
-	pub(crate) op_type: CmpOpType,
-	pub(crate) lhs: Box<Expression>,
-	pub(crate) rhs: Box<Expression>,
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TableGet {
+	pub(crate) table: usize,
+	pub(crate) ref_type: RefType,
+	pub(crate) index: Box<Expression>,
 }
 
-impl CmpOp {
+impl TableGet {
 	#[must_use]
-	pub const fn op_type(&self) -> CmpOpType {
-		self.op_type
+	pub fn new(table: usize, ref_type: RefType, index: Expression) -> Self {
+		Self {
+			table,
+			ref_type,
+			index: index.into(),
+		}
 	}
 
 	#[must_use]
-	pub const fn lhs(&self) -> &Expression {
-		&self.lhs
+	pub const fn table(&self) -> usize {
+		self.table
 	}
 
 	#[must_use]
-	pub const fn rhs(&self) -> &Expression {
-		&self.rhs
+	pub const fn ref_type(&self) -> RefType {
+		self.ref_type
 	}
-}
 
-pub enum Expression {
-	Select(Select),
-	GetTemporary(Temporary),
-	GetLocal(Local),
-	GetGlobal(GetGlobal),
-	LoadAt(LoadAt),
-	MemorySize(MemorySize),
-	Value(Value),
-	UnOp(UnOp),
-	BinOp(BinOp),
-	CmpOp(CmpOp),
+	#[must_use]
+	pub const fn index(&self) -> &Expression {
+		&self.index
+	}
+
+	pub fn index_mut(&mut self) -> &mut Expression {
+		&mut self.index
+	}
 }
 
-#[derive(Clone, Copy)]
-pub struct ResultList {
-	start: usize,
-	end: usize,
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TableSize {
+	pub(crate) table: usize,
 }
 
-impl ResultList {
+impl TableSize {
 	#[must_use]
-	pub const fn new(start: usize, end: usize) -> Self {
-		Self { start, end }
+	pub const fn new(table: usize) -> Self {
+		Self { table }
 	}
 
 	#[must_use]
-	pub const fn is_empty(self) -> bool {
-		self.start == self.end
+	pub const fn table(&self) -> usize {
+		self.table
 	}
+}
+
+#[allow(non_camel_case_types)]
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum LaneType {
+	I8X16,
+	I16X8,
+	I32X4,
+	I64X2,
+	F32X4,
+	F64X2,
+}
+
+// Order of mnemonics is:
+// result_vector
+#[allow(non_camel_case_types)]
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ExtractType {
+	I32_I8X16,
+	I32_U8X16,
+	I32_I16X8,
+	I32_U16X8,
+	I32_I32X4,
+	I64_I64X2,
+	F32_F32X4,
+	F64_F64X2,
+}
+
+impl ExtractType {
+	#[must_use]
+	pub const fn result_type(self) -> ValType {
+		match self {
+			Self::I32_I8X16
+			| Self::I32_U8X16
+			| Self::I32_I16X8
+			| Self::I32_U16X8
+			| Self::I32_I32X4 => ValType::I32,
+			Self::I64_I64X2 => ValType::I64,
+			Self::F32_F32X4 => ValType::F32,
+			Self::F64_F64X2 => ValType::F64,
+		}
+	}
+}
+
+impl TryFrom<&Operator<'_>> for ExtractType {
+	type Error = ();
+
+	fn try_from(inst: &Operator) -> Result<Self, Self::Error> {
+		let result = match inst {
+			Operator::I8x16ExtractLaneS { .. } => Self::I32_I8X16,
+			Operator::I8x16ExtractLaneU { .. } => Self::I32_U8X16,
+			Operator::I16x8ExtractLaneS { .. } => Self::I32_I16X8,
+			Operator::I16x8ExtractLaneU { .. } => Self::I32_U16X8,
+			Operator::I32x4ExtractLane { .. } => Self::I32_I32X4,
+			Operator::I64x2ExtractLane { .. } => Self::I64_I64X2,
+			Operator::F32x4ExtractLane { .. } => Self::F32_F32X4,
+			Operator::F64x2ExtractLane { .. } => Self::F64_F64X2,
+			_ => return Err(()),
+		};
 
-	pub fn iter(self) -> impl Iterator<Item = Temporary> {
-		(self.start..self.end).map(|var| Temporary { var })
+		Ok(result)
 	}
 }
 
-#[derive(Clone, Copy)]
-pub struct Align {
-	pub(crate) new: usize,
-	pub(crate) old: usize,
-	pub(crate) length: usize,
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ExtractLane {
+	pub(crate) op_type: ExtractType,
+	pub(crate) lane: u8,
+	pub(crate) vector: Box<Expression>,
 }
 
-impl Align {
+impl ExtractLane {
 	#[must_use]
-	pub const fn is_aligned(self) -> bool {
-		self.length == 0 || self.new == self.old
+	pub fn new(op_type: ExtractType, lane: u8, vector: Expression) -> Self {
+		Self {
+			op_type,
+			lane,
+			vector: vector.into(),
+		}
 	}
 
 	#[must_use]
-	pub const fn new_range(self) -> ResultList {
-		ResultList::new(self.new, self.new + self.length)
+	pub const fn op_type(&self) -> ExtractType {
+		self.op_type
 	}
 
 	#[must_use]
-	pub const fn old_range(self) -> ResultList {
-		ResultList::new(self.old, self.old + self.length)
+	pub const fn lane(&self) -> u8 {
+		self.lane
+	}
+
+	#[must_use]
+	pub const fn vector(&self) -> &Expression {
+		&self.vector
+	}
+
+	pub fn vector_mut(&mut self) -> &mut Expression {
+		&mut self.vector
 	}
 }
 
-#[derive(Clone, Copy)]
-pub struct Br {
-	pub(crate) target: usize,
-	pub(crate) align: Align,
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ReplaceLane {
+	pub(crate) lane_type: LaneType,
+	pub(crate) lane: u8,
+	pub(crate) vector: Box<Expression>,
+	pub(crate) value: Box<Expression>,
 }
 
-impl Br {
+impl ReplaceLane {
 	#[must_use]
-	pub const fn target(self) -> usize {
-		self.target
+	pub fn new(lane_type: LaneType, lane: u8, vector: Expression, value: Expression) -> Self {
+		Self {
+			lane_type,
+			lane,
+			vector: vector.into(),
+			value: value.into(),
+		}
 	}
 
 	#[must_use]
-	pub const fn align(self) -> Align {
-		self.align
+	pub const fn lane_type(&self) -> LaneType {
+		self.lane_type
 	}
-}
-
-pub struct BrTable {
-	pub(crate) condition: Box<Expression>,
-	pub(crate) data: Vec<Br>,
-	pub(crate) default: Br,
-}
 
-impl BrTable {
 	#[must_use]
-	pub const fn condition(&self) -> &Expression {
-		&self.condition
+	pub const fn lane(&self) -> u8 {
+		self.lane
 	}
 
 	#[must_use]
-	pub fn data(&self) -> &[Br] {
-		&self.data
+	pub const fn vector(&self) -> &Expression {
+		&self.vector
 	}
 
 	#[must_use]
-	pub const fn default(&self) -> Br {
-		self.default
+	pub const fn value(&self) -> &Expression {
+		&self.value
 	}
-}
 
-#[derive(PartialEq, Eq, Clone, Copy)]
-pub enum LabelType {
-	Forward,
-	Backward,
-}
+	pub fn vector_mut(&mut self) -> &mut Expression {
+		&mut self.vector
+	}
 
-pub enum Terminator {
-	Unreachable,
-	Br(Br),
-	BrTable(BrTable),
+	pub fn value_mut(&mut self) -> &mut Expression {
+		&mut self.value
+	}
 }
 
-#[derive(Default)]
-pub struct Block {
-	pub(crate) label_type: Option<LabelType>,
-	pub(crate) code: Vec<Statement>,
-	pub(crate) last: Option<Box<Terminator>>,
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Shuffle {
+	pub(crate) lanes: [u8; 16],
+	pub(crate) lhs: Box<Expression>,
+	pub(crate) rhs: Box<Expression>,
 }
 
-impl Block {
+impl Shuffle {
 	#[must_use]
-	pub const fn label_type(&self) -> Option<LabelType> {
-		self.label_type
+	pub fn new(lanes: [u8; 16], lhs: Expression, rhs: Expression) -> Self {
+		Self {
+			lanes,
+			lhs: lhs.into(),
+			rhs: rhs.into(),
+		}
 	}
 
 	#[must_use]
-	pub fn code(&self) -> &[Statement] {
-		&self.code
+	pub const fn lanes(&self) -> &[u8; 16] {
+		&self.lanes
 	}
 
 	#[must_use]
-	pub fn last(&self) -> Option<&Terminator> {
-		self.last.as_deref()
+	pub const fn lhs(&self) -> &Expression {
+		&self.lhs
+	}
+
+	#[must_use]
+	pub const fn rhs(&self) -> &Expression {
+		&self.rhs
+	}
+
+	pub fn lhs_mut(&mut self) -> &mut Expression {
+		&mut self.lhs
+	}
+
+	pub fn rhs_mut(&mut self) -> &mut Expression {
+		&mut self.rhs
 	}
 }
 
-pub struct BrIf {
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BitSelect {
+	pub(crate) on_true: Box<Expression>,
+	pub(crate) on_false: Box<Expression>,
 	pub(crate) condition: Box<Expression>,
-	pub(crate) target: Br,
 }
 
-impl BrIf {
+impl BitSelect {
 	#[must_use]
-	pub const fn condition(&self) -> &Expression {
-		&self.condition
+	pub fn new(on_true: Expression, on_false: Expression, condition: Expression) -> Self {
+		Self {
+			on_true: on_true.into(),
+			on_false: on_false.into(),
+			condition: condition.into(),
+		}
 	}
 
 	#[must_use]
-	pub const fn target(&self) -> Br {
-		self.target
-	}
+	pub const fn on_true(&self) -> &Expression {
+		&self.on_true
+	}
+
+	#[must_use]
+	pub const fn on_false(&self) -> &Expression {
+		&self.on_false
+	}
+
+	#[must_use]
+	pub const fn condition(&self) -> &Expression {
+		&self.condition
+	}
+
+	pub fn on_true_mut(&mut self) -> &mut Expression {
+		&mut self.on_true
+	}
+
+	pub fn on_false_mut(&mut self) -> &mut Expression {
+		&mut self.on_false
+	}
+
+	pub fn condition_mut(&mut self) -> &mut Expression {
+		&mut self.condition
+	}
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LoadLane {
+	pub(crate) lane_type: LaneType,
+	pub(crate) memory: usize,
+	pub(crate) offset: u64,
+	pub(crate) lane: u8,
+	pub(crate) pointer: Box<Expression>,
+	pub(crate) vector: Box<Expression>,
+}
+
+impl LoadLane {
+	#[must_use]
+	pub fn new(
+		lane_type: LaneType,
+		memory: usize,
+		offset: u64,
+		lane: u8,
+		pointer: Expression,
+		vector: Expression,
+	) -> Self {
+		Self {
+			lane_type,
+			memory,
+			offset,
+			lane,
+			pointer: pointer.into(),
+			vector: vector.into(),
+		}
+	}
+
+	#[must_use]
+	pub const fn lane_type(&self) -> LaneType {
+		self.lane_type
+	}
+
+	#[must_use]
+	pub const fn memory(&self) -> usize {
+		self.memory
+	}
+
+	#[must_use]
+	pub const fn offset(&self) -> u64 {
+		self.offset
+	}
+
+	#[must_use]
+	pub const fn lane(&self) -> u8 {
+		self.lane
+	}
+
+	#[must_use]
+	pub const fn pointer(&self) -> &Expression {
+		&self.pointer
+	}
+
+	#[must_use]
+	pub const fn vector(&self) -> &Expression {
+		&self.vector
+	}
+
+	pub fn pointer_mut(&mut self) -> &mut Expression {
+		&mut self.pointer
+	}
+
+	pub fn vector_mut(&mut self) -> &mut Expression {
+		&mut self.vector
+	}
+}
+
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Value {
+	I32(i32),
+	I64(i64),
+	F32(#[cfg_attr(feature = "serde", serde(with = "crate::serialize::f32_bits"))] f32),
+	F64(#[cfg_attr(feature = "serde", serde(with = "crate::serialize::f64_bits"))] f64),
+	V128(u128),
+}
+
+impl Value {
+	#[must_use]
+	pub const fn ty(self) -> ValType {
+		match self {
+			Self::I32(_) => ValType::I32,
+			Self::I64(_) => ValType::I64,
+			Self::F32(_) => ValType::F32,
+			Self::F64(_) => ValType::F64,
+			Self::V128(_) => ValType::V128,
+		}
+	}
+}
+
+impl From<i32> for Value {
+	fn from(value: i32) -> Self {
+		Self::I32(value)
+	}
+}
+
+impl From<i64> for Value {
+	fn from(value: i64) -> Self {
+		Self::I64(value)
+	}
+}
+
+impl From<u32> for Value {
+	fn from(value: u32) -> Self {
+		Self::F32(f32::from_bits(value))
+	}
+}
+
+impl From<u64> for Value {
+	fn from(value: u64) -> Self {
+		Self::F64(f64::from_bits(value))
+	}
+}
+
+impl From<u128> for Value {
+	fn from(value: u128) -> Self {
+		Self::V128(value)
+	}
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct UnOp {
+	pub(crate) op_type: UnOpType,
+	pub(crate) rhs: Box<Expression>,
+}
+
+impl UnOp {
+	#[must_use]
+	pub fn new(op_type: UnOpType, rhs: Expression) -> Self {
+		Self {
+			op_type,
+			rhs: rhs.into(),
+		}
+	}
+
+	#[must_use]
+	pub const fn op_type(&self) -> UnOpType {
+		self.op_type
+	}
+
+	#[must_use]
+	pub const fn rhs(&self) -> &Expression {
+		&self.rhs
+	}
+
+	pub fn rhs_mut(&mut self) -> &mut Expression {
+		&mut self.rhs
+	}
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BinOp {
+	pub(crate) op_type: BinOpType,
+	pub(crate) lhs: Box<Expression>,
+	pub(crate) rhs: Box<Expression>,
+}
+
+impl BinOp {
+	#[must_use]
+	pub fn new(op_type: BinOpType, lhs: Expression, rhs: Expression) -> Self {
+		Self {
+			op_type,
+			lhs: lhs.into(),
+			rhs: rhs.into(),
+		}
+	}
+
+	#[must_use]
+	pub const fn op_type(&self) -> BinOpType {
+		self.op_type
+	}
+
+	#[must_use]
+	pub const fn lhs(&self) -> &Expression {
+		&self.lhs
+	}
+
+	#[must_use]
+	pub const fn rhs(&self) -> &Expression {
+		&self.rhs
+	}
+
+	pub fn lhs_mut(&mut self) -> &mut Expression {
+		&mut self.lhs
+	}
+
+	pub fn rhs_mut(&mut self) -> &mut Expression {
+		&mut self.rhs
+	}
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CmpOp {
+	pub(crate) op_type: CmpOpType,
+	pub(crate) lhs: Box<Expression>,
+	pub(crate) rhs: Box<Expression>,
+}
+
+impl CmpOp {
+	#[must_use]
+	pub fn new(op_type: CmpOpType, lhs: Expression, rhs: Expression) -> Self {
+		Self {
+			op_type,
+			lhs: lhs.into(),
+			rhs: rhs.into(),
+		}
+	}
+
+	#[must_use]
+	pub const fn op_type(&self) -> CmpOpType {
+		self.op_type
+	}
+
+	#[must_use]
+	pub const fn lhs(&self) -> &Expression {
+		&self.lhs
+	}
+
+	#[must_use]
+	pub const fn rhs(&self) -> &Expression {
+		&self.rhs
+	}
+
+	pub fn lhs_mut(&mut self) -> &mut Expression {
+		&mut self.lhs
+	}
+
+	pub fn rhs_mut(&mut self) -> &mut Expression {
+		&mut self.rhs
+	}
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Expression {
+	Select(Select),
+	GetTemporary(Temporary),
+	GetLocal(Local),
+	GetGlobal(GetGlobal),
+	LoadAt(LoadAt),
+	MemorySize(MemorySize),
+	RefNull(RefNull),
+	RefIsNull(RefIsNull),
+	RefFunc(RefFunc),
+	TableGet(TableGet),
+	TableSize(TableSize),
+	ExtractLane(ExtractLane),
+	ReplaceLane(ReplaceLane),
+	Shuffle(Shuffle),
+	BitSelect(BitSelect),
+	LoadLane(LoadLane),
+	Value(Value),
+	UnOp(UnOp),
+	BinOp(BinOp),
+	CmpOp(CmpOp),
+}
+
+impl Expression {
+	/// The type of the value this expression produces.
+	#[must_use]
+	pub fn ty(&self) -> ValType {
+		match self {
+			Self::Select(v) => v.on_true().ty(),
+			Self::GetTemporary(v) => v.ty(),
+			Self::GetLocal(v) => v.ty(),
+			Self::GetGlobal(v) => v.ty(),
+			Self::LoadAt(v) => v.load_type().result_type(),
+			Self::MemorySize(_) | Self::RefIsNull(_) | Self::TableSize(_) | Self::CmpOp(_) => {
+				ValType::I32
+			}
+			Self::RefNull(v) => v.ref_type().into(),
+			Self::RefFunc(_) => ValType::FUNCREF,
+			Self::TableGet(v) => v.ref_type().into(),
+			Self::ExtractLane(v) => v.op_type().result_type(),
+			Self::ReplaceLane(_) | Self::Shuffle(_) | Self::BitSelect(_) | Self::LoadLane(_) => {
+				ValType::V128
+			}
+			Self::Value(v) => v.ty(),
+			Self::UnOp(v) => v.op_type().result_type(),
+			Self::BinOp(v) => v.op_type().result_type(),
+		}
+	}
+}
+
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ResultList {
+	start: usize,
+	end: usize,
+}
+
+impl ResultList {
+	#[must_use]
+	pub const fn new(start: usize, end: usize) -> Self {
+		Self { start, end }
+	}
+
+	#[must_use]
+	pub const fn is_empty(self) -> bool {
+		self.start == self.end
+	}
+
+	/// The temporaries in the list, by number. Their types are those
+	/// of whatever produced the list, such as a call or a block.
+	pub fn iter(self) -> impl Iterator<Item = usize> {
+		self.start..self.end
+	}
+}
+
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Align {
+	pub(crate) new: usize,
+	pub(crate) old: usize,
+	pub(crate) length: usize,
+}
+
+impl Align {
+	#[must_use]
+	pub const fn new(new: usize, old: usize, length: usize) -> Self {
+		Self { new, old, length }
+	}
+
+	#[must_use]
+	pub const fn is_aligned(self) -> bool {
+		self.length == 0 || self.new == self.old
+	}
+
+	#[must_use]
+	pub const fn new_range(self) -> ResultList {
+		ResultList::new(self.new, self.new + self.length)
+	}
+
+	#[must_use]
+	pub const fn old_range(self) -> ResultList {
+		ResultList::new(self.old, self.old + self.length)
+	}
+}
+
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Br {
+	pub(crate) target: usize,
+	pub(crate) align: Align,
+}
+
+impl Br {
+	#[must_use]
+	pub const fn new(target: usize, align: Align) -> Self {
+		Self { target, align }
+	}
+
+	#[must_use]
+	pub const fn target(self) -> usize {
+		self.target
+	}
+
+	#[must_use]
+	pub const fn align(self) -> Align {
+		self.align
+	}
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BrTable {
+	pub(crate) condition: Box<Expression>,
+	pub(crate) data: Vec<Br>,
+	pub(crate) default: Br,
+}
+
+impl BrTable {
+	#[must_use]
+	pub fn new(condition: Expression, data: Vec<Br>, default: Br) -> Self {
+		Self {
+			condition: condition.into(),
+			data,
+			default,
+		}
+	}
+
+	#[must_use]
+	pub const fn condition(&self) -> &Expression {
+		&self.condition
+	}
+
+	#[must_use]
+	pub fn data(&self) -> &[Br] {
+		&self.data
+	}
+
+	#[must_use]
+	pub const fn default(&self) -> Br {
+		self.default
+	}
+
+	pub fn condition_mut(&mut self) -> &mut Expression {
+		&mut self.condition
+	}
+
+	pub fn data_mut(&mut self) -> &mut [Br] {
+		&mut self.data
+	}
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Throw {
+	pub(crate) tag: usize,
+	pub(crate) param_list: Vec<Expression>,
+}
+
+impl Throw {
+	#[must_use]
+	pub const fn new(tag: usize, param_list: Vec<Expression>) -> Self {
+		Self { tag, param_list }
+	}
+
+	#[must_use]
+	pub const fn tag(&self) -> usize {
+		self.tag
+	}
+
+	#[must_use]
+	pub fn param_list(&self) -> &[Expression] {
+		&self.param_list
+	}
+
+	pub fn param_list_mut(&mut self) -> &mut [Expression] {
+		&mut self.param_list
+	}
+}
+
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Rethrow {
+	pub(crate) target: usize,
+}
+
+impl Rethrow {
+	#[must_use]
+	pub const fn new(target: usize) -> Self {
+		Self { target }
+	}
+
+	#[must_use]
+	pub const fn target(self) -> usize {
+		self.target
+	}
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum LabelType {
+	Forward,
+	Backward,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ReturnCall {
+	pub(crate) function: usize,
+	pub(crate) param_list: Vec<Expression>,
+}
+
+impl ReturnCall {
+	#[must_use]
+	pub const fn new(function: usize, param_list: Vec<Expression>) -> Self {
+		Self {
+			function,
+			param_list,
+		}
+	}
+
+	#[must_use]
+	pub const fn function(&self) -> usize {
+		self.function
+	}
+
+	#[must_use]
+	pub fn param_list(&self) -> &[Expression] {
+		&self.param_list
+	}
+
+	pub fn param_list_mut(&mut self) -> &mut [Expression] {
+		&mut self.param_list
+	}
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ReturnCallIndirect {
+	pub(crate) table: usize,
+	pub(crate) index: Box<Expression>,
+	pub(crate) param_list: Vec<Expression>,
+}
+
+impl ReturnCallIndirect {
+	#[must_use]
+	pub fn new(table: usize, index: Expression, param_list: Vec<Expression>) -> Self {
+		Self {
+			table,
+			index: index.into(),
+			param_list,
+		}
+	}
+
+	#[must_use]
+	pub const fn table(&self) -> usize {
+		self.table
+	}
+
+	#[must_use]
+	pub const fn index(&self) -> &Expression {
+		&self.index
+	}
+
+	#[must_use]
+	pub fn param_list(&self) -> &[Expression] {
+		&self.param_list
+	}
+
+	pub fn index_mut(&mut self) -> &mut Expression {
+		&mut self.index
+	}
+
+	pub fn param_list_mut(&mut self) -> &mut [Expression] {
+		&mut self.param_list
+	}
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Terminator {
+	Unreachable,
+	Br(Br),
+	BrTable(BrTable),
+	Throw(Throw),
+	Rethrow(Rethrow),
+	ReturnCall(ReturnCall),
+	ReturnCallIndirect(ReturnCallIndirect),
+}
+
+#[derive(Debug, Default, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Block {
+	pub(crate) label_type: Option<LabelType>,
+	pub(crate) label: Option<usize>,
+	pub(crate) code: Vec<Statement>,
+	pub(crate) last: Option<Box<Terminator>>,
+}
+
+impl Block {
+	#[must_use]
+	pub fn new(
+		label_type: Option<LabelType>,
+		code: Vec<Statement>,
+		last: Option<Terminator>,
+	) -> Self {
+		Self {
+			label_type,
+			label: None,
+			code,
+			last: last.map(Box::new),
+		}
+	}
+
+	#[must_use]
+	pub const fn label_type(&self) -> Option<LabelType> {
+		self.label_type
+	}
+
+	/// Returns the index the name section gives to the label of this block,
+	/// which counts `block`, `loop`, `if` and `try` in the order they appear
+	/// in the function. Blocks that were not part of it, like those of
+	/// inlined functions, have none.
+	#[must_use]
+	pub const fn label(&self) -> Option<usize> {
+		self.label
+	}
+
+	#[must_use]
+	pub fn code(&self) -> &[Statement] {
+		&self.code
+	}
+
+	#[must_use]
+	pub fn last(&self) -> Option<&Terminator> {
+		self.last.as_deref()
+	}
+
+	pub fn code_mut(&mut self) -> &mut Vec<Statement> {
+		&mut self.code
+	}
+
+	pub fn last_mut(&mut self) -> Option<&mut Terminator> {
+		self.last.as_deref_mut()
+	}
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BrIf {
+	pub(crate) condition: Box<Expression>,
+	pub(crate) target: Br,
+}
+
+impl BrIf {
+	#[must_use]
+	pub fn new(condition: Expression, target: Br) -> Self {
+		Self {
+			condition: condition.into(),
+			target,
+		}
+	}
+
+	#[must_use]
+	pub const fn condition(&self) -> &Expression {
+		&self.condition
+	}
+
+	#[must_use]
+	pub const fn target(&self) -> Br {
+		self.target
+	}
+
+	pub fn condition_mut(&mut self) -> &mut Expression {
+		&mut self.condition
+	}
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct If {
+	pub(crate) condition: Box<Expression>,
+	pub(crate) on_true: Box<Block>,
+	pub(crate) on_false: Option<Box<Block>>,
+}
+
+impl If {
+	#[must_use]
+	pub fn new(condition: Expression, on_true: Block, on_false: Option<Block>) -> Self {
+		Self {
+			condition: condition.into(),
+			on_true: on_true.into(),
+			on_false: on_false.map(Box::new),
+		}
+	}
+
+	#[must_use]
+	pub const fn condition(&self) -> &Expression {
+		&self.condition
+	}
+
+	#[must_use]
+	pub const fn on_true(&self) -> &Block {
+		&self.on_true
+	}
+
+	#[must_use]
+	pub fn on_false(&self) -> Option<&Block> {
+		self.on_false.as_deref()
+	}
+
+	pub fn condition_mut(&mut self) -> &mut Expression {
+		&mut self.condition
+	}
+
+	pub fn on_true_mut(&mut self) -> &mut Block {
+		&mut self.on_true
+	}
+
+	pub fn on_false_mut(&mut self) -> Option<&mut Block> {
+		self.on_false.as_deref_mut()
+	}
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Catch {
+	pub(crate) tag: usize,
+	pub(crate) result_list: ResultList,
+	pub(crate) code: Block,
+}
+
+impl Catch {
+	#[must_use]
+	pub const fn new(tag: usize, result_list: ResultList, code: Block) -> Self {
+		Self {
+			tag,
+			result_list,
+			code,
+		}
+	}
+
+	#[must_use]
+	pub const fn tag(&self) -> usize {
+		self.tag
+	}
+
+	#[must_use]
+	pub const fn result_list(&self) -> ResultList {
+		self.result_list
+	}
+
+	#[must_use]
+	pub const fn code(&self) -> &Block {
+		&self.code
+	}
+
+	pub fn code_mut(&mut self) -> &mut Block {
+		&mut self.code
+	}
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Try {
+	pub(crate) code: Box<Block>,
+	pub(crate) catch_list: Vec<Catch>,
+	pub(crate) catch_all: Option<Box<Block>>,
+	pub(crate) delegate: Option<Br>,
+}
+
+impl Try {
+	#[must_use]
+	pub fn new(
+		code: Block,
+		catch_list: Vec<Catch>,
+		catch_all: Option<Block>,
+		delegate: Option<Br>,
+	) -> Self {
+		Self {
+			code: code.into(),
+			catch_list,
+			catch_all: catch_all.map(Box::new),
+			delegate,
+		}
+	}
+
+	#[must_use]
+	pub const fn code(&self) -> &Block {
+		&self.code
+	}
+
+	#[must_use]
+	pub fn catch_list(&self) -> &[Catch] {
+		&self.catch_list
+	}
+
+	#[must_use]
+	pub fn catch_all(&self) -> Option<&Block> {
+		self.catch_all.as_deref()
+	}
+
+	#[must_use]
+	pub const fn delegate(&self) -> Option<Br> {
+		self.delegate
+	}
+
+	pub fn code_mut(&mut self) -> &mut Block {
+		&mut self.code
+	}
+
+	pub fn catch_list_mut(&mut self) -> &mut [Catch] {
+		&mut self.catch_list
+	}
+
+	pub fn catch_all_mut(&mut self) -> Option<&mut Block> {
+		self.catch_all.as_deref_mut()
+	}
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Call {
+	pub(crate) function: usize,
+	pub(crate) param_list: Vec<Expression>,
+	pub(crate) result_list: ResultList,
+}
+
+impl Call {
+	#[must_use]
+	pub const fn new(
+		function: usize,
+		param_list: Vec<Expression>,
+		result_list: ResultList,
+	) -> Self {
+		Self {
+			function,
+			param_list,
+			result_list,
+		}
+	}
+
+	#[must_use]
+	pub const fn function(&self) -> usize {
+		self.function
+	}
+
+	#[must_use]
+	pub fn param_list(&self) -> &[Expression] {
+		&self.param_list
+	}
+
+	#[must_use]
+	pub const fn result_list(&self) -> ResultList {
+		self.result_list
+	}
+
+	pub fn param_list_mut(&mut self) -> &mut [Expression] {
+		&mut self.param_list
+	}
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CallIndirect {
+	pub(crate) table: usize,
+	pub(crate) index: Box<Expression>,
+	pub(crate) param_list: Vec<Expression>,
+	pub(crate) result_list: ResultList,
+}
+
+impl CallIndirect {
+	#[must_use]
+	pub fn new(
+		table: usize,
+		index: Expression,
+		param_list: Vec<Expression>,
+		result_list: ResultList,
+	) -> Self {
+		Self {
+			table,
+			index: index.into(),
+			param_list,
+			result_list,
+		}
+	}
+
+	#[must_use]
+	pub const fn table(&self) -> usize {
+		self.table
+	}
+
+	#[must_use]
+	pub const fn index(&self) -> &Expression {
+		&self.index
+	}
+
+	#[must_use]
+	pub fn param_list(&self) -> &[Expression] {
+		&self.param_list
+	}
+
+	#[must_use]
+	pub const fn result_list(&self) -> ResultList {
+		self.result_list
+	}
+
+	pub fn index_mut(&mut self) -> &mut Expression {
+		&mut self.index
+	}
+
+	pub fn param_list_mut(&mut self) -> &mut [Expression] {
+		&mut self.param_list
+	}
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SetTemporary {
+	pub(crate) var: Temporary,
+	pub(crate) value: Box<Expression>,
+}
+
+impl SetTemporary {
+	#[must_use]
+	pub fn new(var: Temporary, value: Expression) -> Self {
+		Self {
+			var,
+			value: value.into(),
+		}
+	}
+
+	#[must_use]
+	pub const fn var(&self) -> Temporary {
+		self.var
+	}
+
+	#[must_use]
+	pub const fn value(&self) -> &Expression {
+		&self.value
+	}
+
+	pub fn value_mut(&mut self) -> &mut Expression {
+		&mut self.value
+	}
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SetLocal {
+	pub(crate) var: Local,
+	pub(crate) value: Box<Expression>,
+}
+
+impl SetLocal {
+	#[must_use]
+	pub fn new(var: Local, value: Expression) -> Self {
+		Self {
+			var,
+			value: value.into(),
+		}
+	}
+
+	#[must_use]
+	pub const fn var(&self) -> Local {
+		self.var
+	}
+
+	#[must_use]
+	pub const fn value(&self) -> &Expression {
+		&self.value
+	}
+
+	pub fn value_mut(&mut self) -> &mut Expression {
+		&mut self.value
+	}
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SetGlobal {
+	pub(crate) var: usize,
+	pub(crate) value: Box<Expression>,
+}
+
+impl SetGlobal {
+	#[must_use]
+	pub fn new(var: usize, value: Expression) -> Self {
+		Self {
+			var,
+			value: value.into(),
+		}
+	}
+
+	#[must_use]
+	pub const fn var(&self) -> usize {
+		self.var
+	}
+
+	#[must_use]
+	pub const fn value(&self) -> &Expression {
+		&self.value
+	}
+
+	pub fn value_mut(&mut self) -> &mut Expression {
+		&mut self.value
+	}
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct StoreAt {
+	pub(crate) store_type: StoreType,
+	pub(crate) memory: usize,
+	pub(crate) offset: u64,
+	pub(crate) pointer: Box<Expression>,
+	pub(crate) value: Box<Expression>,
+	pub(crate) is_atomic: bool,
+}
+
+impl StoreAt {
+	#[must_use]
+	pub fn new(
+		store_type: StoreType,
+		memory: usize,
+		offset: u64,
+		pointer: Expression,
+		value: Expression,
+	) -> Self {
+		Self {
+			store_type,
+			memory,
+			offset,
+			pointer: pointer.into(),
+			value: value.into(),
+			is_atomic: false,
+		}
+	}
+
+	/// Makes the store atomic, so that it traps when its address is not
+	/// a multiple of its size.
+	#[must_use]
+	pub fn into_atomic(self) -> Self {
+		Self {
+			is_atomic: true,
+			..self
+		}
+	}
+
+	#[must_use]
+	pub const fn store_type(&self) -> StoreType {
+		self.store_type
+	}
+
+	#[must_use]
+	pub const fn memory(&self) -> usize {
+		self.memory
+	}
+
+	#[must_use]
+	pub const fn offset(&self) -> u64 {
+		self.offset
+	}
+
+	#[must_use]
+	pub const fn pointer(&self) -> &Expression {
+		&self.pointer
+	}
+
+	#[must_use]
+	pub const fn value(&self) -> &Expression {
+		&self.value
+	}
+
+	#[must_use]
+	pub const fn is_atomic(&self) -> bool {
+		self.is_atomic
+	}
+
+	pub fn pointer_mut(&mut self) -> &mut Expression {
+		&mut self.pointer
+	}
+
+	pub fn value_mut(&mut self) -> &mut Expression {
+		&mut self.value
+	}
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct StoreLane {
+	pub(crate) lane_type: LaneType,
+	pub(crate) memory: usize,
+	pub(crate) offset: u64,
+	pub(crate) lane: u8,
+	pub(crate) pointer: Box<Expression>,
+	pub(crate) vector: Box<Expression>,
 }
 
-pub struct If {
-	pub(crate) condition: Box<Expression>,
-	pub(crate) on_true: Box<Block>,
-	pub(crate) on_false: Option<Box<Block>>,
+impl StoreLane {
+	#[must_use]
+	pub fn new(
+		lane_type: LaneType,
+		memory: usize,
+		offset: u64,
+		lane: u8,
+		pointer: Expression,
+		vector: Expression,
+	) -> Self {
+		Self {
+			lane_type,
+			memory,
+			offset,
+			lane,
+			pointer: pointer.into(),
+			vector: vector.into(),
+		}
+	}
+
+	#[must_use]
+	pub const fn lane_type(&self) -> LaneType {
+		self.lane_type
+	}
+
+	#[must_use]
+	pub const fn memory(&self) -> usize {
+		self.memory
+	}
+
+	#[must_use]
+	pub const fn offset(&self) -> u64 {
+		self.offset
+	}
+
+	#[must_use]
+	pub const fn lane(&self) -> u8 {
+		self.lane
+	}
+
+	#[must_use]
+	pub const fn pointer(&self) -> &Expression {
+		&self.pointer
+	}
+
+	#[must_use]
+	pub const fn vector(&self) -> &Expression {
+		&self.vector
+	}
+
+	pub fn pointer_mut(&mut self) -> &mut Expression {
+		&mut self.pointer
+	}
+
+	pub fn vector_mut(&mut self) -> &mut Expression {
+		&mut self.vector
+	}
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MemoryGrow {
+	pub(crate) memory: usize,
+	pub(crate) result: Temporary,
+	pub(crate) size: Box<Expression>,
+}
+
+impl MemoryGrow {
+	#[must_use]
+	pub fn new(memory: usize, result: Temporary, size: Expression) -> Self {
+		Self {
+			memory,
+			result,
+			size: size.into(),
+		}
+	}
+
+	#[must_use]
+	pub const fn memory(&self) -> usize {
+		self.memory
+	}
+
+	#[must_use]
+	pub const fn result(&self) -> Temporary {
+		self.result
+	}
+
+	#[must_use]
+	pub const fn size(&self) -> &Expression {
+		&self.size
+	}
+
+	pub fn size_mut(&mut self) -> &mut Expression {
+		&mut self.size
+	}
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MemoryArgument {
+	pub(crate) memory: usize,
+	pub(crate) pointer: Box<Expression>,
+}
+
+impl MemoryArgument {
+	#[must_use]
+	pub fn new(memory: usize, pointer: Expression) -> Self {
+		Self {
+			memory,
+			pointer: pointer.into(),
+		}
+	}
+
+	#[must_use]
+	pub const fn memory(&self) -> usize {
+		self.memory
+	}
+
+	#[must_use]
+	pub const fn pointer(&self) -> &Expression {
+		&self.pointer
+	}
+
+	pub fn pointer_mut(&mut self) -> &mut Expression {
+		&mut self.pointer
+	}
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MemoryCopy {
+	pub(crate) destination: MemoryArgument,
+	pub(crate) source: MemoryArgument,
+	pub(crate) size: Box<Expression>,
+}
+
+impl MemoryCopy {
+	#[must_use]
+	pub fn new(destination: MemoryArgument, source: MemoryArgument, size: Expression) -> Self {
+		Self {
+			destination,
+			source,
+			size: size.into(),
+		}
+	}
+
+	#[must_use]
+	pub const fn destination(&self) -> &MemoryArgument {
+		&self.destination
+	}
+
+	#[must_use]
+	pub const fn source(&self) -> &MemoryArgument {
+		&self.source
+	}
+
+	#[must_use]
+	pub const fn size(&self) -> &Expression {
+		&self.size
+	}
+
+	pub fn destination_mut(&mut self) -> &mut MemoryArgument {
+		&mut self.destination
+	}
+
+	pub fn source_mut(&mut self) -> &mut MemoryArgument {
+		&mut self.source
+	}
+
+	pub fn size_mut(&mut self) -> &mut Expression {
+		&mut self.size
+	}
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MemoryFill {
+	pub(crate) destination: MemoryArgument,
+	pub(crate) size: Box<Expression>,
+	pub(crate) value: Box<Expression>,
+}
+
+impl MemoryFill {
+	#[must_use]
+	pub fn new(destination: MemoryArgument, size: Expression, value: Expression) -> Self {
+		Self {
+			destination,
+			size: size.into(),
+			value: value.into(),
+		}
+	}
+
+	#[must_use]
+	pub const fn destination(&self) -> &MemoryArgument {
+		&self.destination
+	}
+
+	#[must_use]
+	pub const fn size(&self) -> &Expression {
+		&self.size
+	}
+
+	#[must_use]
+	pub const fn value(&self) -> &Expression {
+		&self.value
+	}
+
+	pub fn destination_mut(&mut self) -> &mut MemoryArgument {
+		&mut self.destination
+	}
+
+	pub fn size_mut(&mut self) -> &mut Expression {
+		&mut self.size
+	}
+
+	pub fn value_mut(&mut self) -> &mut Expression {
+		&mut self.value
+	}
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MemoryInit {
+	pub(crate) destination: MemoryArgument,
+	pub(crate) data: usize,
+	pub(crate) offset: Box<Expression>,
+	pub(crate) size: Box<Expression>,
+}
+
+impl MemoryInit {
+	#[must_use]
+	pub fn new(
+		destination: MemoryArgument,
+		data: usize,
+		offset: Expression,
+		size: Expression,
+	) -> Self {
+		Self {
+			destination,
+			data,
+			offset: offset.into(),
+			size: size.into(),
+		}
+	}
+
+	#[must_use]
+	pub const fn destination(&self) -> &MemoryArgument {
+		&self.destination
+	}
+
+	#[must_use]
+	pub const fn data(&self) -> usize {
+		self.data
+	}
+
+	#[must_use]
+	pub const fn offset(&self) -> &Expression {
+		&self.offset
+	}
+
+	#[must_use]
+	pub const fn size(&self) -> &Expression {
+		&self.size
+	}
+
+	pub fn destination_mut(&mut self) -> &mut MemoryArgument {
+		&mut self.destination
+	}
+
+	pub fn offset_mut(&mut self) -> &mut Expression {
+		&mut self.offset
+	}
+
+	pub fn size_mut(&mut self) -> &mut Expression {
+		&mut self.size
+	}
+}
+
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DataDrop {
+	pub(crate) data: usize,
+}
+
+impl DataDrop {
+	#[must_use]
+	pub const fn new(data: usize) -> Self {
+		Self { data }
+	}
+
+	#[must_use]
+	pub const fn data(&self) -> usize {
+		self.data
+	}
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AtomicRmw {
+	pub(crate) op_type: RmwOpType,
+	pub(crate) store_type: StoreType,
+	pub(crate) memory: usize,
+	pub(crate) offset: u64,
+	pub(crate) result: Temporary,
+	pub(crate) pointer: Box<Expression>,
+	pub(crate) value: Box<Expression>,
+}
+
+impl AtomicRmw {
+	#[must_use]
+	pub fn new(
+		op_type: RmwOpType,
+		store_type: StoreType,
+		memory: usize,
+		offset: u64,
+		result: Temporary,
+		pointer: Expression,
+		value: Expression,
+	) -> Self {
+		Self {
+			op_type,
+			store_type,
+			memory,
+			offset,
+			result,
+			pointer: pointer.into(),
+			value: value.into(),
+		}
+	}
+
+	#[must_use]
+	pub const fn op_type(&self) -> RmwOpType {
+		self.op_type
+	}
+
+	#[must_use]
+	pub const fn store_type(&self) -> StoreType {
+		self.store_type
+	}
+
+	#[must_use]
+	pub const fn memory(&self) -> usize {
+		self.memory
+	}
+
+	#[must_use]
+	pub const fn offset(&self) -> u64 {
+		self.offset
+	}
+
+	#[must_use]
+	pub const fn result(&self) -> Temporary {
+		self.result
+	}
+
+	#[must_use]
+	pub const fn pointer(&self) -> &Expression {
+		&self.pointer
+	}
+
+	#[must_use]
+	pub const fn value(&self) -> &Expression {
+		&self.value
+	}
+
+	pub fn pointer_mut(&mut self) -> &mut Expression {
+		&mut self.pointer
+	}
+
+	pub fn value_mut(&mut self) -> &mut Expression {
+		&mut self.value
+	}
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AtomicCmpxchg {
+	pub(crate) store_type: StoreType,
+	pub(crate) memory: usize,
+	pub(crate) offset: u64,
+	pub(crate) result: Temporary,
+	pub(crate) pointer: Box<Expression>,
+	pub(crate) expected: Box<Expression>,
+	pub(crate) replacement: Box<Expression>,
 }
 
-impl If {
+impl AtomicCmpxchg {
+	#[must_use]
+	pub fn new(
+		store_type: StoreType,
+		memory: usize,
+		offset: u64,
+		result: Temporary,
+		pointer: Expression,
+		expected: Expression,
+		replacement: Expression,
+	) -> Self {
+		Self {
+			store_type,
+			memory,
+			offset,
+			result,
+			pointer: pointer.into(),
+			expected: expected.into(),
+			replacement: replacement.into(),
+		}
+	}
+
+	#[must_use]
+	pub const fn store_type(&self) -> StoreType {
+		self.store_type
+	}
+
+	#[must_use]
+	pub const fn memory(&self) -> usize {
+		self.memory
+	}
+
+	#[must_use]
+	pub const fn offset(&self) -> u64 {
+		self.offset
+	}
+
 	#[must_use]
-	pub const fn condition(&self) -> &Expression {
-		&self.condition
+	pub const fn result(&self) -> Temporary {
+		self.result
 	}
 
 	#[must_use]
-	pub const fn on_true(&self) -> &Block {
-		&self.on_true
+	pub const fn pointer(&self) -> &Expression {
+		&self.pointer
 	}
 
 	#[must_use]
-	pub fn on_false(&self) -> Option<&Block> {
-		self.on_false.as_deref()
+	pub const fn expected(&self) -> &Expression {
+		&self.expected
+	}
+
+	#[must_use]
+	pub const fn replacement(&self) -> &Expression {
+		&self.replacement
+	}
+
+	pub fn pointer_mut(&mut self) -> &mut Expression {
+		&mut self.pointer
+	}
+
+	pub fn expected_mut(&mut self) -> &mut Expression {
+		&mut self.expected
+	}
+
+	pub fn replacement_mut(&mut self) -> &mut Expression {
+		&mut self.replacement
 	}
 }
 
-pub struct Call {
-	pub(crate) function: usize,
-	pub(crate) param_list: Vec<Expression>,
-	pub(crate) result_list: ResultList,
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AtomicWait {
+	pub(crate) load_type: LoadType,
+	pub(crate) memory: usize,
+	pub(crate) offset: u64,
+	pub(crate) result: Temporary,
+	pub(crate) pointer: Box<Expression>,
+	pub(crate) expected: Box<Expression>,
+	pub(crate) timeout: Box<Expression>,
 }
 
-impl Call {
+impl AtomicWait {
 	#[must_use]
-	pub const fn function(&self) -> usize {
-		self.function
+	pub fn new(
+		load_type: LoadType,
+		memory: usize,
+		offset: u64,
+		result: Temporary,
+		pointer: Expression,
+		expected: Expression,
+		timeout: Expression,
+	) -> Self {
+		Self {
+			load_type,
+			memory,
+			offset,
+			result,
+			pointer: pointer.into(),
+			expected: expected.into(),
+			timeout: timeout.into(),
+		}
 	}
 
 	#[must_use]
-	pub fn param_list(&self) -> &[Expression] {
-		&self.param_list
+	pub const fn load_type(&self) -> LoadType {
+		self.load_type
 	}
 
 	#[must_use]
-	pub const fn result_list(&self) -> ResultList {
-		self.result_list
+	pub const fn memory(&self) -> usize {
+		self.memory
 	}
-}
 
-pub struct CallIndirect {
-	pub(crate) table: usize,
-	pub(crate) index: Box<Expression>,
-	pub(crate) param_list: Vec<Expression>,
-	pub(crate) result_list: ResultList,
-}
+	#[must_use]
+	pub const fn offset(&self) -> u64 {
+		self.offset
+	}
 
-impl CallIndirect {
 	#[must_use]
-	pub const fn table(&self) -> usize {
-		self.table
+	pub const fn result(&self) -> Temporary {
+		self.result
 	}
 
 	#[must_use]
-	pub const fn index(&self) -> &Expression {
-		&self.index
+	pub const fn pointer(&self) -> &Expression {
+		&self.pointer
 	}
 
 	#[must_use]
-	pub fn param_list(&self) -> &[Expression] {
-		&self.param_list
+	pub const fn expected(&self) -> &Expression {
+		&self.expected
 	}
 
 	#[must_use]
-	pub const fn result_list(&self) -> ResultList {
-		self.result_list
+	pub const fn timeout(&self) -> &Expression {
+		&self.timeout
+	}
+
+	pub fn pointer_mut(&mut self) -> &mut Expression {
+		&mut self.pointer
+	}
+
+	pub fn expected_mut(&mut self) -> &mut Expression {
+		&mut self.expected
+	}
+
+	pub fn timeout_mut(&mut self) -> &mut Expression {
+		&mut self.timeout
 	}
 }
 
-pub struct SetTemporary {
-	pub(crate) var: Temporary,
-	pub(crate) value: Box<Expression>,
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AtomicNotify {
+	pub(crate) memory: usize,
+	pub(crate) offset: u64,
+	pub(crate) result: Temporary,
+	pub(crate) pointer: Box<Expression>,
+	pub(crate) count: Box<Expression>,
 }
 
-impl SetTemporary {
+impl AtomicNotify {
 	#[must_use]
-	pub const fn var(&self) -> Temporary {
-		self.var
+	pub fn new(
+		memory: usize,
+		offset: u64,
+		result: Temporary,
+		pointer: Expression,
+		count: Expression,
+	) -> Self {
+		Self {
+			memory,
+			offset,
+			result,
+			pointer: pointer.into(),
+			count: count.into(),
+		}
 	}
 
 	#[must_use]
-	pub const fn value(&self) -> &Expression {
-		&self.value
+	pub const fn memory(&self) -> usize {
+		self.memory
 	}
-}
 
-pub struct SetLocal {
-	pub(crate) var: Local,
-	pub(crate) value: Box<Expression>,
-}
+	#[must_use]
+	pub const fn offset(&self) -> u64 {
+		self.offset
+	}
 
-impl SetLocal {
 	#[must_use]
-	pub const fn var(&self) -> Local {
-		self.var
+	pub const fn result(&self) -> Temporary {
+		self.result
 	}
 
 	#[must_use]
-	pub const fn value(&self) -> &Expression {
-		&self.value
+	pub const fn pointer(&self) -> &Expression {
+		&self.pointer
+	}
+
+	#[must_use]
+	pub const fn count(&self) -> &Expression {
+		&self.count
+	}
+
+	pub fn pointer_mut(&mut self) -> &mut Expression {
+		&mut self.pointer
+	}
+
+	pub fn count_mut(&mut self) -> &mut Expression {
+		&mut self.count
 	}
 }
 
-pub struct SetGlobal {
-	pub(crate) var: usize,
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TableSet {
+	pub(crate) table: usize,
+	pub(crate) index: Box<Expression>,
 	pub(crate) value: Box<Expression>,
 }
 
-impl SetGlobal {
+impl TableSet {
 	#[must_use]
-	pub const fn var(&self) -> usize {
-		self.var
+	pub fn new(table: usize, index: Expression, value: Expression) -> Self {
+		Self {
+			table,
+			index: index.into(),
+			value: value.into(),
+		}
+	}
+
+	#[must_use]
+	pub const fn table(&self) -> usize {
+		self.table
+	}
+
+	#[must_use]
+	pub const fn index(&self) -> &Expression {
+		&self.index
 	}
 
 	#[must_use]
 	pub const fn value(&self) -> &Expression {
 		&self.value
 	}
+
+	pub fn index_mut(&mut self) -> &mut Expression {
+		&mut self.index
+	}
+
+	pub fn value_mut(&mut self) -> &mut Expression {
+		&mut self.value
+	}
 }
 
-pub struct StoreAt {
-	pub(crate) store_type: StoreType,
-	pub(crate) memory: usize,
-	pub(crate) offset: u32,
-	pub(crate) pointer: Box<Expression>,
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TableGrow {
+	pub(crate) table: usize,
+	pub(crate) result: Temporary,
+	pub(crate) size: Box<Expression>,
 	pub(crate) value: Box<Expression>,
 }
 
-impl StoreAt {
+impl TableGrow {
 	#[must_use]
-	pub const fn store_type(&self) -> StoreType {
-		self.store_type
+	pub fn new(table: usize, result: Temporary, size: Expression, value: Expression) -> Self {
+		Self {
+			table,
+			result,
+			size: size.into(),
+			value: value.into(),
+		}
 	}
 
 	#[must_use]
-	pub const fn memory(&self) -> usize {
-		self.memory
+	pub const fn table(&self) -> usize {
+		self.table
 	}
 
 	#[must_use]
-	pub const fn offset(&self) -> u32 {
-		self.offset
+	pub const fn result(&self) -> Temporary {
+		self.result
 	}
 
 	#[must_use]
-	pub const fn pointer(&self) -> &Expression {
-		&self.pointer
+	pub const fn size(&self) -> &Expression {
+		&self.size
 	}
 
 	#[must_use]
 	pub const fn value(&self) -> &Expression {
 		&self.value
 	}
+
+	pub fn size_mut(&mut self) -> &mut Expression {
+		&mut self.size
+	}
+
+	pub fn value_mut(&mut self) -> &mut Expression {
+		&mut self.value
+	}
 }
 
-pub struct MemoryGrow {
-	pub(crate) memory: usize,
-	pub(crate) result: Temporary,
-	pub(crate) size: Box<Expression>,
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TableArgument {
+	pub(crate) table: usize,
+	pub(crate) index: Box<Expression>,
 }
 
-impl MemoryGrow {
+impl TableArgument {
 	#[must_use]
-	pub const fn memory(&self) -> usize {
-		self.memory
+	pub fn new(table: usize, index: Expression) -> Self {
+		Self {
+			table,
+			index: index.into(),
+		}
 	}
 
 	#[must_use]
-	pub const fn result(&self) -> Temporary {
-		self.result
+	pub const fn table(&self) -> usize {
+		self.table
 	}
 
 	#[must_use]
-	pub const fn size(&self) -> &Expression {
-		&self.size
+	pub const fn index(&self) -> &Expression {
+		&self.index
+	}
+
+	pub fn index_mut(&mut self) -> &mut Expression {
+		&mut self.index
 	}
 }
 
-pub struct MemoryArgument {
-	pub(crate) memory: usize,
-	pub(crate) pointer: Box<Expression>,
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TableFill {
+	pub(crate) destination: TableArgument,
+	pub(crate) size: Box<Expression>,
+	pub(crate) value: Box<Expression>,
 }
 
-impl MemoryArgument {
+impl TableFill {
 	#[must_use]
-	pub const fn memory(&self) -> usize {
-		self.memory
+	pub fn new(destination: TableArgument, size: Expression, value: Expression) -> Self {
+		Self {
+			destination,
+			size: size.into(),
+			value: value.into(),
+		}
 	}
 
 	#[must_use]
-	pub const fn pointer(&self) -> &Expression {
-		&self.pointer
+	pub const fn destination(&self) -> &TableArgument {
+		&self.destination
+	}
+
+	#[must_use]
+	pub const fn size(&self) -> &Expression {
+		&self.size
+	}
+
+	#[must_use]
+	pub const fn value(&self) -> &Expression {
+		&self.value
+	}
+
+	pub fn destination_mut(&mut self) -> &mut TableArgument {
+		&mut self.destination
+	}
+
+	pub fn size_mut(&mut self) -> &mut Expression {
+		&mut self.size
+	}
+
+	pub fn value_mut(&mut self) -> &mut Expression {
+		&mut self.value
 	}
 }
 
-pub struct MemoryCopy {
-	pub(crate) destination: MemoryArgument,
-	pub(crate) source: MemoryArgument,
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TableCopy {
+	pub(crate) destination: TableArgument,
+	pub(crate) source: TableArgument,
 	pub(crate) size: Box<Expression>,
 }
 
-impl MemoryCopy {
+impl TableCopy {
 	#[must_use]
-	pub const fn destination(&self) -> &MemoryArgument {
+	pub fn new(destination: TableArgument, source: TableArgument, size: Expression) -> Self {
+		Self {
+			destination,
+			source,
+			size: size.into(),
+		}
+	}
+
+	#[must_use]
+	pub const fn destination(&self) -> &TableArgument {
 		&self.destination
 	}
 
 	#[must_use]
-	pub const fn source(&self) -> &MemoryArgument {
+	pub const fn source(&self) -> &TableArgument {
 		&self.source
 	}
 
@@ -975,77 +3513,204 @@ impl MemoryCopy {
 	pub const fn size(&self) -> &Expression {
 		&self.size
 	}
+
+	pub fn destination_mut(&mut self) -> &mut TableArgument {
+		&mut self.destination
+	}
+
+	pub fn source_mut(&mut self) -> &mut TableArgument {
+		&mut self.source
+	}
+
+	pub fn size_mut(&mut self) -> &mut Expression {
+		&mut self.size
+	}
 }
 
-pub struct MemoryFill {
-	pub(crate) destination: MemoryArgument,
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TableInit {
+	pub(crate) destination: TableArgument,
+	pub(crate) element: usize,
+	pub(crate) offset: Box<Expression>,
 	pub(crate) size: Box<Expression>,
-	pub(crate) value: Box<Expression>,
 }
 
-impl MemoryFill {
+impl TableInit {
 	#[must_use]
-	pub const fn destination(&self) -> &MemoryArgument {
+	pub fn new(
+		destination: TableArgument,
+		element: usize,
+		offset: Expression,
+		size: Expression,
+	) -> Self {
+		Self {
+			destination,
+			element,
+			offset: offset.into(),
+			size: size.into(),
+		}
+	}
+
+	#[must_use]
+	pub const fn destination(&self) -> &TableArgument {
 		&self.destination
 	}
 
+	#[must_use]
+	pub const fn element(&self) -> usize {
+		self.element
+	}
+
+	#[must_use]
+	pub const fn offset(&self) -> &Expression {
+		&self.offset
+	}
+
 	#[must_use]
 	pub const fn size(&self) -> &Expression {
 		&self.size
 	}
 
+	pub fn destination_mut(&mut self) -> &mut TableArgument {
+		&mut self.destination
+	}
+
+	pub fn offset_mut(&mut self) -> &mut Expression {
+		&mut self.offset
+	}
+
+	pub fn size_mut(&mut self) -> &mut Expression {
+		&mut self.size
+	}
+}
+
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ElemDrop {
+	pub(crate) element: usize,
+}
+
+impl ElemDrop {
 	#[must_use]
-	pub const fn value(&self) -> &Expression {
-		&self.value
+	pub const fn new(element: usize) -> Self {
+		Self { element }
+	}
+
+	#[must_use]
+	pub const fn element(&self) -> usize {
+		self.element
 	}
 }
 
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Statement {
 	Block(Block),
 	BrIf(BrIf),
 	If(If),
+	Try(Try),
 	Call(Call),
 	CallIndirect(CallIndirect),
 	SetTemporary(SetTemporary),
 	SetLocal(SetLocal),
 	SetGlobal(SetGlobal),
 	StoreAt(StoreAt),
+	StoreLane(StoreLane),
 	MemoryGrow(MemoryGrow),
 	MemoryCopy(MemoryCopy),
 	MemoryFill(MemoryFill),
-}
-
+	MemoryInit(MemoryInit),
+	DataDrop(DataDrop),
+	AtomicRmw(AtomicRmw),
+	AtomicCmpxchg(AtomicCmpxchg),
+	AtomicWait(AtomicWait),
+	AtomicNotify(AtomicNotify),
+	TableSet(TableSet),
+	TableGrow(TableGrow),
+	TableFill(TableFill),
+	TableCopy(TableCopy),
+	TableInit(TableInit),
+	ElemDrop(ElemDrop),
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct FuncData {
+	#[cfg_attr(feature = "serde", serde(with = "crate::serialize::val_type_list"))]
+	pub(crate) param_data: Vec<ValType>,
+	#[cfg_attr(feature = "serde", serde(with = "crate::serialize::val_type_list"))]
 	pub(crate) local_data: Vec<ValType>,
+	#[cfg_attr(
+		feature = "serde",
+		serde(with = "crate::serialize::val_type_option_list")
+	)]
+	pub(crate) temporary_data: Vec<Option<ValType>>,
 	pub(crate) num_result: usize,
-	pub(crate) num_param: usize,
-	pub(crate) num_stack: usize,
 	pub(crate) code: Block,
 }
 
 impl FuncData {
+	#[must_use]
+	pub const fn new(
+		param_data: Vec<ValType>,
+		local_data: Vec<ValType>,
+		temporary_data: Vec<Option<ValType>>,
+		num_result: usize,
+		code: Block,
+	) -> Self {
+		Self {
+			param_data,
+			local_data,
+			temporary_data,
+			num_result,
+			code,
+		}
+	}
+
+	#[must_use]
+	pub fn param_data(&self) -> &[ValType] {
+		&self.param_data
+	}
+
 	#[must_use]
 	pub fn local_data(&self) -> &[ValType] {
 		&self.local_data
 	}
 
+	/// The type held by each temporary. Temporaries are reused as the
+	/// stack grows and shrinks, so one that holds values of different
+	/// types at different points, or that is never used, has no type.
+	#[must_use]
+	pub fn temporary_data(&self) -> &[Option<ValType>] {
+		&self.temporary_data
+	}
+
 	#[must_use]
 	pub const fn num_result(&self) -> usize {
 		self.num_result
 	}
 
 	#[must_use]
-	pub const fn num_param(&self) -> usize {
-		self.num_param
+	pub fn num_param(&self) -> usize {
+		self.param_data.len()
 	}
 
 	#[must_use]
-	pub const fn num_stack(&self) -> usize {
-		self.num_stack
+	pub fn num_stack(&self) -> usize {
+		self.temporary_data.len()
 	}
 
 	#[must_use]
 	pub const fn code(&self) -> &Block {
 		&self.code
 	}
+
+	pub fn local_data_mut(&mut self) -> &mut Vec<ValType> {
+		&mut self.local_data
+	}
+
+	pub fn code_mut(&mut self) -> &mut Block {
+		&mut self.code
+	}
 }
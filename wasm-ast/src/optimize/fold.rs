@@ -0,0 +1,465 @@
+use crate::{
+	node::{BinOpType, CmpOpType, Expression, FuncData, UnOpType, Value},
+	visit::{DriverMut, VisitorMut},
+};
+
+macro_rules! impl_float_min_max {
+	($min:ident, $max:ident, $float:ty) => {
+		// NaN operands propagate and negative zero is less than positive zero,
+		// neither of which `min` and `max` from the standard library guarantee
+		fn $min(lhs: $float, rhs: $float) -> $float {
+			if lhs.is_nan() || rhs.is_nan() {
+				lhs + rhs
+			} else if lhs == rhs {
+				<$float>::from_bits(lhs.to_bits() | rhs.to_bits())
+			} else {
+				lhs.min(rhs)
+			}
+		}
+
+		fn $max(lhs: $float, rhs: $float) -> $float {
+			if lhs.is_nan() || rhs.is_nan() {
+				lhs + rhs
+			} else if lhs == rhs {
+				<$float>::from_bits(lhs.to_bits() & rhs.to_bits())
+			} else {
+				lhs.max(rhs)
+			}
+		}
+	};
+}
+
+impl_float_min_max!(min_f32, max_f32, f32);
+impl_float_min_max!(min_f64, max_f64, f64);
+
+macro_rules! impl_float_nearest {
+	($name:ident, $float:ty) => {
+		// Rounds halfway cases to the even neighbor, which is what halving,
+		// rounding away from zero, and doubling again gives for them
+		#[allow(clippy::float_cmp)]
+		fn $name(value: $float) -> $float {
+			let rounded = value.round();
+
+			if (rounded - value).abs() == 0.5 {
+				(value / 2.0).round() * 2.0
+			} else {
+				rounded
+			}
+		}
+	};
+}
+
+impl_float_nearest!(nearest_f32, f32);
+impl_float_nearest!(nearest_f64, f64);
+
+// Bounds are exclusive and exactly representable, so any value that
+// would trap on conversion, including NaN, is rejected
+fn truncate_i32(value: f64) -> Option<i32> {
+	(value > -2_147_483_649.0 && value < 2_147_483_648.0).then_some(value as i32)
+}
+
+fn truncate_u32(value: f64) -> Option<i32> {
+	(value > -1.0 && value < 4_294_967_296.0).then_some(value as u32 as i32)
+}
+
+fn truncate_i64(value: f64) -> Option<i64> {
+	(-9_223_372_036_854_775_808.0..9_223_372_036_854_775_808.0)
+		.contains(&value)
+		.then_some(value as i64)
+}
+
+fn truncate_u64(value: f64) -> Option<i64> {
+	(value > -1.0 && value < 18_446_744_073_709_551_616.0).then_some(value as u64 as i64)
+}
+
+fn un_op_i32(op_type: UnOpType, rhs: i32) -> Option<Value> {
+	let value = match op_type {
+		UnOpType::Clz_I32 => Value::I32(rhs.leading_zeros() as i32),
+		UnOpType::Ctz_I32 => Value::I32(rhs.trailing_zeros() as i32),
+		UnOpType::Popcnt_I32 => Value::I32(rhs.count_ones() as i32),
+		UnOpType::Extend_I32_N8 => Value::I32(i32::from(rhs as i8)),
+		UnOpType::Extend_I32_N16 => Value::I32(i32::from(rhs as i16)),
+		UnOpType::Extend_I64_I32 => Value::I64(i64::from(rhs)),
+		UnOpType::Extend_I64_U32 => Value::I64(i64::from(rhs as u32)),
+		UnOpType::Convert_F32_I32 => Value::F32(rhs as f32),
+		UnOpType::Convert_F32_U32 => Value::F32(rhs as u32 as f32),
+		UnOpType::Convert_F64_I32 => Value::F64(f64::from(rhs)),
+		UnOpType::Convert_F64_U32 => Value::F64(f64::from(rhs as u32)),
+		UnOpType::Reinterpret_F32_I32 => Value::F32(f32::from_bits(rhs as u32)),
+		_ => return None,
+	};
+
+	Some(value)
+}
+
+fn un_op_i64(op_type: UnOpType, rhs: i64) -> Option<Value> {
+	let value = match op_type {
+		UnOpType::Clz_I64 => Value::I64(rhs.leading_zeros().into()),
+		UnOpType::Ctz_I64 => Value::I64(rhs.trailing_zeros().into()),
+		UnOpType::Popcnt_I64 => Value::I64(rhs.count_ones().into()),
+		UnOpType::Extend_I64_N8 => Value::I64(i64::from(rhs as i8)),
+		UnOpType::Extend_I64_N16 => Value::I64(i64::from(rhs as i16)),
+		UnOpType::Extend_I64_N32 => Value::I64(i64::from(rhs as i32)),
+		UnOpType::Wrap_I32_I64 => Value::I32(rhs as i32),
+		UnOpType::Saturate_U32_I64 => {
+			let data = u32::try_from(rhs as u64).unwrap_or(u32::MAX);
+
+			Value::I32(data as i32)
+		}
+		UnOpType::Convert_F32_I64 => Value::F32(rhs as f32),
+		UnOpType::Convert_F32_U64 => Value::F32(rhs as u64 as f32),
+		UnOpType::Convert_F64_I64 => Value::F64(rhs as f64),
+		UnOpType::Convert_F64_U64 => Value::F64(rhs as u64 as f64),
+		UnOpType::Reinterpret_F64_I64 => Value::F64(f64::from_bits(rhs as u64)),
+		_ => return None,
+	};
+
+	Some(value)
+}
+
+fn un_op_f32(op_type: UnOpType, rhs: f32) -> Option<Value> {
+	let value = match op_type {
+		UnOpType::Abs_F32 => Value::F32(rhs.abs()),
+		UnOpType::Neg_F32 => Value::F32(-rhs),
+		UnOpType::Ceil_F32 => Value::F32(rhs.ceil()),
+		UnOpType::Floor_F32 => Value::F32(rhs.floor()),
+		UnOpType::Truncate_F32 => Value::F32(rhs.trunc()),
+		UnOpType::Nearest_F32 => Value::F32(nearest_f32(rhs)),
+		UnOpType::Sqrt_F32 => Value::F32(rhs.sqrt()),
+		UnOpType::Truncate_I32_F32 => Value::I32(truncate_i32(rhs.into())?),
+		UnOpType::Truncate_U32_F32 => Value::I32(truncate_u32(rhs.into())?),
+		UnOpType::Truncate_I64_F32 => Value::I64(truncate_i64(rhs.into())?),
+		UnOpType::Truncate_U64_F32 => Value::I64(truncate_u64(rhs.into())?),
+		UnOpType::Saturate_I32_F32 => Value::I32(rhs as i32),
+		UnOpType::Saturate_U32_F32 => Value::I32(rhs as u32 as i32),
+		UnOpType::Saturate_I64_F32 => Value::I64(rhs as i64),
+		UnOpType::Saturate_U64_F32 => Value::I64(rhs as u64 as i64),
+		UnOpType::Promote_F64_F32 => Value::F64(rhs.into()),
+		UnOpType::Reinterpret_I32_F32 => Value::I32(rhs.to_bits() as i32),
+		_ => return None,
+	};
+
+	Some(value)
+}
+
+fn un_op_f64(op_type: UnOpType, rhs: f64) -> Option<Value> {
+	let value = match op_type {
+		UnOpType::Abs_F64 => Value::F64(rhs.abs()),
+		UnOpType::Neg_F64 => Value::F64(-rhs),
+		UnOpType::Ceil_F64 => Value::F64(rhs.ceil()),
+		UnOpType::Floor_F64 => Value::F64(rhs.floor()),
+		UnOpType::Truncate_F64 => Value::F64(rhs.trunc()),
+		UnOpType::Nearest_F64 => Value::F64(nearest_f64(rhs)),
+		UnOpType::Sqrt_F64 => Value::F64(rhs.sqrt()),
+		UnOpType::Truncate_I32_F64 => Value::I32(truncate_i32(rhs)?),
+		UnOpType::Truncate_U32_F64 => Value::I32(truncate_u32(rhs)?),
+		UnOpType::Truncate_I64_F64 => Value::I64(truncate_i64(rhs)?),
+		UnOpType::Truncate_U64_F64 => Value::I64(truncate_u64(rhs)?),
+		UnOpType::Saturate_I32_F64 => Value::I32(rhs as i32),
+		UnOpType::Saturate_U32_F64 => Value::I32(rhs as u32 as i32),
+		UnOpType::Saturate_I64_F64 => Value::I64(rhs as i64),
+		UnOpType::Saturate_U64_F64 => Value::I64(rhs as u64 as i64),
+		UnOpType::Demote_F32_F64 => Value::F32(rhs as f32),
+		UnOpType::Reinterpret_I64_F64 => Value::I64(rhs.to_bits() as i64),
+		_ => return None,
+	};
+
+	Some(value)
+}
+
+fn bin_op_i32(op_type: BinOpType, lhs: i32, rhs: i32) -> Option<i32> {
+	let value = match op_type {
+		BinOpType::Add_I32 => lhs.wrapping_add(rhs),
+		BinOpType::Sub_I32 => lhs.wrapping_sub(rhs),
+		BinOpType::Mul_I32 => lhs.wrapping_mul(rhs),
+		BinOpType::DivS_I32 => lhs.checked_div(rhs)?,
+		BinOpType::DivU_I32 => (lhs as u32).checked_div(rhs as u32)? as i32,
+		BinOpType::RemS_I32 => (rhs != 0).then(|| lhs.wrapping_rem(rhs))?,
+		BinOpType::RemU_I32 => (lhs as u32).checked_rem(rhs as u32)? as i32,
+		BinOpType::And_I32 => lhs & rhs,
+		BinOpType::Or_I32 => lhs | rhs,
+		BinOpType::Xor_I32 => lhs ^ rhs,
+		BinOpType::Shl_I32 => lhs.wrapping_shl(rhs as u32),
+		BinOpType::ShrS_I32 => lhs.wrapping_shr(rhs as u32),
+		BinOpType::ShrU_I32 => (lhs as u32).wrapping_shr(rhs as u32) as i32,
+		BinOpType::Rotl_I32 => lhs.rotate_left((rhs & 31) as u32),
+		BinOpType::Rotr_I32 => lhs.rotate_right((rhs & 31) as u32),
+		_ => return None,
+	};
+
+	Some(value)
+}
+
+fn bin_op_i64(op_type: BinOpType, lhs: i64, rhs: i64) -> Option<i64> {
+	let value = match op_type {
+		BinOpType::Add_I64 => lhs.wrapping_add(rhs),
+		BinOpType::Sub_I64 => lhs.wrapping_sub(rhs),
+		BinOpType::Mul_I64 => lhs.wrapping_mul(rhs),
+		BinOpType::DivS_I64 => lhs.checked_div(rhs)?,
+		BinOpType::DivU_I64 => (lhs as u64).checked_div(rhs as u64)? as i64,
+		BinOpType::RemS_I64 => (rhs != 0).then(|| lhs.wrapping_rem(rhs))?,
+		BinOpType::RemU_I64 => (lhs as u64).checked_rem(rhs as u64)? as i64,
+		BinOpType::And_I64 => lhs & rhs,
+		BinOpType::Or_I64 => lhs | rhs,
+		BinOpType::Xor_I64 => lhs ^ rhs,
+		BinOpType::Shl_I64 => lhs.wrapping_shl(rhs as u32),
+		BinOpType::ShrS_I64 => lhs.wrapping_shr(rhs as u32),
+		BinOpType::ShrU_I64 => (lhs as u64).wrapping_shr(rhs as u32) as i64,
+		BinOpType::Rotl_I64 => lhs.rotate_left((rhs & 63) as u32),
+		BinOpType::Rotr_I64 => lhs.rotate_right((rhs & 63) as u32),
+		_ => return None,
+	};
+
+	Some(value)
+}
+
+fn bin_op_f32(op_type: BinOpType, lhs: f32, rhs: f32) -> Option<f32> {
+	let value = match op_type {
+		BinOpType::Add_F32 => lhs + rhs,
+		BinOpType::Sub_F32 => lhs - rhs,
+		BinOpType::Mul_F32 => lhs * rhs,
+		BinOpType::Div_F32 => lhs / rhs,
+		BinOpType::Min_F32 => min_f32(lhs, rhs),
+		BinOpType::Max_F32 => max_f32(lhs, rhs),
+		BinOpType::Copysign_F32 => lhs.copysign(rhs),
+		_ => return None,
+	};
+
+	Some(value)
+}
+
+fn bin_op_f64(op_type: BinOpType, lhs: f64, rhs: f64) -> Option<f64> {
+	let value = match op_type {
+		BinOpType::Add_F64 => lhs + rhs,
+		BinOpType::Sub_F64 => lhs - rhs,
+		BinOpType::Mul_F64 => lhs * rhs,
+		BinOpType::Div_F64 => lhs / rhs,
+		BinOpType::Min_F64 => min_f64(lhs, rhs),
+		BinOpType::Max_F64 => max_f64(lhs, rhs),
+		BinOpType::Copysign_F64 => lhs.copysign(rhs),
+		_ => return None,
+	};
+
+	Some(value)
+}
+
+fn cmp_op_i32(op_type: CmpOpType, lhs: i32, rhs: i32) -> Option<bool> {
+	let (lhs_u, rhs_u) = (lhs as u32, rhs as u32);
+	let value = match op_type {
+		CmpOpType::Eq_I32 => lhs == rhs,
+		CmpOpType::Ne_I32 => lhs != rhs,
+		CmpOpType::LtS_I32 => lhs < rhs,
+		CmpOpType::LtU_I32 => lhs_u < rhs_u,
+		CmpOpType::GtS_I32 => lhs > rhs,
+		CmpOpType::GtU_I32 => lhs_u > rhs_u,
+		CmpOpType::LeS_I32 => lhs <= rhs,
+		CmpOpType::LeU_I32 => lhs_u <= rhs_u,
+		CmpOpType::GeS_I32 => lhs >= rhs,
+		CmpOpType::GeU_I32 => lhs_u >= rhs_u,
+		_ => return None,
+	};
+
+	Some(value)
+}
+
+fn cmp_op_i64(op_type: CmpOpType, lhs: i64, rhs: i64) -> Option<bool> {
+	let (lhs_u, rhs_u) = (lhs as u64, rhs as u64);
+	let value = match op_type {
+		CmpOpType::Eq_I64 => lhs == rhs,
+		CmpOpType::Ne_I64 => lhs != rhs,
+		CmpOpType::LtS_I64 => lhs < rhs,
+		CmpOpType::LtU_I64 => lhs_u < rhs_u,
+		CmpOpType::GtS_I64 => lhs > rhs,
+		CmpOpType::GtU_I64 => lhs_u > rhs_u,
+		CmpOpType::LeS_I64 => lhs <= rhs,
+		CmpOpType::LeU_I64 => lhs_u <= rhs_u,
+		CmpOpType::GeS_I64 => lhs >= rhs,
+		CmpOpType::GeU_I64 => lhs_u >= rhs_u,
+		_ => return None,
+	};
+
+	Some(value)
+}
+
+#[allow(clippy::float_cmp)]
+fn cmp_op_f32(op_type: CmpOpType, lhs: f32, rhs: f32) -> Option<bool> {
+	let value = match op_type {
+		CmpOpType::Eq_F32 => lhs == rhs,
+		CmpOpType::Ne_F32 => lhs != rhs,
+		CmpOpType::Lt_F32 => lhs < rhs,
+		CmpOpType::Gt_F32 => lhs > rhs,
+		CmpOpType::Le_F32 => lhs <= rhs,
+		CmpOpType::Ge_F32 => lhs >= rhs,
+		_ => return None,
+	};
+
+	Some(value)
+}
+
+#[allow(clippy::float_cmp)]
+fn cmp_op_f64(op_type: CmpOpType, lhs: f64, rhs: f64) -> Option<bool> {
+	let value = match op_type {
+		CmpOpType::Eq_F64 => lhs == rhs,
+		CmpOpType::Ne_F64 => lhs != rhs,
+		CmpOpType::Lt_F64 => lhs < rhs,
+		CmpOpType::Gt_F64 => lhs > rhs,
+		CmpOpType::Le_F64 => lhs <= rhs,
+		CmpOpType::Ge_F64 => lhs >= rhs,
+		_ => return None,
+	};
+
+	Some(value)
+}
+
+/// Evaluates a unary operation on a known value. Returns `None` if the
+/// operation would trap or is not one of the scalar operations.
+#[must_use]
+pub fn un_op(op_type: UnOpType, rhs: Value) -> Option<Value> {
+	match rhs {
+		Value::I32(rhs) => un_op_i32(op_type, rhs),
+		Value::I64(rhs) => un_op_i64(op_type, rhs),
+		Value::F32(rhs) => un_op_f32(op_type, rhs),
+		Value::F64(rhs) => un_op_f64(op_type, rhs),
+		Value::V128(_) => None,
+	}
+}
+
+/// Evaluates a binary operation on known values. Returns `None` if the
+/// operation would trap or is not one of the scalar operations.
+#[must_use]
+pub fn bin_op(op_type: BinOpType, lhs: Value, rhs: Value) -> Option<Value> {
+	match (lhs, rhs) {
+		(Value::I32(lhs), Value::I32(rhs)) => bin_op_i32(op_type, lhs, rhs).map(Value::I32),
+		(Value::I64(lhs), Value::I64(rhs)) => bin_op_i64(op_type, lhs, rhs).map(Value::I64),
+		(Value::F32(lhs), Value::F32(rhs)) => bin_op_f32(op_type, lhs, rhs).map(Value::F32),
+		(Value::F64(lhs), Value::F64(rhs)) => bin_op_f64(op_type, lhs, rhs).map(Value::F64),
+		_ => None,
+	}
+}
+
+/// Evaluates a comparison on known values. Returns `None` if the operand
+/// types do not match the comparison.
+#[must_use]
+pub fn cmp_op(op_type: CmpOpType, lhs: Value, rhs: Value) -> Option<Value> {
+	let result = match (lhs, rhs) {
+		(Value::I32(lhs), Value::I32(rhs)) => cmp_op_i32(op_type, lhs, rhs),
+		(Value::I64(lhs), Value::I64(rhs)) => cmp_op_i64(op_type, lhs, rhs),
+		(Value::F32(lhs), Value::F32(rhs)) => cmp_op_f32(op_type, lhs, rhs),
+		(Value::F64(lhs), Value::F64(rhs)) => cmp_op_f64(op_type, lhs, rhs),
+		_ => None,
+	};
+
+	result.map(|v| Value::I32(v.into()))
+}
+
+const fn can_trap_un_op(op_type: UnOpType) -> bool {
+	matches!(
+		op_type,
+		UnOpType::Truncate_I32_F32
+			| UnOpType::Truncate_I32_F64
+			| UnOpType::Truncate_U32_F32
+			| UnOpType::Truncate_U32_F64
+			| UnOpType::Truncate_I64_F32
+			| UnOpType::Truncate_I64_F64
+			| UnOpType::Truncate_U64_F32
+			| UnOpType::Truncate_U64_F64
+	)
+}
+
+const fn can_trap_bin_op(op_type: BinOpType) -> bool {
+	matches!(
+		op_type,
+		BinOpType::DivS_I32
+			| BinOpType::DivU_I32
+			| BinOpType::RemS_I32
+			| BinOpType::RemU_I32
+			| BinOpType::DivS_I64
+			| BinOpType::DivU_I64
+			| BinOpType::RemS_I64
+			| BinOpType::RemU_I64
+	)
+}
+
+// Only expressions that can be dropped without changing behavior,
+// so anything that may trap or touch memory and tables is excluded
+pub(crate) fn is_removable(data: &Expression) -> bool {
+	match data {
+		Expression::GetTemporary(_)
+		| Expression::GetLocal(_)
+		| Expression::GetGlobal(_)
+		| Expression::MemorySize(_)
+		| Expression::RefNull(_)
+		| Expression::RefFunc(_)
+		| Expression::TableSize(_)
+		| Expression::Value(_) => true,
+		Expression::Select(v) => {
+			is_removable(v.condition()) && is_removable(v.on_true()) && is_removable(v.on_false())
+		}
+		Expression::RefIsNull(v) => is_removable(v.value()),
+		Expression::UnOp(v) => !can_trap_un_op(v.op_type()) && is_removable(v.rhs()),
+		Expression::BinOp(v) => {
+			!can_trap_bin_op(v.op_type()) && is_removable(v.lhs()) && is_removable(v.rhs())
+		}
+		Expression::CmpOp(v) => is_removable(v.lhs()) && is_removable(v.rhs()),
+		_ => false,
+	}
+}
+
+const fn as_value(data: &Expression) -> Option<Value> {
+	if let Expression::Value(value) = data {
+		Some(*value)
+	} else {
+		None
+	}
+}
+
+pub(crate) fn take_expression(data: &mut Expression) -> Expression {
+	std::mem::replace(data, Expression::Value(Value::I32(0)))
+}
+
+struct Visit;
+
+impl Visit {
+	fn try_fold(data: &mut Expression) -> Option<Expression> {
+		match data {
+			Expression::UnOp(v) => un_op(v.op_type(), as_value(v.rhs())?).map(Expression::Value),
+			Expression::BinOp(v) => {
+				let (lhs, rhs) = (as_value(v.lhs())?, as_value(v.rhs())?);
+
+				bin_op(v.op_type(), lhs, rhs).map(Expression::Value)
+			}
+			Expression::CmpOp(v) => {
+				let (lhs, rhs) = (as_value(v.lhs())?, as_value(v.rhs())?);
+
+				cmp_op(v.op_type(), lhs, rhs).map(Expression::Value)
+			}
+			Expression::Select(v) => {
+				let Value::I32(condition) = as_value(v.condition())? else {
+					return None;
+				};
+
+				if condition == 0 {
+					is_removable(v.on_true()).then(|| take_expression(v.on_false_mut()))
+				} else {
+					is_removable(v.on_false()).then(|| take_expression(v.on_true_mut()))
+				}
+			}
+			_ => None,
+		}
+	}
+}
+
+impl VisitorMut for Visit {
+	fn visit_expression(&mut self, data: &mut Expression) {
+		if let Some(result) = Self::try_fold(data) {
+			*data = result;
+		}
+	}
+}
+
+/// Folds operations on known values into a single value with the same
+/// results as the WebAssembly semantics. Operations that would trap are
+/// left in place so that they trap at runtime.
+pub fn run(ast: &mut FuncData) {
+	ast.accept_mut(&mut Visit);
+}
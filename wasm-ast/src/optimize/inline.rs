@@ -0,0 +1,334 @@
+use std::collections::HashMap;
+
+use wasmparser::ValType;
+
+use crate::{
+	module::TypeInfo,
+	node::{
+		Align, AtomicCmpxchg, AtomicNotify, AtomicRmw, AtomicWait, Block, Br, BrIf, BrTable, Call,
+		CallIndirect, Expression, FuncData, Local, MemoryGrow, RefNull, RefType, ReturnCall,
+		ReturnCallIndirect, SetLocal, SetTemporary, Statement, TableGrow, Temporary, Terminator,
+		Try, Value,
+	},
+	stack::find_temporary_data,
+	visit::{Driver, DriverMut, Visitor, VisitorMut},
+};
+
+// Measures a function, noting anything that would stop it from being
+// copied as is into another function
+#[derive(Default)]
+struct Summary {
+	size: usize,
+	is_leaf: bool,
+}
+
+impl Summary {
+	fn of(ast: &FuncData) -> Self {
+		let mut summary = Self {
+			size: 0,
+			is_leaf: true,
+		};
+
+		ast.accept(&mut summary);
+
+		summary
+	}
+}
+
+impl Visitor for Summary {
+	fn visit_expression(&mut self, _: &Expression) {
+		self.size += 1;
+	}
+
+	fn visit_terminator(&mut self, _: &Terminator) {
+		self.size += 1;
+	}
+
+	fn visit_statement(&mut self, _: &Statement) {
+		self.size += 1;
+	}
+
+	fn visit_return_call(&mut self, _: &ReturnCall) {
+		self.is_leaf = false;
+	}
+
+	fn visit_return_call_indirect(&mut self, _: &ReturnCallIndirect) {
+		self.is_leaf = false;
+	}
+
+	// Handlers are written relative to the enclosing function
+	fn visit_try(&mut self, _: &Try) {
+		self.is_leaf = false;
+	}
+
+	fn visit_call(&mut self, _: &Call) {
+		self.is_leaf = false;
+	}
+
+	fn visit_call_indirect(&mut self, _: &CallIndirect) {
+		self.is_leaf = false;
+	}
+}
+
+// Moves a callee's variables into the space set aside by the caller,
+// dropping its labels as the names of the caller do not apply to them
+struct Remap<'a> {
+	temporary: usize,
+	local_map: &'a [usize],
+}
+
+impl Remap<'_> {
+	fn temporary(&self, temporary: &mut Temporary) {
+		temporary.var += self.temporary;
+	}
+
+	fn align(&self, align: &mut Align) {
+		align.new += self.temporary;
+		align.old += self.temporary;
+	}
+}
+
+impl VisitorMut for Remap<'_> {
+	fn visit_get_temporary(&mut self, temporary: &mut Temporary) {
+		self.temporary(temporary);
+	}
+
+	fn visit_get_local(&mut self, local: &mut Local) {
+		local.var = self.local_map[local.var];
+	}
+
+	fn visit_br(&mut self, br: &mut Br) {
+		self.align(&mut br.align);
+	}
+
+	fn visit_block(&mut self, block: &mut Block) {
+		block.label = None;
+	}
+
+	fn visit_br_table(&mut self, table: &mut BrTable) {
+		for br in &mut table.data {
+			self.align(&mut br.align);
+		}
+
+		self.align(&mut table.default.align);
+	}
+
+	fn visit_br_if(&mut self, br_if: &mut BrIf) {
+		self.align(&mut br_if.target.align);
+	}
+
+	fn visit_set_temporary(&mut self, set: &mut SetTemporary) {
+		self.temporary(&mut set.var);
+	}
+
+	fn visit_set_local(&mut self, set: &mut SetLocal) {
+		set.var.var = self.local_map[set.var.var];
+	}
+
+	fn visit_memory_grow(&mut self, grow: &mut MemoryGrow) {
+		self.temporary(&mut grow.result);
+	}
+
+	fn visit_atomic_rmw(&mut self, rmw: &mut AtomicRmw) {
+		self.temporary(&mut rmw.result);
+	}
+
+	fn visit_atomic_cmpxchg(&mut self, cmpxchg: &mut AtomicCmpxchg) {
+		self.temporary(&mut cmpxchg.result);
+	}
+
+	fn visit_atomic_wait(&mut self, wait: &mut AtomicWait) {
+		self.temporary(&mut wait.result);
+	}
+
+	fn visit_atomic_notify(&mut self, notify: &mut AtomicNotify) {
+		self.temporary(&mut notify.result);
+	}
+
+	fn visit_table_grow(&mut self, grow: &mut TableGrow) {
+		self.temporary(&mut grow.result);
+	}
+}
+
+fn zero_of(ty: ValType) -> Expression {
+	match ty {
+		v if v == ValType::EXTERNREF => Expression::RefNull(RefNull::new(RefType::Extern)),
+		ValType::I32 => Expression::Value(Value::I32(0)),
+		ValType::I64 => Expression::Value(Value::I64(0)),
+		ValType::F32 => Expression::Value(Value::F32(0.0)),
+		ValType::F64 => Expression::Value(Value::F64(0.0)),
+		ValType::V128 => Expression::Value(Value::V128(0)),
+		ValType::Ref(_) => Expression::RefNull(RefNull::new(RefType::Func)),
+	}
+}
+
+struct Callee<'a> {
+	ast: FuncData,
+	type_list: Vec<ValType>,
+	result_type: &'a [ValType],
+}
+
+// Inlined bodies never contain calls, so they can never overlap and
+// every call site shares the same temporaries and extra locals
+struct Inliner<'a> {
+	callee_map: &'a HashMap<usize, Callee<'a>>,
+	local_data: Vec<ValType>,
+	num_param: usize,
+	num_local: usize,
+	num_stack: usize,
+	base: usize,
+}
+
+impl Inliner<'_> {
+	// Extra locals are reused between call sites when their types match,
+	// as every use starts by assigning to them
+	fn get_local_map(&mut self, type_list: &[ValType]) -> Vec<usize> {
+		let mut taken = vec![false; self.local_data.len()];
+
+		type_list
+			.iter()
+			.map(|&ty| {
+				let found = (self.num_local..self.local_data.len())
+					.find(|&i| !taken[i] && self.local_data[i] == ty);
+
+				let index = found.unwrap_or_else(|| {
+					self.local_data.push(ty);
+					taken.push(false);
+					self.local_data.len() - 1
+				});
+
+				taken[index] = true;
+
+				self.num_param + index
+			})
+			.collect()
+	}
+
+	// Parameters are assigned first, then locals are zeroed as the body
+	// may run more than once, then results are copied out after the body
+	fn expand(&mut self, call: Call, code: &mut Vec<Statement>) {
+		let callee = &self.callee_map[&call.function];
+		let local_map = self.get_local_map(&callee.type_list);
+
+		let mut param_list = call.param_list.into_iter();
+
+		for (&var, &ty) in local_map.iter().zip(&callee.type_list) {
+			let value = param_list.next().unwrap_or_else(|| zero_of(ty));
+
+			code.push(Statement::SetLocal(SetLocal::new(
+				Local::new(var, ty),
+				value,
+			)));
+		}
+
+		let mut block = callee.ast.code.clone();
+
+		block.accept_mut(&mut Remap {
+			temporary: self.base,
+			local_map: &local_map,
+		});
+
+		code.push(Statement::Block(block));
+
+		for (i, (var, &ty)) in call.result_list.iter().zip(callee.result_type).enumerate() {
+			let value = Expression::GetTemporary(Temporary::new(self.base + i, ty));
+
+			code.push(Statement::SetTemporary(SetTemporary::new(
+				Temporary::new(var, ty),
+				value,
+			)));
+		}
+
+		self.num_stack = self.num_stack.max(self.base + callee.ast.num_stack());
+	}
+
+	fn block(&mut self, block: &mut Block) {
+		let code = std::mem::take(block.code_mut());
+
+		for mut stat in code {
+			match &mut stat {
+				Statement::Block(v) => self.block(v),
+				Statement::If(v) => {
+					self.block(v.on_true_mut());
+
+					if let Some(v) = v.on_false_mut() {
+						self.block(v);
+					}
+				}
+				Statement::Try(v) => {
+					self.block(v.code_mut());
+
+					for catch in v.catch_list_mut() {
+						self.block(catch.code_mut());
+					}
+
+					if let Some(v) = v.catch_all_mut() {
+						self.block(v);
+					}
+				}
+				_ => {}
+			}
+
+			match stat {
+				Statement::Call(v) if self.callee_map.contains_key(&v.function) => {
+					self.expand(v, block.code_mut());
+				}
+				stat => block.code_mut().push(stat),
+			}
+		}
+	}
+}
+
+/// Replaces calls to small functions that make no calls of their own with
+/// a copy of their body. A function is small if it has at most `threshold`
+/// nodes. The list holds the functions defined by the module, starting
+/// at index `offset` in the function space.
+pub fn run(func_list: &mut [FuncData], type_info: &TypeInfo, offset: usize, threshold: usize) {
+	let callee_map: HashMap<_, _> = func_list
+		.iter()
+		.enumerate()
+		.filter(|(_, ast)| {
+			let summary = Summary::of(ast);
+
+			summary.is_leaf && summary.size <= threshold
+		})
+		.map(|(i, ast)| {
+			let index = i + offset;
+			let type_list = ast
+				.param_data()
+				.iter()
+				.chain(ast.local_data())
+				.copied()
+				.collect();
+
+			let callee = Callee {
+				ast: ast.clone(),
+				type_list,
+				result_type: type_info.func_type(index).unwrap().results(),
+			};
+
+			(index, callee)
+		})
+		.collect();
+
+	if callee_map.is_empty() {
+		return;
+	}
+
+	for ast in func_list {
+		let mut inliner = Inliner {
+			callee_map: &callee_map,
+			local_data: std::mem::take(&mut ast.local_data),
+			num_param: ast.num_param(),
+			num_local: 0,
+			num_stack: ast.num_stack(),
+			base: ast.num_stack(),
+		};
+
+		inliner.num_local = inliner.local_data.len();
+		inliner.block(&mut ast.code);
+
+		ast.local_data = inliner.local_data;
+		ast.temporary_data = find_temporary_data(&ast.code, inliner.num_stack);
+	}
+}
@@ -0,0 +1,531 @@
+use std::collections::BTreeSet;
+
+use crate::{
+	node::{
+		AtomicCmpxchg, AtomicNotify, AtomicRmw, AtomicWait, Block, Br, BrIf, BrTable, Call,
+		CallIndirect, FuncData, GetGlobal, LabelType, LoadAt, LoadLane, Local, MemoryCopy,
+		MemoryFill, MemoryGrow, MemoryInit, MemorySize, ResultList, SetGlobal, SetLocal,
+		SetTemporary, Statement, StoreAt, StoreLane, TableCopy, TableFill, TableGet, TableGrow,
+		TableInit, TableSet, TableSize, Temporary, Terminator, Try,
+	},
+	visit::{Driver, Visitor},
+};
+
+/// Every variable and piece of state that a node reads or writes.
+/// Branch alignments are kept apart from the other temporaries as
+/// they only take effect when the branch is taken.
+#[derive(Default)]
+pub struct Access {
+	pub temporary_read: BTreeSet<usize>,
+	pub temporary_write: BTreeSet<usize>,
+	pub local_read: BTreeSet<usize>,
+	pub local_write: BTreeSet<usize>,
+	pub align_read: BTreeSet<usize>,
+	pub align_write: BTreeSet<usize>,
+	pub global_read: bool,
+	pub global_write: bool,
+	pub memory_read: bool,
+	pub memory_write: bool,
+	pub table_read: bool,
+	pub table_write: bool,
+}
+
+impl Access {
+	#[must_use]
+	pub fn of<T: Driver<Self>>(node: &T) -> Self {
+		let mut access = Self::default();
+
+		node.accept(&mut access);
+
+		access
+	}
+
+	#[must_use]
+	pub fn mentions(&self, var: usize) -> bool {
+		self.temporary_read.contains(&var)
+			|| self.temporary_write.contains(&var)
+			|| self.align_read.contains(&var)
+			|| self.align_write.contains(&var)
+	}
+
+	/// Returns whether running `self` after `other` could change its result.
+	#[must_use]
+	pub fn depends_on(&self, other: &Self) -> bool {
+		!self.temporary_read.is_disjoint(&other.temporary_write)
+			|| !self.temporary_read.is_disjoint(&other.align_write)
+			|| !self.local_read.is_disjoint(&other.local_write)
+			|| (self.global_read && other.global_write)
+			|| (self.memory_read && other.memory_write)
+			|| (self.table_read && other.table_write)
+	}
+
+	fn write_result(&mut self, result: ResultList) {
+		self.temporary_write.extend(result.iter());
+	}
+
+	fn write_all(&mut self) {
+		self.global_write = true;
+		self.memory_write = true;
+		self.table_write = true;
+	}
+
+	fn align(&mut self, br: Br) {
+		let align = br.align();
+
+		if align.is_aligned() {
+			return;
+		}
+
+		self.align_read.extend(align.old_range().iter());
+		self.align_write.extend(align.new_range().iter());
+	}
+}
+
+impl Visitor for Access {
+	fn visit_get_temporary(&mut self, temporary: Temporary) {
+		self.temporary_read.insert(temporary.var());
+	}
+
+	fn visit_get_local(&mut self, local: Local) {
+		self.local_read.insert(local.var());
+	}
+
+	fn visit_get_global(&mut self, _: GetGlobal) {
+		self.global_read = true;
+	}
+
+	fn visit_load_at(&mut self, _: &LoadAt) {
+		self.memory_read = true;
+	}
+
+	fn visit_memory_size(&mut self, _: &MemorySize) {
+		self.memory_read = true;
+	}
+
+	fn visit_table_get(&mut self, _: &TableGet) {
+		self.table_read = true;
+	}
+
+	fn visit_table_size(&mut self, _: &TableSize) {
+		self.table_read = true;
+	}
+
+	fn visit_load_lane(&mut self, _: &LoadLane) {
+		self.memory_read = true;
+	}
+
+	fn visit_br(&mut self, br: Br) {
+		self.align(br);
+	}
+
+	fn visit_br_table(&mut self, table: &BrTable) {
+		for &br in table.data() {
+			self.align(br);
+		}
+
+		self.align(table.default());
+	}
+
+	fn visit_br_if(&mut self, br_if: &BrIf) {
+		self.align(br_if.target());
+	}
+
+	fn visit_try(&mut self, try_: &Try) {
+		for catch in try_.catch_list() {
+			self.write_result(catch.result_list());
+		}
+	}
+
+	fn visit_call(&mut self, call: &Call) {
+		self.write_result(call.result_list());
+		self.write_all();
+	}
+
+	fn visit_call_indirect(&mut self, call: &CallIndirect) {
+		self.write_result(call.result_list());
+		self.write_all();
+	}
+
+	fn visit_set_temporary(&mut self, set: &SetTemporary) {
+		self.temporary_write.insert(set.var().var());
+	}
+
+	fn visit_set_local(&mut self, set: &SetLocal) {
+		self.local_write.insert(set.var().var());
+	}
+
+	fn visit_set_global(&mut self, _: &SetGlobal) {
+		self.global_write = true;
+	}
+
+	fn visit_store_at(&mut self, _: &StoreAt) {
+		self.memory_write = true;
+	}
+
+	fn visit_store_lane(&mut self, _: &StoreLane) {
+		self.memory_write = true;
+	}
+
+	fn visit_memory_grow(&mut self, grow: &MemoryGrow) {
+		self.temporary_write.insert(grow.result().var());
+		self.memory_write = true;
+	}
+
+	fn visit_memory_copy(&mut self, _: &MemoryCopy) {
+		self.memory_write = true;
+	}
+
+	fn visit_memory_fill(&mut self, _: &MemoryFill) {
+		self.memory_write = true;
+	}
+
+	fn visit_memory_init(&mut self, _: &MemoryInit) {
+		self.memory_write = true;
+	}
+
+	fn visit_atomic_rmw(&mut self, rmw: &AtomicRmw) {
+		self.temporary_write.insert(rmw.result().var());
+		self.memory_write = true;
+	}
+
+	fn visit_atomic_cmpxchg(&mut self, cmpxchg: &AtomicCmpxchg) {
+		self.temporary_write.insert(cmpxchg.result().var());
+		self.memory_write = true;
+	}
+
+	fn visit_atomic_wait(&mut self, wait: &AtomicWait) {
+		self.temporary_write.insert(wait.result().var());
+		self.memory_read = true;
+	}
+
+	fn visit_atomic_notify(&mut self, notify: &AtomicNotify) {
+		self.temporary_write.insert(notify.result().var());
+	}
+
+	fn visit_table_set(&mut self, _: &TableSet) {
+		self.table_write = true;
+	}
+
+	fn visit_table_grow(&mut self, grow: &TableGrow) {
+		self.temporary_write.insert(grow.result().var());
+		self.table_write = true;
+	}
+
+	fn visit_table_fill(&mut self, _: &TableFill) {
+		self.table_write = true;
+	}
+
+	fn visit_table_copy(&mut self, _: &TableCopy) {
+		self.table_write = true;
+	}
+
+	fn visit_table_init(&mut self, _: &TableInit) {
+		self.table_write = true;
+	}
+}
+
+/// A set of temporaries and locals whose values may still be read.
+#[derive(Default, Clone, PartialEq, Eq)]
+pub struct LiveSet {
+	pub temporary: BTreeSet<usize>,
+	pub local: BTreeSet<usize>,
+}
+
+impl LiveSet {
+	fn extend(&mut self, other: &Self) {
+		self.temporary.extend(&other.temporary);
+		self.local.extend(&other.local);
+	}
+
+	fn read(&mut self, access: &Access) {
+		self.temporary.extend(&access.temporary_read);
+		self.local.extend(&access.local_read);
+	}
+
+	fn kill(&mut self, access: &Access) {
+		self.temporary
+			.retain(|v| !access.temporary_write.contains(v));
+		self.local.retain(|v| !access.local_write.contains(v));
+	}
+}
+
+/// The live sets around a statement or terminator. The `middle` set is
+/// taken after its own expressions are evaluated but before it writes
+/// or branches anywhere.
+#[derive(Default)]
+pub struct Point {
+	pub after: LiveSet,
+	pub middle: LiveSet,
+}
+
+/// Backward liveness of every temporary and local in a block and the
+/// blocks nested in it. Live sets are found by position rather than by
+/// node, so they stay valid while code elsewhere in the function changes.
+#[derive(Default)]
+pub struct Liveness {
+	entry: LiveSet,
+	point_list: Vec<Point>,
+	nested_list: Vec<Vec<Liveness>>,
+}
+
+impl Liveness {
+	/// Returns the liveness of the body of a function.
+	#[must_use]
+	pub fn new(ast: &FuncData) -> Self {
+		let mut builder = Builder {
+			label_list: Vec::new(),
+			handler: LiveSet::default(),
+		};
+
+		let result = LiveSet {
+			temporary: (0..ast.num_result()).collect(),
+			local: BTreeSet::new(),
+		};
+
+		builder.block(ast.code(), result.clone(), result)
+	}
+
+	/// Returns what is live when the block starts. For the body of a
+	/// function, this means locals read before being written on some path,
+	/// and for a handler, what is live once it has written its results.
+	#[must_use]
+	pub const fn entry(&self) -> &LiveSet {
+		&self.entry
+	}
+
+	/// Returns the live sets around the statement at `index`, or around
+	/// the terminator if `index` is the length of the code.
+	#[must_use]
+	pub fn point(&self, index: usize) -> &Point {
+		&self.point_list[index]
+	}
+
+	/// Returns the liveness of the blocks nested in the statement at
+	/// `index`, in the order that their statements are visited.
+	#[must_use]
+	pub fn nested(&self, index: usize) -> &[Self] {
+		&self.nested_list[index]
+	}
+}
+
+struct Builder {
+	label_list: Vec<LiveSet>,
+	handler: LiveSet,
+}
+
+impl Builder {
+	// Values moved by the alignment are live under their old name
+	fn br_live(&self, br: Br) -> LiveSet {
+		let index = self.label_list.len() - 1 - br.target();
+		let label = &self.label_list[index];
+		let align = br.align();
+
+		if align.is_aligned() {
+			return label.clone();
+		}
+
+		let mut live = label.clone();
+		let moved: Vec<_> = align
+			.new_range()
+			.iter()
+			.zip(align.old_range().iter())
+			.filter(|(new, _)| label.temporary.contains(new))
+			.map(|(_, old)| old)
+			.collect();
+
+		for new in align.new_range().iter() {
+			live.temporary.remove(&new);
+		}
+
+		live.temporary.extend(moved);
+		live
+	}
+
+	fn terminator(&mut self, last: &Terminator) -> (LiveSet, Point) {
+		let mut middle = self.handler.clone();
+
+		match last {
+			Terminator::Br(v) => middle.extend(&self.br_live(*v)),
+			Terminator::BrTable(v) => {
+				for &br in v.data() {
+					middle.extend(&self.br_live(br));
+				}
+
+				middle.extend(&self.br_live(v.default()));
+			}
+			Terminator::Unreachable
+			| Terminator::Throw(_)
+			| Terminator::Rethrow(_)
+			| Terminator::ReturnCall(_)
+			| Terminator::ReturnCallIndirect(_) => {}
+		}
+
+		let mut live = middle.clone();
+
+		live.read(&Access::of(last));
+
+		let after = LiveSet::default();
+
+		(live, Point { after, middle })
+	}
+
+	fn block(&mut self, block: &Block, label: LiveSet, after: LiveSet) -> Liveness {
+		self.label_list.push(label);
+
+		let mut point_list = Vec::new();
+		let mut nested_list = Vec::new();
+
+		let mut live = match block.last() {
+			Some(v) => {
+				let (live, point) = self.terminator(v);
+
+				point_list.push(point);
+				nested_list.push(Vec::new());
+
+				live
+			}
+			None => after,
+		};
+
+		for v in block.code().iter().rev() {
+			let (before, point, nested) = self.statement(v, live);
+
+			point_list.push(point);
+			nested_list.push(nested);
+			live = before;
+		}
+
+		self.label_list.pop();
+
+		point_list.reverse();
+		nested_list.reverse();
+
+		Liveness {
+			entry: live,
+			point_list,
+			nested_list,
+		}
+	}
+
+	// Loops are iterated until the live set at their head stops growing
+	fn block_backward(&mut self, block: &Block, after: &LiveSet) -> Liveness {
+		let mut label = LiveSet::default();
+
+		loop {
+			let liveness = self.block(block, label.clone(), after.clone());
+
+			if liveness.entry == label {
+				return liveness;
+			}
+
+			label = liveness.entry;
+		}
+	}
+
+	// Any statement in the body may throw, so everything live at the
+	// start of a handler is live throughout the body
+	fn try_(&mut self, try_: &Try, after: &LiveSet) -> Vec<Liveness> {
+		let mut handler = self.handler.clone();
+		let mut nested = vec![Liveness::default()];
+
+		for catch in try_.catch_list() {
+			let liveness = self.block(catch.code(), after.clone(), after.clone());
+			let mut live = liveness.entry.clone();
+
+			for result in catch.result_list().iter() {
+				live.temporary.remove(&result);
+			}
+
+			handler.extend(&live);
+			nested.push(liveness);
+		}
+
+		if let Some(v) = try_.catch_all() {
+			let liveness = self.block(v, after.clone(), after.clone());
+
+			handler.extend(&liveness.entry);
+			nested.push(liveness);
+		}
+
+		let handler = std::mem::replace(&mut self.handler, handler);
+
+		nested[0] = self.block(try_.code(), after.clone(), after.clone());
+		self.handler = handler;
+
+		nested
+	}
+
+	fn statement(
+		&mut self,
+		stat: &Statement,
+		mut after: LiveSet,
+	) -> (LiveSet, Point, Vec<Liveness>) {
+		after.extend(&self.handler);
+
+		let (middle, live, nested) = match stat {
+			Statement::Block(v) => {
+				let liveness = if v.label_type() == Some(LabelType::Backward) {
+					self.block_backward(v, &after)
+				} else {
+					self.block(v, after.clone(), after.clone())
+				};
+
+				let live = liveness.entry.clone();
+
+				(live.clone(), live, vec![liveness])
+			}
+			Statement::Try(v) => {
+				let nested = self.try_(v, &after);
+				let live = nested[0].entry.clone();
+
+				(live.clone(), live, nested)
+			}
+			Statement::If(v) => {
+				let on_true = self.block(v.on_true(), after.clone(), after.clone());
+				let mut middle = on_true.entry.clone();
+				let mut nested = vec![on_true];
+
+				if let Some(v) = v.on_false() {
+					let on_false = self.block(v, after.clone(), after.clone());
+
+					middle.extend(&on_false.entry);
+					nested.push(on_false);
+				} else {
+					middle.extend(&after);
+				}
+
+				let mut live = middle.clone();
+
+				live.read(&Access::of(v.condition()));
+
+				(middle, live, nested)
+			}
+			Statement::BrIf(v) => {
+				let mut middle = after.clone();
+
+				middle.extend(&self.br_live(v.target()));
+
+				let mut live = middle.clone();
+
+				live.read(&Access::of(v.condition()));
+
+				(middle, live, Vec::new())
+			}
+			_ => {
+				let access = Access::of(stat);
+				let mut middle = after.clone();
+
+				middle.kill(&access);
+				middle.extend(&self.handler);
+
+				let mut live = middle.clone();
+
+				live.read(&access);
+
+				(middle, live, Vec::new())
+			}
+		};
+
+		(live, Point { after, middle }, nested)
+	}
+}
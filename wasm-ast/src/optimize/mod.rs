@@ -0,0 +1,64 @@
+use crate::{module::TypeInfo, node::FuncData};
+
+pub mod fold;
+pub mod inline;
+pub mod liveness;
+pub mod propagate;
+pub mod reach;
+pub mod register;
+
+/// Selects which optimization passes are run over built functions,
+/// and whether functions that fail to build are skipped. Every pass
+/// is disabled by default.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Options {
+	pub fold_constants: bool,
+	pub propagate_copies: bool,
+	/// Functions with at most this many nodes are inlined into their
+	/// callers, where zero disables inlining.
+	pub inline_threshold: usize,
+	/// Functions, globals, and element and data segments that can never
+	/// be used are left out of the translated module.
+	pub remove_unreachable: bool,
+	/// Functions that fail to build are translated as functions that
+	/// trap when called, rather than failing the whole module.
+	pub skip_failed: bool,
+}
+
+impl Options {
+	/// Returns options with every pass enabled.
+	#[must_use]
+	pub const fn all() -> Self {
+		Self {
+			fold_constants: true,
+			propagate_copies: true,
+			inline_threshold: 32,
+			remove_unreachable: true,
+			skip_failed: false,
+		}
+	}
+
+	/// Runs the enabled passes over a function in place.
+	pub fn apply(&self, ast: &mut FuncData) {
+		if self.fold_constants {
+			fold::run(ast);
+		}
+
+		if self.propagate_copies {
+			propagate::run(ast);
+		}
+	}
+
+	/// Runs the enabled passes over every function defined by a module,
+	/// including those that work across functions. The list starts at
+	/// index `offset` in the function space.
+	pub fn apply_list(&self, func_list: &mut [FuncData], type_info: &TypeInfo, offset: usize) {
+		if self.inline_threshold != 0 {
+			inline::run(func_list, type_info, offset, self.inline_threshold);
+		}
+
+		for ast in func_list {
+			self.apply(ast);
+		}
+	}
+}
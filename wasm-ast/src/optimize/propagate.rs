@@ -0,0 +1,326 @@
+use crate::{
+	node::{
+		Block, Expression, FuncData, Local, Select, SetLocal, Statement, Temporary, Terminator,
+	},
+	stack::find_temporary_data,
+	visit::{Driver, DriverMut, Visitor, VisitorMut},
+};
+
+use super::{
+	fold::{is_removable, take_expression},
+	liveness::{Access, Liveness},
+};
+
+// Counts the reads of a temporary, noting whether any happen
+// inside an arm of a `Select` that may be skipped at runtime
+struct Count {
+	var: usize,
+	read: usize,
+	guarded: bool,
+}
+
+impl Count {
+	const fn new(var: usize) -> Self {
+		Self {
+			var,
+			read: 0,
+			guarded: false,
+		}
+	}
+
+	// Only the expressions evaluated by the statement itself count,
+	// not those of any nested blocks
+	fn shallow(stat: &Statement, var: usize) -> Self {
+		let mut count = Self::new(var);
+
+		match stat {
+			Statement::Block(_) | Statement::Try(_) => {}
+			Statement::If(v) => v.condition().accept(&mut count),
+			_ => stat.accept(&mut count),
+		}
+
+		count
+	}
+
+	fn shallow_last(last: &Terminator, var: usize) -> Self {
+		let mut count = Self::new(var);
+
+		last.accept(&mut count);
+
+		count
+	}
+}
+
+impl Visitor for Count {
+	fn visit_select(&mut self, select: &Select) {
+		let on_true = Access::of(select.on_true());
+		let on_false = Access::of(select.on_false());
+
+		self.guarded |= on_true.temporary_read.contains(&self.var)
+			|| on_false.temporary_read.contains(&self.var);
+	}
+
+	fn visit_get_temporary(&mut self, temporary: Temporary) {
+		if temporary.var() == self.var {
+			self.read += 1;
+		}
+	}
+}
+
+struct Replace {
+	var: usize,
+	value: Option<Expression>,
+}
+
+impl VisitorMut for Replace {
+	fn visit_expression(&mut self, data: &mut Expression) {
+		if !matches!(data, Expression::GetTemporary(v) if v.var() == self.var) {
+			return;
+		}
+
+		if let Some(value) = self.value.take() {
+			*data = value;
+		}
+	}
+}
+
+struct Renumber {
+	local_map: Vec<usize>,
+}
+
+impl VisitorMut for Renumber {
+	fn visit_get_local(&mut self, local: &mut Local) {
+		local.var = self.local_map[local.var];
+	}
+
+	fn visit_set_local(&mut self, set: &mut SetLocal) {
+		set.var.var = self.local_map[set.var.var];
+	}
+}
+
+struct Pass {
+	changed: bool,
+}
+
+impl Pass {
+	// The use at `index` may take the value only if it is the sole read,
+	// happens in the statement's own expressions, and the temporary is
+	// dead right after it
+	fn is_single_use(
+		block: &Block,
+		liveness: &Liveness,
+		index: usize,
+		var: usize,
+		may_trap: bool,
+	) -> bool {
+		let mut deep = Count::new(var);
+		let (shallow, access) = if let Some(stat) = block.code().get(index) {
+			stat.accept(&mut deep);
+
+			(Count::shallow(stat, var), Access::of(stat))
+		} else {
+			let last = block.last().unwrap();
+
+			last.accept(&mut deep);
+
+			(Count::shallow_last(last, var), Access::of(last))
+		};
+
+		// Writes from nested blocks may happen after the read
+		if matches!(block.code().get(index), Some(Statement::If(_)))
+			&& access.temporary_write.contains(&var)
+		{
+			return false;
+		}
+
+		!access.align_read.contains(&var)
+			&& !access.align_write.contains(&var)
+			&& deep.read == 1
+			&& shallow.read == 1
+			&& (!may_trap || !shallow.guarded)
+			&& !liveness.point(index).middle.temporary.contains(&var)
+	}
+
+	// Finds where a temporary set at `index` is next read, provided its
+	// value can be moved there unchanged. Values that may trap are only
+	// moved into the very next statement so traps stay in order.
+	fn find_use(
+		block: &Block,
+		liveness: &Liveness,
+		access_list: &[Access],
+		index: usize,
+		var: usize,
+		value: &Expression,
+	) -> Option<usize> {
+		let access = Access::of(value);
+		let may_trap = !is_removable(value);
+
+		for (i, other) in access_list.iter().enumerate().skip(index + 1) {
+			if other.mentions(var) {
+				return Self::is_single_use(block, liveness, i, var, may_trap).then_some(i);
+			}
+
+			if may_trap || access.depends_on(other) {
+				return None;
+			}
+		}
+
+		None
+	}
+
+	fn simplify(&mut self, block: &mut Block, liveness: &Liveness) {
+		let mut access_list: Vec<_> = block.code().iter().map(Access::of).collect();
+
+		if let Some(v) = block.last() {
+			access_list.push(Access::of(v));
+		}
+
+		let mut used = vec![false; access_list.len()];
+		let mut removed = vec![false; block.code().len()];
+		let mut forward_list = Vec::new();
+
+		for (i, stat) in block.code().iter().enumerate() {
+			if used[i] {
+				continue;
+			}
+
+			let after = &liveness.point(i).after;
+
+			match stat {
+				Statement::SetTemporary(v) => {
+					let var = v.var().var();
+
+					if !after.temporary.contains(&var) && is_removable(v.value()) {
+						removed[i] = true;
+					} else if let Some(j) =
+						Self::find_use(block, liveness, &access_list, i, var, v.value())
+					{
+						if !used[j] {
+							used[j] = true;
+							removed[i] = true;
+							forward_list.push((i, j, var));
+						}
+					}
+				}
+				Statement::SetLocal(v) => {
+					removed[i] = !after.local.contains(&v.var().var()) && is_removable(v.value());
+				}
+				_ => {}
+			}
+		}
+
+		for (i, j, var) in forward_list {
+			let Statement::SetTemporary(v) = &mut block.code_mut()[i] else {
+				unreachable!()
+			};
+
+			let mut replace = Replace {
+				var,
+				value: Some(take_expression(v.value_mut())),
+			};
+
+			match block.code_mut().get_mut(j) {
+				Some(Statement::If(v)) => v.condition_mut().accept_mut(&mut replace),
+				Some(stat) => stat.accept_mut(&mut replace),
+				None => block.last_mut().unwrap().accept_mut(&mut replace),
+			}
+		}
+
+		if removed.contains(&true) {
+			let mut removed = removed.into_iter();
+
+			block.code_mut().retain(|_| !removed.next().unwrap());
+
+			self.changed = true;
+		}
+	}
+
+	// Nested blocks are simplified first, which leaves the positions of
+	// the statements around them as they were when liveness was found
+	fn block(&mut self, block: &mut Block, liveness: &Liveness) {
+		for (i, stat) in block.code_mut().iter_mut().enumerate() {
+			let mut nested = liveness.nested(i).iter();
+
+			match stat {
+				Statement::Block(v) => self.block(v, nested.next().unwrap()),
+				Statement::If(v) => {
+					self.block(v.on_true_mut(), nested.next().unwrap());
+
+					if let Some(v) = v.on_false_mut() {
+						self.block(v, nested.next().unwrap());
+					}
+				}
+				Statement::Try(v) => {
+					self.block(v.code_mut(), nested.next().unwrap());
+
+					for catch in v.catch_list_mut() {
+						self.block(catch.code_mut(), nested.next().unwrap());
+					}
+
+					if let Some(v) = v.catch_all_mut() {
+						self.block(v, nested.next().unwrap());
+					}
+				}
+				_ => {}
+			}
+		}
+
+		self.simplify(block, liveness);
+	}
+}
+
+// Temporaries keep their numbering, so the stack only shrinks down to
+// the highest one still in use, while unused locals are dropped outright
+fn shrink(ast: &mut FuncData) {
+	let access = Access::of(&*ast);
+
+	let num_stack = access
+		.temporary_read
+		.iter()
+		.chain(&access.temporary_write)
+		.chain(&access.align_read)
+		.chain(&access.align_write)
+		.max()
+		.map_or(0, |v| v + 1)
+		.max(ast.num_result);
+
+	ast.temporary_data = find_temporary_data(&ast.code, num_stack);
+
+	let mut local_map: Vec<_> = (0..ast.num_param()).collect();
+	let mut next = ast.num_param();
+
+	ast.local_data.retain(|_| {
+		let var = local_map.len();
+
+		if access.local_read.contains(&var) || access.local_write.contains(&var) {
+			local_map.push(next);
+			next += 1;
+
+			true
+		} else {
+			local_map.push(usize::MAX);
+
+			false
+		}
+	});
+
+	ast.accept_mut(&mut Renumber { local_map });
+}
+
+/// Removes writes to temporaries and locals that are never read again,
+/// and moves values read exactly once into their reader when nothing in
+/// between could change them.
+pub fn run(ast: &mut FuncData) {
+	loop {
+		let liveness = Liveness::new(ast);
+		let mut pass = Pass { changed: false };
+
+		pass.block(&mut ast.code, &liveness);
+
+		if !pass.changed {
+			break;
+		}
+	}
+
+	shrink(ast);
+}
@@ -0,0 +1,271 @@
+use wasmparser::{ConstExpr, DataKind, ElementItems, ElementKind, ExternalKind, Operator};
+
+use crate::{
+	error::{Error, IndexSpace, Result},
+	module::{External, Module},
+	node::{
+		Call, DataDrop, ElemDrop, FuncData, GetGlobal, MemoryInit, RefFunc, ReturnCall, SetGlobal,
+		TableInit,
+	},
+	visit::{Driver, Visitor},
+};
+
+/// The functions, globals, and element and data segments that can be used
+/// once a module is instantiated. Imports are always considered used.
+pub struct Reachable {
+	function: Vec<bool>,
+	global: Vec<bool>,
+	element: Vec<bool>,
+	data: Vec<bool>,
+}
+
+impl Reachable {
+	/// Marks every function and global as used.
+	#[must_use]
+	pub fn full(wasm: &Module) -> Self {
+		Self {
+			function: vec![true; wasm.function_space()],
+			global: vec![true; wasm.global_space()],
+			element: vec![true; wasm.element_section().len()],
+			data: vec![true; wasm.data_section().len()],
+		}
+	}
+
+	/// Finds what is used starting from the exports, the start function,
+	/// and active segments, then following the functions, globals and
+	/// segments that those call or reference. Passive segments are only
+	/// used if a reachable function initializes from or drops them.
+	/// The list holds the functions defined by the module.
+	///
+	/// # Errors
+	///
+	/// Returns an error if a segment or global initializer cannot be read.
+	pub fn new(wasm: &Module, func_list: &[FuncData]) -> Result<Self> {
+		let mut visit = Visit {
+			function: vec![false; wasm.function_space()],
+			global: vec![false; wasm.global_space()],
+			element: vec![false; wasm.element_section().len()],
+			data: vec![false; wasm.data_section().len()],
+			pending: Vec::new(),
+		};
+
+		visit.load_root_list(wasm)?;
+
+		let func_offset = wasm.import_count(External::Func);
+		let global_offset = wasm.import_count(External::Global);
+
+		for i in 0..func_offset {
+			visit.add_function(i);
+		}
+
+		for i in 0..global_offset {
+			visit.global[i] = true;
+		}
+
+		while let Some(next) = visit.pending.pop() {
+			match next {
+				Pending::Function(index) => {
+					if let Some(ast) = index.checked_sub(func_offset).map(|i| &func_list[i]) {
+						ast.accept(&mut visit);
+					}
+				}
+				Pending::Global(index) => {
+					if let Some(global) = index
+						.checked_sub(global_offset)
+						.map(|i| &wasm.global_section()[i])
+					{
+						visit.add_constant(&global.init_expr)?;
+					}
+				}
+				Pending::Element(index) => {
+					let element = &wasm.element_section()[index];
+
+					// Declared segments only make their functions available
+					// to `ref.func`, which is followed on its own
+					if !matches!(element.kind, ElementKind::Declared) {
+						visit.add_element_items(&element.items)?;
+					}
+				}
+			}
+		}
+
+		Ok(Self {
+			function: visit.function,
+			global: visit.global,
+			element: visit.element,
+			data: visit.data,
+		})
+	}
+
+	#[must_use]
+	pub fn has_function(&self, index: usize) -> bool {
+		self.function[index]
+	}
+
+	#[must_use]
+	pub fn has_global(&self, index: usize) -> bool {
+		self.global[index]
+	}
+
+	#[must_use]
+	pub fn has_element(&self, index: usize) -> bool {
+		self.element[index]
+	}
+
+	#[must_use]
+	pub fn has_data(&self, index: usize) -> bool {
+		self.data[index]
+	}
+}
+
+enum Pending {
+	Function(usize),
+	Global(usize),
+	Element(usize),
+}
+
+// Indices can only fail to fit on targets narrower than 32 bits
+fn to_index(space: IndexSpace, index: u32, offset: usize) -> Result<usize> {
+	usize::try_from(index).map_err(|_| Error::Invalid {
+		function: None,
+		offset,
+		message: format!("{space} index {index} is too large"),
+	})
+}
+
+struct Visit {
+	function: Vec<bool>,
+	global: Vec<bool>,
+	element: Vec<bool>,
+	data: Vec<bool>,
+	pending: Vec<Pending>,
+}
+
+impl Visit {
+	fn add_function(&mut self, index: usize) {
+		if !std::mem::replace(&mut self.function[index], true) {
+			self.pending.push(Pending::Function(index));
+		}
+	}
+
+	fn add_global(&mut self, index: usize) {
+		if !std::mem::replace(&mut self.global[index], true) {
+			self.pending.push(Pending::Global(index));
+		}
+	}
+
+	fn add_element(&mut self, index: usize) {
+		if !std::mem::replace(&mut self.element[index], true) {
+			self.pending.push(Pending::Element(index));
+		}
+	}
+
+	fn add_constant(&mut self, init: &ConstExpr) -> Result<()> {
+		for op in init.get_operators_reader().into_iter_with_offsets() {
+			let (op, offset) = op?;
+
+			match op {
+				Operator::RefFunc { function_index } => {
+					self.add_function(to_index(IndexSpace::Function, function_index, offset)?);
+				}
+				Operator::GlobalGet { global_index } => {
+					self.add_global(to_index(IndexSpace::Global, global_index, offset)?);
+				}
+				_ => {}
+			}
+		}
+
+		Ok(())
+	}
+
+	// Anything placed in a table may be called indirectly, so every item
+	// of a segment in use is kept
+	fn add_element_items(&mut self, items: &ElementItems) -> Result<()> {
+		match items.clone() {
+			ElementItems::Functions(functions) => {
+				for index in functions.into_iter_with_offsets() {
+					let (offset, index) = index?;
+
+					self.add_function(to_index(IndexSpace::Function, index, offset)?);
+				}
+			}
+			ElementItems::Expressions(expressions) => {
+				for init in expressions {
+					self.add_constant(&init?)?;
+				}
+			}
+		}
+
+		Ok(())
+	}
+
+	fn load_root_list(&mut self, wasm: &Module) -> Result<()> {
+		for export in wasm.export_section() {
+			let index = export.index.try_into().unwrap();
+
+			match export.kind {
+				ExternalKind::Func => self.add_function(index),
+				ExternalKind::Global => self.add_global(index),
+				_ => {}
+			}
+		}
+
+		if let Some(start) = wasm.start_section() {
+			self.add_function(start.try_into().unwrap());
+		}
+
+		for (i, element) in wasm.element_section().iter().enumerate() {
+			if let ElementKind::Active { offset_expr, .. } = &element.kind {
+				self.add_constant(offset_expr)?;
+				self.add_element(i);
+			}
+		}
+
+		for (i, data) in wasm.data_section().iter().enumerate() {
+			if let DataKind::Active { offset_expr, .. } = &data.kind {
+				self.add_constant(offset_expr)?;
+				self.data[i] = true;
+			}
+		}
+
+		Ok(())
+	}
+}
+
+impl Visitor for Visit {
+	fn visit_get_global(&mut self, global: GetGlobal) {
+		self.add_global(global.var());
+	}
+
+	fn visit_ref_func(&mut self, ref_func: RefFunc) {
+		self.add_function(ref_func.function());
+	}
+
+	fn visit_return_call(&mut self, call: &ReturnCall) {
+		self.add_function(call.function());
+	}
+
+	fn visit_call(&mut self, call: &Call) {
+		self.add_function(call.function());
+	}
+
+	fn visit_set_global(&mut self, set: &SetGlobal) {
+		self.add_global(set.var());
+	}
+
+	fn visit_memory_init(&mut self, init: &MemoryInit) {
+		self.data[init.data()] = true;
+	}
+
+	fn visit_data_drop(&mut self, drop: DataDrop) {
+		self.data[drop.data()] = true;
+	}
+
+	fn visit_table_init(&mut self, init: &TableInit) {
+		self.add_element(init.element());
+	}
+
+	fn visit_elem_drop(&mut self, drop: ElemDrop) {
+		self.add_element(drop.element());
+	}
+}
@@ -0,0 +1,417 @@
+use std::collections::{BTreeSet, HashSet};
+
+use crate::node::{Block, FuncData, LabelType, Statement};
+
+use super::liveness::{Access, LiveSet, Liveness};
+
+// Uses inside a loop count this many times more than those outside
+const LOOP_FACTOR: usize = 8;
+
+// Collects what every `try` body, nested ones included, reads and writes
+fn add_try_access(block: &Block, access_list: &mut Vec<Access>) {
+	for stat in block.code() {
+		match stat {
+			Statement::Block(v) => add_try_access(v, access_list),
+			Statement::If(v) => {
+				add_try_access(v.on_true(), access_list);
+
+				if let Some(v) = v.on_false() {
+					add_try_access(v, access_list);
+				}
+			}
+			Statement::Try(v) => {
+				access_list.push(Access::of(v.code()));
+				add_try_access(v.code(), access_list);
+
+				for catch in v.catch_list() {
+					add_try_access(catch.code(), access_list);
+				}
+
+				if let Some(v) = v.catch_all() {
+					add_try_access(v, access_list);
+				}
+			}
+			_ => {}
+		}
+	}
+}
+
+/// Where a temporary or local is kept once registers are assigned.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Location {
+	/// A local of the translated function, where the first ones are
+	/// always the parameters in order
+	Register(usize),
+	/// A slot of the table holding everything that did not fit
+	Spill(usize),
+}
+
+/// An assignment of temporaries and locals to a limited number of
+/// registers. Variables that are never live at the same time share a
+/// register, and those used least often, with uses inside loops counting
+/// for more, are spilled once registers run out.
+#[derive(Default)]
+pub struct Allocation {
+	temporary: Vec<Location>,
+	local: Vec<Location>,
+	owner_list: Vec<Option<usize>>,
+	initial_list: Vec<usize>,
+	num_spill: usize,
+}
+
+impl Allocation {
+	/// Assigns registers for a function with at most `available` of them
+	/// besides its parameters. Parameters and the locals picked out by
+	/// `is_named` never share their register with anything else.
+	#[must_use]
+	pub fn new(ast: &FuncData, available: usize, is_named: impl Fn(usize) -> bool) -> Self {
+		let liveness = Liveness::new(ast);
+		let mut graph = Graph::new(ast);
+
+		graph.add_entry(ast, &liveness);
+		graph.add_block(ast.code(), &liveness, 0);
+
+		let named: Vec<_> = (0..graph.num_local)
+			.map(|var| var < ast.num_param() || (is_named(var) && graph.weight[var] != 0))
+			.collect();
+
+		let class_list = graph.color(ast.num_param(), &named);
+		let mut allocation = graph.assign(&class_list, ast.num_param(), available, &named);
+
+		allocation.initial_list = liveness
+			.entry()
+			.local
+			.iter()
+			.copied()
+			.filter(|&var| var >= ast.num_param())
+			.collect();
+
+		allocation
+	}
+
+	/// Spills the lightest registers until no `try` body mentions more
+	/// than `limit` of them, as each body is written as a closure that
+	/// captures them. Parameters are never spilled.
+	pub fn limit_capture(&mut self, ast: &FuncData, limit: usize) {
+		let mut access_list = Vec::new();
+
+		add_try_access(ast.code(), &mut access_list);
+
+		for access in access_list {
+			let local = access.local_read.iter().chain(&access.local_write);
+			let temporary = access
+				.temporary_read
+				.iter()
+				.chain(&access.temporary_write)
+				.chain(&access.align_read)
+				.chain(&access.align_write);
+			let register_list: BTreeSet<_> = local
+				.map(|&var| self.local[var])
+				.chain(temporary.map(|&var| self.temporary[var]))
+				.filter_map(|location| match location {
+					Location::Register(index) => Some(index),
+					Location::Spill(_) => None,
+				})
+				.collect();
+
+			let excess = register_list.len().saturating_sub(limit);
+
+			for &register in register_list.iter().rev().take(excess) {
+				if register < ast.num_param() {
+					break;
+				}
+
+				self.spill(register);
+			}
+		}
+	}
+
+	// Registers are assigned heaviest first, so the later ones are used
+	// the least and are the cheapest to move into a new spill slot
+	fn spill(&mut self, register: usize) {
+		let from = Location::Register(register);
+		let to = Location::Spill(self.num_spill);
+
+		for location in self.local.iter_mut().chain(&mut self.temporary) {
+			if *location == from {
+				*location = to;
+			}
+		}
+
+		self.owner_list[register] = None;
+		self.num_spill += 1;
+	}
+
+	#[must_use]
+	pub fn temporary(&self, var: usize) -> Location {
+		self.temporary[var]
+	}
+
+	#[must_use]
+	pub fn local(&self, var: usize) -> Location {
+		self.local[var]
+	}
+
+	#[must_use]
+	pub fn num_register(&self) -> usize {
+		self.owner_list.len()
+	}
+
+	#[must_use]
+	pub const fn num_spill(&self) -> usize {
+		self.num_spill
+	}
+
+	/// Returns the local that a register is kept for alone, if any.
+	#[must_use]
+	pub fn owner(&self, register: usize) -> Option<usize> {
+		self.owner_list[register]
+	}
+
+	/// Returns the locals besides parameters that may be read before
+	/// they are written, and so must start out as zero.
+	#[must_use]
+	pub fn initial_list(&self) -> &[usize] {
+		&self.initial_list
+	}
+}
+
+// Locals come first, followed by temporaries
+struct Graph {
+	edge_list: Vec<HashSet<usize>>,
+	weight: Vec<usize>,
+	num_local: usize,
+}
+
+impl Graph {
+	fn new(ast: &FuncData) -> Self {
+		let num_local = ast.num_param() + ast.local_data().len();
+		let len = num_local + ast.num_stack();
+
+		Self {
+			edge_list: vec![HashSet::new(); len],
+			weight: vec![0; len],
+			num_local,
+		}
+	}
+
+	fn live_list(&self, live: &LiveSet) -> Vec<usize> {
+		let temporary = live.temporary.iter().map(|v| v + self.num_local);
+
+		live.local.iter().copied().chain(temporary).collect()
+	}
+
+	fn add_edge(&mut self, a: usize, b: usize) {
+		if a == b {
+			return;
+		}
+
+		self.edge_list[a].insert(b);
+		self.edge_list[b].insert(a);
+	}
+
+	// Everything written at once interferes with everything else that is
+	// live right after, including the other values written
+	fn add_write(&mut self, write: &[usize], live: &LiveSet) {
+		let live = self.live_list(live);
+
+		for (i, &a) in write.iter().enumerate() {
+			for &b in write[i + 1..].iter().chain(&live) {
+				self.add_edge(a, b);
+			}
+		}
+	}
+
+	fn add_weight(&mut self, access: &Access, scale: usize) {
+		let local = access.local_read.union(&access.local_write).copied();
+		let temporary = access
+			.temporary_read
+			.iter()
+			.chain(&access.temporary_write)
+			.chain(&access.align_read)
+			.chain(&access.align_write)
+			.map(|v| v + self.num_local);
+
+		for var in local.chain(temporary) {
+			self.weight[var] = self.weight[var].saturating_add(scale);
+		}
+	}
+
+	// Parameters and locals read before being written hold their values
+	// from the start of the function, while results are read at its end
+	fn add_entry(&mut self, ast: &FuncData, liveness: &Liveness) {
+		let mut write: Vec<_> = (0..ast.num_param()).collect();
+
+		write.extend(self.live_list(liveness.entry()));
+		write.sort_unstable();
+		write.dedup();
+
+		self.add_write(&write, &LiveSet::default());
+
+		let result = (0..ast.num_result()).map(|v| v + self.num_local);
+
+		for var in (0..ast.num_param()).chain(result) {
+			self.weight[var] = self.weight[var].saturating_add(1);
+		}
+	}
+
+	fn add_access(&mut self, access: &Access, live: &LiveSet, scale: usize) {
+		let temporary = access
+			.temporary_write
+			.iter()
+			.chain(&access.align_write)
+			.map(|v| v + self.num_local);
+		let write: Vec<_> = access
+			.local_write
+			.iter()
+			.copied()
+			.chain(temporary)
+			.collect();
+
+		self.add_write(&write, live);
+		self.add_weight(access, scale);
+	}
+
+	fn add_block(&mut self, block: &Block, liveness: &Liveness, depth: u32) {
+		let depth = depth + u32::from(block.label_type() == Some(LabelType::Backward));
+		let scale = LOOP_FACTOR.saturating_pow(depth);
+
+		for (i, stat) in block.code().iter().enumerate() {
+			let mut nested = liveness.nested(i).iter();
+
+			match stat {
+				Statement::Block(v) => self.add_block(v, nested.next().unwrap(), depth),
+				Statement::If(v) => {
+					self.add_weight(&Access::of(v.condition()), scale);
+					self.add_block(v.on_true(), nested.next().unwrap(), depth);
+
+					if let Some(v) = v.on_false() {
+						self.add_block(v, nested.next().unwrap(), depth);
+					}
+				}
+				Statement::Try(v) => {
+					self.add_block(v.code(), nested.next().unwrap(), depth);
+
+					for catch in v.catch_list() {
+						let write: Vec<_> = catch
+							.result_list()
+							.iter()
+							.map(|v| v + self.num_local)
+							.collect();
+
+						for &var in &write {
+							self.weight[var] = self.weight[var].saturating_add(scale);
+						}
+
+						let catch_liveness = nested.next().unwrap();
+
+						self.add_write(&write, catch_liveness.entry());
+						self.add_block(catch.code(), catch_liveness, depth);
+					}
+
+					if let Some(v) = v.catch_all() {
+						self.add_block(v, nested.next().unwrap(), depth);
+					}
+				}
+				Statement::BrIf(_) => {
+					let live = &liveness.point(i).middle;
+
+					self.add_access(&Access::of(stat), live, scale);
+				}
+				_ => {
+					let live = &liveness.point(i).after;
+
+					self.add_access(&Access::of(stat), live, scale);
+				}
+			}
+		}
+
+		if let Some(last) = block.last() {
+			let live = &liveness.point(block.code().len()).middle;
+
+			self.add_access(&Access::of(last), live, scale);
+		}
+	}
+
+	// Heavier variables pick first, taking the lowest class that nothing
+	// they interfere with is in. Parameters start in their own classes.
+	fn color(&self, num_param: usize, named: &[bool]) -> Vec<usize> {
+		let mut class_list: Vec<_> = (0..num_param).collect();
+		let mut shared = vec![false; num_param];
+		let mut order: Vec<_> = (num_param..self.weight.len()).collect();
+
+		order.sort_by_key(|&v| std::cmp::Reverse(self.weight[v]));
+		class_list.resize(self.weight.len(), usize::MAX);
+
+		for var in order {
+			let is_named = named.get(var).copied().unwrap_or(false);
+			let used: HashSet<_> = self.edge_list[var].iter().map(|&v| class_list[v]).collect();
+
+			let class = (0..shared.len())
+				.find(|&c| !is_named && shared[c] && !used.contains(&c))
+				.unwrap_or_else(|| {
+					shared.push(!is_named);
+					shared.len() - 1
+				});
+
+			class_list[var] = class;
+		}
+
+		class_list
+	}
+
+	// Parameters keep the first registers and the heaviest other classes
+	// take what is left, while the rest are spilled
+	fn assign(
+		&self,
+		class_list: &[usize],
+		num_param: usize,
+		available: usize,
+		named: &[bool],
+	) -> Allocation {
+		let len = class_list
+			.iter()
+			.max()
+			.map_or(num_param, |v| num_param.max(v + 1));
+		let mut class_weight = vec![0_usize; len];
+		let mut owner_list: Vec<_> = (0..len.min(num_param + available))
+			.map(|v| (v < num_param).then_some(v))
+			.collect();
+
+		for (var, &class) in class_list.iter().enumerate() {
+			class_weight[class] = class_weight[class].saturating_add(self.weight[var]);
+		}
+
+		let mut order: Vec<_> = (num_param..len).collect();
+
+		order.sort_by_key(|&v| std::cmp::Reverse(class_weight[v]));
+
+		let mut location = vec![Location::Spill(0); len];
+
+		for (i, class) in (0..num_param).chain(order).enumerate() {
+			location[class] = match i.checked_sub(num_param + available) {
+				Some(index) => Location::Spill(index),
+				None => Location::Register(i),
+			};
+		}
+
+		for (var, &class) in class_list[num_param..self.num_local].iter().enumerate() {
+			let var = var + num_param;
+
+			if let (true, Location::Register(index)) = (named[var], location[class]) {
+				owner_list[index] = Some(var);
+			}
+		}
+
+		let mut local: Vec<_> = class_list.iter().map(|&v| location[v]).collect();
+		let temporary = local.split_off(self.num_local);
+
+		Allocation {
+			temporary,
+			local,
+			owner_list,
+			initial_list: Vec::new(),
+			num_spill: len.saturating_sub(num_param + available),
+		}
+	}
+}
@@ -0,0 +1,248 @@
+use std::collections::{BTreeSet, HashSet};
+
+use crate::{
+	node::{Block, Expression, FuncData, Statement, Terminator},
+	optimize::liveness::Access,
+	visit::{Driver, Visitor},
+};
+
+/// Nested blocks of a function that are written as functions of their
+/// own, so that no single Lua function grows past the limits placed on
+/// its code size and jump distances. Outlined blocks keep using the
+/// variables of the function they are taken from.
+#[derive(Default)]
+pub struct Outline {
+	block_set: HashSet<usize>,
+}
+
+impl Outline {
+	/// Picks blocks to outline so that every function, including those
+	/// made from outlined blocks, has at most `size` nodes where possible.
+	/// The largest blocks are picked first, as long as they never return
+	/// from the function, never rethrow exceptions caught outside of them,
+	/// and mention at most `variable` temporaries and locals.
+	#[must_use]
+	pub fn new(ast: &FuncData, size: usize, variable: usize) -> Self {
+		let mut builder = Builder {
+			block_set: HashSet::new(),
+			max_size: size,
+			max_variable: variable,
+		};
+
+		if Count::of(ast) > size {
+			builder.function(ast.code());
+		}
+
+		Self {
+			block_set: builder.block_set,
+		}
+	}
+
+	#[must_use]
+	pub fn contains(&self, block: &Block) -> bool {
+		let id = block as *const _ as usize;
+
+		self.block_set.contains(&id)
+	}
+
+	#[must_use]
+	pub fn len(&self) -> usize {
+		self.block_set.len()
+	}
+
+	#[must_use]
+	pub fn is_empty(&self) -> bool {
+		self.block_set.is_empty()
+	}
+}
+
+#[derive(Default)]
+struct Count {
+	size: usize,
+}
+
+impl Count {
+	fn of<T: Driver<Self>>(node: &T) -> usize {
+		let mut count = Self::default();
+
+		node.accept(&mut count);
+
+		count.size
+	}
+}
+
+impl Visitor for Count {
+	fn visit_expression(&mut self, _: &Expression) {
+		self.size += 1;
+	}
+
+	fn visit_terminator(&mut self, _: &Terminator) {
+		self.size += 1;
+	}
+
+	fn visit_statement(&mut self, _: &Statement) {
+		self.size += 1;
+	}
+}
+
+// What is left of a block once its outlined blocks are taken out,
+// along with everything it needs from the function around it. Caught
+// exceptions are held in locals of the scope that catches them, so
+// `escape` counts the labels outside of the block that are rethrown to.
+struct Summary {
+	size: usize,
+	is_leaf: bool,
+	escape: usize,
+	local: BTreeSet<usize>,
+	temporary: BTreeSet<usize>,
+}
+
+impl Summary {
+	fn add<T: Driver<Access> + Driver<Count>>(&mut self, node: &T) {
+		let access = Access::of(node);
+
+		self.size += Count::of(node);
+		self.local
+			.extend(access.local_read.union(&access.local_write));
+		self.temporary.extend(
+			access
+				.temporary_read
+				.union(&access.temporary_write)
+				.chain(&access.align_read)
+				.chain(&access.align_write),
+		);
+	}
+
+	// Rethrowing to the label `target` levels up from one `depth` labels
+	// into the block reaches outside of it when it goes past them all
+	fn add_target(&mut self, target: usize, depth: usize) {
+		self.escape = self.escape.max((target + 1).saturating_sub(depth));
+	}
+
+	fn merge(&mut self, other: Self) {
+		self.size += other.size;
+		self.is_leaf &= other.is_leaf;
+		self.local.extend(other.local);
+		self.temporary.extend(other.temporary);
+	}
+}
+
+struct Builder {
+	block_set: HashSet<usize>,
+	max_size: usize,
+	max_variable: usize,
+}
+
+impl Builder {
+	// Nested blocks are kept apart as they are the ones that can be
+	// outlined, while the arms of other statements are merged in. The
+	// `depth` is the number of labels from the outermost block down to
+	// and including this one.
+	fn block(
+		&mut self,
+		block: &Block,
+		depth: usize,
+		summary: &mut Summary,
+		nested: &mut Vec<(usize, Summary)>,
+	) {
+		for stat in block.code() {
+			match stat {
+				Statement::Block(v) => {
+					let id = v as *const _ as usize;
+					let inner = self.function(v);
+
+					summary.escape = summary.escape.max(inner.escape.saturating_sub(depth));
+					nested.push((id, inner));
+				}
+				Statement::If(v) => {
+					summary.size += 1;
+					summary.add(v.condition());
+					self.block(v.on_true(), depth + 1, summary, nested);
+
+					if let Some(v) = v.on_false() {
+						self.block(v, depth + 1, summary, nested);
+					}
+				}
+				Statement::Try(v) => {
+					summary.size += 1;
+					self.block(v.code(), depth + 1, summary, nested);
+
+					for catch in v.catch_list() {
+						summary.temporary.extend(catch.result_list().iter());
+						self.block(catch.code(), depth + 1, summary, nested);
+					}
+
+					if let Some(v) = v.catch_all() {
+						self.block(v, depth + 1, summary, nested);
+					}
+
+					if let Some(v) = v.delegate() {
+						summary.add_target(v.target(), depth);
+					}
+				}
+				_ => summary.add(stat),
+			}
+		}
+
+		if let Some(last) = block.last() {
+			summary.add(last);
+			summary.is_leaf &= !matches!(
+				last,
+				Terminator::ReturnCall(_) | Terminator::ReturnCallIndirect(_)
+			);
+
+			if let Terminator::Rethrow(v) = last {
+				summary.add_target(v.target(), depth);
+			}
+		}
+	}
+
+	fn can_outline(&self, summary: &Summary) -> bool {
+		summary.is_leaf
+			&& summary.escape == 0
+			&& summary.size > 1
+			&& summary.local.len() + summary.temporary.len() <= self.max_variable
+	}
+
+	// Treats a block as if it were a function of its own, outlining its
+	// largest nested blocks until it is well within the limit. Each
+	// outlined block is left as a single call.
+	fn function(&mut self, block: &Block) -> Summary {
+		let mut summary = Summary {
+			size: 1,
+			is_leaf: true,
+			escape: 0,
+			local: BTreeSet::new(),
+			temporary: BTreeSet::new(),
+		};
+
+		let mut nested = Vec::new();
+
+		self.block(block, 1, &mut summary, &mut nested);
+
+		let mut size: usize = summary.size + nested.iter().map(|v| v.1.size).sum::<usize>();
+
+		if size > self.max_size {
+			nested.sort_by_key(|v| std::cmp::Reverse(v.1.size));
+
+			for (id, inner) in &mut nested {
+				if size <= self.max_size / 2 {
+					break;
+				}
+
+				if self.can_outline(inner) {
+					self.block_set.insert(*id);
+
+					size -= inner.size - 1;
+					inner.size = 1;
+				}
+			}
+		}
+
+		for (_, inner) in nested {
+			summary.merge(inner);
+		}
+
+		summary
+	}
+}
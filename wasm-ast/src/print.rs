@@ -0,0 +1,753 @@
+use std::fmt::{Display, Formatter, Result};
+
+use crate::node::{
+	Align, AtomicCmpxchg, AtomicNotify, AtomicRmw, AtomicWait, BinOp, BitSelect, Block, Br, BrIf,
+	BrTable, Call, CallIndirect, Catch, CmpOp, DataDrop, ElemDrop, Expression, ExtractLane,
+	FuncData, GetGlobal, If, LabelType, LoadAt, LoadLane, Local, MemoryCopy, MemoryFill,
+	MemoryGrow, MemoryInit, MemorySize, RefFunc, RefIsNull, RefNull, ReplaceLane, ResultList,
+	Rethrow, ReturnCall, ReturnCallIndirect, Select, SetGlobal, SetLocal, SetTemporary, Shuffle,
+	Statement, StoreAt, StoreLane, TableCopy, TableFill, TableGet, TableGrow, TableInit, TableSet,
+	TableSize, Temporary, Terminator, Throw, Try, UnOp, Value,
+};
+
+fn write_indentation(depth: usize, f: &mut Formatter) -> Result {
+	(0..depth).try_for_each(|_| f.write_str("\t"))
+}
+
+fn write_separated<T: Display>(list: impl IntoIterator<Item = T>, f: &mut Formatter) -> Result {
+	let mut iter = list.into_iter();
+
+	if let Some(first) = iter.next() {
+		write!(f, "{first}")?;
+	}
+
+	iter.try_for_each(|v| write!(f, ", {v}"))
+}
+
+fn write_call(name: impl Display, param_list: &[Expression], f: &mut Formatter) -> Result {
+	write!(f, "{name}(")?;
+	write_separated(param_list, f)?;
+	write!(f, ")")
+}
+
+// Statements that produce values are written as assignments
+fn write_result(result: impl Display, f: &mut Formatter) -> Result {
+	write!(f, "{result} = ")
+}
+
+// Blocks span multiple lines and so need to know
+// how deep they are nested to be indented properly
+trait Print {
+	fn print(&self, depth: usize, f: &mut Formatter) -> Result;
+}
+
+impl Display for Temporary {
+	fn fmt(&self, f: &mut Formatter) -> Result {
+		write!(f, "tmp_{}", self.var())
+	}
+}
+
+impl Display for Local {
+	fn fmt(&self, f: &mut Formatter) -> Result {
+		write!(f, "loc_{}", self.var())
+	}
+}
+
+impl Display for GetGlobal {
+	fn fmt(&self, f: &mut Formatter) -> Result {
+		write!(f, "global_{}", self.var())
+	}
+}
+
+impl Display for ResultList {
+	fn fmt(&self, f: &mut Formatter) -> Result {
+		write_separated(self.iter(), f)
+	}
+}
+
+impl Display for Value {
+	fn fmt(&self, f: &mut Formatter) -> Result {
+		match self {
+			Self::I32(v) => write!(f, "{v}_i32"),
+			Self::I64(v) => write!(f, "{v}_i64"),
+			Self::F32(v) => write!(f, "{v:?}_f32"),
+			Self::F64(v) => write!(f, "{v:?}_f64"),
+			Self::V128(v) => write!(f, "{v:#034x}_v128"),
+		}
+	}
+}
+
+impl Display for Select {
+	fn fmt(&self, f: &mut Formatter) -> Result {
+		let (condition, on_true, on_false) = (self.condition(), self.on_true(), self.on_false());
+
+		write!(f, "(select {condition} {on_true} {on_false})")
+	}
+}
+
+impl Display for LoadAt {
+	fn fmt(&self, f: &mut Formatter) -> Result {
+		let name = if self.is_atomic() {
+			"atomic_load"
+		} else {
+			"load"
+		};
+
+		write!(
+			f,
+			"({name} {:?} memory={} offset={} {})",
+			self.load_type(),
+			self.memory(),
+			self.offset(),
+			self.pointer()
+		)
+	}
+}
+
+impl Display for MemorySize {
+	fn fmt(&self, f: &mut Formatter) -> Result {
+		write!(f, "(memory_size memory={})", self.memory())
+	}
+}
+
+impl Display for RefNull {
+	fn fmt(&self, f: &mut Formatter) -> Result {
+		write!(f, "(ref_null {:?})", self.ref_type())
+	}
+}
+
+impl Display for RefIsNull {
+	fn fmt(&self, f: &mut Formatter) -> Result {
+		write!(f, "(ref_is_null {})", self.value())
+	}
+}
+
+impl Display for RefFunc {
+	fn fmt(&self, f: &mut Formatter) -> Result {
+		write!(f, "(ref_func {})", self.function())
+	}
+}
+
+impl Display for TableGet {
+	fn fmt(&self, f: &mut Formatter) -> Result {
+		write!(f, "(table_get table={} {})", self.table(), self.index())
+	}
+}
+
+impl Display for TableSize {
+	fn fmt(&self, f: &mut Formatter) -> Result {
+		write!(f, "(table_size table={})", self.table())
+	}
+}
+
+impl Display for ExtractLane {
+	fn fmt(&self, f: &mut Formatter) -> Result {
+		write!(
+			f,
+			"(extract_lane {:?} lane={} {})",
+			self.op_type(),
+			self.lane(),
+			self.vector()
+		)
+	}
+}
+
+impl Display for ReplaceLane {
+	fn fmt(&self, f: &mut Formatter) -> Result {
+		write!(
+			f,
+			"(replace_lane {:?} lane={} {} {})",
+			self.lane_type(),
+			self.lane(),
+			self.vector(),
+			self.value()
+		)
+	}
+}
+
+impl Display for Shuffle {
+	fn fmt(&self, f: &mut Formatter) -> Result {
+		write!(
+			f,
+			"(shuffle {:?} {} {})",
+			self.lanes(),
+			self.lhs(),
+			self.rhs()
+		)
+	}
+}
+
+impl Display for BitSelect {
+	fn fmt(&self, f: &mut Formatter) -> Result {
+		write!(
+			f,
+			"(bit_select {} {} {})",
+			self.on_true(),
+			self.on_false(),
+			self.condition()
+		)
+	}
+}
+
+impl Display for LoadLane {
+	fn fmt(&self, f: &mut Formatter) -> Result {
+		write!(
+			f,
+			"(load_lane {:?} memory={} offset={} lane={} {} {})",
+			self.lane_type(),
+			self.memory(),
+			self.offset(),
+			self.lane(),
+			self.pointer(),
+			self.vector()
+		)
+	}
+}
+
+impl Display for UnOp {
+	fn fmt(&self, f: &mut Formatter) -> Result {
+		write!(f, "({:?} {})", self.op_type(), self.rhs())
+	}
+}
+
+impl Display for BinOp {
+	fn fmt(&self, f: &mut Formatter) -> Result {
+		write!(f, "({:?} {} {})", self.op_type(), self.lhs(), self.rhs())
+	}
+}
+
+impl Display for CmpOp {
+	fn fmt(&self, f: &mut Formatter) -> Result {
+		write!(f, "({:?} {} {})", self.op_type(), self.lhs(), self.rhs())
+	}
+}
+
+impl Display for Expression {
+	fn fmt(&self, f: &mut Formatter) -> Result {
+		match self {
+			Self::Select(e) => e.fmt(f),
+			Self::GetTemporary(e) => e.fmt(f),
+			Self::GetLocal(e) => e.fmt(f),
+			Self::GetGlobal(e) => e.fmt(f),
+			Self::LoadAt(e) => e.fmt(f),
+			Self::MemorySize(e) => e.fmt(f),
+			Self::RefNull(e) => e.fmt(f),
+			Self::RefIsNull(e) => e.fmt(f),
+			Self::RefFunc(e) => e.fmt(f),
+			Self::TableGet(e) => e.fmt(f),
+			Self::TableSize(e) => e.fmt(f),
+			Self::ExtractLane(e) => e.fmt(f),
+			Self::ReplaceLane(e) => e.fmt(f),
+			Self::Shuffle(e) => e.fmt(f),
+			Self::BitSelect(e) => e.fmt(f),
+			Self::LoadLane(e) => e.fmt(f),
+			Self::Value(e) => e.fmt(f),
+			Self::UnOp(e) => e.fmt(f),
+			Self::BinOp(e) => e.fmt(f),
+			Self::CmpOp(e) => e.fmt(f),
+		}
+	}
+}
+
+impl Display for Align {
+	fn fmt(&self, f: &mut Formatter) -> Result {
+		if self.is_aligned() {
+			return Ok(());
+		}
+
+		write!(f, " [{}] <- [{}]", self.new_range(), self.old_range())
+	}
+}
+
+impl Display for Br {
+	fn fmt(&self, f: &mut Formatter) -> Result {
+		write!(f, "@{}{}", self.target(), self.align())
+	}
+}
+
+impl Display for BrTable {
+	fn fmt(&self, f: &mut Formatter) -> Result {
+		write!(f, "br_table {} [", self.condition())?;
+		write_separated(self.data(), f)?;
+		write!(f, "] default {}", self.default())
+	}
+}
+
+impl Display for Throw {
+	fn fmt(&self, f: &mut Formatter) -> Result {
+		write_call(
+			format_args!("throw tag={} ", self.tag()),
+			self.param_list(),
+			f,
+		)
+	}
+}
+
+impl Display for Rethrow {
+	fn fmt(&self, f: &mut Formatter) -> Result {
+		write!(f, "rethrow @{}", self.target())
+	}
+}
+
+impl Display for ReturnCall {
+	fn fmt(&self, f: &mut Formatter) -> Result {
+		let name = format_args!("return_call {}", self.function());
+
+		write_call(name, self.param_list(), f)
+	}
+}
+
+impl Display for ReturnCallIndirect {
+	fn fmt(&self, f: &mut Formatter) -> Result {
+		let name = format_args!(
+			"return_call_indirect table={} {}",
+			self.table(),
+			self.index()
+		);
+
+		write_call(name, self.param_list(), f)
+	}
+}
+
+impl Display for Terminator {
+	fn fmt(&self, f: &mut Formatter) -> Result {
+		match self {
+			Self::Unreachable => write!(f, "unreachable"),
+			Self::Br(t) => write!(f, "br {t}"),
+			Self::BrTable(t) => t.fmt(f),
+			Self::Throw(t) => t.fmt(f),
+			Self::Rethrow(t) => t.fmt(f),
+			Self::ReturnCall(t) => t.fmt(f),
+			Self::ReturnCallIndirect(t) => t.fmt(f),
+		}
+	}
+}
+
+impl Print for Block {
+	fn print(&self, depth: usize, f: &mut Formatter) -> Result {
+		match self.label_type() {
+			Some(LabelType::Forward) => write!(f, "forward ")?,
+			Some(LabelType::Backward) => write!(f, "backward ")?,
+			None => {}
+		}
+
+		writeln!(f, "{{")?;
+
+		for v in self.code() {
+			write_indentation(depth + 1, f)?;
+			v.print(depth + 1, f)?;
+			writeln!(f)?;
+		}
+
+		if let Some(v) = self.last() {
+			write_indentation(depth + 1, f)?;
+			writeln!(f, "{v}")?;
+		}
+
+		write_indentation(depth, f)?;
+		write!(f, "}}")
+	}
+}
+
+impl Print for BrIf {
+	fn print(&self, _: usize, f: &mut Formatter) -> Result {
+		write!(f, "br_if {} {}", self.condition(), self.target())
+	}
+}
+
+impl Print for If {
+	fn print(&self, depth: usize, f: &mut Formatter) -> Result {
+		write!(f, "if {} ", self.condition())?;
+		self.on_true().print(depth, f)?;
+
+		if let Some(v) = self.on_false() {
+			write!(f, " else ")?;
+			v.print(depth, f)?;
+		}
+
+		Ok(())
+	}
+}
+
+impl Print for Catch {
+	fn print(&self, depth: usize, f: &mut Formatter) -> Result {
+		write!(f, " catch tag={} ", self.tag())?;
+
+		if !self.result_list().is_empty() {
+			write!(f, "[{}] ", self.result_list())?;
+		}
+
+		self.code().print(depth, f)
+	}
+}
+
+impl Print for Try {
+	fn print(&self, depth: usize, f: &mut Formatter) -> Result {
+		write!(f, "try ")?;
+		self.code().print(depth, f)?;
+
+		for v in self.catch_list() {
+			v.print(depth, f)?;
+		}
+
+		if let Some(v) = self.catch_all() {
+			write!(f, " catch_all ")?;
+			v.print(depth, f)?;
+		}
+
+		if let Some(v) = self.delegate() {
+			write!(f, " delegate {v}")?;
+		}
+
+		Ok(())
+	}
+}
+
+impl Print for Call {
+	fn print(&self, _: usize, f: &mut Formatter) -> Result {
+		if !self.result_list().is_empty() {
+			write_result(self.result_list(), f)?;
+		}
+
+		let name = format_args!("call {}", self.function());
+
+		write_call(name, self.param_list(), f)
+	}
+}
+
+impl Print for CallIndirect {
+	fn print(&self, _: usize, f: &mut Formatter) -> Result {
+		if !self.result_list().is_empty() {
+			write_result(self.result_list(), f)?;
+		}
+
+		let name = format_args!("call_indirect table={} {}", self.table(), self.index());
+
+		write_call(name, self.param_list(), f)
+	}
+}
+
+impl Print for SetTemporary {
+	fn print(&self, _: usize, f: &mut Formatter) -> Result {
+		write_result(self.var(), f)?;
+		write!(f, "{}", self.value())
+	}
+}
+
+impl Print for SetLocal {
+	fn print(&self, _: usize, f: &mut Formatter) -> Result {
+		write_result(self.var(), f)?;
+		write!(f, "{}", self.value())
+	}
+}
+
+impl Print for SetGlobal {
+	fn print(&self, _: usize, f: &mut Formatter) -> Result {
+		write_result(GetGlobal::new(self.var(), self.value().ty()), f)?;
+		write!(f, "{}", self.value())
+	}
+}
+
+impl Print for StoreAt {
+	fn print(&self, _: usize, f: &mut Formatter) -> Result {
+		let name = if self.is_atomic() {
+			"atomic_store"
+		} else {
+			"store"
+		};
+
+		write!(
+			f,
+			"{name} {:?} memory={} offset={} {} {}",
+			self.store_type(),
+			self.memory(),
+			self.offset(),
+			self.pointer(),
+			self.value()
+		)
+	}
+}
+
+impl Print for StoreLane {
+	fn print(&self, _: usize, f: &mut Formatter) -> Result {
+		write!(
+			f,
+			"store_lane {:?} memory={} offset={} lane={} {} {}",
+			self.lane_type(),
+			self.memory(),
+			self.offset(),
+			self.lane(),
+			self.pointer(),
+			self.vector()
+		)
+	}
+}
+
+impl Print for MemoryGrow {
+	fn print(&self, _: usize, f: &mut Formatter) -> Result {
+		write_result(self.result(), f)?;
+		write!(f, "memory_grow memory={} {}", self.memory(), self.size())
+	}
+}
+
+impl Print for MemoryCopy {
+	fn print(&self, _: usize, f: &mut Formatter) -> Result {
+		let (destination, source) = (self.destination(), self.source());
+
+		write!(
+			f,
+			"memory_copy memory={} {} memory={} {} {}",
+			destination.memory(),
+			destination.pointer(),
+			source.memory(),
+			source.pointer(),
+			self.size()
+		)
+	}
+}
+
+impl Print for MemoryFill {
+	fn print(&self, _: usize, f: &mut Formatter) -> Result {
+		let destination = self.destination();
+
+		write!(
+			f,
+			"memory_fill memory={} {} {} {}",
+			destination.memory(),
+			destination.pointer(),
+			self.size(),
+			self.value()
+		)
+	}
+}
+
+impl Print for MemoryInit {
+	fn print(&self, _: usize, f: &mut Formatter) -> Result {
+		let destination = self.destination();
+
+		write!(
+			f,
+			"memory_init memory={} data={} {} {} {}",
+			destination.memory(),
+			self.data(),
+			destination.pointer(),
+			self.offset(),
+			self.size()
+		)
+	}
+}
+
+impl Print for DataDrop {
+	fn print(&self, _: usize, f: &mut Formatter) -> Result {
+		write!(f, "data_drop data={}", self.data())
+	}
+}
+
+impl Print for AtomicRmw {
+	fn print(&self, _: usize, f: &mut Formatter) -> Result {
+		write_result(self.result(), f)?;
+		write!(
+			f,
+			"atomic_rmw {:?} {:?} memory={} offset={} {} {}",
+			self.op_type(),
+			self.store_type(),
+			self.memory(),
+			self.offset(),
+			self.pointer(),
+			self.value()
+		)
+	}
+}
+
+impl Print for AtomicCmpxchg {
+	fn print(&self, _: usize, f: &mut Formatter) -> Result {
+		write_result(self.result(), f)?;
+		write!(
+			f,
+			"atomic_cmpxchg {:?} memory={} offset={} {} {} {}",
+			self.store_type(),
+			self.memory(),
+			self.offset(),
+			self.pointer(),
+			self.expected(),
+			self.replacement()
+		)
+	}
+}
+
+impl Print for AtomicWait {
+	fn print(&self, _: usize, f: &mut Formatter) -> Result {
+		write_result(self.result(), f)?;
+		write!(
+			f,
+			"atomic_wait {:?} memory={} offset={} {} {} {}",
+			self.load_type(),
+			self.memory(),
+			self.offset(),
+			self.pointer(),
+			self.expected(),
+			self.timeout()
+		)
+	}
+}
+
+impl Print for AtomicNotify {
+	fn print(&self, _: usize, f: &mut Formatter) -> Result {
+		write_result(self.result(), f)?;
+		write!(
+			f,
+			"atomic_notify memory={} offset={} {} {}",
+			self.memory(),
+			self.offset(),
+			self.pointer(),
+			self.count()
+		)
+	}
+}
+
+impl Print for TableSet {
+	fn print(&self, _: usize, f: &mut Formatter) -> Result {
+		write!(
+			f,
+			"table_set table={} {} {}",
+			self.table(),
+			self.index(),
+			self.value()
+		)
+	}
+}
+
+impl Print for TableGrow {
+	fn print(&self, _: usize, f: &mut Formatter) -> Result {
+		write_result(self.result(), f)?;
+		write!(
+			f,
+			"table_grow table={} {} {}",
+			self.table(),
+			self.size(),
+			self.value()
+		)
+	}
+}
+
+impl Print for TableFill {
+	fn print(&self, _: usize, f: &mut Formatter) -> Result {
+		let destination = self.destination();
+
+		write!(
+			f,
+			"table_fill table={} {} {} {}",
+			destination.table(),
+			destination.index(),
+			self.size(),
+			self.value()
+		)
+	}
+}
+
+impl Print for TableCopy {
+	fn print(&self, _: usize, f: &mut Formatter) -> Result {
+		let (destination, source) = (self.destination(), self.source());
+
+		write!(
+			f,
+			"table_copy table={} {} table={} {} {}",
+			destination.table(),
+			destination.index(),
+			source.table(),
+			source.index(),
+			self.size()
+		)
+	}
+}
+
+impl Print for TableInit {
+	fn print(&self, _: usize, f: &mut Formatter) -> Result {
+		let destination = self.destination();
+
+		write!(
+			f,
+			"table_init table={} element={} {} {} {}",
+			destination.table(),
+			self.element(),
+			destination.index(),
+			self.offset(),
+			self.size()
+		)
+	}
+}
+
+impl Print for ElemDrop {
+	fn print(&self, _: usize, f: &mut Formatter) -> Result {
+		write!(f, "elem_drop element={}", self.element())
+	}
+}
+
+impl Print for Statement {
+	fn print(&self, depth: usize, f: &mut Formatter) -> Result {
+		match self {
+			Self::Block(s) => {
+				write!(f, "block ")?;
+				s.print(depth, f)
+			}
+			Self::BrIf(s) => s.print(depth, f),
+			Self::If(s) => s.print(depth, f),
+			Self::Try(s) => s.print(depth, f),
+			Self::Call(s) => s.print(depth, f),
+			Self::CallIndirect(s) => s.print(depth, f),
+			Self::SetTemporary(s) => s.print(depth, f),
+			Self::SetLocal(s) => s.print(depth, f),
+			Self::SetGlobal(s) => s.print(depth, f),
+			Self::StoreAt(s) => s.print(depth, f),
+			Self::StoreLane(s) => s.print(depth, f),
+			Self::MemoryGrow(s) => s.print(depth, f),
+			Self::MemoryCopy(s) => s.print(depth, f),
+			Self::MemoryFill(s) => s.print(depth, f),
+			Self::MemoryInit(s) => s.print(depth, f),
+			Self::DataDrop(s) => s.print(depth, f),
+			Self::AtomicRmw(s) => s.print(depth, f),
+			Self::AtomicCmpxchg(s) => s.print(depth, f),
+			Self::AtomicWait(s) => s.print(depth, f),
+			Self::AtomicNotify(s) => s.print(depth, f),
+			Self::TableSet(s) => s.print(depth, f),
+			Self::TableGrow(s) => s.print(depth, f),
+			Self::TableFill(s) => s.print(depth, f),
+			Self::TableCopy(s) => s.print(depth, f),
+			Self::TableInit(s) => s.print(depth, f),
+			Self::ElemDrop(s) => s.print(depth, f),
+		}
+	}
+}
+
+impl Display for Block {
+	fn fmt(&self, f: &mut Formatter) -> Result {
+		self.print(0, f)
+	}
+}
+
+impl Display for Statement {
+	fn fmt(&self, f: &mut Formatter) -> Result {
+		self.print(0, f)
+	}
+}
+
+impl Display for FuncData {
+	fn fmt(&self, f: &mut Formatter) -> Result {
+		write!(f, "params [")?;
+		write_separated(self.param_data().iter().map(|v| format!("{v:?}")), f)?;
+		write!(f, "], results {}, temporaries [", self.num_result())?;
+
+		// Temporaries holding more than one type are shown as `_`
+		let temporary_list = self.temporary_data().iter().map(|v| match v {
+			Some(v) => format!("{v:?}"),
+			None => "_".to_string(),
+		});
+
+		write_separated(temporary_list, f)?;
+		write!(f, "], locals [")?;
+		write_separated(self.local_data().iter().map(|v| format!("{v:?}")), f)?;
+		write!(f, "] ")?;
+
+		self.code().print(0, f)
+	}
+}
@@ -0,0 +1,131 @@
+// Floats are stored as their bits so that NaN payloads survive
+// formats without a representation for them
+macro_rules! impl_float_bits {
+	($name:ident, $float:ty, $bits:ty) => {
+		pub mod $name {
+			use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+			#[allow(clippy::missing_errors_doc, clippy::trivially_copy_pass_by_ref)]
+			pub fn serialize<S: Serializer>(
+				value: &$float,
+				serializer: S,
+			) -> Result<S::Ok, S::Error> {
+				value.to_bits().serialize(serializer)
+			}
+
+			#[allow(clippy::missing_errors_doc)]
+			pub fn deserialize<'de, D: Deserializer<'de>>(
+				deserializer: D,
+			) -> Result<$float, D::Error> {
+				<$bits>::deserialize(deserializer).map(<$float>::from_bits)
+			}
+		}
+	};
+}
+
+impl_float_bits!(f32_bits, f32, u32);
+impl_float_bits!(f64_bits, f64, u64);
+
+// `wasmparser` types do not implement `serde` traits, so the few
+// that appear in the tree are converted through local mirrors
+#[derive(serde::Serialize, serde::Deserialize)]
+enum LocalType {
+	I32,
+	I64,
+	F32,
+	F64,
+	V128,
+	Ref(crate::node::RefType),
+}
+
+impl From<wasmparser::ValType> for LocalType {
+	fn from(value: wasmparser::ValType) -> Self {
+		use crate::node::RefType;
+		use wasmparser::ValType;
+
+		match value {
+			v if v == ValType::EXTERNREF => Self::Ref(RefType::Extern),
+			ValType::I32 => Self::I32,
+			ValType::I64 => Self::I64,
+			ValType::F32 => Self::F32,
+			ValType::F64 => Self::F64,
+			ValType::V128 => Self::V128,
+			ValType::Ref(_) => Self::Ref(RefType::Func),
+		}
+	}
+}
+
+impl From<LocalType> for wasmparser::ValType {
+	fn from(value: LocalType) -> Self {
+		match value {
+			LocalType::I32 => Self::I32,
+			LocalType::I64 => Self::I64,
+			LocalType::F32 => Self::F32,
+			LocalType::F64 => Self::F64,
+			LocalType::V128 => Self::V128,
+			LocalType::Ref(ref_type) => ref_type.into(),
+		}
+	}
+}
+
+pub mod val_type {
+	use serde::{Deserialize, Deserializer, Serialize, Serializer};
+	use wasmparser::ValType;
+
+	use super::LocalType;
+
+	#[allow(clippy::missing_errors_doc, clippy::trivially_copy_pass_by_ref)]
+	pub fn serialize<S: Serializer>(value: &ValType, serializer: S) -> Result<S::Ok, S::Error> {
+		LocalType::from(*value).serialize(serializer)
+	}
+
+	#[allow(clippy::missing_errors_doc)]
+	pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<ValType, D::Error> {
+		LocalType::deserialize(deserializer).map(ValType::from)
+	}
+}
+
+pub mod val_type_list {
+	use serde::{Deserialize, Deserializer, Serializer};
+	use wasmparser::ValType;
+
+	use super::LocalType;
+
+	#[allow(clippy::missing_errors_doc)]
+	pub fn serialize<S: Serializer>(list: &[ValType], serializer: S) -> Result<S::Ok, S::Error> {
+		serializer.collect_seq(list.iter().copied().map(LocalType::from))
+	}
+
+	#[allow(clippy::missing_errors_doc)]
+	pub fn deserialize<'de, D: Deserializer<'de>>(
+		deserializer: D,
+	) -> Result<Vec<ValType>, D::Error> {
+		let list = Vec::<LocalType>::deserialize(deserializer)?;
+
+		Ok(list.into_iter().map(ValType::from).collect())
+	}
+}
+
+pub mod val_type_option_list {
+	use serde::{Deserialize, Deserializer, Serializer};
+	use wasmparser::ValType;
+
+	use super::LocalType;
+
+	#[allow(clippy::missing_errors_doc)]
+	pub fn serialize<S: Serializer>(
+		list: &[Option<ValType>],
+		serializer: S,
+	) -> Result<S::Ok, S::Error> {
+		serializer.collect_seq(list.iter().map(|v| v.map(LocalType::from)))
+	}
+
+	#[allow(clippy::missing_errors_doc)]
+	pub fn deserialize<'de, D: Deserializer<'de>>(
+		deserializer: D,
+	) -> Result<Vec<Option<ValType>>, D::Error> {
+		let list = Vec::<Option<LocalType>>::deserialize(deserializer)?;
+
+		Ok(list.into_iter().map(|v| v.map(ValType::from)).collect())
+	}
+}
@@ -0,0 +1,108 @@
+use crate::node::{Block, Br, Expression, LabelType, Statement, Terminator};
+
+/// A loop built from blocks and branches that can be written as a
+/// structured loop with its condition checked at one end.
+#[derive(Clone, Copy)]
+pub enum LoopShape<'a> {
+	/// A forward block holding only a loop that starts by branching out
+	/// of the block and ends by branching back to its start. The code
+	/// runs until the condition holds, checking it before every run.
+	While {
+		condition: &'a Expression,
+		code: &'a [Statement],
+	},
+	/// A loop that ends by conditionally branching back to its start,
+	/// which nothing else branches to. The code runs at least once and
+	/// then again for as long as the condition holds.
+	Repeat {
+		code: &'a [Statement],
+		condition: &'a Expression,
+	},
+}
+
+impl<'a> LoopShape<'a> {
+	/// Recognizes either shape in a block, where a `While` is matched on
+	/// the forward block around the loop. Branches that move values are
+	/// never part of a shape.
+	#[must_use]
+	pub fn of(block: &'a Block) -> Option<Self> {
+		match block.label_type()? {
+			LabelType::Forward => Self::of_while(block),
+			LabelType::Backward => Self::of_repeat(block),
+		}
+	}
+
+	fn of_while(block: &'a Block) -> Option<Self> {
+		let ([Statement::Block(inner)], None) = (block.code(), block.last()) else {
+			return None;
+		};
+
+		let Some((Statement::BrIf(head), code)) = inner.code().split_first() else {
+			return None;
+		};
+
+		let Some(Terminator::Br(last)) = inner.last() else {
+			return None;
+		};
+
+		let is_while = inner.label_type() == Some(LabelType::Backward)
+			&& is_plain_br(head.target(), 1)
+			&& is_plain_br(*last, 0);
+
+		is_while.then_some(Self::While {
+			condition: head.condition(),
+			code,
+		})
+	}
+
+	fn of_repeat(block: &'a Block) -> Option<Self> {
+		let Some((Statement::BrIf(last), code)) = block.code().split_last() else {
+			return None;
+		};
+
+		let is_repeat = block.last().is_none()
+			&& is_plain_br(last.target(), 0)
+			&& !code.iter().any(|v| has_branch_to(v, 0));
+
+		is_repeat.then_some(Self::Repeat {
+			code,
+			condition: last.condition(),
+		})
+	}
+}
+
+fn is_plain_br(br: Br, target: usize) -> bool {
+	br.target() == target && br.align().is_aligned()
+}
+
+fn has_branch_in(block: &Block, target: usize) -> bool {
+	let branches = |br: &Br| br.target() == target;
+
+	block.code().iter().any(|v| has_branch_to(v, target))
+		|| match block.last() {
+			Some(Terminator::Br(v)) => branches(v),
+			Some(Terminator::BrTable(v)) => v.data().iter().any(branches) || branches(&v.default()),
+			_ => false,
+		}
+}
+
+// Targets count from the innermost label around the statement
+fn has_branch_to(stat: &Statement, target: usize) -> bool {
+	match stat {
+		Statement::Block(v) => has_branch_in(v, target + 1),
+		Statement::BrIf(v) => v.target().target() == target,
+		Statement::If(v) => {
+			has_branch_in(v.on_true(), target + 1)
+				|| v.on_false().is_some_and(|v| has_branch_in(v, target + 1))
+		}
+		Statement::Try(v) => {
+			has_branch_in(v.code(), target + 1)
+				|| v.catch_list()
+					.iter()
+					.any(|v| has_branch_in(v.code(), target + 1))
+				|| v.catch_all().is_some_and(|v| has_branch_in(v, target + 1))
+				|| v.delegate().is_some_and(|v| v.target() == target)
+		}
+		_ => false,
+	}
+}
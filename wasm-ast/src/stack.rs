@@ -1,28 +1,41 @@
+use wasmparser::ValType;
+
 use crate::{
 	node::{
-		Align, Expression, GetGlobal, LoadAt, Local, ResultList, SetTemporary, Statement, Temporary,
+		Align, AtomicCmpxchg, AtomicNotify, AtomicRmw, AtomicWait, Block, Expression, GetGlobal,
+		LoadAt, LoadLane, Local, MemoryGrow, ResultList, SetTemporary, Statement, TableGet,
+		TableGrow, TableSize, Temporary,
 	},
 	visit::{Driver, Visitor},
 };
 
-pub struct ReadGet<A, B, C> {
+pub struct ReadGet<A, B, C, D> {
 	has_local: A,
 	has_global: B,
 	has_memory: C,
+	has_table: D,
 	result: bool,
 }
 
-impl<A, B, C> ReadGet<A, B, C>
+impl<A, B, C, D> ReadGet<A, B, C, D>
 where
 	A: Fn(Local) -> bool,
 	B: Fn(GetGlobal) -> bool,
-	C: Fn(&LoadAt) -> bool,
+	C: Fn(usize) -> bool,
+	D: Fn(usize) -> bool,
 {
-	pub fn run<D: Driver<Self>>(node: &D, has_local: A, has_global: B, has_memory: C) -> bool {
+	pub fn run<N: Driver<Self>>(
+		node: &N,
+		has_local: A,
+		has_global: B,
+		has_memory: C,
+		has_table: D,
+	) -> bool {
 		let mut visitor = Self {
 			has_local,
 			has_global,
 			has_memory,
+			has_table,
 			result: false,
 		};
 
@@ -32,18 +45,31 @@ where
 	}
 }
 
-impl<A, B, C> Visitor for ReadGet<A, B, C>
+impl<A, B, C, D> Visitor for ReadGet<A, B, C, D>
 where
 	A: Fn(Local) -> bool,
 	B: Fn(GetGlobal) -> bool,
-	C: Fn(&LoadAt) -> bool,
+	C: Fn(usize) -> bool,
+	D: Fn(usize) -> bool,
 {
 	fn visit_get_global(&mut self, get_global: GetGlobal) {
 		self.result |= (self.has_global)(get_global);
 	}
 
 	fn visit_load_at(&mut self, load_at: &LoadAt) {
-		self.result |= (self.has_memory)(load_at);
+		self.result |= (self.has_memory)(load_at.memory());
+	}
+
+	fn visit_load_lane(&mut self, load_lane: &LoadLane) {
+		self.result |= (self.has_memory)(load_lane.memory());
+	}
+
+	fn visit_table_get(&mut self, table_get: &TableGet) {
+		self.result |= (self.has_table)(table_get.table());
+	}
+
+	fn visit_table_size(&mut self, table_size: &TableSize) {
+		self.result |= (self.has_table)(table_size.table());
 	}
 
 	fn visit_get_local(&mut self, local: Local) {
@@ -51,6 +77,99 @@ where
 	}
 }
 
+struct ReadTemporary<'a> {
+	var_list: &'a [usize],
+	result: bool,
+}
+
+impl ReadTemporary<'_> {
+	fn run(node: &Expression, var_list: &[usize]) -> bool {
+		let mut visitor = ReadTemporary {
+			var_list,
+			result: false,
+		};
+
+		node.accept(&mut visitor);
+
+		visitor.result
+	}
+}
+
+impl Visitor for ReadTemporary<'_> {
+	fn visit_get_temporary(&mut self, temporary: Temporary) {
+		self.result |= self.var_list.contains(&temporary.var());
+	}
+}
+
+// Types are only known from the nodes that name a temporary, so one
+// that is written by a call but never read is left untyped
+struct TemporaryType {
+	data: Vec<Option<ValType>>,
+	mixed: Vec<bool>,
+}
+
+impl TemporaryType {
+	fn add(&mut self, temporary: Temporary) {
+		let Temporary { var, ty } = temporary;
+
+		match self.data[var] {
+			Some(old) if old != ty => self.mixed[var] = true,
+			_ => self.data[var] = Some(ty),
+		}
+	}
+}
+
+impl Visitor for TemporaryType {
+	fn visit_get_temporary(&mut self, temporary: Temporary) {
+		self.add(temporary);
+	}
+
+	fn visit_set_temporary(&mut self, set: &SetTemporary) {
+		self.add(set.var());
+	}
+
+	fn visit_memory_grow(&mut self, grow: &MemoryGrow) {
+		self.add(grow.result());
+	}
+
+	fn visit_atomic_rmw(&mut self, rmw: &AtomicRmw) {
+		self.add(rmw.result());
+	}
+
+	fn visit_atomic_cmpxchg(&mut self, cmpxchg: &AtomicCmpxchg) {
+		self.add(cmpxchg.result());
+	}
+
+	fn visit_atomic_wait(&mut self, wait: &AtomicWait) {
+		self.add(wait.result());
+	}
+
+	fn visit_atomic_notify(&mut self, notify: &AtomicNotify) {
+		self.add(notify.result());
+	}
+
+	fn visit_table_grow(&mut self, grow: &TableGrow) {
+		self.add(grow.result());
+	}
+}
+
+// Finds the single type held by each of the first `len` temporaries
+pub fn find_temporary_data(code: &Block, len: usize) -> Vec<Option<ValType>> {
+	let mut visitor = TemporaryType {
+		data: vec![None; len],
+		mixed: vec![false; len],
+	};
+
+	code.accept(&mut visitor);
+
+	visitor
+		.data
+		.into_iter()
+		.zip(visitor.mixed)
+		.map(|(ty, mixed)| ty.filter(|_| !mixed))
+		.collect()
+}
+
 #[derive(Default)]
 pub struct Stack {
 	var_list: Vec<Expression>,
@@ -63,39 +182,39 @@ impl Stack {
 		self.var_list.len()
 	}
 
-	pub fn split_last(&mut self, num_param: usize, num_result: usize) -> Self {
-		let desired = self.len() - num_param;
+	pub fn split_last(&mut self, num_param: usize, num_result: usize) -> Option<Self> {
+		let desired = self.len().checked_sub(num_param)?;
 		let var_list = self.var_list.split_off(desired);
 
-		Self {
+		Some(Self {
 			var_list,
 			capacity: self.capacity.max(desired + num_result),
 			previous: self.previous + desired,
-		}
+		})
 	}
 
 	pub fn push(&mut self, data: Expression) {
 		self.var_list.push(data);
 	}
 
-	pub fn pop(&mut self) -> Expression {
-		self.var_list.pop().unwrap()
+	pub fn pop(&mut self) -> Option<Expression> {
+		self.var_list.pop()
 	}
 
-	pub fn pop_len(&'_ mut self, len: usize) -> impl Iterator<Item = Expression> + '_ {
-		let desired = self.len() - len;
+	pub fn pop_len(&'_ mut self, len: usize) -> Option<impl Iterator<Item = Expression> + '_> {
+		let desired = self.len().checked_sub(len)?;
 
-		self.var_list.drain(desired..)
+		Some(self.var_list.drain(desired..))
 	}
 
-	pub fn push_temporaries(&mut self, num: usize) -> ResultList {
+	pub fn push_temporaries(&mut self, type_list: &[ValType]) -> ResultList {
 		let start = self.previous + self.len();
-		let range = start..start + num;
+		let range = start..start + type_list.len();
 
 		self.capacity = self.capacity.max(range.end);
 
-		for var in range.clone() {
-			let data = Expression::GetTemporary(Temporary { var });
+		for (var, &ty) in range.clone().zip(type_list) {
+			let data = Expression::GetTemporary(Temporary { var, ty });
 
 			self.push(data);
 		}
@@ -103,8 +222,10 @@ impl Stack {
 		ResultList::new(range.start, range.end)
 	}
 
-	pub fn push_temporary(&mut self) -> Temporary {
-		self.push_temporaries(1).iter().next().unwrap()
+	pub fn push_temporary(&mut self, ty: ValType) -> Temporary {
+		let var = self.push_temporaries(&[ty]).iter().next().unwrap();
+
+		Temporary { var, ty }
 	}
 
 	// Return the alignment necessary for this block to branch out to a
@@ -120,23 +241,36 @@ impl Stack {
 	}
 
 	// Try to leak a slot's value to a `SetTemporary` instruction,
-	// adjusting the capacity and old index accordingly
+	// adjusting the capacity and old index accordingly. A slot that
+	// still reads the temporary of a leaked slot above it is leaked
+	// too, so the value is read before being overwritten.
 	pub fn leak_into<P>(&mut self, code: &mut Vec<Statement>, predicate: P)
 	where
 		P: Fn(&Expression) -> bool,
 	{
-		for (i, old) in self.var_list.iter_mut().enumerate() {
+		let mut written = Vec::new();
+
+		for (i, old) in self.var_list.iter().enumerate().rev() {
 			let var = self.previous + i;
 			let is_temporary =
 				matches!(old, Expression::GetTemporary(temporary) if temporary.var() == var);
 
-			if is_temporary || !predicate(old) {
+			if !is_temporary && (predicate(old) || ReadTemporary::run(old, &written)) {
+				written.push(var);
+			}
+		}
+
+		for (i, old) in self.var_list.iter_mut().enumerate() {
+			let var = self.previous + i;
+
+			if !written.contains(&var) {
 				continue;
 			}
 
-			let get = Expression::GetTemporary(Temporary { var });
+			let temporary = Temporary { var, ty: old.ty() };
+			let get = Expression::GetTemporary(temporary);
 			let set = Statement::SetTemporary(SetTemporary {
-				var: Temporary { var },
+				var: temporary,
 				value: std::mem::replace(old, get).into(),
 			});
 
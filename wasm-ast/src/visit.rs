@@ -1,7 +1,11 @@
 use crate::node::{
-	BinOp, Block, Br, BrIf, BrTable, Call, CallIndirect, CmpOp, Expression, FuncData, GetGlobal,
-	If, LoadAt, Local, MemoryCopy, MemoryFill, MemoryGrow, MemorySize, Select, SetGlobal, SetLocal,
-	SetTemporary, Statement, StoreAt, Temporary, Terminator, UnOp, Value,
+	AtomicCmpxchg, AtomicNotify, AtomicRmw, AtomicWait, BinOp, BitSelect, Block, Br, BrIf, BrTable,
+	Call, CallIndirect, CmpOp, DataDrop, ElemDrop, Expression, ExtractLane, FuncData, GetGlobal,
+	If, LoadAt, LoadLane, Local, MemoryCopy, MemoryFill, MemoryGrow, MemoryInit, MemorySize,
+	RefFunc, RefIsNull, RefNull, ReplaceLane, Rethrow, ReturnCall, ReturnCallIndirect, Select,
+	SetGlobal, SetLocal, SetTemporary, Shuffle, Statement, StoreAt, StoreLane, TableCopy,
+	TableFill, TableGet, TableGrow, TableInit, TableSet, TableSize, Temporary, Terminator, Throw,
+	Try, UnOp, Value,
 };
 
 pub trait Visitor {
@@ -17,6 +21,26 @@ pub trait Visitor {
 
 	fn visit_memory_size(&mut self, _: &MemorySize) {}
 
+	fn visit_ref_null(&mut self, _: RefNull) {}
+
+	fn visit_ref_is_null(&mut self, _: &RefIsNull) {}
+
+	fn visit_ref_func(&mut self, _: RefFunc) {}
+
+	fn visit_table_get(&mut self, _: &TableGet) {}
+
+	fn visit_table_size(&mut self, _: &TableSize) {}
+
+	fn visit_extract_lane(&mut self, _: &ExtractLane) {}
+
+	fn visit_replace_lane(&mut self, _: &ReplaceLane) {}
+
+	fn visit_shuffle(&mut self, _: &Shuffle) {}
+
+	fn visit_bit_select(&mut self, _: &BitSelect) {}
+
+	fn visit_load_lane(&mut self, _: &LoadLane) {}
+
 	fn visit_value(&mut self, _: Value) {}
 
 	fn visit_un_op(&mut self, _: &UnOp) {}
@@ -33,6 +57,14 @@ pub trait Visitor {
 
 	fn visit_br_table(&mut self, _: &BrTable) {}
 
+	fn visit_throw(&mut self, _: &Throw) {}
+
+	fn visit_rethrow(&mut self, _: Rethrow) {}
+
+	fn visit_return_call(&mut self, _: &ReturnCall) {}
+
+	fn visit_return_call_indirect(&mut self, _: &ReturnCallIndirect) {}
+
 	fn visit_terminator(&mut self, _: &Terminator) {}
 
 	fn visit_block(&mut self, _: &Block) {}
@@ -41,6 +73,8 @@ pub trait Visitor {
 
 	fn visit_if(&mut self, _: &If) {}
 
+	fn visit_try(&mut self, _: &Try) {}
+
 	fn visit_call(&mut self, _: &Call) {}
 
 	fn visit_call_indirect(&mut self, _: &CallIndirect) {}
@@ -53,15 +87,157 @@ pub trait Visitor {
 
 	fn visit_store_at(&mut self, _: &StoreAt) {}
 
+	fn visit_store_lane(&mut self, _: &StoreLane) {}
+
 	fn visit_memory_grow(&mut self, _: &MemoryGrow) {}
 
 	fn visit_memory_copy(&mut self, _: &MemoryCopy) {}
 
 	fn visit_memory_fill(&mut self, _: &MemoryFill) {}
 
+	fn visit_memory_init(&mut self, _: &MemoryInit) {}
+
+	fn visit_data_drop(&mut self, _: DataDrop) {}
+
+	fn visit_atomic_rmw(&mut self, _: &AtomicRmw) {}
+
+	fn visit_atomic_cmpxchg(&mut self, _: &AtomicCmpxchg) {}
+
+	fn visit_atomic_wait(&mut self, _: &AtomicWait) {}
+
+	fn visit_atomic_notify(&mut self, _: &AtomicNotify) {}
+
+	fn visit_table_set(&mut self, _: &TableSet) {}
+
+	fn visit_table_grow(&mut self, _: &TableGrow) {}
+
+	fn visit_table_fill(&mut self, _: &TableFill) {}
+
+	fn visit_table_copy(&mut self, _: &TableCopy) {}
+
+	fn visit_table_init(&mut self, _: &TableInit) {}
+
+	fn visit_elem_drop(&mut self, _: ElemDrop) {}
+
 	fn visit_statement(&mut self, _: &Statement) {}
 }
 
+/// Like `Visitor`, but every node may be rewritten in place. As children
+/// are visited before their parents, a parent sees its rewritten children.
+pub trait VisitorMut {
+	fn visit_select(&mut self, _: &mut Select) {}
+
+	fn visit_get_temporary(&mut self, _: &mut Temporary) {}
+
+	fn visit_get_local(&mut self, _: &mut Local) {}
+
+	fn visit_get_global(&mut self, _: &mut GetGlobal) {}
+
+	fn visit_load_at(&mut self, _: &mut LoadAt) {}
+
+	fn visit_memory_size(&mut self, _: &mut MemorySize) {}
+
+	fn visit_ref_null(&mut self, _: &mut RefNull) {}
+
+	fn visit_ref_is_null(&mut self, _: &mut RefIsNull) {}
+
+	fn visit_ref_func(&mut self, _: &mut RefFunc) {}
+
+	fn visit_table_get(&mut self, _: &mut TableGet) {}
+
+	fn visit_table_size(&mut self, _: &mut TableSize) {}
+
+	fn visit_extract_lane(&mut self, _: &mut ExtractLane) {}
+
+	fn visit_replace_lane(&mut self, _: &mut ReplaceLane) {}
+
+	fn visit_shuffle(&mut self, _: &mut Shuffle) {}
+
+	fn visit_bit_select(&mut self, _: &mut BitSelect) {}
+
+	fn visit_load_lane(&mut self, _: &mut LoadLane) {}
+
+	fn visit_value(&mut self, _: &mut Value) {}
+
+	fn visit_un_op(&mut self, _: &mut UnOp) {}
+
+	fn visit_bin_op(&mut self, _: &mut BinOp) {}
+
+	fn visit_cmp_op(&mut self, _: &mut CmpOp) {}
+
+	fn visit_expression(&mut self, _: &mut Expression) {}
+
+	fn visit_unreachable(&mut self) {}
+
+	fn visit_br(&mut self, _: &mut Br) {}
+
+	fn visit_br_table(&mut self, _: &mut BrTable) {}
+
+	fn visit_throw(&mut self, _: &mut Throw) {}
+
+	fn visit_rethrow(&mut self, _: &mut Rethrow) {}
+
+	fn visit_return_call(&mut self, _: &mut ReturnCall) {}
+
+	fn visit_return_call_indirect(&mut self, _: &mut ReturnCallIndirect) {}
+
+	fn visit_terminator(&mut self, _: &mut Terminator) {}
+
+	fn visit_block(&mut self, _: &mut Block) {}
+
+	fn visit_br_if(&mut self, _: &mut BrIf) {}
+
+	fn visit_if(&mut self, _: &mut If) {}
+
+	fn visit_try(&mut self, _: &mut Try) {}
+
+	fn visit_call(&mut self, _: &mut Call) {}
+
+	fn visit_call_indirect(&mut self, _: &mut CallIndirect) {}
+
+	fn visit_set_temporary(&mut self, _: &mut SetTemporary) {}
+
+	fn visit_set_local(&mut self, _: &mut SetLocal) {}
+
+	fn visit_set_global(&mut self, _: &mut SetGlobal) {}
+
+	fn visit_store_at(&mut self, _: &mut StoreAt) {}
+
+	fn visit_store_lane(&mut self, _: &mut StoreLane) {}
+
+	fn visit_memory_grow(&mut self, _: &mut MemoryGrow) {}
+
+	fn visit_memory_copy(&mut self, _: &mut MemoryCopy) {}
+
+	fn visit_memory_fill(&mut self, _: &mut MemoryFill) {}
+
+	fn visit_memory_init(&mut self, _: &mut MemoryInit) {}
+
+	fn visit_data_drop(&mut self, _: &mut DataDrop) {}
+
+	fn visit_atomic_rmw(&mut self, _: &mut AtomicRmw) {}
+
+	fn visit_atomic_cmpxchg(&mut self, _: &mut AtomicCmpxchg) {}
+
+	fn visit_atomic_wait(&mut self, _: &mut AtomicWait) {}
+
+	fn visit_atomic_notify(&mut self, _: &mut AtomicNotify) {}
+
+	fn visit_table_set(&mut self, _: &mut TableSet) {}
+
+	fn visit_table_grow(&mut self, _: &mut TableGrow) {}
+
+	fn visit_table_fill(&mut self, _: &mut TableFill) {}
+
+	fn visit_table_copy(&mut self, _: &mut TableCopy) {}
+
+	fn visit_table_init(&mut self, _: &mut TableInit) {}
+
+	fn visit_elem_drop(&mut self, _: &mut ElemDrop) {}
+
+	fn visit_statement(&mut self, _: &mut Statement) {}
+}
+
 pub trait Driver<T: Visitor> {
 	fn accept(&self, visitor: &mut T);
 }
@@ -128,6 +304,139 @@ impl<T: Visitor> Driver<T> for MemoryFill {
 	}
 }
 
+impl<T: Visitor> Driver<T> for RefNull {
+	fn accept(&self, visitor: &mut T) {
+		visitor.visit_ref_null(*self);
+	}
+}
+
+impl<T: Visitor> Driver<T> for RefIsNull {
+	fn accept(&self, visitor: &mut T) {
+		self.value().accept(visitor);
+
+		visitor.visit_ref_is_null(self);
+	}
+}
+
+impl<T: Visitor> Driver<T> for RefFunc {
+	fn accept(&self, visitor: &mut T) {
+		visitor.visit_ref_func(*self);
+	}
+}
+
+impl<T: Visitor> Driver<T> for TableGet {
+	fn accept(&self, visitor: &mut T) {
+		self.index().accept(visitor);
+
+		visitor.visit_table_get(self);
+	}
+}
+
+impl<T: Visitor> Driver<T> for TableSize {
+	fn accept(&self, visitor: &mut T) {
+		visitor.visit_table_size(self);
+	}
+}
+
+impl<T: Visitor> Driver<T> for MemoryInit {
+	fn accept(&self, visitor: &mut T) {
+		self.destination().pointer().accept(visitor);
+		self.offset().accept(visitor);
+		self.size().accept(visitor);
+
+		visitor.visit_memory_init(self);
+	}
+}
+
+impl<T: Visitor> Driver<T> for DataDrop {
+	fn accept(&self, visitor: &mut T) {
+		visitor.visit_data_drop(*self);
+	}
+}
+
+impl<T: Visitor> Driver<T> for AtomicRmw {
+	fn accept(&self, visitor: &mut T) {
+		self.pointer().accept(visitor);
+		self.value().accept(visitor);
+
+		visitor.visit_atomic_rmw(self);
+	}
+}
+
+impl<T: Visitor> Driver<T> for AtomicCmpxchg {
+	fn accept(&self, visitor: &mut T) {
+		self.pointer().accept(visitor);
+		self.expected().accept(visitor);
+		self.replacement().accept(visitor);
+
+		visitor.visit_atomic_cmpxchg(self);
+	}
+}
+
+impl<T: Visitor> Driver<T> for AtomicWait {
+	fn accept(&self, visitor: &mut T) {
+		self.pointer().accept(visitor);
+		self.expected().accept(visitor);
+		self.timeout().accept(visitor);
+
+		visitor.visit_atomic_wait(self);
+	}
+}
+
+impl<T: Visitor> Driver<T> for AtomicNotify {
+	fn accept(&self, visitor: &mut T) {
+		self.pointer().accept(visitor);
+		self.count().accept(visitor);
+
+		visitor.visit_atomic_notify(self);
+	}
+}
+
+impl<T: Visitor> Driver<T> for ExtractLane {
+	fn accept(&self, visitor: &mut T) {
+		self.vector().accept(visitor);
+
+		visitor.visit_extract_lane(self);
+	}
+}
+
+impl<T: Visitor> Driver<T> for ReplaceLane {
+	fn accept(&self, visitor: &mut T) {
+		self.vector().accept(visitor);
+		self.value().accept(visitor);
+
+		visitor.visit_replace_lane(self);
+	}
+}
+
+impl<T: Visitor> Driver<T> for Shuffle {
+	fn accept(&self, visitor: &mut T) {
+		self.lhs().accept(visitor);
+		self.rhs().accept(visitor);
+
+		visitor.visit_shuffle(self);
+	}
+}
+
+impl<T: Visitor> Driver<T> for BitSelect {
+	fn accept(&self, visitor: &mut T) {
+		self.on_true().accept(visitor);
+		self.on_false().accept(visitor);
+		self.condition().accept(visitor);
+
+		visitor.visit_bit_select(self);
+	}
+}
+
+impl<T: Visitor> Driver<T> for LoadLane {
+	fn accept(&self, visitor: &mut T) {
+		self.pointer().accept(visitor);
+		self.vector().accept(visitor);
+
+		visitor.visit_load_lane(self);
+	}
+}
+
 impl<T: Visitor> Driver<T> for Value {
 	fn accept(&self, visitor: &mut T) {
 		visitor.visit_value(*self);
@@ -169,6 +478,16 @@ impl<T: Visitor> Driver<T> for Expression {
 			Self::GetGlobal(v) => v.accept(visitor),
 			Self::LoadAt(v) => v.accept(visitor),
 			Self::MemorySize(v) => v.accept(visitor),
+			Self::RefNull(v) => v.accept(visitor),
+			Self::RefIsNull(v) => v.accept(visitor),
+			Self::RefFunc(v) => v.accept(visitor),
+			Self::TableGet(v) => v.accept(visitor),
+			Self::TableSize(v) => v.accept(visitor),
+			Self::ExtractLane(v) => v.accept(visitor),
+			Self::ReplaceLane(v) => v.accept(visitor),
+			Self::Shuffle(v) => v.accept(visitor),
+			Self::BitSelect(v) => v.accept(visitor),
+			Self::LoadLane(v) => v.accept(visitor),
 			Self::Value(v) => v.accept(visitor),
 			Self::UnOp(v) => v.accept(visitor),
 			Self::BinOp(v) => v.accept(visitor),
@@ -193,12 +512,54 @@ impl<T: Visitor> Driver<T> for BrTable {
 	}
 }
 
+impl<T: Visitor> Driver<T> for Throw {
+	fn accept(&self, visitor: &mut T) {
+		for v in self.param_list() {
+			v.accept(visitor);
+		}
+
+		visitor.visit_throw(self);
+	}
+}
+
+impl<T: Visitor> Driver<T> for Rethrow {
+	fn accept(&self, visitor: &mut T) {
+		visitor.visit_rethrow(*self);
+	}
+}
+
+impl<T: Visitor> Driver<T> for ReturnCall {
+	fn accept(&self, visitor: &mut T) {
+		for v in self.param_list() {
+			v.accept(visitor);
+		}
+
+		visitor.visit_return_call(self);
+	}
+}
+
+impl<T: Visitor> Driver<T> for ReturnCallIndirect {
+	fn accept(&self, visitor: &mut T) {
+		self.index().accept(visitor);
+
+		for v in self.param_list() {
+			v.accept(visitor);
+		}
+
+		visitor.visit_return_call_indirect(self);
+	}
+}
+
 impl<T: Visitor> Driver<T> for Terminator {
 	fn accept(&self, visitor: &mut T) {
 		match self {
 			Self::Unreachable => visitor.visit_unreachable(),
 			Self::Br(v) => v.accept(visitor),
 			Self::BrTable(v) => v.accept(visitor),
+			Self::Throw(v) => v.accept(visitor),
+			Self::Rethrow(v) => v.accept(visitor),
+			Self::ReturnCall(v) => v.accept(visitor),
+			Self::ReturnCallIndirect(v) => v.accept(visitor),
 		}
 
 		visitor.visit_terminator(self);
@@ -240,6 +601,26 @@ impl<T: Visitor> Driver<T> for If {
 	}
 }
 
+impl<T: Visitor> Driver<T> for Try {
+	fn accept(&self, visitor: &mut T) {
+		self.code().accept(visitor);
+
+		for v in self.catch_list() {
+			v.code().accept(visitor);
+		}
+
+		if let Some(v) = self.catch_all() {
+			v.accept(visitor);
+		}
+
+		if let Some(v) = self.delegate() {
+			v.accept(visitor);
+		}
+
+		visitor.visit_try(self);
+	}
+}
+
 impl<T: Visitor> Driver<T> for Call {
 	fn accept(&self, visitor: &mut T) {
 		for v in self.param_list() {
@@ -295,6 +676,15 @@ impl<T: Visitor> Driver<T> for StoreAt {
 	}
 }
 
+impl<T: Visitor> Driver<T> for StoreLane {
+	fn accept(&self, visitor: &mut T) {
+		self.pointer().accept(visitor);
+		self.vector().accept(visitor);
+
+		visitor.visit_store_lane(self);
+	}
+}
+
 impl<T: Visitor> Driver<T> for MemoryGrow {
 	fn accept(&self, visitor: &mut T) {
 		self.size().accept(visitor);
@@ -303,29 +693,647 @@ impl<T: Visitor> Driver<T> for MemoryGrow {
 	}
 }
 
-impl<T: Visitor> Driver<T> for Statement {
+impl<T: Visitor> Driver<T> for TableSet {
 	fn accept(&self, visitor: &mut T) {
-		match self {
-			Self::Block(v) => v.accept(visitor),
-			Self::BrIf(v) => v.accept(visitor),
-			Self::If(v) => v.accept(visitor),
-			Self::Call(v) => v.accept(visitor),
-			Self::CallIndirect(v) => v.accept(visitor),
-			Self::SetTemporary(v) => v.accept(visitor),
-			Self::SetLocal(v) => v.accept(visitor),
-			Self::SetGlobal(v) => v.accept(visitor),
-			Self::StoreAt(v) => v.accept(visitor),
-			Self::MemoryGrow(v) => v.accept(visitor),
-			Self::MemoryCopy(v) => v.accept(visitor),
-			Self::MemoryFill(v) => v.accept(visitor),
-		}
+		self.index().accept(visitor);
+		self.value().accept(visitor);
 
-		visitor.visit_statement(self);
+		visitor.visit_table_set(self);
 	}
 }
 
-impl<T: Visitor> Driver<T> for FuncData {
+impl<T: Visitor> Driver<T> for TableGrow {
 	fn accept(&self, visitor: &mut T) {
-		self.code().accept(visitor);
+		self.size().accept(visitor);
+		self.value().accept(visitor);
+
+		visitor.visit_table_grow(self);
+	}
+}
+
+impl<T: Visitor> Driver<T> for TableFill {
+	fn accept(&self, visitor: &mut T) {
+		self.destination().index().accept(visitor);
+		self.size().accept(visitor);
+		self.value().accept(visitor);
+
+		visitor.visit_table_fill(self);
+	}
+}
+
+impl<T: Visitor> Driver<T> for TableCopy {
+	fn accept(&self, visitor: &mut T) {
+		self.destination().index().accept(visitor);
+		self.source().index().accept(visitor);
+		self.size().accept(visitor);
+
+		visitor.visit_table_copy(self);
+	}
+}
+
+impl<T: Visitor> Driver<T> for TableInit {
+	fn accept(&self, visitor: &mut T) {
+		self.destination().index().accept(visitor);
+		self.offset().accept(visitor);
+		self.size().accept(visitor);
+
+		visitor.visit_table_init(self);
+	}
+}
+
+impl<T: Visitor> Driver<T> for ElemDrop {
+	fn accept(&self, visitor: &mut T) {
+		visitor.visit_elem_drop(*self);
+	}
+}
+
+impl<T: Visitor> Driver<T> for Statement {
+	fn accept(&self, visitor: &mut T) {
+		match self {
+			Self::Block(v) => v.accept(visitor),
+			Self::BrIf(v) => v.accept(visitor),
+			Self::If(v) => v.accept(visitor),
+			Self::Try(v) => v.accept(visitor),
+			Self::Call(v) => v.accept(visitor),
+			Self::CallIndirect(v) => v.accept(visitor),
+			Self::SetTemporary(v) => v.accept(visitor),
+			Self::SetLocal(v) => v.accept(visitor),
+			Self::SetGlobal(v) => v.accept(visitor),
+			Self::StoreAt(v) => v.accept(visitor),
+			Self::StoreLane(v) => v.accept(visitor),
+			Self::MemoryGrow(v) => v.accept(visitor),
+			Self::MemoryCopy(v) => v.accept(visitor),
+			Self::MemoryFill(v) => v.accept(visitor),
+			Self::MemoryInit(v) => v.accept(visitor),
+			Self::DataDrop(v) => v.accept(visitor),
+			Self::AtomicRmw(v) => v.accept(visitor),
+			Self::AtomicCmpxchg(v) => v.accept(visitor),
+			Self::AtomicWait(v) => v.accept(visitor),
+			Self::AtomicNotify(v) => v.accept(visitor),
+			Self::TableSet(v) => v.accept(visitor),
+			Self::TableGrow(v) => v.accept(visitor),
+			Self::TableFill(v) => v.accept(visitor),
+			Self::TableCopy(v) => v.accept(visitor),
+			Self::TableInit(v) => v.accept(visitor),
+			Self::ElemDrop(v) => v.accept(visitor),
+		}
+
+		visitor.visit_statement(self);
+	}
+}
+
+impl<T: Visitor> Driver<T> for FuncData {
+	fn accept(&self, visitor: &mut T) {
+		self.code().accept(visitor);
+	}
+}
+
+pub trait DriverMut<T: VisitorMut> {
+	fn accept_mut(&mut self, visitor: &mut T);
+}
+
+impl<T: VisitorMut> DriverMut<T> for Select {
+	fn accept_mut(&mut self, visitor: &mut T) {
+		self.condition.accept_mut(visitor);
+		self.on_true.accept_mut(visitor);
+		self.on_false.accept_mut(visitor);
+
+		visitor.visit_select(self);
+	}
+}
+
+impl<T: VisitorMut> DriverMut<T> for Temporary {
+	fn accept_mut(&mut self, visitor: &mut T) {
+		visitor.visit_get_temporary(self);
+	}
+}
+
+impl<T: VisitorMut> DriverMut<T> for Local {
+	fn accept_mut(&mut self, visitor: &mut T) {
+		visitor.visit_get_local(self);
+	}
+}
+
+impl<T: VisitorMut> DriverMut<T> for GetGlobal {
+	fn accept_mut(&mut self, visitor: &mut T) {
+		visitor.visit_get_global(self);
+	}
+}
+
+impl<T: VisitorMut> DriverMut<T> for LoadAt {
+	fn accept_mut(&mut self, visitor: &mut T) {
+		self.pointer.accept_mut(visitor);
+
+		visitor.visit_load_at(self);
+	}
+}
+
+impl<T: VisitorMut> DriverMut<T> for MemorySize {
+	fn accept_mut(&mut self, visitor: &mut T) {
+		visitor.visit_memory_size(self);
+	}
+}
+
+impl<T: VisitorMut> DriverMut<T> for MemoryCopy {
+	fn accept_mut(&mut self, visitor: &mut T) {
+		self.destination.pointer.accept_mut(visitor);
+		self.source.pointer.accept_mut(visitor);
+		self.size.accept_mut(visitor);
+
+		visitor.visit_memory_copy(self);
+	}
+}
+
+impl<T: VisitorMut> DriverMut<T> for MemoryFill {
+	fn accept_mut(&mut self, visitor: &mut T) {
+		self.destination.pointer.accept_mut(visitor);
+		self.size.accept_mut(visitor);
+		self.value.accept_mut(visitor);
+
+		visitor.visit_memory_fill(self);
+	}
+}
+
+impl<T: VisitorMut> DriverMut<T> for RefNull {
+	fn accept_mut(&mut self, visitor: &mut T) {
+		visitor.visit_ref_null(self);
+	}
+}
+
+impl<T: VisitorMut> DriverMut<T> for RefIsNull {
+	fn accept_mut(&mut self, visitor: &mut T) {
+		self.value.accept_mut(visitor);
+
+		visitor.visit_ref_is_null(self);
+	}
+}
+
+impl<T: VisitorMut> DriverMut<T> for RefFunc {
+	fn accept_mut(&mut self, visitor: &mut T) {
+		visitor.visit_ref_func(self);
+	}
+}
+
+impl<T: VisitorMut> DriverMut<T> for TableGet {
+	fn accept_mut(&mut self, visitor: &mut T) {
+		self.index.accept_mut(visitor);
+
+		visitor.visit_table_get(self);
+	}
+}
+
+impl<T: VisitorMut> DriverMut<T> for TableSize {
+	fn accept_mut(&mut self, visitor: &mut T) {
+		visitor.visit_table_size(self);
+	}
+}
+
+impl<T: VisitorMut> DriverMut<T> for MemoryInit {
+	fn accept_mut(&mut self, visitor: &mut T) {
+		self.destination.pointer.accept_mut(visitor);
+		self.offset.accept_mut(visitor);
+		self.size.accept_mut(visitor);
+
+		visitor.visit_memory_init(self);
+	}
+}
+
+impl<T: VisitorMut> DriverMut<T> for DataDrop {
+	fn accept_mut(&mut self, visitor: &mut T) {
+		visitor.visit_data_drop(self);
+	}
+}
+
+impl<T: VisitorMut> DriverMut<T> for AtomicRmw {
+	fn accept_mut(&mut self, visitor: &mut T) {
+		self.pointer.accept_mut(visitor);
+		self.value.accept_mut(visitor);
+
+		visitor.visit_atomic_rmw(self);
+	}
+}
+
+impl<T: VisitorMut> DriverMut<T> for AtomicCmpxchg {
+	fn accept_mut(&mut self, visitor: &mut T) {
+		self.pointer.accept_mut(visitor);
+		self.expected.accept_mut(visitor);
+		self.replacement.accept_mut(visitor);
+
+		visitor.visit_atomic_cmpxchg(self);
+	}
+}
+
+impl<T: VisitorMut> DriverMut<T> for AtomicWait {
+	fn accept_mut(&mut self, visitor: &mut T) {
+		self.pointer.accept_mut(visitor);
+		self.expected.accept_mut(visitor);
+		self.timeout.accept_mut(visitor);
+
+		visitor.visit_atomic_wait(self);
+	}
+}
+
+impl<T: VisitorMut> DriverMut<T> for AtomicNotify {
+	fn accept_mut(&mut self, visitor: &mut T) {
+		self.pointer.accept_mut(visitor);
+		self.count.accept_mut(visitor);
+
+		visitor.visit_atomic_notify(self);
+	}
+}
+
+impl<T: VisitorMut> DriverMut<T> for ExtractLane {
+	fn accept_mut(&mut self, visitor: &mut T) {
+		self.vector.accept_mut(visitor);
+
+		visitor.visit_extract_lane(self);
+	}
+}
+
+impl<T: VisitorMut> DriverMut<T> for ReplaceLane {
+	fn accept_mut(&mut self, visitor: &mut T) {
+		self.vector.accept_mut(visitor);
+		self.value.accept_mut(visitor);
+
+		visitor.visit_replace_lane(self);
+	}
+}
+
+impl<T: VisitorMut> DriverMut<T> for Shuffle {
+	fn accept_mut(&mut self, visitor: &mut T) {
+		self.lhs.accept_mut(visitor);
+		self.rhs.accept_mut(visitor);
+
+		visitor.visit_shuffle(self);
+	}
+}
+
+impl<T: VisitorMut> DriverMut<T> for BitSelect {
+	fn accept_mut(&mut self, visitor: &mut T) {
+		self.on_true.accept_mut(visitor);
+		self.on_false.accept_mut(visitor);
+		self.condition.accept_mut(visitor);
+
+		visitor.visit_bit_select(self);
+	}
+}
+
+impl<T: VisitorMut> DriverMut<T> for LoadLane {
+	fn accept_mut(&mut self, visitor: &mut T) {
+		self.pointer.accept_mut(visitor);
+		self.vector.accept_mut(visitor);
+
+		visitor.visit_load_lane(self);
+	}
+}
+
+impl<T: VisitorMut> DriverMut<T> for Value {
+	fn accept_mut(&mut self, visitor: &mut T) {
+		visitor.visit_value(self);
+	}
+}
+
+impl<T: VisitorMut> DriverMut<T> for UnOp {
+	fn accept_mut(&mut self, visitor: &mut T) {
+		self.rhs.accept_mut(visitor);
+
+		visitor.visit_un_op(self);
+	}
+}
+
+impl<T: VisitorMut> DriverMut<T> for BinOp {
+	fn accept_mut(&mut self, visitor: &mut T) {
+		self.lhs.accept_mut(visitor);
+		self.rhs.accept_mut(visitor);
+
+		visitor.visit_bin_op(self);
+	}
+}
+
+impl<T: VisitorMut> DriverMut<T> for CmpOp {
+	fn accept_mut(&mut self, visitor: &mut T) {
+		self.lhs.accept_mut(visitor);
+		self.rhs.accept_mut(visitor);
+
+		visitor.visit_cmp_op(self);
+	}
+}
+
+impl<T: VisitorMut> DriverMut<T> for Expression {
+	fn accept_mut(&mut self, visitor: &mut T) {
+		match self {
+			Self::Select(v) => v.accept_mut(visitor),
+			Self::GetTemporary(v) => v.accept_mut(visitor),
+			Self::GetLocal(v) => v.accept_mut(visitor),
+			Self::GetGlobal(v) => v.accept_mut(visitor),
+			Self::LoadAt(v) => v.accept_mut(visitor),
+			Self::MemorySize(v) => v.accept_mut(visitor),
+			Self::RefNull(v) => v.accept_mut(visitor),
+			Self::RefIsNull(v) => v.accept_mut(visitor),
+			Self::RefFunc(v) => v.accept_mut(visitor),
+			Self::TableGet(v) => v.accept_mut(visitor),
+			Self::TableSize(v) => v.accept_mut(visitor),
+			Self::ExtractLane(v) => v.accept_mut(visitor),
+			Self::ReplaceLane(v) => v.accept_mut(visitor),
+			Self::Shuffle(v) => v.accept_mut(visitor),
+			Self::BitSelect(v) => v.accept_mut(visitor),
+			Self::LoadLane(v) => v.accept_mut(visitor),
+			Self::Value(v) => v.accept_mut(visitor),
+			Self::UnOp(v) => v.accept_mut(visitor),
+			Self::BinOp(v) => v.accept_mut(visitor),
+			Self::CmpOp(v) => v.accept_mut(visitor),
+		}
+
+		visitor.visit_expression(self);
+	}
+}
+
+impl<T: VisitorMut> DriverMut<T> for Br {
+	fn accept_mut(&mut self, visitor: &mut T) {
+		visitor.visit_br(self);
+	}
+}
+
+impl<T: VisitorMut> DriverMut<T> for BrTable {
+	fn accept_mut(&mut self, visitor: &mut T) {
+		self.condition.accept_mut(visitor);
+
+		visitor.visit_br_table(self);
+	}
+}
+
+impl<T: VisitorMut> DriverMut<T> for Throw {
+	fn accept_mut(&mut self, visitor: &mut T) {
+		for v in &mut self.param_list {
+			v.accept_mut(visitor);
+		}
+
+		visitor.visit_throw(self);
+	}
+}
+
+impl<T: VisitorMut> DriverMut<T> for Rethrow {
+	fn accept_mut(&mut self, visitor: &mut T) {
+		visitor.visit_rethrow(self);
+	}
+}
+
+impl<T: VisitorMut> DriverMut<T> for ReturnCall {
+	fn accept_mut(&mut self, visitor: &mut T) {
+		for v in &mut self.param_list {
+			v.accept_mut(visitor);
+		}
+
+		visitor.visit_return_call(self);
+	}
+}
+
+impl<T: VisitorMut> DriverMut<T> for ReturnCallIndirect {
+	fn accept_mut(&mut self, visitor: &mut T) {
+		self.index.accept_mut(visitor);
+
+		for v in &mut self.param_list {
+			v.accept_mut(visitor);
+		}
+
+		visitor.visit_return_call_indirect(self);
+	}
+}
+
+impl<T: VisitorMut> DriverMut<T> for Terminator {
+	fn accept_mut(&mut self, visitor: &mut T) {
+		match self {
+			Self::Unreachable => visitor.visit_unreachable(),
+			Self::Br(v) => v.accept_mut(visitor),
+			Self::BrTable(v) => v.accept_mut(visitor),
+			Self::Throw(v) => v.accept_mut(visitor),
+			Self::Rethrow(v) => v.accept_mut(visitor),
+			Self::ReturnCall(v) => v.accept_mut(visitor),
+			Self::ReturnCallIndirect(v) => v.accept_mut(visitor),
+		}
+
+		visitor.visit_terminator(self);
+	}
+}
+
+impl<T: VisitorMut> DriverMut<T> for Block {
+	fn accept_mut(&mut self, visitor: &mut T) {
+		for v in &mut self.code {
+			v.accept_mut(visitor);
+		}
+
+		if let Some(v) = &mut self.last {
+			v.accept_mut(visitor);
+		}
+
+		visitor.visit_block(self);
+	}
+}
+
+impl<T: VisitorMut> DriverMut<T> for BrIf {
+	fn accept_mut(&mut self, visitor: &mut T) {
+		self.condition.accept_mut(visitor);
+
+		visitor.visit_br_if(self);
+	}
+}
+
+impl<T: VisitorMut> DriverMut<T> for If {
+	fn accept_mut(&mut self, visitor: &mut T) {
+		self.condition.accept_mut(visitor);
+		self.on_true.accept_mut(visitor);
+
+		if let Some(v) = &mut self.on_false {
+			v.accept_mut(visitor);
+		}
+
+		visitor.visit_if(self);
+	}
+}
+
+impl<T: VisitorMut> DriverMut<T> for Try {
+	fn accept_mut(&mut self, visitor: &mut T) {
+		self.code.accept_mut(visitor);
+
+		for v in &mut self.catch_list {
+			v.code.accept_mut(visitor);
+		}
+
+		if let Some(v) = &mut self.catch_all {
+			v.accept_mut(visitor);
+		}
+
+		if let Some(v) = &mut self.delegate {
+			v.accept_mut(visitor);
+		}
+
+		visitor.visit_try(self);
+	}
+}
+
+impl<T: VisitorMut> DriverMut<T> for Call {
+	fn accept_mut(&mut self, visitor: &mut T) {
+		for v in &mut self.param_list {
+			v.accept_mut(visitor);
+		}
+
+		visitor.visit_call(self);
+	}
+}
+
+impl<T: VisitorMut> DriverMut<T> for CallIndirect {
+	fn accept_mut(&mut self, visitor: &mut T) {
+		self.index.accept_mut(visitor);
+
+		for v in &mut self.param_list {
+			v.accept_mut(visitor);
+		}
+
+		visitor.visit_call_indirect(self);
+	}
+}
+
+impl<T: VisitorMut> DriverMut<T> for SetTemporary {
+	fn accept_mut(&mut self, visitor: &mut T) {
+		self.value.accept_mut(visitor);
+
+		visitor.visit_set_temporary(self);
+	}
+}
+
+impl<T: VisitorMut> DriverMut<T> for SetLocal {
+	fn accept_mut(&mut self, visitor: &mut T) {
+		self.value.accept_mut(visitor);
+
+		visitor.visit_set_local(self);
+	}
+}
+
+impl<T: VisitorMut> DriverMut<T> for SetGlobal {
+	fn accept_mut(&mut self, visitor: &mut T) {
+		self.value.accept_mut(visitor);
+
+		visitor.visit_set_global(self);
+	}
+}
+
+impl<T: VisitorMut> DriverMut<T> for StoreAt {
+	fn accept_mut(&mut self, visitor: &mut T) {
+		self.pointer.accept_mut(visitor);
+		self.value.accept_mut(visitor);
+
+		visitor.visit_store_at(self);
+	}
+}
+
+impl<T: VisitorMut> DriverMut<T> for StoreLane {
+	fn accept_mut(&mut self, visitor: &mut T) {
+		self.pointer.accept_mut(visitor);
+		self.vector.accept_mut(visitor);
+
+		visitor.visit_store_lane(self);
+	}
+}
+
+impl<T: VisitorMut> DriverMut<T> for MemoryGrow {
+	fn accept_mut(&mut self, visitor: &mut T) {
+		self.size.accept_mut(visitor);
+
+		visitor.visit_memory_grow(self);
+	}
+}
+
+impl<T: VisitorMut> DriverMut<T> for TableSet {
+	fn accept_mut(&mut self, visitor: &mut T) {
+		self.index.accept_mut(visitor);
+		self.value.accept_mut(visitor);
+
+		visitor.visit_table_set(self);
+	}
+}
+
+impl<T: VisitorMut> DriverMut<T> for TableGrow {
+	fn accept_mut(&mut self, visitor: &mut T) {
+		self.size.accept_mut(visitor);
+		self.value.accept_mut(visitor);
+
+		visitor.visit_table_grow(self);
+	}
+}
+
+impl<T: VisitorMut> DriverMut<T> for TableFill {
+	fn accept_mut(&mut self, visitor: &mut T) {
+		self.destination.index.accept_mut(visitor);
+		self.size.accept_mut(visitor);
+		self.value.accept_mut(visitor);
+
+		visitor.visit_table_fill(self);
+	}
+}
+
+impl<T: VisitorMut> DriverMut<T> for TableCopy {
+	fn accept_mut(&mut self, visitor: &mut T) {
+		self.destination.index.accept_mut(visitor);
+		self.source.index.accept_mut(visitor);
+		self.size.accept_mut(visitor);
+
+		visitor.visit_table_copy(self);
+	}
+}
+
+impl<T: VisitorMut> DriverMut<T> for TableInit {
+	fn accept_mut(&mut self, visitor: &mut T) {
+		self.destination.index.accept_mut(visitor);
+		self.offset.accept_mut(visitor);
+		self.size.accept_mut(visitor);
+
+		visitor.visit_table_init(self);
+	}
+}
+
+impl<T: VisitorMut> DriverMut<T> for ElemDrop {
+	fn accept_mut(&mut self, visitor: &mut T) {
+		visitor.visit_elem_drop(self);
+	}
+}
+
+impl<T: VisitorMut> DriverMut<T> for Statement {
+	fn accept_mut(&mut self, visitor: &mut T) {
+		match self {
+			Self::Block(v) => v.accept_mut(visitor),
+			Self::BrIf(v) => v.accept_mut(visitor),
+			Self::If(v) => v.accept_mut(visitor),
+			Self::Try(v) => v.accept_mut(visitor),
+			Self::Call(v) => v.accept_mut(visitor),
+			Self::CallIndirect(v) => v.accept_mut(visitor),
+			Self::SetTemporary(v) => v.accept_mut(visitor),
+			Self::SetLocal(v) => v.accept_mut(visitor),
+			Self::SetGlobal(v) => v.accept_mut(visitor),
+			Self::StoreAt(v) => v.accept_mut(visitor),
+			Self::StoreLane(v) => v.accept_mut(visitor),
+			Self::MemoryGrow(v) => v.accept_mut(visitor),
+			Self::MemoryCopy(v) => v.accept_mut(visitor),
+			Self::MemoryFill(v) => v.accept_mut(visitor),
+			Self::MemoryInit(v) => v.accept_mut(visitor),
+			Self::DataDrop(v) => v.accept_mut(visitor),
+			Self::AtomicRmw(v) => v.accept_mut(visitor),
+			Self::AtomicCmpxchg(v) => v.accept_mut(visitor),
+			Self::AtomicWait(v) => v.accept_mut(visitor),
+			Self::AtomicNotify(v) => v.accept_mut(visitor),
+			Self::TableSet(v) => v.accept_mut(visitor),
+			Self::TableGrow(v) => v.accept_mut(visitor),
+			Self::TableFill(v) => v.accept_mut(visitor),
+			Self::TableCopy(v) => v.accept_mut(visitor),
+			Self::TableInit(v) => v.accept_mut(visitor),
+			Self::ElemDrop(v) => v.accept_mut(visitor),
+		}
+
+		visitor.visit_statement(self);
+	}
+}
+
+impl<T: VisitorMut> DriverMut<T> for FuncData {
+	fn accept_mut(&mut self, visitor: &mut T) {
+		self.code.accept_mut(visitor);
 	}
 }